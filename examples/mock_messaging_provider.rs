@@ -0,0 +1,107 @@
+//! A local stand-in for the SMS and messaging provider.
+//!
+//! Point the portal at it with `SMS_PROVIDER_BASE_URL=http://127.0.0.1:9091` (and/or
+//! `MESSAGING_PROVIDER_BASE_URL`) and share the same API key. Sent messages are logged
+//! and listed at `GET /messages`; numbers ending in `0000` are rejected so failures can
+//! be exercised.
+//!
+//! cargo run --example mock_messaging_provider
+use actix_web::{App, HttpRequest, HttpResponse, HttpServer, web};
+use dotenv::dotenv;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::env;
+use std::sync::Mutex;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+struct MockState {
+    api_key: String,
+    messages: Mutex<Vec<SentMessage>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OutboundMessage {
+    channel: String,
+    from: String,
+    to: String,
+    text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SentMessage {
+    id: String,
+    channel: String,
+    from: String,
+    to: String,
+    text: String,
+}
+
+async fn send(
+    state: web::Data<MockState>,
+    req: HttpRequest,
+    message: web::Json<OutboundMessage>,
+) -> HttpResponse {
+    let authorized = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .is_some_and(|key| key == state.api_key);
+    if !authorized {
+        return HttpResponse::Unauthorized().json(json!({ "message": "Invalid API key" }));
+    }
+
+    if message.to.ends_with("0000") {
+        warn!("Rejected {} to {}", message.channel, message.to);
+        return HttpResponse::UnprocessableEntity()
+            .json(json!({ "message": "Recipient is unreachable" }));
+    }
+
+    let sent = SentMessage {
+        id: format!("msg_{}", Uuid::new_v4().simple()),
+        channel: message.channel.clone(),
+        from: message.from.clone(),
+        to: message.to.clone(),
+        text: message.text.clone(),
+    };
+    info!(
+        "Sent {} {} to {}: {}",
+        sent.channel, sent.id, sent.to, sent.text
+    );
+    state.messages.lock().unwrap().push(sent.clone());
+
+    HttpResponse::Ok().json(json!({ "id": sent.id, "status": "queued" }))
+}
+
+async fn messages(state: web::Data<MockState>) -> HttpResponse {
+    HttpResponse::Ok().json(&*state.messages.lock().unwrap())
+}
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    dotenv().ok();
+    tracing_subscriber::fmt::init();
+
+    let port: u16 = env::var("MOCK_MESSAGING_PORT")
+        .ok()
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(9091);
+
+    let state = web::Data::new(MockState {
+        api_key: env::var("SMS_PROVIDER_API_KEY").unwrap_or_else(|_| "mock-api-key".to_string()),
+        messages: Mutex::new(Vec::new()),
+    });
+
+    info!("Mock messaging provider listening on port {}", port);
+
+    HttpServer::new(move || {
+        App::new()
+            .app_data(state.clone())
+            .route("/messages", web::post().to(send))
+            .route("/messages", web::get().to(messages))
+    })
+    .bind(("127.0.0.1", port))?
+    .run()
+    .await
+}
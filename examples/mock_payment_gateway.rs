@@ -0,0 +1,145 @@
+//! A local stand-in for the payment gateway.
+//!
+//! Point the portal at it with `PAYMENT_GATEWAY_BASE_URL=http://127.0.0.1:9090`
+//! and share the same `PAYMENT_GATEWAY_SECRET_KEY`. Opening the returned
+//! `authorization_url` (optionally with `?outcome=failed`) fires a signed
+//! webhook back at the portal.
+//!
+//! cargo run --example mock_payment_gateway
+use actix_web::{App, HttpResponse, HttpServer, web};
+use dotenv::dotenv;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::Sha512;
+use std::collections::HashMap;
+use std::env;
+use std::sync::Mutex;
+use tracing::{error, info};
+
+struct MockState {
+    secret_key: String,
+    base_url: String,
+    webhook_url: String,
+    transactions: Mutex<HashMap<String, i64>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InitializeTransaction {
+    email: String,
+    amount: i64,
+    reference: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckoutQuery {
+    outcome: Option<String>,
+}
+
+async fn initialize(
+    state: web::Data<MockState>,
+    request: web::Json<InitializeTransaction>,
+) -> HttpResponse {
+    info!(
+        "Initialized {} for {} ({} kobo)",
+        request.reference, request.email, request.amount
+    );
+
+    state
+        .transactions
+        .lock()
+        .unwrap()
+        .insert(request.reference.clone(), request.amount);
+
+    HttpResponse::Ok().json(json!({
+        "status": true,
+        "message": "Authorization URL created",
+        "data": {
+            "authorization_url": format!("{}/checkout/{}", state.base_url, request.reference),
+            "access_code": format!("mock_{}", request.reference),
+            "reference": request.reference,
+        }
+    }))
+}
+
+async fn checkout(
+    state: web::Data<MockState>,
+    path: web::Path<String>,
+    query: web::Query<CheckoutQuery>,
+) -> HttpResponse {
+    let reference = path.into_inner();
+
+    let Some(amount) = state.transactions.lock().unwrap().get(&reference).copied() else {
+        return HttpResponse::NotFound().body("Unknown transaction reference");
+    };
+
+    let event = match query.outcome.as_deref() {
+        Some("failed") => "charge.failed",
+        _ => "charge.success",
+    };
+
+    let body = json!({
+        "event": event,
+        "data": { "reference": reference, "amount": amount }
+    })
+    .to_string();
+
+    let mut mac = Hmac::<Sha512>::new_from_slice(state.secret_key.as_bytes()).unwrap();
+    mac.update(body.as_bytes());
+    let signature = hex::encode(mac.finalize().into_bytes());
+
+    match reqwest::Client::new()
+        .post(&state.webhook_url)
+        .header("x-paystack-signature", signature)
+        .header("content-type", "application/json")
+        .body(body)
+        .send()
+        .await
+    {
+        Ok(response) => {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            info!("Delivered {} for {}: {}", event, reference, status);
+            HttpResponse::Ok().body(format!(
+                "Webhook {} delivered ({}): {}",
+                event, status, text
+            ))
+        }
+        Err(e) => {
+            error!("Failed to deliver webhook: {}", e);
+            HttpResponse::BadGateway().body(format!("Webhook delivery failed: {}", e))
+        }
+    }
+}
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    dotenv().ok();
+    tracing_subscriber::fmt::init();
+
+    let port: u16 = env::var("MOCK_GATEWAY_PORT")
+        .ok()
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(9090);
+
+    let state = web::Data::new(MockState {
+        secret_key: env::var("PAYMENT_GATEWAY_SECRET_KEY")
+            .unwrap_or_else(|_| "mock-secret-key".to_string()),
+        base_url: format!("http://127.0.0.1:{}", port),
+        webhook_url: env::var("MOCK_GATEWAY_WEBHOOK_URL")
+            .unwrap_or_else(|_| "http://127.0.0.1:8080/api/v1/webhooks/payments".to_string()),
+        transactions: Mutex::new(HashMap::new()),
+    });
+
+    info!("Mock payment gateway listening on port {}", port);
+
+    HttpServer::new(move || {
+        App::new()
+            .app_data(state.clone())
+            .route("/transaction/initialize", web::post().to(initialize))
+            .route("/checkout/{reference}", web::get().to(checkout))
+    })
+    .bind(("127.0.0.1", port))?
+    .run()
+    .await
+}
@@ -0,0 +1,18 @@
+ALTER TYPE payment_status ADD VALUE IF NOT EXISTS 'failed';
+
+ALTER TABLE payments
+    ADD COLUMN IF NOT EXISTS reference VARCHAR(100) UNIQUE,
+    ADD COLUMN IF NOT EXISTS gateway VARCHAR(50);
+
+CREATE TABLE IF NOT EXISTS payment_webhook_events (
+    id UUID PRIMARY KEY,
+    gateway VARCHAR(50) NOT NULL,
+    event VARCHAR(100) NOT NULL,
+    reference VARCHAR(100) NOT NULL,
+    payload JSONB NOT NULL,
+    received_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    UNIQUE (gateway, event, reference)
+);
+
+CREATE INDEX IF NOT EXISTS idx_payment_webhook_events_reference ON payment_webhook_events(reference);
+CREATE INDEX IF NOT EXISTS idx_payment_webhook_events_received_at ON payment_webhook_events(received_at);
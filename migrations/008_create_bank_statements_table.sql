@@ -0,0 +1,37 @@
+CREATE TYPE statement_line_status AS ENUM ('unmatched', 'matched', 'ignored');
+CREATE TYPE statement_match_method AS ENUM ('reference', 'narration', 'manual');
+
+ALTER TABLE payments
+    ADD COLUMN IF NOT EXISTS verified_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS verified_at TIMESTAMP WITH TIME ZONE;
+
+CREATE TABLE IF NOT EXISTS bank_statements (
+    id UUID PRIMARY KEY,
+    filename TEXT,
+    uploaded_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS bank_statement_lines (
+    id UUID PRIMARY KEY,
+    statement_id UUID NOT NULL REFERENCES bank_statements(id) ON DELETE CASCADE,
+    transaction_date DATE NOT NULL,
+    narration TEXT NOT NULL,
+    reference TEXT,
+    amount DECIMAL(20,9) NOT NULL,
+    status statement_line_status NOT NULL DEFAULT 'unmatched',
+    suggested_user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    payment_id UUID REFERENCES payments(id) ON DELETE SET NULL,
+    match_method statement_match_method,
+    matched_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    matched_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_bank_statements_uploaded_by ON bank_statements(uploaded_by);
+CREATE INDEX IF NOT EXISTS idx_bank_statements_created_at ON bank_statements(created_at);
+CREATE INDEX IF NOT EXISTS idx_bank_statement_lines_statement_id ON bank_statement_lines(statement_id);
+CREATE INDEX IF NOT EXISTS idx_bank_statement_lines_status ON bank_statement_lines(status);
+CREATE INDEX IF NOT EXISTS idx_bank_statement_lines_payment_id ON bank_statement_lines(payment_id);
@@ -0,0 +1,13 @@
+CREATE SEQUENCE IF NOT EXISTS payment_receipt_number_seq;
+
+CREATE TABLE IF NOT EXISTS payment_receipts (
+    id UUID PRIMARY KEY,
+    payment_id UUID UNIQUE NOT NULL REFERENCES payments(id) ON DELETE CASCADE,
+    receipt_number VARCHAR(50) UNIQUE NOT NULL,
+    issued_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    emailed_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_payment_receipts_issued_at ON payment_receipts(issued_at);
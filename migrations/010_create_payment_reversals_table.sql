@@ -0,0 +1,35 @@
+CREATE TYPE payment_entry_type AS ENUM ('payment', 'refund', 'reversal');
+CREATE TYPE reversal_status AS ENUM ('pending', 'approved', 'rejected');
+
+-- Refunds and reversals are recorded as compensating payments with a negative amount,
+-- so the original entry is never altered or removed.
+ALTER TABLE payments
+    ADD COLUMN IF NOT EXISTS entry_type payment_entry_type NOT NULL DEFAULT 'payment',
+    ADD COLUMN IF NOT EXISTS reverses_payment_id UUID REFERENCES payments(id) ON DELETE RESTRICT;
+
+ALTER TABLE payments
+    ADD CONSTRAINT payments_compensating_entry_check CHECK (
+        (entry_type = 'payment' AND reverses_payment_id IS NULL)
+        OR (entry_type <> 'payment' AND reverses_payment_id IS NOT NULL AND amount < 0)
+    );
+
+CREATE TABLE IF NOT EXISTS payment_reversals (
+    id UUID PRIMARY KEY,
+    payment_id UUID NOT NULL REFERENCES payments(id) ON DELETE RESTRICT,
+    entry_type payment_entry_type NOT NULL,
+    amount DECIMAL(20,9) NOT NULL CHECK (amount > 0),
+    reason TEXT NOT NULL CHECK (length(trim(reason)) > 0),
+    status reversal_status NOT NULL DEFAULT 'pending',
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    reviewed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    reviewed_at TIMESTAMP WITH TIME ZONE,
+    review_note TEXT,
+    compensating_payment_id UUID REFERENCES payments(id) ON DELETE RESTRICT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    CHECK (entry_type <> 'payment')
+);
+
+CREATE INDEX IF NOT EXISTS idx_payments_reverses_payment_id ON payments(reverses_payment_id);
+CREATE INDEX IF NOT EXISTS idx_payment_reversals_payment_id ON payment_reversals(payment_id);
+CREATE INDEX IF NOT EXISTS idx_payment_reversals_status ON payment_reversals(status);
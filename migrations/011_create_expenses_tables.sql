@@ -0,0 +1,46 @@
+CREATE TYPE expense_status AS ENUM ('pending', 'approved', 'rejected');
+
+CREATE TABLE IF NOT EXISTS expense_categories (
+    id UUID PRIMARY KEY,
+    name VARCHAR(100) NOT NULL UNIQUE,
+    description TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS expenses (
+    id UUID PRIMARY KEY,
+    category_id UUID NOT NULL REFERENCES expense_categories(id),
+    amount DECIMAL(20,9) NOT NULL CHECK (amount > 0),
+    payee VARCHAR(255) NOT NULL,
+    description TEXT,
+    spent_on DATE NOT NULL,
+    event_id UUID REFERENCES events(id) ON DELETE SET NULL,
+    project VARCHAR(255),
+    receipt_key TEXT,
+    receipt_content_type VARCHAR(100),
+    status expense_status NOT NULL DEFAULT 'pending',
+    recorded_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    approved_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    approved_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS budgets (
+    id UUID PRIMARY KEY,
+    category_id UUID NOT NULL REFERENCES expense_categories(id) ON DELETE CASCADE,
+    year INTEGER NOT NULL CHECK (year BETWEEN 2000 AND 2100),
+    amount DECIMAL(20,9) NOT NULL CHECK (amount >= 0),
+    notes TEXT,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    UNIQUE (category_id, year)
+);
+
+CREATE INDEX IF NOT EXISTS idx_expenses_category_id ON expenses(category_id);
+CREATE INDEX IF NOT EXISTS idx_expenses_status ON expenses(status);
+CREATE INDEX IF NOT EXISTS idx_expenses_spent_on ON expenses(spent_on);
+CREATE INDEX IF NOT EXISTS idx_expenses_event_id ON expenses(event_id);
+CREATE INDEX IF NOT EXISTS idx_budgets_year ON budgets(year);
@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS campaigns (
+    id UUID PRIMARY KEY,
+    title TEXT NOT NULL,
+    description TEXT,
+    target_amount DECIMAL(20,9) NOT NULL CHECK (target_amount > 0),
+    deadline DATE,
+    allow_anonymous BOOLEAN NOT NULL DEFAULT true,
+    is_closed BOOLEAN NOT NULL DEFAULT false,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+-- Donations go through the payments pipeline, so a payment now belongs to either a
+-- contribution or a campaign.
+ALTER TABLE payments
+    ALTER COLUMN contribution_id DROP NOT NULL,
+    ADD COLUMN IF NOT EXISTS campaign_id UUID REFERENCES campaigns(id) ON DELETE RESTRICT,
+    ADD COLUMN IF NOT EXISTS is_anonymous BOOLEAN NOT NULL DEFAULT false;
+
+ALTER TABLE payments
+    ADD CONSTRAINT payments_target_check CHECK ((contribution_id IS NULL) <> (campaign_id IS NULL));
+
+CREATE INDEX IF NOT EXISTS idx_campaigns_deadline ON campaigns(deadline);
+CREATE INDEX IF NOT EXISTS idx_campaigns_created_at ON campaigns(created_at);
+CREATE INDEX IF NOT EXISTS idx_payments_campaign_id ON payments(campaign_id);
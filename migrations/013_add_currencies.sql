@@ -0,0 +1,35 @@
+-- Amounts are stored in the currency they were paid in. NGN is the base currency every
+-- ledger and report total is converted to.
+ALTER TABLE contributions ADD COLUMN IF NOT EXISTS currency VARCHAR(3) NOT NULL DEFAULT 'NGN';
+
+CREATE TABLE IF NOT EXISTS exchange_rates (
+    id UUID PRIMARY KEY,
+    currency VARCHAR(3) NOT NULL,
+    rate DECIMAL(20,9) NOT NULL CHECK (rate > 0),
+    effective_date DATE NOT NULL,
+    notes TEXT,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    UNIQUE (currency, effective_date),
+    CHECK (currency <> 'NGN')
+);
+
+CREATE INDEX IF NOT EXISTS idx_exchange_rates_currency_date ON exchange_rates(currency, effective_date DESC);
+
+-- Units of the base currency per unit of `code` on `day`; NULL when no rate has been entered yet.
+CREATE OR REPLACE FUNCTION exchange_rate_on(code VARCHAR, day DATE) RETURNS DECIMAL AS $$
+    SELECT CASE WHEN code = 'NGN' THEN 1::DECIMAL ELSE (
+        SELECT rate FROM exchange_rates
+        WHERE currency = code AND effective_date <= day
+        ORDER BY effective_date DESC
+        LIMIT 1
+    ) END
+$$ LANGUAGE SQL STABLE;
+
+ALTER TABLE payments
+    ADD COLUMN IF NOT EXISTS currency VARCHAR(3) NOT NULL DEFAULT 'NGN',
+    ADD COLUMN IF NOT EXISTS exchange_rate DECIMAL(20,9) NOT NULL DEFAULT 1 CHECK (exchange_rate > 0),
+    ADD COLUMN IF NOT EXISTS base_amount DECIMAL(20,9) GENERATED ALWAYS AS (ROUND(amount * exchange_rate, 2)) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_payments_currency ON payments(currency);
@@ -0,0 +1,17 @@
+ALTER TABLE users ADD COLUMN IF NOT EXISTS dues_reminders_enabled BOOLEAN NOT NULL DEFAULT true;
+
+-- One row per reminder attempt. The unique key stops a member being reminded twice at the
+-- same offset for a contribution, even if the scheduler runs more often than daily.
+CREATE TABLE IF NOT EXISTS dues_reminders (
+    id UUID PRIMARY KEY,
+    contribution_id UUID NOT NULL REFERENCES contributions(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    offset_days INTEGER NOT NULL,
+    sent_at TIMESTAMP WITH TIME ZONE,
+    error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    UNIQUE (contribution_id, user_id, offset_days)
+);
+
+CREATE INDEX IF NOT EXISTS idx_dues_reminders_user_id ON dues_reminders(user_id);
+CREATE INDEX IF NOT EXISTS idx_dues_reminders_created_at ON dues_reminders(created_at);
@@ -0,0 +1,41 @@
+CREATE TYPE email_status AS ENUM ('pending', 'sending', 'sent', 'failed');
+
+-- Outgoing mail is written here in the same transaction as the change that triggers it and
+-- delivered asynchronously by the outbox dispatcher.
+CREATE TABLE IF NOT EXISTS email_outbox (
+    id UUID PRIMARY KEY,
+    to_email VARCHAR(255) NOT NULL,
+    to_name VARCHAR(255),
+    subject TEXT NOT NULL,
+    html_body TEXT NOT NULL,
+    text_body TEXT,
+    status email_status NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL DEFAULT 5 CHECK (max_attempts > 0),
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    locked_at TIMESTAMP WITH TIME ZONE,
+    last_error TEXT,
+    sent_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_email_outbox_deliverable ON email_outbox(next_attempt_at) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_email_outbox_status ON email_outbox(status);
+CREATE INDEX IF NOT EXISTS idx_email_outbox_to_email ON email_outbox(to_email);
+CREATE INDEX IF NOT EXISTS idx_email_outbox_created_at ON email_outbox(created_at);
+
+CREATE TABLE IF NOT EXISTS email_outbox_attachments (
+    id UUID PRIMARY KEY,
+    email_id UUID NOT NULL REFERENCES email_outbox(id) ON DELETE CASCADE,
+    filename VARCHAR(255) NOT NULL,
+    content_type VARCHAR(100) NOT NULL,
+    content BYTEA NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_email_outbox_attachments_email_id ON email_outbox_attachments(email_id);
+
+-- Reminders are now queued rather than sent inline; delivery status lives on the outbox row.
+ALTER TABLE dues_reminders ADD COLUMN IF NOT EXISTS email_id UUID REFERENCES email_outbox(id) ON DELETE SET NULL;
+ALTER TABLE dues_reminders DROP COLUMN IF EXISTS error;
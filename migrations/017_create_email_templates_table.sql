@@ -0,0 +1,93 @@
+-- Editable email templates. Every save adds a new version; exactly one version per name is
+-- active. Bodies use Tera syntax and extend the shared "layout" template. A NULL text_body
+-- means the plain-text alternative is generated from the rendered HTML.
+CREATE TABLE IF NOT EXISTS email_templates (
+    id UUID PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    version INTEGER NOT NULL CHECK (version > 0),
+    subject TEXT NOT NULL,
+    html_body TEXT NOT NULL,
+    text_body TEXT,
+    is_active BOOLEAN NOT NULL DEFAULT false,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    UNIQUE (name, version)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_email_templates_active ON email_templates(name) WHERE is_active;
+
+INSERT INTO email_templates (id, name, version, subject, html_body, is_active, created_at) VALUES
+(gen_random_uuid(), 'layout', 1, '', $tpl$<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <title>{{ subject }}</title>
+    <style>
+        body { font-family: Arial, sans-serif; line-height: 1.6; color: #333; }
+        .container { max-width: 600px; margin: 0 auto; padding: 20px; }
+        .header { background-color: #4CAF50; color: white; padding: 20px; text-align: center; }
+        .content { padding: 20px; background-color: #f9f9f9; }
+        .button { display: inline-block; background-color: #4CAF50; color: white; padding: 12px 24px; text-decoration: none; border-radius: 5px; margin: 20px 0; }
+        .footer { padding: 20px; text-align: center; color: #666; font-size: 12px; }
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="header">
+            <h1>{% block heading %}{{ app_name }}{% endblock heading %}</h1>
+        </div>
+        <div class="content">
+            {% block content %}{% endblock content %}
+        </div>
+        <div class="footer">
+            {% block footer %}{% endblock footer %}
+            <p>&copy; {{ year }} {{ app_name }}. All rights reserved.</p>
+        </div>
+    </div>
+</body>
+</html>
+$tpl$, true, NOW()),
+(gen_random_uuid(), 'verification', 1, 'Verify your {{ app_name }} account', $tpl${% extends "layout.html" %}
+{% block heading %}Welcome to {{ app_name }}!{% endblock heading %}
+{% block content %}
+<h2>Hi {{ user_name }}!</h2>
+<p>Thank you for registering with {{ app_name }}. To complete your registration, please verify your email address by clicking the button below:</p>
+<div style="text-align: center;">
+    <a href="{{ verification_link }}" class="button">Verify Email Address</a>
+</div>
+<p>If the button above doesn't work, you can also copy and paste the following link into your browser:</p>
+<p style="word-break: break-all;"><a href="{{ verification_link }}">{{ verification_link }}</a></p>
+<p>This verification link will expire in 24 hours for security reasons.</p>
+<p>If you didn't create an account with {{ app_name }}, please ignore this email.</p>
+{% endblock content %}
+$tpl$, true, NOW()),
+(gen_random_uuid(), 'welcome', 1, 'Welcome to {{ app_name }} - Email Verified!', $tpl${% extends "layout.html" %}
+{% block heading %}Welcome to {{ app_name }}!{% endblock heading %}
+{% block content %}
+<h2>Hi {{ user_name }}!</h2>
+<p>Your email has been successfully verified. Welcome to {{ app_name }}!</p>
+<p>You can now access all features of your account.</p>
+<p>If you have any questions, feel free to contact our support team.</p>
+{% endblock content %}
+$tpl$, true, NOW()),
+(gen_random_uuid(), 'receipt', 1, 'Payment receipt {{ receipt_number }}', $tpl${% extends "layout.html" %}
+{% block heading %}Payment Received{% endblock heading %}
+{% block content %}
+<h2>Hi {{ user_name }}!</h2>
+<p>Your payment of <strong>{{ amount }}</strong> towards <strong>{{ contribution_title }}</strong> has been verified.</p>
+<p>Your receipt <strong>{{ receipt_number }}</strong> is attached to this email. You can also download it from the portal at any time.</p>
+<p>Thank you for supporting the association.</p>
+{% endblock content %}
+$tpl$, true, NOW()),
+(gen_random_uuid(), 'dues_reminder', 1, '{% if offset_days < 0 %}Payment Reminder{% elif offset_days == 0 %}Payment Due Today{% else %}Payment Overdue{% endif %}: {{ contribution_title }}', $tpl${% extends "layout.html" %}
+{% block heading %}{% if offset_days < 0 %}Payment Reminder{% elif offset_days == 0 %}Payment Due Today{% else %}Payment Overdue{% endif %}{% endblock heading %}
+{% block content %}
+<h2>Hi {{ user_name }}!</h2>
+<p><strong>{{ contribution_title }}</strong> {% if offset_days < 0 %}is due in {{ days }} days, on {{ due_date }}{% elif offset_days == 0 %}is due today, {{ due_date }}{% else %}was due on {{ due_date }} and is now {{ days }} days overdue{% endif %}.</p>
+<p>{% if amount %}Your outstanding balance is {{ amount }}.{% else %}We have not yet recorded a payment from you.{% endif %}</p>
+<p><a href="{{ payments_link }}" class="button">Pay now</a></p>
+<p>If you have already paid, please ignore this email; it can take a little while for payments to be verified.</p>
+{% endblock content %}
+{% block footer %}<p>You can turn off dues reminders in your account settings.</p>{% endblock footer %}
+$tpl$, true, NOW())
+ON CONFLICT (name, version) DO NOTHING;
@@ -0,0 +1,81 @@
+CREATE TABLE IF NOT EXISTS committees (
+    id UUID PRIMARY KEY,
+    name VARCHAR(255) NOT NULL UNIQUE,
+    description TEXT,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS committee_members (
+    committee_id UUID NOT NULL REFERENCES committees(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    added_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (committee_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_committee_members_user_id ON committee_members(user_id);
+
+CREATE TYPE announcement_audience AS ENUM ('everyone', 'role', 'users', 'committee');
+
+ALTER TABLE announcements ADD COLUMN IF NOT EXISTS audience announcement_audience NOT NULL DEFAULT 'everyone';
+ALTER TABLE announcements ADD COLUMN IF NOT EXISTS audience_role user_roles;
+ALTER TABLE announcements ADD COLUMN IF NOT EXISTS audience_user_ids UUID[] NOT NULL DEFAULT '{}';
+-- A committee audience with no committee (after the committee is deleted) reaches nobody
+ALTER TABLE announcements ADD COLUMN IF NOT EXISTS committee_id UUID REFERENCES committees(id) ON DELETE SET NULL;
+ALTER TABLE announcements ADD CONSTRAINT announcements_audience_role_check
+    CHECK (audience <> 'role' OR audience_role IS NOT NULL);
+ALTER TABLE announcements ADD CONSTRAINT announcements_audience_users_check
+    CHECK (audience <> 'users' OR cardinality(audience_user_ids) > 0);
+
+CREATE INDEX IF NOT EXISTS idx_announcements_committee_id ON announcements(committee_id);
+
+-- Whether a user is in an announcement's audience. Used both for who can see a post and who
+-- it is emailed to.
+CREATE OR REPLACE FUNCTION in_announcement_audience(p_announcement_id UUID, p_user_id UUID)
+RETURNS BOOLEAN AS $$
+    SELECT CASE a.audience
+        WHEN 'everyone' THEN true
+        WHEN 'role' THEN u.user_role = a.audience_role
+        WHEN 'users' THEN u.id = ANY(a.audience_user_ids)
+        WHEN 'committee' THEN EXISTS (
+            SELECT 1 FROM committee_members m
+            WHERE m.committee_id = a.committee_id AND m.user_id = u.id
+        )
+    END
+    FROM announcements a, users u
+    WHERE a.id = p_announcement_id AND u.id = p_user_id
+$$ LANGUAGE SQL STABLE;
+
+-- Mailing lists a user has opted out of, e.g. 'announcements'
+CREATE TABLE IF NOT EXISTS email_unsubscribes (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    list VARCHAR(50) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (user_id, list)
+);
+
+-- One row per member an announcement was emailed to; delivery status is on the outbox row.
+CREATE TABLE IF NOT EXISTS announcement_deliveries (
+    id UUID PRIMARY KEY,
+    announcement_id UUID NOT NULL REFERENCES announcements(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    email_id UUID REFERENCES email_outbox(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    UNIQUE (announcement_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_announcement_deliveries_user_id ON announcement_deliveries(user_id);
+
+INSERT INTO email_templates (id, name, version, subject, html_body, is_active, created_at) VALUES
+(gen_random_uuid(), 'announcement', 1, '{{ title }}', $tpl${% extends "layout.html" %}
+{% block heading %}{{ title }}{% endblock heading %}
+{% block content %}
+<h2>Hi {{ user_name }}!</h2>
+{% if body %}<p>{{ body | escape | linebreaksbr | safe }}</p>{% endif %}
+<p style="color: #666;">Posted by {{ posted_by }}</p>
+<p><a href="{{ announcement_link }}" class="button">View in the portal</a></p>
+{% endblock content %}
+{% block footer %}<p>You are receiving this because you are a member of {{ app_name }}. <a href="{{ unsubscribe_link }}">Unsubscribe from announcement emails</a>.</p>{% endblock footer %}
+$tpl$, true, NOW())
+ON CONFLICT (name, version) DO NOTHING;
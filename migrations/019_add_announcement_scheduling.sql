@@ -0,0 +1,12 @@
+-- Announcements are live from publish_at until expires_at, unless still a draft
+ALTER TABLE announcements ADD COLUMN IF NOT EXISTS publish_at TIMESTAMP WITH TIME ZONE;
+UPDATE announcements SET publish_at = created_at WHERE publish_at IS NULL;
+ALTER TABLE announcements ALTER COLUMN publish_at SET NOT NULL;
+ALTER TABLE announcements ADD COLUMN IF NOT EXISTS expires_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE announcements ADD COLUMN IF NOT EXISTS is_draft BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE announcements ADD COLUMN IF NOT EXISTS is_pinned BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE announcements ADD CONSTRAINT announcements_expiry_check
+    CHECK (expires_at IS NULL OR expires_at > publish_at);
+
+CREATE INDEX IF NOT EXISTS idx_announcements_feed
+    ON announcements(is_pinned DESC, publish_at DESC) WHERE NOT is_draft;
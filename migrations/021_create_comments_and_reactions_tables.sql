@@ -0,0 +1,37 @@
+-- Comments and reactions belong to exactly one announcement or photo
+CREATE TABLE IF NOT EXISTS comments (
+    id UUID PRIMARY KEY,
+    announcement_id UUID REFERENCES announcements(id) ON DELETE CASCADE,
+    photo_id UUID REFERENCES photos(id) ON DELETE CASCADE,
+    parent_id UUID REFERENCES comments(id) ON DELETE CASCADE,
+    author_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    edited_at TIMESTAMP WITH TIME ZONE,
+    -- Deleted comments stay as placeholders so their replies keep their place in the thread
+    deleted_at TIMESTAMP WITH TIME ZONE,
+    hidden_at TIMESTAMP WITH TIME ZONE,
+    hidden_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    hidden_reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    CONSTRAINT comments_target_check CHECK (num_nonnulls(announcement_id, photo_id) = 1)
+);
+
+CREATE INDEX IF NOT EXISTS idx_comments_announcement_id ON comments(announcement_id);
+CREATE INDEX IF NOT EXISTS idx_comments_photo_id ON comments(photo_id);
+CREATE INDEX IF NOT EXISTS idx_comments_parent_id ON comments(parent_id);
+
+CREATE TABLE IF NOT EXISTS reactions (
+    id UUID PRIMARY KEY,
+    announcement_id UUID REFERENCES announcements(id) ON DELETE CASCADE,
+    photo_id UUID REFERENCES photos(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    emoji VARCHAR(32) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    CONSTRAINT reactions_target_check CHECK (num_nonnulls(announcement_id, photo_id) = 1)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_reactions_announcement_user_emoji
+    ON reactions(announcement_id, user_id, emoji) WHERE announcement_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_reactions_photo_user_emoji
+    ON reactions(photo_id, user_id, emoji) WHERE photo_id IS NOT NULL;
@@ -0,0 +1,37 @@
+-- Bodies are Markdown; body_html is the sanitised rendering, refreshed whenever the body changes.
+-- Existing plain-text bodies are escaped as a single paragraph.
+ALTER TABLE announcements ADD COLUMN IF NOT EXISTS body_html TEXT;
+UPDATE announcements
+SET body_html = '<p>' || replace(replace(replace(replace(body, '&', '&amp;'), '<', '&lt;'), '>', '&gt;'), '"', '&quot;') || '</p>'
+WHERE body IS NOT NULL AND body_html IS NULL;
+
+CREATE TABLE IF NOT EXISTS announcement_attachments (
+    id UUID PRIMARY KEY,
+    announcement_id UUID NOT NULL REFERENCES announcements(id) ON DELETE CASCADE,
+    filename VARCHAR(255) NOT NULL,
+    content_type VARCHAR(100) NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    storage_key TEXT NOT NULL,
+    uploaded_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_announcement_attachments_announcement_id
+    ON announcement_attachments(announcement_id);
+
+-- The announcement email shows the rendered Markdown
+UPDATE email_templates SET is_active = false WHERE name = 'announcement';
+
+INSERT INTO email_templates (id, name, version, subject, html_body, is_active, created_at) VALUES
+(gen_random_uuid(), 'announcement', 2, '{{ title }}', $tpl${% extends "layout.html" %}
+{% block heading %}{{ title }}{% endblock heading %}
+{% block content %}
+<h2>Hi {{ user_name }}!</h2>
+{% if body_html %}{{ body_html | safe }}{% endif %}
+{% if attachment_count %}<p>This announcement has {{ attachment_count }} attachment{{ attachment_count | pluralize }} in the portal.</p>{% endif %}
+<p style="color: #666;">Posted by {{ posted_by }}</p>
+<p><a href="{{ announcement_link }}" class="button">View in the portal</a></p>
+{% endblock content %}
+{% block footer %}<p>You are receiving this because you are a member of {{ app_name }}. <a href="{{ unsubscribe_link }}">Unsubscribe from announcement emails</a>.</p>{% endblock footer %}
+$tpl$, true, NOW())
+ON CONFLICT (name, version) DO NOTHING;
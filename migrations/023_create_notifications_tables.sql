@@ -0,0 +1,45 @@
+CREATE TYPE notification_kind AS ENUM (
+    'payment_verified',
+    'payment_rejected',
+    'announcement',
+    'event_reminder',
+    'photo_tag',
+    'role_change'
+);
+
+CREATE TABLE IF NOT EXISTS notifications (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind notification_kind NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT,
+    -- Portal path the notification opens, e.g. /announcements/{id}
+    link TEXT,
+    -- The payment, announcement, event or photo the notification is about
+    subject_id UUID,
+    read_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_user_id_created_at ON notifications(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_notifications_unread ON notifications(user_id) WHERE read_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_notifications_subject ON notifications(kind, subject_id);
+
+-- Kinds a user has turned off. Every kind is on unless a row says otherwise.
+CREATE TABLE IF NOT EXISTS notification_preferences (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind notification_kind NOT NULL,
+    enabled BOOLEAN NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (user_id, kind)
+);
+
+CREATE TABLE IF NOT EXISTS photo_tags (
+    photo_id UUID NOT NULL REFERENCES photos(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    tagged_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (photo_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_photo_tags_user_id ON photo_tags(user_id);
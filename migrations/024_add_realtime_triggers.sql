@@ -0,0 +1,37 @@
+-- Real-time push: every server instance LISTENs on portal_events and forwards matching
+-- events to its connected clients. Payloads only carry ids; instances load the rows.
+
+CREATE OR REPLACE FUNCTION push_notification_created()
+RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('portal_events', json_build_object(
+        'type', 'notification',
+        'id', NEW.id,
+        'user_id', NEW.user_id
+    )::text);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER notifications_push
+    AFTER INSERT ON notifications
+    FOR EACH ROW EXECUTE FUNCTION push_notification_created();
+
+CREATE OR REPLACE FUNCTION push_payment_status()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'UPDATE' AND OLD.status = NEW.status THEN
+        RETURN NEW;
+    END IF;
+    PERFORM pg_notify('portal_events', json_build_object(
+        'type', 'payment',
+        'id', NEW.id,
+        'user_id', NEW.user_id
+    )::text);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER payments_push
+    AFTER INSERT OR UPDATE OF status ON payments
+    FOR EACH ROW EXECUTE FUNCTION push_payment_status();
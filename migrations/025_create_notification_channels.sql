@@ -0,0 +1,33 @@
+CREATE TYPE notification_channel AS ENUM ('email', 'sms', 'messaging');
+
+-- Channels beyond the in-app centre a user has opted into. Every channel is off unless a
+-- row says otherwise; SMS and messaging go to users.phone.
+CREATE TABLE IF NOT EXISTS notification_channel_preferences (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    channel notification_channel NOT NULL,
+    enabled BOOLEAN NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (user_id, channel)
+);
+
+-- One row per notification sent over a channel, so a retried job never sends it twice.
+CREATE TABLE IF NOT EXISTS notification_deliveries (
+    notification_id UUID NOT NULL REFERENCES notifications(id) ON DELETE CASCADE,
+    channel notification_channel NOT NULL,
+    -- The provider's message id, or the outbox email id for email
+    provider_reference TEXT,
+    sent_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (notification_id, channel)
+);
+
+INSERT INTO email_templates (id, name, version, subject, html_body, is_active, created_at) VALUES
+(gen_random_uuid(), 'notification', 1, '{{ title }}', $tpl${% extends "layout.html" %}
+{% block heading %}{{ title }}{% endblock heading %}
+{% block content %}
+<h2>Hi {{ user_name }}!</h2>
+{% if body %}<p>{{ body }}</p>{% endif %}
+{% if link %}<p><a href="{{ link }}" class="button">Open in the portal</a></p>{% endif %}
+{% endblock content %}
+{% block footer %}<p>You are receiving this because you turned on email notifications. You can turn them off in your notification settings.</p>{% endblock footer %}
+$tpl$, true, NOW())
+ON CONFLICT (name, version) DO NOTHING;
@@ -0,0 +1,25 @@
+-- Deleting hides a row; only an admin purge removes it for good
+ALTER TABLE users ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+-- Deleting signs the member out for good; this keeps whether they were active so a restore
+-- puts the account back as it was
+ALTER TABLE users ADD COLUMN IF NOT EXISTS active_before_delete BOOLEAN;
+ALTER TABLE contributions ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE payments ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE announcements ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE photos ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_users_deleted_at ON users(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_contributions_deleted_at ON contributions(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_payments_deleted_at ON payments(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_announcements_deleted_at ON announcements(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_photos_deleted_at ON photos(deleted_at) WHERE deleted_at IS NOT NULL;
+
+-- Payment history outlives its member and contribution: purging either is refused while
+-- payments still reference it
+ALTER TABLE payments DROP CONSTRAINT IF EXISTS payments_user_id_fkey;
+ALTER TABLE payments
+    ADD CONSTRAINT payments_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE RESTRICT;
+ALTER TABLE payments DROP CONSTRAINT IF EXISTS payments_contribution_id_fkey;
+ALTER TABLE payments
+    ADD CONSTRAINT payments_contribution_id_fkey
+    FOREIGN KEY (contribution_id) REFERENCES contributions(id) ON DELETE RESTRICT;
@@ -0,0 +1,691 @@
+use crate::models::{
+    announcement::AnnouncementError, announcement_attachment::AnnouncementAttachmentError,
+    announcement_delivery::AnnouncementDeliveryError, announcement_read::AnnouncementReadError,
+    audit_log::AuditLogError, bank_statement::BankStatementError, budget::BudgetError,
+    campaign::CampaignError, comment::CommentError, committee::CommitteeError,
+    contribution::ContributionError, dues_reminder::DuesReminderError,
+    email_outbox::OutboxEmailError, email_template::EmailTemplateError,
+    email_unsubscribe::EmailUnsubscribeError, exchange_rate::ExchangeRateError,
+    expense::ExpenseError, job::JobError, notification::NotificationError,
+    notification_channel::NotificationChannelError, payment::PaymentError,
+    payment_reversal::PaymentReversalError, photo::PhotoError, photo_tag::PhotoTagError,
+    reaction::ReactionError, receipt::ReceiptError, report::ReportError, user::UserError,
+};
+use crate::services::{
+    announcement_broadcast::AnnouncementBroadcastError, email::EmailError,
+    notification_channels::ChannelError, payment_gateway::PaymentGatewayError,
+    reconciliation::StatementParseError, report_export::ExportError, storage::StorageError,
+};
+use crate::utils::helpers::ApiResponse;
+use crate::utils::validation::FieldError;
+use actix_web::error::{JsonPayloadError, PathError, QueryPayloadError};
+use actix_web::http::StatusCode;
+use actix_web::{HttpRequest, HttpResponse, ResponseError};
+use thiserror::Error;
+use tracing::error;
+
+/// Every error a handler can return. Each maps to an HTTP status and a stable, machine-readable
+/// `code` that clients can match on; the message is for people and may change.
+#[derive(Debug, Error)]
+pub enum AppError {
+    #[error("{message}")]
+    BadRequest { code: &'static str, message: String },
+    #[error("{message}")]
+    Unauthorized { code: &'static str, message: String },
+    #[error("{message}")]
+    Forbidden { code: &'static str, message: String },
+    #[error("{message}")]
+    NotFound { code: &'static str, message: String },
+    #[error("{message}")]
+    Conflict { code: &'static str, message: String },
+    #[error("{0}")]
+    PayloadTooLarge(String),
+    #[error("{0}")]
+    UnsupportedMediaType(String),
+    #[error("{message}")]
+    Unprocessable { code: &'static str, message: String },
+    /// A request that parsed but broke its validation rules; every failing field is listed.
+    #[error("Validation failed")]
+    Validation(Vec<FieldError>),
+    #[error("{message}")]
+    BadGateway { code: &'static str, message: String },
+    /// Details are logged and never sent to the client.
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+impl AppError {
+    pub fn bad_request(code: &'static str, message: impl Into<String>) -> Self {
+        Self::BadRequest {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn unauthorized(code: &'static str, message: impl Into<String>) -> Self {
+        Self::Unauthorized {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// The caller's role doesn't allow the action.
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::Forbidden {
+            code: "forbidden",
+            message: message.into(),
+        }
+    }
+
+    pub fn not_found(code: &'static str, message: impl Into<String>) -> Self {
+        Self::NotFound {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn conflict(code: &'static str, message: impl Into<String>) -> Self {
+        Self::Conflict {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn unprocessable(code: &'static str, message: impl Into<String>) -> Self {
+        Self::Unprocessable {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn internal(detail: impl std::fmt::Display) -> Self {
+        Self::Internal(detail.to_string())
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::BadRequest { code, .. }
+            | Self::Unauthorized { code, .. }
+            | Self::Forbidden { code, .. }
+            | Self::NotFound { code, .. }
+            | Self::Conflict { code, .. }
+            | Self::Unprocessable { code, .. }
+            | Self::BadGateway { code, .. } => code,
+            Self::PayloadTooLarge(_) => "payload_too_large",
+            Self::UnsupportedMediaType(_) => "unsupported_media_type",
+            Self::Validation(_) => "validation_failed",
+            Self::Internal(_) => "internal_error",
+        }
+    }
+}
+
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::BadRequest { .. } => StatusCode::BAD_REQUEST,
+            Self::Unauthorized { .. } => StatusCode::UNAUTHORIZED,
+            Self::Forbidden { .. } => StatusCode::FORBIDDEN,
+            Self::NotFound { .. } => StatusCode::NOT_FOUND,
+            Self::Conflict { .. } => StatusCode::CONFLICT,
+            Self::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Self::Unprocessable { .. } | Self::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::BadGateway { .. } => StatusCode::BAD_GATEWAY,
+            Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let message = match self {
+            Self::Internal(detail) => {
+                error!("{}", detail);
+                "Internal server error".to_string()
+            }
+            e => e.to_string(),
+        };
+        let mut response = ApiResponse::<()>::error(self.code(), message);
+        if let Self::Validation(errors) = self {
+            response = response.with_errors(errors.clone());
+        }
+        response.to_response(self.status_code())
+    }
+}
+
+/// Error handler for `web::JsonConfig`, so malformed bodies get the same error shape.
+pub fn json_error_handler(err: JsonPayloadError, _: &HttpRequest) -> actix_web::Error {
+    match err {
+        JsonPayloadError::OverflowKnownLength { .. } | JsonPayloadError::Overflow { .. } => {
+            AppError::PayloadTooLarge(err.to_string())
+        }
+        JsonPayloadError::ContentType => {
+            AppError::UnsupportedMediaType("Content-Type must be application/json".to_string())
+        }
+        JsonPayloadError::Deserialize(e) if e.is_data() => {
+            AppError::bad_request("invalid_body", e.to_string())
+        }
+        e => AppError::bad_request("invalid_json", e.to_string()),
+    }
+    .into()
+}
+
+/// Error handler for `web::QueryConfig`.
+pub fn query_error_handler(err: QueryPayloadError, _: &HttpRequest) -> actix_web::Error {
+    AppError::bad_request("invalid_query", err.to_string()).into()
+}
+
+/// Error handler for `web::PathConfig`.
+pub fn path_error_handler(err: PathError, _: &HttpRequest) -> actix_web::Error {
+    AppError::bad_request("invalid_path", err.to_string()).into()
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        Self::internal(format!("Database error: {}", e))
+    }
+}
+
+impl From<UserError> for AppError {
+    fn from(e: UserError) -> Self {
+        match e {
+            UserError::NotFound { .. } | UserError::NotFoundByEmail { .. } => {
+                Self::not_found("user_not_found", e.to_string())
+            }
+            UserError::EmailAlreadyExists { .. } => Self::conflict("email_taken", e.to_string()),
+            UserError::InvalidVerificationCode => {
+                Self::bad_request("invalid_verification_code", e.to_string())
+            }
+            UserError::VerificationCodeExpired => {
+                Self::bad_request("verification_code_expired", e.to_string())
+            }
+            UserError::HasPayments { .. } => Self::conflict("user_has_payments", e.to_string()),
+            UserError::HasContributionPayments { .. } => {
+                Self::conflict("user_contributions_have_payments", e.to_string())
+            }
+            UserError::InUse { .. } => Self::conflict("user_in_use", e.to_string()),
+            UserError::Database(_) | UserError::PasswordHash => Self::internal(e),
+        }
+    }
+}
+
+impl From<ContributionError> for AppError {
+    fn from(e: ContributionError) -> Self {
+        match e {
+            ContributionError::NotFound { .. } => {
+                Self::not_found("contribution_not_found", e.to_string())
+            }
+            ContributionError::NoUpdateFields => {
+                Self::bad_request("no_update_fields", e.to_string())
+            }
+            ContributionError::HasPayments { .. } => {
+                Self::conflict("contribution_has_payments", e.to_string())
+            }
+            ContributionError::Database(_) => Self::internal(e),
+        }
+    }
+}
+
+impl From<PaymentError> for AppError {
+    fn from(e: PaymentError) -> Self {
+        match e {
+            PaymentError::NotFound { .. } => Self::not_found("payment_not_found", e.to_string()),
+            PaymentError::NoUpdateFields => Self::bad_request("no_update_fields", e.to_string()),
+            PaymentError::Immutable { .. } => Self::conflict("payment_immutable", e.to_string()),
+            PaymentError::HasReversals { .. } => {
+                Self::conflict("payment_has_reversals", e.to_string())
+            }
+            PaymentError::DuplicateGatewayEvent { .. } => {
+                Self::conflict("duplicate_gateway_event", e.to_string())
+            }
+            PaymentError::ExchangeRate(e) => e.into(),
+            PaymentError::Database(_) => Self::internal(e),
+        }
+    }
+}
+
+impl From<PaymentReversalError> for AppError {
+    fn from(e: PaymentReversalError) -> Self {
+        match e {
+            PaymentReversalError::NotFound { .. } => {
+                Self::not_found("reversal_not_found", e.to_string())
+            }
+            PaymentReversalError::PaymentNotFound { .. } => {
+                Self::not_found("payment_not_found", e.to_string())
+            }
+            PaymentReversalError::PaymentNotVerified { .. } => {
+                Self::conflict("payment_not_verified", e.to_string())
+            }
+            PaymentReversalError::NotAnOriginalPayment { .. } => {
+                Self::conflict("payment_not_reversible", e.to_string())
+            }
+            PaymentReversalError::ExceedsRemaining { .. } => {
+                Self::bad_request("amount_exceeds_refundable", e.to_string())
+            }
+            PaymentReversalError::AlreadyReviewed { .. } => {
+                Self::conflict("reversal_already_reviewed", e.to_string())
+            }
+            PaymentReversalError::SelfApproval => Self::forbidden(e.to_string()),
+            PaymentReversalError::Database(_) => Self::internal(e),
+        }
+    }
+}
+
+impl From<ExchangeRateError> for AppError {
+    fn from(e: ExchangeRateError) -> Self {
+        match e {
+            ExchangeRateError::NotFound { .. } => {
+                Self::not_found("exchange_rate_not_found", e.to_string())
+            }
+            ExchangeRateError::Missing { .. } => {
+                Self::unprocessable("exchange_rate_missing", e.to_string())
+            }
+            ExchangeRateError::InvalidCurrency(_) => {
+                Self::bad_request("invalid_currency", e.to_string())
+            }
+            ExchangeRateError::Database(_) => Self::internal(e),
+        }
+    }
+}
+
+impl From<AnnouncementError> for AppError {
+    fn from(e: AnnouncementError) -> Self {
+        match e {
+            AnnouncementError::NotFound { .. } => {
+                Self::not_found("announcement_not_found", e.to_string())
+            }
+            AnnouncementError::NoUpdateFields => {
+                Self::bad_request("no_update_fields", e.to_string())
+            }
+            AnnouncementError::InvalidSchedule => {
+                Self::bad_request("invalid_schedule", e.to_string())
+            }
+            AnnouncementError::Database(_) => Self::internal(e),
+        }
+    }
+}
+
+impl From<AnnouncementAttachmentError> for AppError {
+    fn from(e: AnnouncementAttachmentError) -> Self {
+        match e {
+            AnnouncementAttachmentError::NotFound { .. } => {
+                Self::not_found("attachment_not_found", e.to_string())
+            }
+            AnnouncementAttachmentError::Database(_) => Self::internal(e),
+        }
+    }
+}
+
+impl From<PhotoError> for AppError {
+    fn from(e: PhotoError) -> Self {
+        match e {
+            PhotoError::NotFound { .. } => Self::not_found("photo_not_found", e.to_string()),
+            PhotoError::NoUpdateFields => Self::bad_request("no_update_fields", e.to_string()),
+            PhotoError::Database(_) => Self::internal(e),
+        }
+    }
+}
+
+impl From<PhotoTagError> for AppError {
+    fn from(e: PhotoTagError) -> Self {
+        match e {
+            PhotoTagError::NotFound { .. } => Self::not_found("photo_tag_not_found", e.to_string()),
+            PhotoTagError::UserNotFound { .. } => {
+                Self::bad_request("tagged_user_not_found", e.to_string())
+            }
+            PhotoTagError::Database(_) => Self::internal(e),
+        }
+    }
+}
+
+impl From<CommentError> for AppError {
+    fn from(e: CommentError) -> Self {
+        match e {
+            CommentError::NotFound { .. } => Self::not_found("comment_not_found", e.to_string()),
+            CommentError::InvalidParent => Self::bad_request("invalid_parent", e.to_string()),
+            CommentError::Deleted { .. } => Self::conflict("comment_deleted", e.to_string()),
+            CommentError::Database(_) => Self::internal(e),
+        }
+    }
+}
+
+impl From<ReactionError> for AppError {
+    fn from(e: ReactionError) -> Self {
+        match e {
+            ReactionError::InvalidEmoji => Self::bad_request("invalid_emoji", e.to_string()),
+            ReactionError::Database(_) => Self::internal(e),
+        }
+    }
+}
+
+impl From<CommitteeError> for AppError {
+    fn from(e: CommitteeError) -> Self {
+        match e {
+            CommitteeError::NotFound { .. } => {
+                Self::not_found("committee_not_found", e.to_string())
+            }
+            CommitteeError::NameTaken { .. } => {
+                Self::conflict("committee_name_taken", e.to_string())
+            }
+            CommitteeError::MemberNotFound { .. } => {
+                Self::not_found("committee_member_not_found", e.to_string())
+            }
+            CommitteeError::Database(_) => Self::internal(e),
+        }
+    }
+}
+
+impl From<CampaignError> for AppError {
+    fn from(e: CampaignError) -> Self {
+        match e {
+            CampaignError::NotFound { .. } => Self::not_found("campaign_not_found", e.to_string()),
+            CampaignError::HasDonations { .. } => {
+                Self::conflict("campaign_has_donations", e.to_string())
+            }
+            CampaignError::NoUpdateFields => Self::bad_request("no_update_fields", e.to_string()),
+            CampaignError::Database(_) => Self::internal(e),
+        }
+    }
+}
+
+impl From<ExpenseError> for AppError {
+    fn from(e: ExpenseError) -> Self {
+        match e {
+            ExpenseError::NotFound { .. } => Self::not_found("expense_not_found", e.to_string()),
+            ExpenseError::CategoryNotFound { .. } => {
+                Self::not_found("expense_category_not_found", e.to_string())
+            }
+            ExpenseError::CategoryAlreadyExists { .. } => {
+                Self::conflict("expense_category_exists", e.to_string())
+            }
+            ExpenseError::AlreadyReviewed { .. } => {
+                Self::conflict("expense_already_reviewed", e.to_string())
+            }
+            ExpenseError::SelfApproval => Self::forbidden(e.to_string()),
+            ExpenseError::NoUpdateFields => Self::bad_request("no_update_fields", e.to_string()),
+            ExpenseError::Database(_) => Self::internal(e),
+        }
+    }
+}
+
+impl From<BudgetError> for AppError {
+    fn from(e: BudgetError) -> Self {
+        match e {
+            BudgetError::NotFound { .. } => Self::not_found("budget_not_found", e.to_string()),
+            BudgetError::CategoryNotFound { .. } => {
+                Self::not_found("expense_category_not_found", e.to_string())
+            }
+            BudgetError::Database(_) => Self::internal(e),
+        }
+    }
+}
+
+impl From<BankStatementError> for AppError {
+    fn from(e: BankStatementError) -> Self {
+        match e {
+            BankStatementError::LineNotFound { .. } => {
+                Self::not_found("statement_line_not_found", e.to_string())
+            }
+            BankStatementError::LineAlreadyReconciled { .. } => {
+                Self::conflict("statement_line_reconciled", e.to_string())
+            }
+            BankStatementError::PaymentNotPending { .. } => {
+                Self::conflict("payment_not_pending", e.to_string())
+            }
+            BankStatementError::Database(_) => Self::internal(e),
+        }
+    }
+}
+
+impl From<StatementParseError> for AppError {
+    fn from(e: StatementParseError) -> Self {
+        Self::bad_request("invalid_statement", e.to_string())
+    }
+}
+
+impl From<ReceiptError> for AppError {
+    fn from(e: ReceiptError) -> Self {
+        match e {
+            ReceiptError::NotFound { .. } => Self::not_found("receipt_not_found", e.to_string()),
+            ReceiptError::PaymentNotVerified { .. } => {
+                Self::conflict("payment_not_verified", e.to_string())
+            }
+            ReceiptError::Database(_) | ReceiptError::Render(_) | ReceiptError::Email(_) => {
+                Self::internal(e)
+            }
+        }
+    }
+}
+
+impl From<ReportError> for AppError {
+    fn from(e: ReportError) -> Self {
+        match e {
+            ReportError::InvalidPeriod => Self::bad_request("invalid_period", e.to_string()),
+            ReportError::Database(_) => Self::internal(e),
+        }
+    }
+}
+
+impl From<JobError> for AppError {
+    fn from(e: JobError) -> Self {
+        match e {
+            JobError::NotFound { .. } => Self::not_found("job_not_found", e.to_string()),
+            JobError::NotRetryable { .. } => Self::conflict("job_not_retryable", e.to_string()),
+            JobError::LockLost { .. } => Self::conflict("job_lock_lost", e.to_string()),
+            JobError::Database(_) => Self::internal(e),
+        }
+    }
+}
+
+impl From<OutboxEmailError> for AppError {
+    fn from(e: OutboxEmailError) -> Self {
+        match e {
+            OutboxEmailError::NotFound { .. } => Self::not_found("email_not_found", e.to_string()),
+            OutboxEmailError::NotRetryable { .. } => {
+                Self::conflict("email_not_retryable", e.to_string())
+            }
+            OutboxEmailError::Database(_) => Self::internal(e),
+        }
+    }
+}
+
+impl From<EmailTemplateError> for AppError {
+    fn from(e: EmailTemplateError) -> Self {
+        match e {
+            EmailTemplateError::NotFound { .. } => {
+                Self::not_found("email_template_not_found", e.to_string())
+            }
+            EmailTemplateError::VersionNotFound { .. } => {
+                Self::not_found("email_template_version_not_found", e.to_string())
+            }
+            EmailTemplateError::Database(_) => Self::internal(e),
+        }
+    }
+}
+
+impl From<EmailError> for AppError {
+    fn from(e: EmailError) -> Self {
+        match e {
+            EmailError::Template(_) => Self::unprocessable("template_render_failed", e.to_string()),
+            EmailError::TemplateStore(e) => e.into(),
+            _ => Self::internal(e),
+        }
+    }
+}
+
+impl From<NotificationError> for AppError {
+    fn from(e: NotificationError) -> Self {
+        match e {
+            NotificationError::NotFound { .. } => {
+                Self::not_found("notification_not_found", e.to_string())
+            }
+            NotificationError::Database(_) => Self::internal(e),
+        }
+    }
+}
+
+impl From<ChannelError> for AppError {
+    fn from(e: ChannelError) -> Self {
+        match e {
+            ChannelError::NoPhone { .. } => Self::unprocessable("no_phone_number", e.to_string()),
+            ChannelError::Request(_) | ChannelError::Rejected(_) => Self::BadGateway {
+                code: "messaging_provider_error",
+                message: e.to_string(),
+            },
+            _ => Self::internal(e),
+        }
+    }
+}
+
+impl From<PaymentGatewayError> for AppError {
+    fn from(e: PaymentGatewayError) -> Self {
+        match e {
+            PaymentGatewayError::InvalidAmount(_) => {
+                Self::bad_request("invalid_amount", e.to_string())
+            }
+            PaymentGatewayError::Config(_) => Self::internal(e),
+            PaymentGatewayError::Request(_) | PaymentGatewayError::Rejected(_) => {
+                Self::BadGateway {
+                    code: "payment_gateway_error",
+                    message: e.to_string(),
+                }
+            }
+        }
+    }
+}
+
+impl From<StorageError> for AppError {
+    fn from(e: StorageError) -> Self {
+        match e {
+            StorageError::UnsupportedType(_) => Self::UnsupportedMediaType(e.to_string()),
+            StorageError::InvalidKey(_) | StorageError::Io(_) => Self::internal(e),
+        }
+    }
+}
+
+impl From<AnnouncementBroadcastError> for AppError {
+    fn from(e: AnnouncementBroadcastError) -> Self {
+        match e {
+            AnnouncementBroadcastError::Announcement(e) => e.into(),
+            AnnouncementBroadcastError::Attachment(e) => e.into(),
+            AnnouncementBroadcastError::User(e) => e.into(),
+            e => Self::internal(e),
+        }
+    }
+}
+
+impl From<ExportError> for AppError {
+    fn from(e: ExportError) -> Self {
+        Self::internal(e)
+    }
+}
+
+/// Errors from stores that can only fail on the database.
+macro_rules! database_errors {
+    ($($error:ty),* $(,)?) => {
+        $(
+            impl From<$error> for AppError {
+                fn from(e: $error) -> Self {
+                    Self::internal(e)
+                }
+            }
+        )*
+    };
+}
+
+database_errors!(
+    AnnouncementDeliveryError,
+    AnnouncementReadError,
+    AuditLogError,
+    DuesReminderError,
+    EmailUnsubscribeError,
+    NotificationChannelError,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::body::MessageBody;
+    use serde_json::{Value, json};
+    use uuid::Uuid;
+
+    fn body(error: &AppError) -> Value {
+        let bytes = error.error_response().into_body().try_into_bytes().unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn variants_map_to_status_and_code() {
+        let cases = [
+            (
+                AppError::bad_request("invalid_body", "x"),
+                400,
+                "invalid_body",
+            ),
+            (
+                AppError::unauthorized("invalid_token", "x"),
+                401,
+                "invalid_token",
+            ),
+            (AppError::forbidden("x"), 403, "forbidden"),
+            (
+                AppError::not_found("user_not_found", "x"),
+                404,
+                "user_not_found",
+            ),
+            (AppError::conflict("email_taken", "x"), 409, "email_taken"),
+            (
+                AppError::PayloadTooLarge("x".into()),
+                413,
+                "payload_too_large",
+            ),
+            (
+                AppError::UnsupportedMediaType("x".into()),
+                415,
+                "unsupported_media_type",
+            ),
+            (
+                AppError::unprocessable("invalid_amount", "x"),
+                422,
+                "invalid_amount",
+            ),
+            (AppError::internal("x"), 500, "internal_error"),
+        ];
+
+        for (error, status, code) in cases {
+            assert_eq!(error.status_code().as_u16(), status, "{}", code);
+            assert_eq!(error.code(), code);
+        }
+    }
+
+    #[test]
+    fn responses_carry_the_code_and_message() {
+        let error = AppError::conflict("payment_immutable", "Payment is verified");
+
+        assert_eq!(
+            body(&error),
+            json!({ "success": false, "error": "Payment is verified", "code": "payment_immutable" })
+        );
+    }
+
+    #[test]
+    fn internal_details_are_not_sent_to_clients() {
+        let error = AppError::internal("Database error: relation \"users\" does not exist");
+
+        assert_eq!(body(&error)["error"], "Internal server error");
+    }
+
+    #[test]
+    fn model_errors_keep_their_meaning() {
+        let id = Uuid::new_v4();
+
+        let error = AppError::from(PaymentError::NotFound { id });
+        assert_eq!(error.status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(error.code(), "payment_not_found");
+
+        let error = AppError::from(PaymentError::HasReversals { id });
+        assert_eq!(error.status_code(), StatusCode::CONFLICT);
+        assert_eq!(error.code(), "payment_has_reversals");
+
+        let error = AppError::from(PaymentError::Database(sqlx::Error::RowNotFound));
+        assert_eq!(error.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
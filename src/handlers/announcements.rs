@@ -1,222 +1,769 @@
+use crate::errors::AppError;
+use crate::handlers::comments::check_target;
 use crate::models::announcement::{
-    Announcement, AnnouncementError, CreateAnnouncement, UpdateAnnouncement,
+    Announcement, AnnouncementAudience, AnnouncementDetail, AnnouncementError, AnnouncementFilter,
+    AnnouncementTarget, CreateAnnouncement, UpdateAnnouncement,
 };
+use crate::models::announcement_attachment::{
+    AnnouncementAttachment, AnnouncementAttachmentError, CreateAnnouncementAttachment,
+};
+use crate::models::announcement_delivery::AnnouncementDelivery;
+use crate::models::announcement_read::{AnnouncementRead, MarkedRead, UnreadCount};
+use crate::models::comment::ContentTarget;
+use crate::models::committee::Committee;
 use crate::models::user::UserRole;
-use crate::requests::announcement::{CreateAnnouncementRequest, UpdateAnnouncementRequest};
+use crate::requests::announcement::{
+    AnnouncementAudienceRequest, AnnouncementQuery, AttachmentUploadQuery,
+    CreateAnnouncementRequest, UpdateAnnouncementRequest,
+};
+use crate::services::audit::{AuditContext, snapshot};
+use crate::services::jobs::{self, JobKind};
+use crate::services::storage::{ATTACHMENT_TYPES, StorageError, StorageService};
+use crate::utils::pagination::ListQuery;
+use crate::utils::validation::{FieldError, ValidJson, ValidQuery, Validate};
 use crate::{
     database::connection::DbPool, middleware::auth::AuthenticatedUser, utils::helpers::ApiResponse,
 };
-use actix_web::{HttpResponse, Result, web};
-use chrono::{DateTime, TimeZone, Utc};
-use tracing::{error, info};
+use actix_web::http::header::{self, ContentDisposition};
+use actix_web::{HttpRequest, HttpResponse, web};
+use chrono::Utc;
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
+pub fn can_see_all_announcements(user: &AuthenticatedUser) -> bool {
+    matches!(user.user_role, UserRole::Admin | UserRole::SuperAdmin)
+}
+
+fn can_manage_trash(user: &AuthenticatedUser) -> bool {
+    matches!(user.user_role, UserRole::Admin | UserRole::SuperAdmin)
+}
+
+fn pin_denied() -> AppError {
+    AppError::forbidden("Only admins can pin announcements")
+}
+
+fn audience_denied() -> AppError {
+    AppError::forbidden("Only admins can post to a narrower audience than everyone")
+}
+
+fn email_denied() -> AppError {
+    AppError::forbidden("Only admins can email announcements")
+}
+
+/// Turns validated audience fields into a target, checking that the committee exists.
+async fn resolve_target(
+    pool: &DbPool,
+    request: &AnnouncementAudienceRequest,
+) -> Result<AnnouncementTarget, AppError> {
+    // The request's `Validate` rules have already checked the fields, so this only
+    // guards against being called with a body that skipped them
+    request.check()?;
+    let audience = request
+        .audience
+        .parse::<AnnouncementAudience>()
+        .map_err(|()| AppError::bad_request("invalid_audience", "Invalid audience"))?;
+
+    let mut target = AnnouncementTarget {
+        audience,
+        ..AnnouncementTarget::default()
+    };
+
+    match audience {
+        AnnouncementAudience::Everyone => {}
+        AnnouncementAudience::Role => {
+            target.audience_role = request.role.as_deref().and_then(|role| role.parse().ok());
+        }
+        AnnouncementAudience::Users => {
+            let mut user_ids = request.user_ids.clone().unwrap_or_default();
+            user_ids.sort_unstable();
+            user_ids.dedup();
+            target.audience_user_ids = user_ids;
+        }
+        AnnouncementAudience::Committee => {
+            let committee_id = request.committee_id;
+            if let Some(committee_id) = committee_id
+                && Committee::find_by_id(pool, committee_id).await?.is_none()
+            {
+                return Err(AppError::Validation(vec![FieldError {
+                    field: "committee_id".to_string(),
+                    code: "not_found",
+                    message: format!("Committee with ID {} not found", committee_id),
+                }]));
+            }
+            target.committee_id = committee_id;
+        }
+    }
+
+    Ok(target)
+}
+
+/// The uploaded name without any directory part or characters that would break the
+/// Content-Disposition header, falling back to a generic name.
+fn attachment_filename(filename: Option<&str>, content_type: &str) -> String {
+    let name: String = filename
+        .and_then(|name| name.rsplit(['/', '\\']).next())
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"')
+        .take(255)
+        .collect();
+    let name = name.trim();
+
+    if name.is_empty() || name == "." || name == ".." {
+        let extension =
+            StorageService::extension_for(ATTACHMENT_TYPES, content_type).unwrap_or("bin");
+        format!("attachment.{}", extension)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Loads an announcement the user may manage: its poster or an admin.
+async fn find_managed(
+    pool: &DbPool,
+    announcement_id: Uuid,
+    user: &AuthenticatedUser,
+) -> Result<Announcement, AppError> {
+    let existing = Announcement::find_by_id(pool, announcement_id)
+        .await?
+        .ok_or(AnnouncementError::NotFound {
+            id: announcement_id,
+        })?;
+    if existing.posted_by != user.user_id && !can_see_all_announcements(user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+    Ok(existing)
+}
+
+/// Queues the audience email, sent when the post is published.
+async fn queue_broadcast(pool: &DbPool, announcement: &Announcement) {
+    if let Err(e) = jobs::enqueue_at(
+        pool,
+        JobKind::BroadcastAnnouncement {
+            announcement_id: announcement.id,
+        },
+        announcement.publish_at,
+    )
+    .await
+    {
+        error!("Failed to queue announcement email: {}", e);
+    }
+}
+
+/// Queues in-app notifications for the audience, delivered when the post is published.
+async fn queue_notifications(pool: &DbPool, announcement: &Announcement) {
+    if let Err(e) = jobs::enqueue_at(
+        pool,
+        JobKind::NotifyAnnouncement {
+            announcement_id: announcement.id,
+        },
+        announcement.publish_at,
+    )
+    .await
+    {
+        error!("Failed to queue announcement notifications: {}", e);
+    }
+}
+
 pub async fn create(
     pool: web::Data<DbPool>,
-    request: web::Json<CreateAnnouncementRequest>,
+    request: ValidJson<CreateAnnouncementRequest>,
     user: AuthenticatedUser,
-) -> Result<HttpResponse> {
+) -> Result<HttpResponse, AppError> {
     info!("Creating announcement for user: {}", user.user_id);
 
+    let is_admin = can_see_all_announcements(&user);
+    if request.is_pinned.is_some() && !is_admin {
+        return Err(pin_denied());
+    }
+
+    let is_draft = request.is_draft.unwrap_or(false);
+    let send_email = request.send_email.unwrap_or(false);
+    if send_email && !is_admin {
+        return Err(email_denied());
+    }
+    if is_draft && send_email {
+        return Err(AppError::bad_request(
+            "draft_not_sendable",
+            "Draft announcements cannot be emailed",
+        ));
+    }
+
+    let target = match &request.audience {
+        Some(audience) => resolve_target(&pool, audience).await?,
+        None => AnnouncementTarget::default(),
+    };
+    if target.audience != AnnouncementAudience::Everyone && !is_admin {
+        return Err(audience_denied());
+    }
+
     let create_announcement = CreateAnnouncement {
         title: request.title.clone(),
         body: request.body.clone(),
         posted_by: user.user_id,
+        target,
+        publish_at: request.publish_at.unwrap_or_else(Utc::now),
+        expires_at: request.expires_at,
+        is_draft,
+        is_pinned: request.is_pinned.unwrap_or(false),
     };
 
-    match Announcement::create(&pool, create_announcement).await {
-        Ok(announcement) => {
-            info!(
-                "Successfully created announcement with ID: {}",
-                announcement.id
-            );
-            Ok(HttpResponse::Created().json(ApiResponse::success(announcement)))
-        }
-        Err(AnnouncementError::Database(e)) => {
-            error!("Database error creating announcement: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to create announcement".to_string(),
-                )),
-            )
-        }
-        Err(e) => {
-            error!("Error creating announcement: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
-        }
+    let announcement = Announcement::create(&pool, create_announcement).await?;
+    info!(
+        "Successfully created announcement with ID: {}",
+        announcement.id
+    );
+    if send_email {
+        queue_broadcast(&pool, &announcement).await;
+    }
+    if !announcement.is_draft {
+        queue_notifications(&pool, &announcement).await;
     }
+    Ok(HttpResponse::Created().json(ApiResponse::success(announcement)))
 }
 
 pub async fn get_announcement(
     pool: web::Data<DbPool>,
     path: web::Path<Uuid>,
-) -> Result<HttpResponse> {
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
     let announcement_id = path.into_inner();
     info!("Getting announcement {}", announcement_id);
 
-    match Announcement::find_by_id(&pool, announcement_id).await {
-        Ok(Some(announcement)) => Ok(HttpResponse::Ok().json(ApiResponse::success(announcement))),
-        Ok(None) => Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error(
-            "Announcement not found".to_string(),
-        ))),
-        Err(AnnouncementError::Database(e)) => {
-            error!("Database error getting announcement: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to retrieve announcement".to_string(),
-                )),
-            )
-        }
-        Err(e) => {
-            error!("Error getting announcement: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
-        }
+    let visible = can_see_all_announcements(&user)
+        || Announcement::is_visible_to(&pool, announcement_id, user.user_id).await?;
+
+    // Announcements outside the user's audience are reported as missing
+    let announcement = Announcement::find_by_id(&pool, announcement_id)
+        .await?
+        .filter(|_| visible)
+        .ok_or(AnnouncementError::NotFound {
+            id: announcement_id,
+        })?;
+
+    if let Err(e) = AnnouncementRead::mark_read(&pool, announcement_id, user.user_id).await {
+        error!("Failed to record announcement read: {}", e);
     }
+    let attachments = AnnouncementAttachment::find_by_announcement(&pool, announcement_id).await?;
+    Ok(
+        HttpResponse::Ok().json(ApiResponse::success(AnnouncementDetail {
+            announcement,
+            attachments,
+        })),
+    )
 }
 
-pub async fn all(pool: web::Data<DbPool>) -> Result<HttpResponse> {
+pub async fn all(
+    pool: web::Data<DbPool>,
+    query: ListQuery<AnnouncementQuery>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
     info!("Getting all announcements");
 
-    match Announcement::find_all(&pool).await {
-        Ok(announcements) => Ok(HttpResponse::Ok().json(ApiResponse::success(announcements))),
-        Err(AnnouncementError::Database(e)) => {
-            error!("Database error getting all announcements: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to retrieve announcements".to_string(),
-                )),
-            )
-        }
-        Err(e) => {
-            error!("Error getting all announcements: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
-        }
-    }
+    let filter = AnnouncementFilter {
+        all_audiences: can_see_all_announcements(&user),
+        include_unpublished: query.filter.include_unpublished.unwrap_or(false),
+        pinned: query.filter.pinned,
+    };
+
+    let announcements =
+        Announcement::find_visible(&pool, user.user_id, &filter, &query.page).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::paginated(announcements)))
 }
 
 pub async fn update(
     pool: web::Data<DbPool>,
     path: web::Path<Uuid>,
-    request: web::Json<UpdateAnnouncementRequest>,
+    request: ValidJson<UpdateAnnouncementRequest>,
     user: AuthenticatedUser,
-) -> Result<HttpResponse> {
+) -> Result<HttpResponse, AppError> {
     let announcement_id = path.into_inner();
     info!(
         "Updating announcement {} for user: {}",
         announcement_id, user.user_id
     );
 
-    match Announcement::find_by_id(&pool, announcement_id).await {
-        Ok(Some(existing)) => {
-            if existing.posted_by != user.user_id
-                && user.user_role != UserRole::Admin
-                && user.user_role != UserRole::SuperAdmin
-            {
-                return Ok(HttpResponse::Forbidden()
-                    .json(ApiResponse::<()>::error("Access denied".to_string())));
-            }
-        }
-        Ok(None) => {
-            return Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error(
-                "Announcement not found".to_string(),
-            )));
-        }
-        Err(e) => {
-            error!("Error checking announcement ownership: {}", e);
-            return Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to verify announcement".to_string(),
-                )),
-            );
-        }
+    let existing = find_managed(&pool, announcement_id, &user).await?;
+
+    let is_admin = can_see_all_announcements(&user);
+    if request.is_pinned.is_some() && !is_admin {
+        return Err(pin_denied());
+    }
+
+    let send_email = request.send_email.unwrap_or(false);
+    if send_email && !is_admin {
+        return Err(email_denied());
+    }
+    if send_email && request.is_draft.unwrap_or(existing.is_draft) {
+        return Err(AppError::bad_request(
+            "draft_not_sendable",
+            "Draft announcements cannot be emailed",
+        ));
+    }
+
+    let target = match &request.audience {
+        Some(audience) => Some(resolve_target(&pool, audience).await?),
+        None => None,
+    };
+    if target
+        .as_ref()
+        .is_some_and(|target| target.audience != AnnouncementAudience::Everyone)
+        && !is_admin
+    {
+        return Err(audience_denied());
     }
 
     let update_data = UpdateAnnouncement {
         title: request.title.clone(),
         body: request.body.clone(),
+        target,
+        publish_at: request.publish_at,
+        expires_at: request.expires_at,
+        is_draft: request.is_draft,
+        is_pinned: request.is_pinned,
     };
 
-    match Announcement::update(&pool, announcement_id, update_data).await {
-        Ok(announcement) => {
-            info!("Successfully updated announcement: {}", announcement_id);
-            Ok(HttpResponse::Ok().json(ApiResponse::success(announcement)))
-        }
-        Err(AnnouncementError::NotFound { id }) => Ok(HttpResponse::NotFound().json(
-            ApiResponse::<()>::error(format!("Announcement {} not found", id)),
-        )),
-        Err(AnnouncementError::NoUpdateFields) => Ok(HttpResponse::BadRequest().json(
-            ApiResponse::<()>::error("No fields provided for update".to_string()),
-        )),
-        Err(AnnouncementError::Database(e)) => {
-            error!("Database error updating announcement: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to update announcement".to_string(),
-                )),
-            )
-        }
-        Err(e) => {
-            error!("Error updating announcement: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
-        }
+    let announcement = Announcement::update(&pool, announcement_id, update_data).await?;
+    info!("Successfully updated announcement: {}", announcement_id);
+    // Members are notified once a post leaves draft or is rescheduled
+    if let Some(updated) = announcement.as_ref().filter(|updated| {
+        !updated.is_draft && (existing.is_draft || updated.publish_at != existing.publish_at)
+    }) {
+        queue_notifications(&pool, updated).await;
+    }
+    // A scheduled post is emailed when it is published
+    if let Some(updated) = announcement.as_ref().filter(|_| send_email) {
+        queue_broadcast(&pool, updated).await;
     }
+    Ok(HttpResponse::Ok().json(ApiResponse::success(announcement)))
 }
 
 pub async fn delete(
     pool: web::Data<DbPool>,
     path: web::Path<Uuid>,
     user: AuthenticatedUser,
-) -> Result<HttpResponse> {
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
     let announcement_id = path.into_inner();
     info!(
         "Deleting announcement {} for user: {}",
         announcement_id, user.user_id
     );
 
-    match Announcement::find_by_id(&pool, announcement_id).await {
-        Ok(Some(existing)) => {
-            if existing.posted_by != user.user_id
-                && user.user_role != UserRole::Admin
-                && user.user_role != UserRole::SuperAdmin
-            {
-                return Ok(HttpResponse::Forbidden()
-                    .json(ApiResponse::<()>::error("Access denied".to_string())));
-            }
-        }
-        Ok(None) => {
-            return Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error(
-                "Announcement not found".to_string(),
-            )));
-        }
-        Err(e) => {
-            error!("Error checking announcement ownership: {}", e);
-            return Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to verify announcement".to_string(),
-                )),
+    let existing = find_managed(&pool, announcement_id, &user).await?;
+
+    let mut tx = pool.begin().await?;
+    Announcement::delete(&mut tx, announcement_id).await?;
+    info!("Successfully deleted announcement: {}", announcement_id);
+    audit
+        .record(
+            &mut tx,
+            "announcement.delete",
+            "announcement",
+            announcement_id,
+            snapshot(&existing),
+            None,
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::<()>::success(())))
+}
+
+pub async fn deleted(
+    pool: web::Data<DbPool>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Getting deleted announcements for user: {}", user.user_id);
+
+    if !can_manage_trash(&user) {
+        return Err(AppError::forbidden(
+            "Only admins can manage deleted announcements",
+        ));
+    }
+
+    let announcements = Announcement::find_deleted(&pool).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(announcements)))
+}
+
+pub async fn restore(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    let announcement_id = path.into_inner();
+    info!(
+        "Restoring announcement {} for user: {}",
+        announcement_id, user.user_id
+    );
+
+    if !can_manage_trash(&user) {
+        return Err(AppError::forbidden("Only admins can restore announcements"));
+    }
+
+    let mut tx = pool.begin().await?;
+    let announcement = Announcement::restore(&mut tx, announcement_id).await?;
+    audit
+        .record(
+            &mut tx,
+            "announcement.restore",
+            "announcement",
+            announcement_id,
+            None,
+            snapshot(&announcement),
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(announcement)))
+}
+
+pub async fn purge(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    let announcement_id = path.into_inner();
+    info!(
+        "Purging announcement {} for user: {}",
+        announcement_id, user.user_id
+    );
+
+    if !can_manage_trash(&user) {
+        return Err(AppError::forbidden("Only admins can purge announcements"));
+    }
+
+    // Rows go with the announcement, but the stored files have to be removed afterwards
+    let attachments = AnnouncementAttachment::find_by_announcement(&pool, announcement_id)
+        .await
+        .unwrap_or_else(|e| {
+            warn!("Failed to list attachments to remove: {}", e);
+            Vec::new()
+        });
+
+    let mut tx = pool.begin().await?;
+    Announcement::purge(&mut tx, announcement_id).await?;
+    audit
+        .record(
+            &mut tx,
+            "announcement.purge",
+            "announcement",
+            announcement_id,
+            None,
+            None,
+        )
+        .await?;
+    tx.commit().await?;
+
+    let storage = StorageService::from_env();
+    for attachment in attachments {
+        if let Err(e) = storage.delete(&attachment.storage_key).await {
+            warn!(
+                "Failed to remove attachment {}: {}",
+                attachment.storage_key, e
             );
         }
     }
+    Ok(HttpResponse::Ok().json(ApiResponse::<()>::success(())))
+}
 
-    match Announcement::delete(&pool, announcement_id).await {
-        Ok(()) => {
-            info!("Successfully deleted announcement: {}", announcement_id);
-            Ok(HttpResponse::Ok().json(ApiResponse::<()>::success(())))
-        }
-        Err(AnnouncementError::NotFound { id }) => Ok(HttpResponse::NotFound().json(
-            ApiResponse::<()>::error(format!("Announcement {} not found", id)),
+/// Emails the announcement to everyone in its audience who has not already received it.
+pub async fn send_email(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let announcement_id = path.into_inner();
+    info!(
+        "Emailing announcement {} by user: {}",
+        announcement_id, user.user_id
+    );
+
+    let announcement = find_managed(&pool, announcement_id, &user).await?;
+    if !can_see_all_announcements(&user) {
+        return Err(email_denied());
+    }
+
+    let now = Utc::now();
+    if announcement.is_draft || announcement.is_expired_at(now) {
+        return Err(AppError::conflict(
+            "announcement_not_sendable",
+            "Draft and expired announcements cannot be emailed",
+        ));
+    }
+
+    let job = jobs::enqueue_at(
+        &pool,
+        JobKind::BroadcastAnnouncement { announcement_id },
+        announcement.publish_at.max(now),
+    )
+    .await?;
+    Ok(
+        HttpResponse::Accepted().json(ApiResponse::success_with_message(
+            job,
+            "Announcement email queued".to_string(),
         )),
-        Err(AnnouncementError::Database(e)) => {
-            error!("Database error deleting announcement: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to delete announcement".to_string(),
-                )),
-            )
+    )
+}
+
+pub async fn deliveries(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let announcement_id = path.into_inner();
+    info!(
+        "Getting email deliveries for announcement {} for user: {}",
+        announcement_id, user.user_id
+    );
+
+    find_managed(&pool, announcement_id, &user).await?;
+
+    let report = AnnouncementDelivery::report(&pool, announcement_id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(report)))
+}
+
+pub async fn mark_read(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let announcement_id = path.into_inner();
+    info!(
+        "Marking announcement {} read for user: {}",
+        announcement_id, user.user_id
+    );
+
+    let visible = match Announcement::find_by_id(&pool, announcement_id).await? {
+        Some(_) if can_see_all_announcements(&user) => true,
+        Some(_) => Announcement::is_visible_to(&pool, announcement_id, user.user_id).await?,
+        None => false,
+    };
+    if !visible {
+        return Err(AnnouncementError::NotFound {
+            id: announcement_id,
+        }
+        .into());
+    }
+
+    let read = AnnouncementRead::mark_read(&pool, announcement_id, user.user_id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(read)))
+}
+
+pub async fn unread_count(
+    pool: web::Data<DbPool>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Counting unread announcements for user: {}", user.user_id);
+
+    let unread = Announcement::unread_count(&pool, user.user_id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(UnreadCount { unread })))
+}
+
+pub async fn mark_all_read(
+    pool: web::Data<DbPool>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Marking all announcements read for user: {}", user.user_id);
+
+    let marked = Announcement::mark_all_read(&pool, user.user_id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success_with_message(
+        MarkedRead { marked },
+        format!("Marked {} announcements as read", marked),
+    )))
+}
+
+/// Read-rate statistics for the poster and admins.
+pub async fn reads(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let announcement_id = path.into_inner();
+    info!(
+        "Getting read statistics for announcement {} for user: {}",
+        announcement_id, user.user_id
+    );
+
+    find_managed(&pool, announcement_id, &user).await?;
+
+    let report = AnnouncementRead::report(&pool, announcement_id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(report)))
+}
+
+pub async fn attachments(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let announcement_id = path.into_inner();
+    info!("Getting attachments for announcement {}", announcement_id);
+
+    check_target(&pool, ContentTarget::Announcement(announcement_id), &user).await?;
+
+    let attachments = AnnouncementAttachment::find_by_announcement(&pool, announcement_id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(attachments)))
+}
+
+pub async fn upload_attachment(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    query: ValidQuery<AttachmentUploadQuery>,
+    req: HttpRequest,
+    body: web::Bytes,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let announcement_id = path.into_inner();
+    info!(
+        "Uploading attachment for announcement {} by user: {}",
+        announcement_id, user.user_id
+    );
+
+    find_managed(&pool, announcement_id, &user).await?;
+
+    if body.is_empty() {
+        return Err(AppError::bad_request(
+            "empty_file",
+            "Attachment file is empty",
+        ));
+    }
+
+    let content_type = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+
+    let storage = StorageService::from_env();
+    let key = storage
+        .save("announcements", ATTACHMENT_TYPES, &content_type, &body)
+        .await
+        .map_err(|e| match e {
+            StorageError::UnsupportedType(_) => AppError::UnsupportedMediaType(
+                "Attachments must be a PDF, image, text, CSV or Office document".to_string(),
+            ),
+            e => e.into(),
+        })?;
+
+    let create_attachment = CreateAnnouncementAttachment {
+        announcement_id,
+        filename: attachment_filename(query.filename.as_deref(), &content_type),
+        content_type,
+        size_bytes: body.len() as i64,
+        storage_key: key.clone(),
+        uploaded_by: user.user_id,
+    };
+
+    match AnnouncementAttachment::create(&pool, create_attachment).await {
+        Ok(attachment) => {
+            info!(
+                "Stored attachment {} for announcement {}",
+                key, announcement_id
+            );
+            Ok(HttpResponse::Created().json(ApiResponse::success(attachment)))
         }
         Err(e) => {
-            error!("Error deleting announcement: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
+            if let Err(e) = storage.delete(&key).await {
+                warn!("Failed to clean up attachment {}: {}", key, e);
+            }
+            Err(e.into())
         }
     }
 }
+
+pub async fn download_attachment(
+    pool: web::Data<DbPool>,
+    path: web::Path<(Uuid, Uuid)>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let (announcement_id, attachment_id) = path.into_inner();
+    info!(
+        "Downloading attachment {} of announcement {}",
+        attachment_id, announcement_id
+    );
+
+    check_target(&pool, ContentTarget::Announcement(announcement_id), &user).await?;
+
+    let attachment = AnnouncementAttachment::find_by_id(&pool, announcement_id, attachment_id)
+        .await?
+        .ok_or(AnnouncementAttachmentError::NotFound { id: attachment_id })?;
+
+    let content = StorageService::from_env()
+        .read(&attachment.storage_key)
+        .await?;
+    Ok(HttpResponse::Ok()
+        .content_type(attachment.content_type)
+        .insert_header(ContentDisposition::attachment(attachment.filename))
+        .body(content))
+}
+
+pub async fn delete_attachment(
+    pool: web::Data<DbPool>,
+    path: web::Path<(Uuid, Uuid)>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let (announcement_id, attachment_id) = path.into_inner();
+    info!(
+        "Deleting attachment {} of announcement {} by user: {}",
+        attachment_id, announcement_id, user.user_id
+    );
+
+    find_managed(&pool, announcement_id, &user).await?;
+
+    let attachment = AnnouncementAttachment::delete(&pool, announcement_id, attachment_id).await?;
+    if let Err(e) = StorageService::from_env()
+        .delete(&attachment.storage_key)
+        .await
+    {
+        warn!(
+            "Failed to remove attachment {}: {}",
+            attachment.storage_key, e
+        );
+    }
+    Ok(
+        HttpResponse::Ok().json(ApiResponse::<()>::success_with_message(
+            (),
+            "Attachment deleted successfully".to_string(),
+        )),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attachment_filename_drops_directories_and_quotes() {
+        assert_eq!(
+            attachment_filename(Some("C:\\docs\\minutes.pdf"), "application/pdf"),
+            "minutes.pdf"
+        );
+        assert_eq!(
+            attachment_filename(Some("../../etc/\"flyer\".png"), "image/png"),
+            "flyer.png"
+        );
+        assert_eq!(
+            attachment_filename(Some("agenda\r\n.txt"), "text/plain"),
+            "agenda.txt"
+        );
+    }
+
+    #[test]
+    fn attachment_filename_falls_back_to_the_content_type() {
+        assert_eq!(
+            attachment_filename(None, "application/pdf"),
+            "attachment.pdf"
+        );
+        assert_eq!(
+            attachment_filename(Some("uploads/.."), "text/csv"),
+            "attachment.csv"
+        );
+        assert_eq!(
+            attachment_filename(Some("  "), "text/html"),
+            "attachment.bin"
+        );
+    }
+}
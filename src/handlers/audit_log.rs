@@ -0,0 +1,42 @@
+use crate::errors::AppError;
+use crate::models::audit_log::{AuditLogEntry, AuditLogFilter};
+use crate::models::user::UserRole;
+use crate::requests::audit_log::AuditLogQuery;
+use crate::utils::validation::ValidQuery;
+use crate::{
+    database::connection::DbPool, middleware::auth::AuthenticatedUser, utils::helpers::ApiResponse,
+};
+use actix_web::{HttpResponse, web};
+use tracing::info;
+
+/// Treasurers are among those audited, so only admins read the log.
+fn can_read_audit_log(user: &AuthenticatedUser) -> bool {
+    matches!(user.user_role, UserRole::Admin | UserRole::SuperAdmin)
+}
+
+pub async fn all(
+    pool: web::Data<DbPool>,
+    query: ValidQuery<AuditLogQuery>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Getting audit log for user: {}", user.user_id);
+
+    if !can_read_audit_log(&user) {
+        return Err(AppError::forbidden("Only admins can view the audit log"));
+    }
+
+    let query = query.into_inner();
+    let filter = AuditLogFilter {
+        actor_id: query.actor_id,
+        action: query.action,
+        entity_type: query.entity_type,
+        entity_id: query.entity_id,
+        from: query.from,
+        to: query.to,
+        before: query.before,
+        limit: query.limit,
+    };
+
+    let entries = AuditLogEntry::find(&pool, filter).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(entries)))
+}
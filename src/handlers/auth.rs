@@ -1,8 +1,9 @@
-use crate::models::user::UserError;
+use crate::errors::AppError;
 use crate::requests::register::RegisterRequest;
 use crate::requests::resend_email_verification::ResendVerificationRequest;
 use crate::requests::verify_email::VerifyEmailRequest;
-use crate::services::email::EmailService;
+use crate::services::email::{EmailError, EmailService};
+use crate::utils::validation::{ValidJson, ValidQuery};
 use crate::{
     database::connection::DbPool,
     models::{
@@ -12,26 +13,62 @@ use crate::{
     services::auth::AuthService,
     utils::helpers::ApiResponse,
 };
-use actix_web::{HttpResponse, Result, web};
-use tracing::error;
+use actix_web::{HttpResponse, web};
+use sqlx::PgConnection;
 use tracing::log::info;
 
+fn auth_service() -> Result<AuthService, AppError> {
+    AuthService::new()
+        .map_err(|e| AppError::internal(format!("Authentication service error: {}", e)))
+}
+
+fn email_service() -> Result<EmailService, AppError> {
+    EmailService::new().map_err(|e| AppError::internal(format!("Email service error: {}", e)))
+}
+
+async fn queue_verification_email(
+    conn: &mut PgConnection,
+    email_service: &EmailService,
+    user: &User,
+) -> Result<(), EmailError> {
+    let Some(code) = user.email_verification_code.as_deref() else {
+        return Ok(());
+    };
+
+    let template = email_service
+        .verification_email(&mut *conn, &user.fullname, code)
+        .await?;
+    email_service
+        .queue(conn, &user.email, Some(&user.fullname), template)
+        .await?;
+
+    Ok(())
+}
+
+async fn queue_welcome_email(
+    conn: &mut PgConnection,
+    email_service: &EmailService,
+    user: &User,
+) -> Result<(), EmailError> {
+    let template = email_service
+        .welcome_email(&mut *conn, &user.fullname)
+        .await?;
+    email_service
+        .queue(conn, &user.email, Some(&user.fullname), template)
+        .await?;
+
+    Ok(())
+}
+
 pub async fn register(
     pool: web::Data<DbPool>,
-    request: web::Json<RegisterRequest>,
-) -> Result<HttpResponse> {
-    let auth_service = AuthService::new().map_err(|e| {
-        error!("Failed to create auth service: {}", e);
-        actix_web::error::ErrorInternalServerError("Authentication service error")
-    })?;
-
-    let email_service = EmailService::new().map_err(|e| {
-        error!("Failed to create email service: {}", e);
-        actix_web::error::ErrorInternalServerError("Email service error")
-    })?;
+    request: ValidJson<RegisterRequest>,
+) -> Result<HttpResponse, AppError> {
+    let auth_service = auth_service()?;
+    let email_service = email_service()?;
 
     let user_role = match request.user_role.as_ref() {
-        Some(role_str) => role_str.parse().unwrap_or_else(|_| UserRole::Member),
+        Some(role_str) => role_str.parse().unwrap_or(UserRole::Member),
         None => UserRole::Member,
     };
 
@@ -45,42 +82,20 @@ pub async fn register(
         is_active,
     };
 
-    let user = match User::create(&pool, create_user).await {
-        Ok(user) => user,
-        Err(UserError::EmailAlreadyExists { email }) => {
-            return Ok(
-                HttpResponse::Conflict().json(ApiResponse::<()>::error(format!(
-                    "Email {} already exists",
-                    email
-                ))),
-            );
-        }
-        Err(e) => {
-            error!("Failed to create user: {}", e);
-            return Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to create user".to_string(),
-                )),
-            );
-        }
-    };
+    let mut tx = pool.begin().await?;
 
-    if let Some(verification_code) = &user.email_verification_code {
-        let template =
-            email_service.generate_verification_template(&user.fullname, verification_code);
+    let user = User::create(&mut tx, create_user).await?;
 
-        if let Err(e) = email_service.send_email(&user.email, Some(&user.fullname), template) {
-            error!("Failed to send verification email: {}", e);
-            // Don't fail registration if email fails, but log it
-        } else {
-            info!("Verification email sent to: {}", user.email);
-        }
-    }
+    // The account is only created if its verification email is queued with it
+    queue_verification_email(&mut tx, &email_service, &user)
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to queue verification email: {}", e)))?;
+
+    tx.commit().await?;
 
-    let token = auth_service.generate_token(&user).map_err(|e| {
-        error!("Failed to generate token: {}", e);
-        actix_web::error::ErrorInternalServerError("Failed to generate token")
-    })?;
+    let token = auth_service
+        .generate_token(&user)
+        .map_err(|e| AppError::internal(format!("Failed to generate token: {}", e)))?;
 
     let user_info = UserInfo {
         id: user.id,
@@ -103,41 +118,36 @@ pub async fn register(
 
 pub async fn login(
     pool: web::Data<DbPool>,
-    request: web::Json<LoginRequest>,
-) -> Result<HttpResponse> {
-    let auth_service = AuthService::new().map_err(|e| {
-        error!("Failed to create auth service: {}", e);
-        actix_web::error::ErrorInternalServerError("Authentication service error")
-    })?;
+    request: ValidJson<LoginRequest>,
+) -> Result<HttpResponse, AppError> {
+    let auth_service = auth_service()?;
 
     let user = auth_service
         .authenticate_user(&pool, &request.email, &request.password)
         .await
-        .map_err(|e| {
-            error!("Authentication error: {}", e);
-            actix_web::error::ErrorInternalServerError("Authentication error")
-        })?
+        .map_err(|e| AppError::internal(format!("Authentication error: {}", e)))?
         .ok_or_else(|| {
-            error!("Invalid credentials for user: {}", request.email);
-            actix_web::error::ErrorUnauthorized("Invalid credentials")
+            info!("Invalid credentials for user: {}", request.email);
+            AppError::unauthorized("invalid_credentials", "Invalid credentials")
         })?;
 
     if !user.is_active {
-        return Ok(HttpResponse::Forbidden().json(ApiResponse::<()>::error(
-            "Account is not active".to_string(),
-        )));
+        return Err(AppError::Forbidden {
+            code: "account_inactive",
+            message: "Account is not active".to_string(),
+        });
     }
 
     if !user.is_email_verified {
-        return Ok(HttpResponse::Forbidden().json(ApiResponse::<()>::error(
-            "Please verify your email address before logging in".to_string(),
-        )));
+        return Err(AppError::Forbidden {
+            code: "email_not_verified",
+            message: "Please verify your email address before logging in".to_string(),
+        });
     }
 
-    let token = auth_service.generate_token(&user).map_err(|e| {
-        error!("Failed to generate token: {}", e);
-        actix_web::error::ErrorInternalServerError("Failed to generate token")
-    })?;
+    let token = auth_service
+        .generate_token(&user)
+        .map_err(|e| AppError::internal(format!("Failed to generate token: {}", e)))?;
 
     let user_info = UserInfo {
         id: user.id,
@@ -154,40 +164,18 @@ pub async fn login(
 
 pub async fn verify_email(
     pool: web::Data<DbPool>,
-    query: web::Query<VerifyEmailRequest>,
-) -> Result<HttpResponse> {
-    let email_service = EmailService::new().map_err(|e| {
-        error!("Failed to create email service: {}", e);
-        actix_web::error::ErrorInternalServerError("Email service error")
-    })?;
-
-    let user = match User::verify_email(&pool, &query.code).await {
-        Ok(user) => user,
-        Err(UserError::InvalidVerificationCode) => {
-            return Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(
-                "Invalid verification code".to_string(),
-            )));
-        }
-        Err(UserError::VerificationCodeExpired) => {
-            return Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(
-                "Verification code has expired".to_string(),
-            )));
-        }
-        Err(e) => {
-            error!("Email verification error: {}", e);
-            return Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Email verification failed".to_string(),
-                )),
-            );
-        }
-    };
+    query: ValidQuery<VerifyEmailRequest>,
+) -> Result<HttpResponse, AppError> {
+    let email_service = email_service()?;
+    let mut tx = pool.begin().await?;
 
-    let welcome_template = email_service.generate_welcome_template(&user.fullname);
-    if let Err(e) = email_service.send_email(&user.email, Some(&user.fullname), welcome_template) {
-        error!("Failed to send welcome email: {}", e);
-        // Don't fail verification if welcome email fails
-    }
+    let user = User::verify_email(&mut tx, &query.code).await?;
+
+    queue_welcome_email(&mut tx, &email_service, &user)
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to queue welcome email: {}", e)))?;
+
+    tx.commit().await?;
 
     info!("Email verified successfully for user: {}", user.email);
 
@@ -201,52 +189,25 @@ pub async fn verify_email(
 
 pub async fn resend_verification(
     pool: web::Data<DbPool>,
-    request: web::Json<ResendVerificationRequest>,
-) -> Result<HttpResponse> {
-    let email_service = EmailService::new().map_err(|e| {
-        error!("Failed to create email service: {}", e);
-        actix_web::error::ErrorInternalServerError("Email service error")
-    })?;
-
-    let user = match User::resend_verification_code(&pool, &request.email).await {
-        Ok(user) => user,
-        Err(UserError::NotFoundByEmail { email }) => {
-            return Ok(
-                HttpResponse::NotFound().json(ApiResponse::<()>::error(format!(
-                    "User with email {} not found",
-                    email
-                ))),
-            );
-        }
-        Err(e) => {
-            error!("Failed to resend verification code: {}", e);
-            return Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to resend verification code".to_string(),
-                )),
-            );
-        }
-    };
+    request: ValidJson<ResendVerificationRequest>,
+) -> Result<HttpResponse, AppError> {
+    let email_service = email_service()?;
+    let mut tx = pool.begin().await?;
+
+    let user = User::resend_verification_code(&mut tx, &request.email).await?;
 
     if user.is_email_verified {
-        return Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(
-            "Email is already verified".to_string(),
-        )));
+        return Err(AppError::bad_request(
+            "email_already_verified",
+            "Email is already verified",
+        ));
     }
 
-    if let Some(verification_code) = &user.email_verification_code {
-        let template =
-            email_service.generate_verification_template(&user.fullname, verification_code);
-
-        if let Err(e) = email_service.send_email(&user.email, Some(&user.fullname), template) {
-            error!("Failed to send verification email: {}", e);
-            return Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to send verification email".to_string(),
-                )),
-            );
-        }
-    }
+    queue_verification_email(&mut tx, &email_service, &user)
+        .await
+        .map_err(|e| AppError::internal(format!("Failed to queue verification email: {}", e)))?;
+
+    tx.commit().await?;
 
     info!("Verification email resent to: {}", user.email);
 
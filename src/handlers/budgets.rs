@@ -0,0 +1,125 @@
+use crate::errors::AppError;
+use crate::models::budget::{Budget, SetBudget};
+use crate::models::user::UserRole;
+use crate::requests::budget::{BudgetYearQuery, SetBudgetRequest};
+use crate::services::audit::{AuditContext, snapshot};
+use crate::utils::validation::{ValidJson, ValidQuery};
+use crate::{
+    database::connection::DbPool, middleware::auth::AuthenticatedUser, utils::helpers::ApiResponse,
+};
+use actix_web::{HttpResponse, web};
+use chrono::{Datelike, Utc};
+use tracing::info;
+use uuid::Uuid;
+
+fn can_manage_budgets(user: &AuthenticatedUser) -> bool {
+    matches!(
+        user.user_role,
+        UserRole::Treasurer | UserRole::Admin | UserRole::SuperAdmin
+    )
+}
+
+pub async fn set_budget(
+    pool: web::Data<DbPool>,
+    request: ValidJson<SetBudgetRequest>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    info!(
+        "Setting {} budget for category {} by user: {}",
+        request.year, request.category_id, user.user_id
+    );
+
+    if !can_manage_budgets(&user) {
+        return Err(AppError::forbidden(
+            "Only treasurers and admins can set budgets",
+        ));
+    }
+
+    let set_budget = SetBudget {
+        category_id: request.category_id,
+        year: request.year,
+        amount: request.amount,
+        notes: request.notes.clone(),
+        created_by: user.user_id,
+    };
+
+    let before = Budget::find_by_category(&pool, set_budget.category_id, set_budget.year).await?;
+    let mut tx = pool.begin().await?;
+    let budget = Budget::set(&mut tx, set_budget).await?;
+    audit
+        .record(
+            &mut tx,
+            "budget.set",
+            "budget",
+            budget.id,
+            before.as_ref().and_then(snapshot),
+            snapshot(&budget),
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(budget)))
+}
+
+pub async fn all(
+    pool: web::Data<DbPool>,
+    query: ValidQuery<BudgetYearQuery>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let year = query.year.unwrap_or_else(|| Utc::now().year());
+    info!("Getting budgets for {}", year);
+
+    if !can_manage_budgets(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let budgets = Budget::find_by_year(&pool, year).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(budgets)))
+}
+
+pub async fn delete(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    let budget_id = path.into_inner();
+    info!("Deleting budget {} by user: {}", budget_id, user.user_id);
+
+    if !can_manage_budgets(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let mut tx = pool.begin().await?;
+    let budget = Budget::delete(&mut tx, budget_id).await?;
+    audit
+        .record(
+            &mut tx,
+            "budget.delete",
+            "budget",
+            budget_id,
+            snapshot(&budget),
+            None,
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::<()>::success(())))
+}
+
+pub async fn report(
+    pool: web::Data<DbPool>,
+    query: ValidQuery<BudgetYearQuery>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let year = query.year.unwrap_or_else(|| Utc::now().year());
+    info!("Generating budget report for {}", year);
+
+    if !can_manage_budgets(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let report = Budget::report(&pool, year).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(report)))
+}
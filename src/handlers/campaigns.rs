@@ -0,0 +1,205 @@
+use crate::errors::AppError;
+use crate::handlers::payments::{PaymentTarget, start_checkout};
+use crate::models::campaign::{
+    Campaign, CampaignError, CampaignProgress, CreateCampaign, UpdateCampaign,
+};
+use crate::models::exchange_rate::{BASE_CURRENCY, normalize_currency};
+use crate::models::user::UserRole;
+use crate::requests::campaign::{CreateCampaignRequest, DonateRequest, UpdateCampaignRequest};
+use crate::services::payment_gateway::PaymentGatewayService;
+use crate::utils::validation::ValidJson;
+use crate::{
+    database::connection::DbPool, middleware::auth::AuthenticatedUser, utils::helpers::ApiResponse,
+};
+use actix_web::{HttpResponse, web};
+use chrono::Utc;
+use tracing::info;
+use uuid::Uuid;
+
+fn can_manage_campaigns(user: &AuthenticatedUser) -> bool {
+    matches!(
+        user.user_role,
+        UserRole::Treasurer | UserRole::Admin | UserRole::SuperAdmin
+    )
+}
+
+/// Loads a campaign that is still accepting donations, refusing anonymous gifts it does not
+/// allow. Checked for gateway donations and for payments recorded by hand.
+pub async fn find_open(
+    pool: &DbPool,
+    campaign_id: Uuid,
+    anonymous: bool,
+) -> Result<CampaignProgress, AppError> {
+    let campaign = Campaign::find_progress(pool, campaign_id)
+        .await?
+        .ok_or(CampaignError::NotFound { id: campaign_id })?;
+
+    if !campaign.is_open(Utc::now().date_naive()) {
+        return Err(AppError::conflict(
+            "campaign_closed",
+            "This campaign is no longer accepting donations",
+        ));
+    }
+
+    if anonymous && !campaign.campaign.allow_anonymous {
+        return Err(AppError::bad_request(
+            "anonymous_not_allowed",
+            "This campaign does not accept anonymous donations",
+        ));
+    }
+
+    Ok(campaign)
+}
+
+pub async fn create(
+    pool: web::Data<DbPool>,
+    request: ValidJson<CreateCampaignRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Creating campaign by user: {}", user.user_id);
+
+    if !can_manage_campaigns(&user) {
+        return Err(AppError::forbidden(
+            "Only treasurers and admins can create campaigns",
+        ));
+    }
+
+    let create_campaign = CreateCampaign {
+        title: request.title.clone(),
+        description: request.description.clone(),
+        target_amount: request.target_amount,
+        deadline: request.deadline,
+        allow_anonymous: request.allow_anonymous.unwrap_or(true),
+        created_by: user.user_id,
+    };
+
+    let campaign = Campaign::create(&pool, create_campaign).await?;
+    info!("Created campaign with ID: {}", campaign.id);
+    Ok(HttpResponse::Created().json(ApiResponse::success(campaign)))
+}
+
+pub async fn all(pool: web::Data<DbPool>) -> Result<HttpResponse, AppError> {
+    info!("Getting all campaigns");
+
+    let campaigns = Campaign::find_all(&pool).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(campaigns)))
+}
+
+pub async fn get_campaign(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let campaign_id = path.into_inner();
+    info!("Getting campaign with ID: {}", campaign_id);
+
+    let campaign = Campaign::find_progress(&pool, campaign_id)
+        .await?
+        .ok_or(CampaignError::NotFound { id: campaign_id })?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(campaign)))
+}
+
+pub async fn donors(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let campaign_id = path.into_inner();
+    info!("Getting donors for campaign: {}", campaign_id);
+
+    Campaign::find_by_id(&pool, campaign_id)
+        .await?
+        .ok_or(CampaignError::NotFound { id: campaign_id })?;
+
+    let donors = Campaign::find_donors(&pool, campaign_id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(donors)))
+}
+
+pub async fn update(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    request: ValidJson<UpdateCampaignRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let campaign_id = path.into_inner();
+    info!(
+        "Updating campaign {} by user: {}",
+        campaign_id, user.user_id
+    );
+
+    if !can_manage_campaigns(&user) {
+        return Err(AppError::forbidden(
+            "Only treasurers and admins can update campaigns",
+        ));
+    }
+
+    let request = request.into_inner();
+    let update_data = UpdateCampaign {
+        title: request.title,
+        description: request.description,
+        target_amount: request.target_amount,
+        deadline: request.deadline,
+        allow_anonymous: request.allow_anonymous,
+        is_closed: request.is_closed,
+    };
+
+    let campaign = Campaign::update(&pool, campaign_id, update_data)
+        .await?
+        .ok_or(CampaignError::NotFound { id: campaign_id })?;
+    info!("Updated campaign with ID: {}", campaign.id);
+    Ok(HttpResponse::Ok().json(ApiResponse::success(campaign)))
+}
+
+pub async fn delete(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let campaign_id = path.into_inner();
+    info!(
+        "Deleting campaign {} by user: {}",
+        campaign_id, user.user_id
+    );
+
+    if !can_manage_campaigns(&user) {
+        return Err(AppError::forbidden(
+            "Only treasurers and admins can delete campaigns",
+        ));
+    }
+
+    Campaign::delete(&pool, campaign_id).await?;
+    info!("Deleted campaign with ID: {}", campaign_id);
+    Ok(HttpResponse::Ok().json(ApiResponse::<()>::success(())))
+}
+
+pub async fn donate(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    request: ValidJson<DonateRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let campaign_id = path.into_inner();
+    info!(
+        "Initializing donation to campaign {} by user: {}",
+        campaign_id, user.user_id
+    );
+
+    let anonymous = request.anonymous.unwrap_or(false);
+    find_open(&pool, campaign_id, anonymous).await?;
+
+    let currency = match request.currency.as_deref().map(normalize_currency) {
+        None => BASE_CURRENCY.to_string(),
+        Some(currency) => currency?,
+    };
+
+    let gateway = PaymentGatewayService::new()?;
+
+    start_checkout(
+        &pool,
+        &gateway,
+        &user,
+        request.amount,
+        currency,
+        PaymentTarget::Campaign(campaign_id),
+        anonymous,
+    )
+    .await
+}
@@ -0,0 +1,198 @@
+use crate::errors::AppError;
+use crate::models::announcement::{Announcement, AnnouncementError};
+use crate::models::comment::{Comment, CommentError, ContentTarget, CreateComment};
+use crate::models::photo::{Photo, PhotoError};
+use crate::models::user::UserRole;
+use crate::requests::comment::{CreateCommentRequest, HideCommentRequest, UpdateCommentRequest};
+use crate::utils::validation::ValidJson;
+use crate::{
+    database::connection::DbPool, middleware::auth::AuthenticatedUser, utils::helpers::ApiResponse,
+};
+use actix_web::{HttpResponse, web};
+use tracing::info;
+use uuid::Uuid;
+
+fn can_moderate(user: &AuthenticatedUser) -> bool {
+    matches!(user.user_role, UserRole::Admin | UserRole::SuperAdmin)
+}
+
+/// Checks the announcement or photo exists and the user can see it. Announcements outside
+/// the user's audience are reported as missing, as they are elsewhere.
+pub async fn check_target(
+    pool: &DbPool,
+    target: ContentTarget,
+    user: &AuthenticatedUser,
+) -> Result<(), AppError> {
+    match target {
+        ContentTarget::Announcement(id) => {
+            let found = if can_moderate(user) {
+                Announcement::find_by_id(pool, id).await?.is_some()
+            } else {
+                Announcement::is_visible_to(pool, id, user.user_id).await?
+            };
+            if !found {
+                return Err(AnnouncementError::NotFound { id }.into());
+            }
+        }
+        ContentTarget::Photo(id) => {
+            if Photo::find_by_id(pool, id).await?.is_none() {
+                return Err(PhotoError::NotFound { id }.into());
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn list(
+    pool: &DbPool,
+    target: ContentTarget,
+    user: &AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!(
+        "Getting comments on {:?} for user: {}",
+        target, user.user_id
+    );
+
+    check_target(pool, target, user).await?;
+
+    let thread = Comment::find_thread(pool, target, can_moderate(user)).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(thread)))
+}
+
+async fn create(
+    pool: &DbPool,
+    target: ContentTarget,
+    request: &CreateCommentRequest,
+    user: &AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Commenting on {:?} for user: {}", target, user.user_id);
+
+    check_target(pool, target, user).await?;
+
+    let create_comment = CreateComment {
+        target,
+        parent_id: request.parent_id,
+        author_id: user.user_id,
+        body: request.body.trim().to_string(),
+    };
+
+    let comment = Comment::create(pool, create_comment).await?;
+    info!("Successfully created comment with ID: {}", comment.id);
+    Ok(HttpResponse::Created().json(ApiResponse::success(comment)))
+}
+
+pub async fn announcement_comments(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    list(&pool, ContentTarget::Announcement(path.into_inner()), &user).await
+}
+
+pub async fn create_announcement_comment(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    request: ValidJson<CreateCommentRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let target = ContentTarget::Announcement(path.into_inner());
+    create(&pool, target, &request, &user).await
+}
+
+pub async fn photo_comments(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    list(&pool, ContentTarget::Photo(path.into_inner()), &user).await
+}
+
+pub async fn create_photo_comment(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    request: ValidJson<CreateCommentRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let target = ContentTarget::Photo(path.into_inner());
+    create(&pool, target, &request, &user).await
+}
+
+/// Only the author can edit a comment; moderators hide it instead.
+pub async fn update(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    request: ValidJson<UpdateCommentRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let comment_id = path.into_inner();
+    info!("Updating comment {} for user: {}", comment_id, user.user_id);
+
+    let existing = Comment::find_by_id(&pool, comment_id)
+        .await?
+        .ok_or(CommentError::NotFound { id: comment_id })?;
+    if existing.author_id != user.user_id {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let comment = Comment::update_body(&pool, comment_id, request.body.trim().to_string()).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(comment)))
+}
+
+pub async fn delete(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let comment_id = path.into_inner();
+    info!("Deleting comment {} for user: {}", comment_id, user.user_id);
+
+    let existing = Comment::find_by_id(&pool, comment_id)
+        .await?
+        .ok_or(CommentError::NotFound { id: comment_id })?;
+    if existing.author_id != user.user_id && !can_moderate(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    Comment::delete(&pool, comment_id).await?;
+    Ok(
+        HttpResponse::Ok().json(ApiResponse::<()>::success_with_message(
+            (),
+            "Comment deleted successfully".to_string(),
+        )),
+    )
+}
+
+pub async fn hide(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    request: ValidJson<HideCommentRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let comment_id = path.into_inner();
+    info!("Hiding comment {} by user: {}", comment_id, user.user_id);
+
+    if !can_moderate(&user) {
+        return Err(AppError::forbidden("Only admins can moderate comments"));
+    }
+
+    let request = request.into_inner();
+    let comment =
+        Comment::set_hidden(&pool, comment_id, Some(user.user_id), request.reason).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(comment)))
+}
+
+pub async fn unhide(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let comment_id = path.into_inner();
+    info!("Unhiding comment {} by user: {}", comment_id, user.user_id);
+
+    if !can_moderate(&user) {
+        return Err(AppError::forbidden("Only admins can moderate comments"));
+    }
+
+    let comment = Comment::set_hidden(&pool, comment_id, None, None).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(comment)))
+}
@@ -0,0 +1,147 @@
+use crate::errors::AppError;
+use crate::models::committee::{Committee, CommitteeError, CommitteeWithMembers, CreateCommittee};
+use crate::models::user::{User, UserError, UserRole};
+use crate::requests::committee::{CommitteeMemberRequest, CreateCommitteeRequest};
+use crate::utils::validation::ValidJson;
+use crate::{
+    database::connection::DbPool, middleware::auth::AuthenticatedUser, utils::helpers::ApiResponse,
+};
+use actix_web::{HttpResponse, web};
+use tracing::info;
+use uuid::Uuid;
+
+fn can_manage_committees(user: &AuthenticatedUser) -> bool {
+    matches!(user.user_role, UserRole::Admin | UserRole::SuperAdmin)
+}
+
+pub async fn create(
+    pool: web::Data<DbPool>,
+    request: ValidJson<CreateCommitteeRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Creating committee by user: {}", user.user_id);
+
+    if !can_manage_committees(&user) {
+        return Err(AppError::forbidden("Only admins can create committees"));
+    }
+
+    let name = request.name.trim();
+    let create_committee = CreateCommittee {
+        name: name.to_string(),
+        description: request.description.clone(),
+        created_by: user.user_id,
+    };
+
+    let committee = Committee::create(&pool, create_committee).await?;
+    info!("Created committee with ID: {}", committee.id);
+    Ok(HttpResponse::Created().json(ApiResponse::success(committee)))
+}
+
+pub async fn all(
+    pool: web::Data<DbPool>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Getting committees for user: {}", user.user_id);
+
+    let committees = Committee::find_all(&pool).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(committees)))
+}
+
+pub async fn get_committee(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let committee_id = path.into_inner();
+    info!(
+        "Getting committee {} for user: {}",
+        committee_id, user.user_id
+    );
+
+    let committee = Committee::find_by_id(&pool, committee_id)
+        .await?
+        .ok_or(CommitteeError::NotFound { id: committee_id })?;
+
+    let members = Committee::find_members(&pool, committee_id).await?;
+    Ok(
+        HttpResponse::Ok().json(ApiResponse::success(CommitteeWithMembers {
+            committee,
+            members,
+        })),
+    )
+}
+
+pub async fn delete(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let committee_id = path.into_inner();
+    info!(
+        "Deleting committee {} by user: {}",
+        committee_id, user.user_id
+    );
+
+    if !can_manage_committees(&user) {
+        return Err(AppError::forbidden("Only admins can delete committees"));
+    }
+
+    Committee::delete(&pool, committee_id).await?;
+    info!("Deleted committee with ID: {}", committee_id);
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn add_member(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    request: ValidJson<CommitteeMemberRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let committee_id = path.into_inner();
+    info!(
+        "Adding user {} to committee {} by user: {}",
+        request.user_id, committee_id, user.user_id
+    );
+
+    if !can_manage_committees(&user) {
+        return Err(AppError::forbidden(
+            "Only admins can manage committee members",
+        ));
+    }
+
+    Committee::find_by_id(&pool, committee_id)
+        .await?
+        .ok_or(CommitteeError::NotFound { id: committee_id })?;
+
+    User::find_by_id(&pool, request.user_id)
+        .await?
+        .ok_or(UserError::NotFound {
+            id: request.user_id,
+        })?;
+
+    Committee::add_member(&pool, committee_id, request.user_id).await?;
+
+    let members = Committee::find_members(&pool, committee_id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(members)))
+}
+
+pub async fn remove_member(
+    pool: web::Data<DbPool>,
+    path: web::Path<(Uuid, Uuid)>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let (committee_id, member_id) = path.into_inner();
+    info!(
+        "Removing user {} from committee {} by user: {}",
+        member_id, committee_id, user.user_id
+    );
+
+    if !can_manage_committees(&user) {
+        return Err(AppError::forbidden(
+            "Only admins can manage committee members",
+        ));
+    }
+
+    Committee::remove_member(&pool, committee_id, member_id).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
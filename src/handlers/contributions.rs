@@ -1,245 +1,295 @@
+use crate::utils::validation::ValidJson;
 use crate::{
     database::connection::DbPool,
+    errors::AppError,
     middleware::auth::AuthenticatedUser,
     models::contribution::{
-        Contribution, ContributionError, CreateContribution, UpdateContribution,
+        Contribution, ContributionError, ContributionFilter, CreateContribution, UpdateContribution,
     },
-    requests::contribution::{ContributionRequest, UpdateContributionRequest},
+    models::exchange_rate::{BASE_CURRENCY, normalize_currency},
+    models::user::UserRole,
+    requests::contribution::{ContributionRequest, ContributionsQuery, UpdateContributionRequest},
+    services::audit::{AuditContext, snapshot},
     utils::helpers::ApiResponse,
+    utils::pagination::ListQuery,
 };
-use actix_web::{HttpResponse, Result, web};
-use chrono::{DateTime, TimeZone, Utc};
-use tracing::{error, info};
+use actix_web::{HttpResponse, web};
+use tracing::info;
 use uuid::Uuid;
 
+fn can_manage_trash(user: &AuthenticatedUser) -> bool {
+    matches!(user.user_role, UserRole::Admin | UserRole::SuperAdmin)
+}
+
+/// Loads a live contribution the user created, for changes only its creator may make.
+async fn find_own(
+    pool: &DbPool,
+    contribution_id: Uuid,
+    user: &AuthenticatedUser,
+) -> Result<Contribution, AppError> {
+    let contribution = Contribution::find_by_id(pool, contribution_id)
+        .await?
+        .ok_or(ContributionError::NotFound {
+            id: contribution_id,
+        })?;
+    if contribution.created_by != user.user_id {
+        return Err(AppError::forbidden("Access denied"));
+    }
+    Ok(contribution)
+}
+
 pub async fn create(
     pool: web::Data<DbPool>,
-    request: web::Json<ContributionRequest>,
+    request: ValidJson<ContributionRequest>,
     user: AuthenticatedUser,
-) -> Result<HttpResponse> {
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
     info!("Creating contribution for user: {}", user.user_id);
 
+    let currency = match request.currency.as_deref() {
+        Some(currency) => normalize_currency(currency)?,
+        None => BASE_CURRENCY.to_string(),
+    };
+
     let create_contribution = CreateContribution {
         title: request.title.clone(),
         description: request.description.clone(),
         amount: request.amount,
-        due_date: request.due_date.unwrap(),
+        currency,
+        due_date: request.due_date,
         created_by: user.user_id,
     };
 
-    match Contribution::create(&pool, create_contribution).await {
-        Ok(contribution) => {
-            info!(
-                "Successfully created contribution with ID: {}",
-                contribution.id
-            );
-            Ok(HttpResponse::Created().json(ApiResponse::success(contribution)))
-        }
-        Err(ContributionError::Database(e)) => {
-            error!("Database error creating contribution: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to create contribution".to_string(),
-                )),
-            )
-        }
-        Err(e) => {
-            error!("Error creating contribution: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
-        }
-    }
+    let mut tx = pool.begin().await?;
+    let contribution = Contribution::create(&mut tx, create_contribution).await?;
+    info!(
+        "Successfully created contribution with ID: {}",
+        contribution.id
+    );
+    audit
+        .record(
+            &mut tx,
+            "contribution.create",
+            "contribution",
+            contribution.id,
+            None,
+            snapshot(&contribution),
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Created().json(ApiResponse::success(contribution)))
 }
 
 pub async fn get_contribution(
     pool: web::Data<DbPool>,
     path: web::Path<Uuid>,
-) -> Result<HttpResponse> {
+) -> Result<HttpResponse, AppError> {
     let contribution_id = path.into_inner();
     info!("Getting contribution {}", contribution_id);
 
-    match Contribution::find_by_id(&pool, contribution_id).await {
-        Ok(Some(contribution)) => Ok(HttpResponse::Ok().json(ApiResponse::success(contribution))),
-        Ok(None) => Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error(
-            "Contribution not found".to_string(),
-        ))),
-        Err(ContributionError::Database(e)) => {
-            error!("Database error getting contribution: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to retrieve contribution".to_string(),
-                )),
-            )
-        }
-        Err(e) => {
-            error!("Error getting contribution: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
-        }
-    }
+    let contribution = Contribution::find_by_id(&pool, contribution_id)
+        .await?
+        .ok_or(ContributionError::NotFound {
+            id: contribution_id,
+        })?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(contribution)))
 }
 
 pub async fn get_user_contributions(
     pool: web::Data<DbPool>,
     user: AuthenticatedUser,
-) -> Result<HttpResponse> {
+) -> Result<HttpResponse, AppError> {
     info!("Getting all contributions for user: {}", user.user_id);
 
-    println!("{:?}", user);
-    match Contribution::find_by_creator(&pool, user.user_id).await {
-        Ok(contributions) => Ok(HttpResponse::Ok().json(ApiResponse::success(contributions))),
-        Err(ContributionError::Database(e)) => {
-            error!("Database error getting user contributions: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to retrieve contributions".to_string(),
-                )),
-            )
-        }
-        Err(e) => {
-            error!("Error getting user contributions: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
-        }
-    }
+    let contributions = Contribution::find_by_creator(&pool, user.user_id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(contributions)))
 }
 
-pub async fn all(pool: web::Data<DbPool>) -> Result<HttpResponse> {
+pub async fn all(
+    pool: web::Data<DbPool>,
+    query: ListQuery<ContributionsQuery>,
+) -> Result<HttpResponse, AppError> {
     info!("Getting all contributions");
 
-    match Contribution::find_all(&pool).await {
-        Ok(contributions) => Ok(HttpResponse::Ok().json(ApiResponse::success(contributions))),
-        Err(ContributionError::Database(e)) => {
-            error!("Database error getting all contributions: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to retrieve contributions".to_string(),
-                )),
-            )
-        }
-        Err(e) => {
-            error!("Error getting all contributions: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
-        }
-    }
+    let currency = query
+        .filter
+        .currency
+        .as_deref()
+        .map(normalize_currency)
+        .transpose()?;
+
+    let filter = ContributionFilter {
+        currency,
+        created_by: query.filter.created_by,
+        due_from: query.filter.due_from,
+        due_to: query.filter.due_to,
+    };
+
+    let contributions = Contribution::find_page(&pool, &filter, &query.page).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::paginated(contributions)))
 }
 
 pub async fn update(
     pool: web::Data<DbPool>,
     path: web::Path<Uuid>,
-    request: web::Json<UpdateContributionRequest>,
+    request: ValidJson<UpdateContributionRequest>,
     user: AuthenticatedUser,
-) -> Result<HttpResponse> {
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
     let contribution_id = path.into_inner();
     info!(
         "Updating contribution {} for user: {}",
         contribution_id, user.user_id
     );
 
-    match Contribution::find_by_id(&pool, contribution_id).await {
-        Ok(Some(existing)) => {
-            if existing.created_by != user.user_id {
-                return Ok(HttpResponse::Forbidden()
-                    .json(ApiResponse::<()>::error("Access denied".to_string())));
-            }
-        }
-        Ok(None) => {
-            return Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error(
-                "Contribution not found".to_string(),
-            )));
-        }
-        Err(e) => {
-            error!("Error checking contribution ownership: {}", e);
-            return Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to verify contribution".to_string(),
-                )),
-            );
-        }
-    }
+    let existing = find_own(&pool, contribution_id, &user).await?;
+
+    let currency = request
+        .currency
+        .as_deref()
+        .map(normalize_currency)
+        .transpose()?;
 
     let update_data = UpdateContribution {
         title: request.title.clone(),
         description: request.description.clone(),
         amount: request.amount,
+        currency,
         due_date: request.due_date,
     };
 
-    match Contribution::update(&pool, contribution_id, update_data).await {
-        Ok(contribution) => {
-            info!("Successfully updated contribution: {}", contribution_id);
-            Ok(HttpResponse::Ok().json(ApiResponse::success(contribution)))
-        }
-        Err(ContributionError::NotFound { id }) => Ok(HttpResponse::NotFound().json(
-            ApiResponse::<()>::error(format!("Contribution {} not found", id)),
-        )),
-        Err(ContributionError::NoUpdateFields) => Ok(HttpResponse::BadRequest().json(
-            ApiResponse::<()>::error("No fields provided for update".to_string()),
-        )),
-        Err(ContributionError::Database(e)) => {
-            error!("Database error updating contribution: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to update contribution".to_string(),
-                )),
+    let mut tx = pool.begin().await?;
+    let contribution = Contribution::update(&mut tx, contribution_id, update_data).await?;
+    info!("Successfully updated contribution: {}", contribution_id);
+    if let Some(updated) = &contribution {
+        audit
+            .record(
+                &mut tx,
+                "contribution.update",
+                "contribution",
+                contribution_id,
+                snapshot(&existing),
+                snapshot(updated),
             )
-        }
-        Err(e) => {
-            error!("Error updating contribution: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
-        }
+            .await?;
     }
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(contribution)))
 }
 
 pub async fn delete(
     pool: web::Data<DbPool>,
     path: web::Path<Uuid>,
     user: AuthenticatedUser,
-) -> Result<HttpResponse> {
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
     let contribution_id = path.into_inner();
     info!(
         "Deleting contribution {} for user: {}",
         contribution_id, user.user_id
     );
 
-    match Contribution::find_by_id(&pool, contribution_id).await {
-        Ok(Some(existing)) => {
-            if existing.created_by != user.user_id {
-                return Ok(HttpResponse::Forbidden()
-                    .json(ApiResponse::<()>::error("Access denied".to_string())));
-            }
-        }
-        Ok(None) => {
-            return Ok(HttpResponse::NotFound().json(ApiResponse::<()>::error(
-                "Contribution not found".to_string(),
-            )));
-        }
-        Err(e) => {
-            error!("Error checking contribution ownership: {}", e);
-            return Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to verify contribution".to_string(),
-                )),
-            );
-        }
+    let existing = find_own(&pool, contribution_id, &user).await?;
+
+    let mut tx = pool.begin().await?;
+    Contribution::delete(&mut tx, contribution_id).await?;
+    info!("Successfully deleted contribution: {}", contribution_id);
+    audit
+        .record(
+            &mut tx,
+            "contribution.delete",
+            "contribution",
+            contribution_id,
+            snapshot(&existing),
+            None,
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::<()>::success(())))
+}
+
+pub async fn deleted(
+    pool: web::Data<DbPool>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Getting deleted contributions for user: {}", user.user_id);
+
+    if !can_manage_trash(&user) {
+        return Err(AppError::forbidden(
+            "Only admins can manage deleted contributions",
+        ));
     }
 
-    match Contribution::delete(&pool, contribution_id).await {
-        Ok(()) => {
-            info!("Successfully deleted contribution: {}", contribution_id);
-            Ok(HttpResponse::Ok().json(ApiResponse::<()>::success(())))
-        }
-        Err(ContributionError::NotFound { id }) => Ok(HttpResponse::NotFound().json(
-            ApiResponse::<()>::error(format!("Contribution {} not found", id)),
-        )),
-        Err(ContributionError::Database(e)) => {
-            error!("Database error deleting contribution: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to delete contribution".to_string(),
-                )),
-            )
-        }
-        Err(e) => {
-            error!("Error deleting contribution: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
-        }
+    let contributions = Contribution::find_deleted(&pool).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(contributions)))
+}
+
+pub async fn restore(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    let contribution_id = path.into_inner();
+    info!(
+        "Restoring contribution {} for user: {}",
+        contribution_id, user.user_id
+    );
+
+    if !can_manage_trash(&user) {
+        return Err(AppError::forbidden("Only admins can restore contributions"));
     }
+
+    let mut tx = pool.begin().await?;
+    let contribution = Contribution::restore(&mut tx, contribution_id).await?;
+    audit
+        .record(
+            &mut tx,
+            "contribution.restore",
+            "contribution",
+            contribution_id,
+            None,
+            snapshot(&contribution),
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(contribution)))
+}
+
+/// Only contributions that have already been deleted can be purged.
+pub async fn purge(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    let contribution_id = path.into_inner();
+    info!(
+        "Purging contribution {} for user: {}",
+        contribution_id, user.user_id
+    );
+
+    if !can_manage_trash(&user) {
+        return Err(AppError::forbidden("Only admins can purge contributions"));
+    }
+
+    let mut tx = pool.begin().await?;
+    Contribution::purge(&mut tx, contribution_id).await?;
+    audit
+        .record(
+            &mut tx,
+            "contribution.purge",
+            "contribution",
+            contribution_id,
+            None,
+            None,
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::<()>::success(())))
 }
@@ -0,0 +1,232 @@
+use crate::errors::AppError;
+use crate::models::email_template::{
+    CreateEmailTemplateVersion, EmailTemplateError, EmailTemplateVersion,
+};
+use crate::models::user::UserRole;
+use crate::requests::email_template::{EmailTemplateRequest, PreviewEmailTemplateRequest};
+use crate::services::email::{
+    EmailError, EmailService, EmailTemplate, LAYOUT_TEMPLATE, TemplateSource, sample_context,
+};
+use crate::utils::validation::ValidJson;
+use crate::{
+    database::connection::DbPool, middleware::auth::AuthenticatedUser, utils::helpers::ApiResponse,
+};
+use actix_web::{HttpResponse, web};
+use serde_json::Value;
+use sqlx::PgConnection;
+use tracing::info;
+
+/// Content templates are previewed inside the layout; the layout is previewed around this one.
+const LAYOUT_PREVIEW_TEMPLATE: &str = "welcome";
+
+fn can_manage_email_templates(user: &AuthenticatedUser) -> bool {
+    matches!(user.user_role, UserRole::Admin | UserRole::SuperAdmin)
+}
+
+fn unknown_template(name: &str) -> EmailTemplateError {
+    EmailTemplateError::NotFound {
+        name: name.to_string(),
+    }
+}
+
+/// Renders `name` with the sample data, applying any unsaved changes in `draft`.
+async fn render_draft(
+    conn: &mut PgConnection,
+    name: &str,
+    draft: PreviewEmailTemplateRequest,
+) -> Result<EmailTemplate, EmailError> {
+    let email_service = EmailService::new()?;
+
+    let mut context = sample_context(name).unwrap_or_default();
+    if let (Value::Object(sample), Some(Value::Object(overrides))) = (&mut context, draft.context) {
+        sample.extend(overrides);
+    }
+
+    let content_name = if name == LAYOUT_TEMPLATE {
+        LAYOUT_PREVIEW_TEMPLATE
+    } else {
+        name
+    };
+    let layout = EmailTemplateVersion::find_active(&mut *conn, LAYOUT_TEMPLATE).await?;
+    let active = EmailTemplateVersion::find_active(&mut *conn, content_name).await?;
+
+    let (layout, template) = if name == LAYOUT_TEMPLATE {
+        (
+            draft.html_body.unwrap_or(layout.html_body),
+            TemplateSource {
+                subject: active.subject,
+                html_body: active.html_body,
+                text_body: active.text_body,
+            },
+        )
+    } else {
+        (
+            layout.html_body,
+            TemplateSource {
+                subject: draft.subject.unwrap_or(active.subject),
+                html_body: draft.html_body.unwrap_or(active.html_body),
+                text_body: draft.text_body.or(active.text_body),
+            },
+        )
+    };
+
+    email_service.render_source(&layout, &template, context)
+}
+
+pub async fn all(
+    pool: web::Data<DbPool>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Getting email templates for user: {}", user.user_id);
+
+    if !can_manage_email_templates(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let templates = EmailTemplateVersion::find_all_active(&pool).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(templates)))
+}
+
+pub async fn get_template(
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let name = path.into_inner();
+    info!("Getting email template {} for user: {}", name, user.user_id);
+
+    if !can_manage_email_templates(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let mut conn = pool.acquire().await?;
+
+    let template = EmailTemplateVersion::find_active(&mut conn, &name).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(template)))
+}
+
+pub async fn versions(
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let name = path.into_inner();
+    info!(
+        "Getting versions of email template {} for user: {}",
+        name, user.user_id
+    );
+
+    if !can_manage_email_templates(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let versions = EmailTemplateVersion::find_versions(&pool, &name).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(versions)))
+}
+
+pub async fn update(
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    request: ValidJson<EmailTemplateRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let name = path.into_inner();
+    info!("Updating email template {} by user: {}", name, user.user_id);
+
+    if !can_manage_email_templates(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    if sample_context(&name).is_none() {
+        return Err(unknown_template(&name).into());
+    }
+
+    let request = request.into_inner();
+
+    // Render against the sample data first so a broken template is never activated
+    let mut conn = pool.acquire().await?;
+    let draft = PreviewEmailTemplateRequest {
+        subject: Some(request.subject.clone()),
+        html_body: Some(request.html_body.clone()),
+        text_body: request.text_body.clone(),
+        context: None,
+    };
+    render_draft(&mut conn, &name, draft).await?;
+    drop(conn);
+
+    let template = CreateEmailTemplateVersion {
+        name,
+        subject: request.subject,
+        html_body: request.html_body,
+        text_body: request.text_body,
+        created_by: user.user_id,
+    };
+
+    let template = EmailTemplateVersion::create_version(&pool, template).await?;
+    info!(
+        "Email template {} is now at version {}",
+        template.name, template.version
+    );
+    Ok(HttpResponse::Ok().json(ApiResponse::success(template)))
+}
+
+pub async fn activate(
+    pool: web::Data<DbPool>,
+    path: web::Path<(String, i32)>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let (name, version) = path.into_inner();
+    info!(
+        "Activating version {} of email template {} by user: {}",
+        version, name, user.user_id
+    );
+
+    if !can_manage_email_templates(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let template = EmailTemplateVersion::activate(&pool, &name, version).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(template)))
+}
+
+/// Renders the active version with sample data.
+pub async fn preview(
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    preview_draft(
+        pool,
+        path,
+        ValidJson(PreviewEmailTemplateRequest::default()),
+        user,
+    )
+    .await
+}
+
+/// Renders unsaved changes with sample data.
+pub async fn preview_draft(
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    request: ValidJson<PreviewEmailTemplateRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let name = path.into_inner();
+    info!(
+        "Previewing email template {} for user: {}",
+        name, user.user_id
+    );
+
+    if !can_manage_email_templates(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    if sample_context(&name).is_none() {
+        return Err(unknown_template(&name).into());
+    }
+
+    let mut conn = pool.acquire().await?;
+
+    let email = render_draft(&mut conn, &name, request.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(email)))
+}
@@ -0,0 +1,123 @@
+use crate::errors::AppError;
+use crate::models::email_outbox::{EmailStatus, OutboxEmail, OutboxEmailError, OutboxEmailFilter};
+use crate::models::email_unsubscribe::{EmailUnsubscribe, MAILING_LISTS};
+use crate::models::user::UserRole;
+use crate::requests::email::{EmailOutboxQuery, UnsubscribeQuery};
+use crate::services::email::EmailService;
+use crate::utils::validation::ValidQuery;
+use crate::{
+    database::connection::DbPool, middleware::auth::AuthenticatedUser, utils::helpers::ApiResponse,
+};
+use actix_web::{HttpResponse, web};
+use tracing::info;
+use uuid::Uuid;
+
+const DEFAULT_EMAILS_LIMIT: i64 = 100;
+
+fn can_manage_emails(user: &AuthenticatedUser) -> bool {
+    matches!(user.user_role, UserRole::Admin | UserRole::SuperAdmin)
+}
+
+pub async fn all(
+    pool: web::Data<DbPool>,
+    query: ValidQuery<EmailOutboxQuery>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Getting outbox emails for user: {}", user.user_id);
+
+    if !can_manage_emails(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let status = query
+        .status
+        .as_deref()
+        .map(str::parse::<EmailStatus>)
+        .transpose()
+        .map_err(|()| {
+            AppError::bad_request(
+                "invalid_status",
+                "Invalid status. Valid values: pending, sending, sent, failed",
+            )
+        })?;
+
+    let filter = OutboxEmailFilter {
+        status,
+        to_email: query.to_email.clone(),
+        limit: query.limit.unwrap_or(DEFAULT_EMAILS_LIMIT).clamp(1, 500),
+    };
+
+    let emails = OutboxEmail::find_all(&pool, filter).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(emails)))
+}
+
+pub async fn get_email(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let email_id = path.into_inner();
+    info!(
+        "Getting outbox email {} for user: {}",
+        email_id, user.user_id
+    );
+
+    if !can_manage_emails(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let email = OutboxEmail::find_by_id(&pool, email_id)
+        .await?
+        .ok_or(OutboxEmailError::NotFound { id: email_id })?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(email)))
+}
+
+pub async fn retry(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let email_id = path.into_inner();
+    info!(
+        "Retrying outbox email {} by user: {}",
+        email_id, user.user_id
+    );
+
+    if !can_manage_emails(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let email = OutboxEmail::retry(&pool, email_id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(email)))
+}
+
+/// Target of the signed link in list emails; needs no login.
+pub async fn unsubscribe(
+    pool: web::Data<DbPool>,
+    query: ValidQuery<UnsubscribeQuery>,
+) -> Result<HttpResponse, AppError> {
+    info!("Unsubscribing user {} from {}", query.user, query.list);
+
+    if !MAILING_LISTS.contains(&query.list.as_str()) {
+        return Err(AppError::bad_request(
+            "unknown_mailing_list",
+            format!("Unknown mailing list {}", query.list),
+        ));
+    }
+
+    let email_service = EmailService::new()?;
+    if !email_service.verify_unsubscribe_token(query.user, &query.list, &query.token) {
+        return Err(AppError::bad_request(
+            "invalid_unsubscribe_link",
+            "Invalid unsubscribe link",
+        ));
+    }
+
+    EmailUnsubscribe::unsubscribe(&pool, query.user, &query.list).await?;
+    Ok(
+        HttpResponse::Ok().json(ApiResponse::<()>::success_with_message(
+            (),
+            format!("You have been unsubscribed from {} emails", query.list),
+        )),
+    )
+}
@@ -0,0 +1,127 @@
+use crate::errors::AppError;
+use crate::models::exchange_rate::{
+    ExchangeRate, ExchangeRateError, SetExchangeRate, normalize_currency,
+};
+use crate::models::user::UserRole;
+use crate::requests::exchange_rate::{ExchangeRateQuery, SetExchangeRateRequest};
+use crate::services::audit::{AuditContext, snapshot};
+use crate::utils::validation::{ValidJson, ValidQuery};
+use crate::{
+    database::connection::DbPool, middleware::auth::AuthenticatedUser, utils::helpers::ApiResponse,
+};
+use actix_web::{HttpResponse, web};
+use chrono::Utc;
+use tracing::info;
+use uuid::Uuid;
+
+fn can_manage_rates(user: &AuthenticatedUser) -> bool {
+    matches!(
+        user.user_role,
+        UserRole::Treasurer | UserRole::Admin | UserRole::SuperAdmin
+    )
+}
+
+pub async fn set_rate(
+    pool: web::Data<DbPool>,
+    request: ValidJson<SetExchangeRateRequest>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    info!(
+        "Setting {} exchange rate by user: {}",
+        request.currency, user.user_id
+    );
+
+    if !can_manage_rates(&user) {
+        return Err(AppError::forbidden(
+            "Only treasurers and admins can set exchange rates",
+        ));
+    }
+
+    let request = request.into_inner();
+    let set_rate = SetExchangeRate {
+        currency: normalize_currency(&request.currency)?,
+        rate: request.rate,
+        effective_date: request
+            .effective_date
+            .unwrap_or_else(|| Utc::now().date_naive()),
+        notes: request.notes,
+        created_by: user.user_id,
+    };
+
+    // Replacing a rate changes what past payments are worth, so keep the old one on record
+    let before =
+        ExchangeRate::find_by_date(&pool, &set_rate.currency, set_rate.effective_date).await?;
+    let mut tx = pool.begin().await?;
+    let rate = ExchangeRate::set(&mut tx, set_rate).await?;
+    info!(
+        "Set {} rate {} effective {}",
+        rate.currency, rate.rate, rate.effective_date
+    );
+    audit
+        .record(
+            &mut tx,
+            "exchange_rate.set",
+            "exchange_rate",
+            rate.id,
+            before.as_ref().and_then(snapshot),
+            snapshot(&rate),
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(rate)))
+}
+
+pub async fn all(
+    pool: web::Data<DbPool>,
+    query: ValidQuery<ExchangeRateQuery>,
+) -> Result<HttpResponse, AppError> {
+    info!("Getting exchange rates");
+
+    let currency = query
+        .currency
+        .as_deref()
+        .map(normalize_currency)
+        .transpose()?;
+
+    let rates = ExchangeRate::find_all(&pool, currency.as_deref()).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(rates)))
+}
+
+pub async fn delete(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    let rate_id = path.into_inner();
+    info!(
+        "Deleting exchange rate {} by user: {}",
+        rate_id, user.user_id
+    );
+
+    if !can_manage_rates(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let existing = ExchangeRate::find_by_id(&pool, rate_id)
+        .await?
+        .ok_or(ExchangeRateError::NotFound { id: rate_id })?;
+
+    let mut tx = pool.begin().await?;
+    ExchangeRate::delete(&mut tx, rate_id).await?;
+    audit
+        .record(
+            &mut tx,
+            "exchange_rate.delete",
+            "exchange_rate",
+            rate_id,
+            snapshot(&existing),
+            None,
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::<()>::success(())))
+}
@@ -0,0 +1,355 @@
+use crate::errors::AppError;
+use crate::models::expense::{
+    CreateExpense, Expense, ExpenseCategory, ExpenseError, ExpenseFilter, ExpenseStatus,
+    UpdateExpense,
+};
+use crate::models::user::UserRole;
+use crate::requests::expense::{
+    CreateExpenseCategoryRequest, CreateExpenseRequest, ExpensesQuery, UpdateExpenseRequest,
+};
+use crate::services::audit::{AuditContext, snapshot};
+use crate::services::storage::{RECEIPT_TYPES, StorageError, StorageService};
+use crate::utils::validation::{ValidJson, ValidQuery};
+use crate::{
+    database::connection::DbPool, middleware::auth::AuthenticatedUser, utils::helpers::ApiResponse,
+};
+use actix_web::{HttpRequest, HttpResponse, http::header, web};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+fn can_manage_expenses(user: &AuthenticatedUser) -> bool {
+    matches!(
+        user.user_role,
+        UserRole::Treasurer | UserRole::Admin | UserRole::SuperAdmin
+    )
+}
+
+pub async fn create_category(
+    pool: web::Data<DbPool>,
+    request: ValidJson<CreateExpenseCategoryRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Creating expense category by user: {}", user.user_id);
+
+    if !can_manage_expenses(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let category = ExpenseCategory::create(
+        &pool,
+        request.name.trim().to_string(),
+        request.description.clone(),
+    )
+    .await?;
+    info!("Created expense category {}", category.id);
+    Ok(HttpResponse::Created().json(ApiResponse::success(category)))
+}
+
+pub async fn categories(
+    pool: web::Data<DbPool>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Getting expense categories for user: {}", user.user_id);
+
+    let categories = ExpenseCategory::find_all(&pool).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(categories)))
+}
+
+pub async fn create(
+    pool: web::Data<DbPool>,
+    request: ValidJson<CreateExpenseRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Recording expense by user: {}", user.user_id);
+
+    if !can_manage_expenses(&user) {
+        return Err(AppError::forbidden(
+            "Only treasurers and admins can record expenses",
+        ));
+    }
+
+    let create_expense = CreateExpense {
+        category_id: request.category_id,
+        amount: request.amount,
+        payee: request.payee.trim().to_string(),
+        description: request.description.clone(),
+        spent_on: request.spent_on,
+        event_id: request.event_id,
+        project: request.project.clone(),
+        recorded_by: user.user_id,
+    };
+
+    let expense = Expense::create(&pool, create_expense).await?;
+    info!("Successfully recorded expense with ID: {}", expense.id);
+    Ok(HttpResponse::Created().json(ApiResponse::success(expense)))
+}
+
+pub async fn all(
+    pool: web::Data<DbPool>,
+    query: ValidQuery<ExpensesQuery>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Getting expenses");
+
+    if !can_manage_expenses(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let status = query
+        .status
+        .as_deref()
+        .map(str::parse::<ExpenseStatus>)
+        .transpose()
+        .map_err(|()| {
+            AppError::bad_request(
+                "invalid_status",
+                "Invalid status. Valid values: pending, approved, rejected",
+            )
+        })?;
+
+    let filter = ExpenseFilter {
+        status,
+        category_id: query.category_id,
+        year: query.year,
+    };
+
+    let expenses = Expense::find_all(&pool, filter).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(expenses)))
+}
+
+pub async fn get_expense(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let expense_id = path.into_inner();
+    info!("Getting expense {}", expense_id);
+
+    if !can_manage_expenses(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let expense = Expense::find_by_id(&pool, expense_id)
+        .await?
+        .ok_or(ExpenseError::NotFound { id: expense_id })?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(expense)))
+}
+
+pub async fn update(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    request: ValidJson<UpdateExpenseRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let expense_id = path.into_inner();
+    info!("Updating expense {} by user: {}", expense_id, user.user_id);
+
+    if !can_manage_expenses(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let update_data = UpdateExpense {
+        category_id: request.category_id,
+        amount: request.amount,
+        payee: request.payee.clone(),
+        description: request.description.as_ref().map(|d| Some(d.clone())),
+        spent_on: request.spent_on,
+        event_id: request.event_id.map(Some),
+        project: request.project.as_ref().map(|p| Some(p.clone())),
+    };
+
+    let expense = Expense::update(&pool, expense_id, update_data).await?;
+    info!("Successfully updated expense: {}", expense_id);
+    Ok(HttpResponse::Ok().json(ApiResponse::success(expense)))
+}
+
+pub async fn delete(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let expense_id = path.into_inner();
+    info!("Deleting expense {} by user: {}", expense_id, user.user_id);
+
+    if !can_manage_expenses(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let receipt_key = Expense::delete(&pool, expense_id).await?;
+    if let Some(key) = receipt_key
+        && let Err(e) = StorageService::from_env().delete(&key).await
+    {
+        warn!("Failed to remove receipt {} for expense: {}", key, e);
+    }
+
+    info!("Successfully deleted expense: {}", expense_id);
+    Ok(HttpResponse::Ok().json(ApiResponse::<()>::success(())))
+}
+
+async fn review(
+    pool: web::Data<DbPool>,
+    expense_id: Uuid,
+    status: ExpenseStatus,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    info!(
+        "Marking expense {} as {:?} by user: {}",
+        expense_id, status, user.user_id
+    );
+
+    if !can_manage_expenses(&user) {
+        return Err(AppError::forbidden(
+            "Only treasurers and admins can review expenses",
+        ));
+    }
+
+    let before = Expense::find_by_id(&pool, expense_id).await.ok().flatten();
+    let action = match status {
+        ExpenseStatus::Approved => "expense.approve",
+        _ => "expense.reject",
+    };
+
+    let mut tx = pool.begin().await?;
+    let expense = Expense::review(&mut tx, expense_id, status, user.user_id).await?;
+    audit
+        .record(
+            &mut tx,
+            action,
+            "expense",
+            expense_id,
+            before.as_ref().and_then(snapshot),
+            snapshot(&expense),
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(expense)))
+}
+
+pub async fn approve(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    review(
+        pool,
+        path.into_inner(),
+        ExpenseStatus::Approved,
+        user,
+        audit,
+    )
+    .await
+}
+
+pub async fn reject(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    review(
+        pool,
+        path.into_inner(),
+        ExpenseStatus::Rejected,
+        user,
+        audit,
+    )
+    .await
+}
+
+pub async fn upload_receipt(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    req: HttpRequest,
+    body: web::Bytes,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let expense_id = path.into_inner();
+    info!(
+        "Uploading receipt for expense {} by user: {}",
+        expense_id, user.user_id
+    );
+
+    if !can_manage_expenses(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    if body.is_empty() {
+        return Err(AppError::bad_request("empty_file", "Receipt file is empty"));
+    }
+
+    let content_type = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+
+    Expense::find_by_id(&pool, expense_id)
+        .await?
+        .ok_or(ExpenseError::NotFound { id: expense_id })?;
+
+    let storage = StorageService::from_env();
+    let key = storage
+        .save("expenses", RECEIPT_TYPES, &content_type, &body)
+        .await
+        .map_err(|e| match e {
+            StorageError::UnsupportedType(_) => AppError::UnsupportedMediaType(
+                "Receipt must be a PDF, JPEG, PNG or WebP file".to_string(),
+            ),
+            e => e.into(),
+        })?;
+
+    match Expense::set_receipt(&pool, expense_id, key.clone(), content_type).await {
+        Ok((expense, replaced)) => {
+            if let Some(old_key) = replaced
+                && let Err(e) = storage.delete(&old_key).await
+            {
+                warn!("Failed to remove replaced receipt {}: {}", old_key, e);
+            }
+
+            info!("Stored receipt {} for expense {}", key, expense_id);
+            Ok(HttpResponse::Ok().json(ApiResponse::success(expense)))
+        }
+        Err(e) => {
+            if let Err(e) = storage.delete(&key).await {
+                warn!("Failed to clean up receipt {}: {}", key, e);
+            }
+            Err(e.into())
+        }
+    }
+}
+
+pub async fn download_receipt(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let expense_id = path.into_inner();
+    info!("Downloading receipt for expense {}", expense_id);
+
+    if !can_manage_expenses(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let expense = Expense::find_by_id(&pool, expense_id)
+        .await?
+        .ok_or(ExpenseError::NotFound { id: expense_id })?;
+    let Some(key) = expense.receipt_key else {
+        return Err(AppError::not_found(
+            "receipt_not_found",
+            "No receipt has been uploaded for this expense",
+        ));
+    };
+
+    let content = StorageService::from_env().read(&key).await?;
+    Ok(HttpResponse::Ok()
+        .content_type(
+            expense
+                .receipt_content_type
+                .unwrap_or_else(|| "application/octet-stream".to_string()),
+        )
+        .body(content))
+}
@@ -0,0 +1,98 @@
+use crate::errors::AppError;
+use crate::models::job::{Job, JobError, JobFilter, JobSchedule, JobStatus};
+use crate::models::user::UserRole;
+use crate::requests::job::JobQuery;
+use crate::utils::validation::ValidQuery;
+use crate::{
+    database::connection::DbPool, middleware::auth::AuthenticatedUser, utils::helpers::ApiResponse,
+};
+use actix_web::{HttpResponse, web};
+use tracing::info;
+use uuid::Uuid;
+
+const DEFAULT_JOBS_LIMIT: i64 = 100;
+
+fn can_manage_jobs(user: &AuthenticatedUser) -> bool {
+    matches!(user.user_role, UserRole::Admin | UserRole::SuperAdmin)
+}
+
+pub async fn all(
+    pool: web::Data<DbPool>,
+    query: ValidQuery<JobQuery>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Getting jobs for user: {}", user.user_id);
+
+    if !can_manage_jobs(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let status = query
+        .status
+        .as_deref()
+        .map(str::parse::<JobStatus>)
+        .transpose()
+        .map_err(|()| {
+            AppError::bad_request(
+                "invalid_status",
+                "Invalid status. Valid values: pending, running, completed, dead",
+            )
+        })?;
+
+    let filter = JobFilter {
+        status,
+        job_type: query.job_type.clone(),
+        limit: query.limit.unwrap_or(DEFAULT_JOBS_LIMIT).clamp(1, 500),
+    };
+
+    let jobs = Job::find_all(&pool, filter).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(jobs)))
+}
+
+pub async fn get_job(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let job_id = path.into_inner();
+    info!("Getting job {} for user: {}", job_id, user.user_id);
+
+    if !can_manage_jobs(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let job = Job::find_by_id(&pool, job_id)
+        .await?
+        .ok_or(JobError::NotFound { id: job_id })?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(job)))
+}
+
+pub async fn retry(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let job_id = path.into_inner();
+    info!("Retrying job {} by user: {}", job_id, user.user_id);
+
+    if !can_manage_jobs(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let job = Job::retry(&pool, job_id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(job)))
+}
+
+pub async fn schedules(
+    pool: web::Data<DbPool>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Getting job schedules for user: {}", user.user_id);
+
+    if !can_manage_jobs(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let schedules = JobSchedule::find_all(&pool).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(schedules)))
+}
@@ -1,6 +1,22 @@
 pub mod announcements;
+pub mod audit_log;
 pub mod auth;
+pub mod budgets;
+pub mod campaigns;
+pub mod comments;
+pub mod committees;
 pub mod contributions;
+pub mod email_templates;
+pub mod emails;
+pub mod exchange_rates;
+pub mod expenses;
+pub mod jobs;
+pub mod notifications;
+pub mod payment_reversals;
 pub mod payments;
 pub mod photos;
+pub mod reactions;
+pub mod realtime;
+pub mod reconciliation;
+pub mod reports;
 pub mod users;
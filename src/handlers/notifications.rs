@@ -0,0 +1,185 @@
+use crate::errors::AppError;
+use crate::models::announcement_read::{MarkedRead, UnreadCount};
+use crate::models::notification::{
+    Notification, NotificationFilter, NotificationKind, NotificationPreference,
+};
+use crate::models::notification_channel::{ChannelKind, ChannelPreference};
+use crate::models::user::User;
+use crate::requests::notification::{
+    NotificationChannelsRequest, NotificationPreferencesRequest, NotificationQuery,
+};
+use crate::services::notification_channels::normalize_phone;
+use crate::utils::validation::{ValidJson, ValidQuery};
+use crate::{
+    database::connection::DbPool, middleware::auth::AuthenticatedUser, utils::helpers::ApiResponse,
+};
+use actix_web::{HttpResponse, web};
+use tracing::info;
+use uuid::Uuid;
+
+const VALID_KINDS: &str =
+    "payment_verified, payment_rejected, announcement, event_reminder, photo_tag, role_change";
+const VALID_CHANNELS: &str = "email, sms, messaging";
+
+pub async fn all(
+    pool: web::Data<DbPool>,
+    query: ValidQuery<NotificationQuery>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Getting notifications for user: {}", user.user_id);
+
+    let kind = query
+        .kind
+        .as_deref()
+        .map(str::parse::<NotificationKind>)
+        .transpose()
+        .map_err(|()| {
+            AppError::bad_request(
+                "invalid_kind",
+                format!("Invalid kind. Valid values: {}", VALID_KINDS),
+            )
+        })?;
+
+    let filter = NotificationFilter {
+        unread_only: query.unread.unwrap_or(false),
+        kind,
+        before: query.before,
+        limit: query.limit,
+    };
+
+    let notifications = Notification::find_for_user(&pool, user.user_id, filter).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(notifications)))
+}
+
+pub async fn unread_count(
+    pool: web::Data<DbPool>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Counting unread notifications for user: {}", user.user_id);
+
+    let unread = Notification::unread_count(&pool, user.user_id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(UnreadCount { unread })))
+}
+
+pub async fn mark_read(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let notification_id = path.into_inner();
+    info!(
+        "Marking notification {} read for user: {}",
+        notification_id, user.user_id
+    );
+
+    let notification = Notification::mark_read(&pool, user.user_id, notification_id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(notification)))
+}
+
+pub async fn mark_all_read(
+    pool: web::Data<DbPool>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Marking all notifications read for user: {}", user.user_id);
+
+    let marked = Notification::mark_all_read(&pool, user.user_id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success_with_message(
+        MarkedRead { marked },
+        format!("Marked {} notifications as read", marked),
+    )))
+}
+
+pub async fn preferences(
+    pool: web::Data<DbPool>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!(
+        "Getting notification preferences for user: {}",
+        user.user_id
+    );
+
+    let preferences = NotificationPreference::find_for_user(&pool, user.user_id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(preferences)))
+}
+
+pub async fn update_preferences(
+    pool: web::Data<DbPool>,
+    request: ValidJson<NotificationPreferencesRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!(
+        "Updating notification preferences for user: {}",
+        user.user_id
+    );
+
+    let mut preferences = Vec::with_capacity(request.preferences.len());
+    for (kind, enabled) in &request.preferences {
+        let kind = kind.parse::<NotificationKind>().map_err(|()| {
+            AppError::bad_request(
+                "invalid_kind",
+                format!("Invalid kind '{}'. Valid values: {}", kind, VALID_KINDS),
+            )
+        })?;
+        preferences.push(NotificationPreference {
+            kind,
+            enabled: *enabled,
+        });
+    }
+
+    let preferences = NotificationPreference::set(&pool, user.user_id, &preferences).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(preferences)))
+}
+
+pub async fn channels(
+    pool: web::Data<DbPool>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Getting notification channels for user: {}", user.user_id);
+
+    let channels = ChannelPreference::find_for_user(&pool, user.user_id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(channels)))
+}
+
+/// SMS and messaging can only be turned on once the user has a phone number on file.
+pub async fn update_channels(
+    pool: web::Data<DbPool>,
+    request: ValidJson<NotificationChannelsRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Updating notification channels for user: {}", user.user_id);
+
+    let mut channels = Vec::with_capacity(request.channels.len());
+    for (channel, enabled) in &request.channels {
+        let channel = channel.parse::<ChannelKind>().map_err(|()| {
+            AppError::bad_request(
+                "invalid_channel",
+                format!(
+                    "Invalid channel '{}'. Valid values: {}",
+                    channel, VALID_CHANNELS
+                ),
+            )
+        })?;
+        channels.push(ChannelPreference {
+            channel,
+            enabled: *enabled,
+        });
+    }
+
+    if channels
+        .iter()
+        .any(|preference| preference.enabled && preference.channel.needs_phone())
+    {
+        let phone = User::find_by_id(&pool, user.user_id)
+            .await?
+            .and_then(|account| account.phone);
+        if phone.as_deref().and_then(normalize_phone).is_none() {
+            return Err(AppError::bad_request(
+                "phone_required",
+                "Add a phone number in international format, e.g. +2348012345678, before turning on SMS or messaging",
+            ));
+        }
+    }
+
+    let channels = ChannelPreference::set(&pool, user.user_id, &channels).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(channels)))
+}
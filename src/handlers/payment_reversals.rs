@@ -0,0 +1,209 @@
+use crate::errors::AppError;
+use crate::models::payment_reversal::{
+    CreatePaymentReversal, PaymentReversal, PaymentReversalError, ReversalStatus,
+};
+use crate::models::user::UserRole;
+use crate::requests::payment_reversal::{
+    PaymentReversalRequest, ReversalsQuery, ReviewReversalRequest,
+};
+use crate::services::audit::{AuditContext, snapshot};
+use crate::utils::validation::{ValidJson, ValidQuery};
+use crate::{
+    database::connection::DbPool, middleware::auth::AuthenticatedUser, utils::helpers::ApiResponse,
+};
+use actix_web::{HttpResponse, web};
+use tracing::info;
+use uuid::Uuid;
+
+fn can_request(user: &AuthenticatedUser) -> bool {
+    matches!(
+        user.user_role,
+        UserRole::Treasurer | UserRole::Admin | UserRole::SuperAdmin
+    )
+}
+
+pub async fn request_reversal(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    request: ValidJson<PaymentReversalRequest>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    let payment_id = path.into_inner();
+    info!(
+        "Requesting {:?} of payment {} by user: {}",
+        request.entry_type, payment_id, user.user_id
+    );
+
+    if !can_request(&user) {
+        return Err(AppError::forbidden(
+            "Only treasurers and admins can refund or reverse payments",
+        ));
+    }
+
+    let create_reversal = CreatePaymentReversal {
+        payment_id,
+        entry_type: request.entry_type,
+        amount: request.amount,
+        reason: request.reason.trim().to_string(),
+        requested_by: user.user_id,
+    };
+
+    let mut tx = pool.begin().await?;
+    let reversal = PaymentReversal::request(&mut tx, create_reversal).await?;
+    info!(
+        "Created {:?} request {} for payment {}",
+        reversal.entry_type, reversal.id, payment_id
+    );
+    audit
+        .record(
+            &mut tx,
+            "payment_reversal.request",
+            "payment_reversal",
+            reversal.id,
+            None,
+            snapshot(&reversal),
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(
+        HttpResponse::Created().json(ApiResponse::success_with_message(
+            reversal,
+            "Awaiting treasurer approval".to_string(),
+        )),
+    )
+}
+
+pub async fn get_payment_ledger(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let payment_id = path.into_inner();
+    info!("Getting ledger for payment {}", payment_id);
+
+    let ledger = PaymentReversal::find_ledger(&pool, payment_id)
+        .await?
+        .ok_or(PaymentReversalError::PaymentNotFound { id: payment_id })?;
+    if ledger.payment.user_id != user.user_id && !can_request(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(ledger)))
+}
+
+pub async fn all(
+    pool: web::Data<DbPool>,
+    query: ValidQuery<ReversalsQuery>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Getting payment reversals");
+
+    if !can_request(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let status = query
+        .status
+        .as_deref()
+        .map(str::parse::<ReversalStatus>)
+        .transpose()
+        .map_err(|()| {
+            AppError::bad_request(
+                "invalid_status",
+                "Invalid status. Valid values: pending, approved, rejected",
+            )
+        })?;
+
+    let reversals = PaymentReversal::find_all(&pool, status).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(reversals)))
+}
+
+pub async fn approve(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    request: ValidJson<ReviewReversalRequest>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    let reversal_id = path.into_inner();
+    info!(
+        "Approving reversal {} by user: {}",
+        reversal_id, user.user_id
+    );
+
+    if user.user_role != UserRole::Treasurer {
+        return Err(AppError::forbidden(
+            "Only the treasurer can approve refunds and reversals",
+        ));
+    }
+
+    let before = PaymentReversal::find_by_id(&pool, reversal_id)
+        .await
+        .ok()
+        .flatten();
+
+    let mut tx = pool.begin().await?;
+    let reversal =
+        PaymentReversal::approve(&mut tx, reversal_id, user.user_id, request.note.clone()).await?;
+    info!(
+        "Approved reversal {} with compensating payment {:?}",
+        reversal.id, reversal.compensating_payment_id
+    );
+    audit
+        .record(
+            &mut tx,
+            "payment_reversal.approve",
+            "payment_reversal",
+            reversal_id,
+            before.as_ref().and_then(snapshot),
+            snapshot(&reversal),
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(reversal)))
+}
+
+pub async fn reject(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    request: ValidJson<ReviewReversalRequest>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    let reversal_id = path.into_inner();
+    info!(
+        "Rejecting reversal {} by user: {}",
+        reversal_id, user.user_id
+    );
+
+    if user.user_role != UserRole::Treasurer {
+        return Err(AppError::forbidden(
+            "Only the treasurer can reject refunds and reversals",
+        ));
+    }
+
+    let before = PaymentReversal::find_by_id(&pool, reversal_id)
+        .await
+        .ok()
+        .flatten();
+
+    let mut tx = pool.begin().await?;
+    let reversal =
+        PaymentReversal::reject(&mut tx, reversal_id, user.user_id, request.note.clone()).await?;
+    audit
+        .record(
+            &mut tx,
+            "payment_reversal.reject",
+            "payment_reversal",
+            reversal_id,
+            before.as_ref().and_then(snapshot),
+            snapshot(&reversal),
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(reversal)))
+}
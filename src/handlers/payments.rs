@@ -1,230 +1,648 @@
-use crate::models::payment::{CreatePayment, Payment, PaymentError, PaymentStatus, UpdatePayment};
+use crate::errors::AppError;
+use crate::handlers::campaigns::find_open;
+use crate::models::contribution::{Contribution, ContributionError};
+use crate::models::exchange_rate::{BASE_CURRENCY, ExchangeRate, normalize_currency};
+use crate::models::payment::{
+    CreatePayment, Payment, PaymentCheckout, PaymentEntryType, PaymentError, PaymentFilter,
+    PaymentStatus, UpdatePayment,
+};
+use crate::models::receipt::{PaymentReceipt, ReceiptError};
 use crate::models::user::UserRole;
-use crate::requests::payment::{PaymentRequest, UpdatePaymentRequest};
+use crate::requests::payment::{
+    InitializePaymentRequest, PaymentRequest, PaymentsQuery, UpdatePaymentRequest,
+};
+use crate::services::audit::{AuditContext, snapshot};
+use crate::services::notifications;
+use crate::services::payment_gateway::{GatewayEvent, PaymentGatewayService};
+use crate::services::receipt::{ReceiptService, issue_receipt};
+use crate::utils::pagination::ListQuery;
+use crate::utils::validation::ValidJson;
 use crate::{
     database::connection::DbPool, middleware::auth::AuthenticatedUser, utils::helpers::ApiResponse,
 };
-use actix_web::{HttpResponse, Result, web};
-use chrono::{DateTime, TimeZone, Utc};
-use tracing::{error, info};
+use actix_web::{HttpRequest, HttpResponse, http::header, web};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use serde_json::json;
+use tracing::{info, warn};
 use uuid::Uuid;
 
+fn can_manage_trash(user: &AuthenticatedUser) -> bool {
+    matches!(user.user_role, UserRole::Admin | UserRole::SuperAdmin)
+}
+
+/// Moving a payment to another member or contribution changes whose ledger it counts toward.
+fn can_reassign(user: &AuthenticatedUser) -> bool {
+    matches!(user.user_role, UserRole::Admin | UserRole::SuperAdmin)
+}
+
+fn can_verify(user: &AuthenticatedUser) -> bool {
+    matches!(
+        user.user_role,
+        UserRole::Treasurer | UserRole::Admin | UserRole::SuperAdmin
+    )
+}
+
+fn payment_filter(query: &PaymentsQuery) -> Result<PaymentFilter, AppError> {
+    let status = query
+        .status
+        .as_deref()
+        .map(str::parse::<PaymentStatus>)
+        .transpose()
+        .map_err(|()| {
+            AppError::bad_request(
+                "invalid_status",
+                "Invalid status. Valid values: pending, verified, failed",
+            )
+        })?;
+
+    Ok(PaymentFilter {
+        status,
+        user_id: query.user_id,
+        contribution_id: query.contribution_id,
+        campaign_id: query.campaign_id,
+        from: query.from,
+        to: query.to,
+    })
+}
+
+/// Audit action for a payment whose status became `status`.
+fn status_action(status: &PaymentStatus) -> &'static str {
+    match status {
+        PaymentStatus::Verified => "payment.verify",
+        PaymentStatus::Failed => "payment.reject",
+        PaymentStatus::Pending => "payment.update",
+    }
+}
+
 pub async fn create(
     pool: web::Data<DbPool>,
-    request: web::Json<PaymentRequest>,
+    request: ValidJson<PaymentRequest>,
     user: AuthenticatedUser,
-) -> Result<HttpResponse> {
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
     info!("Creating contribution for user: {}", user.user_id);
 
+    let currency = match request.currency.as_deref() {
+        Some(currency) => normalize_currency(currency)?,
+        None => BASE_CURRENCY.to_string(),
+    };
+
+    // Members can only record their own payments, and only for a treasurer to verify
+    let (user_id, status) = if can_verify(&user) {
+        (request.user_id, request.status.clone())
+    } else {
+        (user.user_id, PaymentStatus::Pending)
+    };
+
+    let is_anonymous = request.is_anonymous.unwrap_or(false);
+    if let Some(campaign_id) = request.campaign_id {
+        find_open(&pool, campaign_id, is_anonymous).await?;
+    }
+
     let create_payment = CreatePayment {
-        user_id: request.user_id.clone(),
-        contribution_id: request.contribution_id.clone(),
-        amount: request.amount.clone(),
+        user_id,
+        contribution_id: request.contribution_id,
+        campaign_id: request.campaign_id,
+        is_anonymous,
+        amount: request.amount,
+        currency,
         receipt_url: request.receipt_url.clone(),
-        status: request.status.clone(),
+        status,
+        reference: None,
+        gateway: None,
+        verified_by: Some(user.user_id),
     };
 
-    match Payment::create(&pool, create_payment).await {
-        Ok(payment) => {
-            info!("Successfully created payment with ID: {}", payment.id);
-            Ok(HttpResponse::Created().json(ApiResponse::success(payment)))
-        }
-        Err(PaymentError::Database(e)) => {
-            error!("Database error creating payment: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to create payment".to_string(),
-                )),
-            )
-        }
-        Err(e) => {
-            error!("Error creating payment: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
-        }
+    let mut tx = pool.begin().await?;
+    let payment = Payment::create(&mut tx, create_payment).await?;
+    audit
+        .record(
+            &mut tx,
+            "payment.create",
+            "payment",
+            payment.id,
+            None,
+            snapshot(&payment),
+        )
+        .await?;
+    tx.commit().await?;
+
+    info!("Successfully created payment with ID: {}", payment.id);
+    if payment.status == PaymentStatus::Verified {
+        issue_receipt(&pool, payment.id).await;
     }
+    notifications::notify_payment_status(&pool, &payment).await;
+    Ok(HttpResponse::Created().json(ApiResponse::success(payment)))
 }
 
-pub async fn get_payment(pool: web::Data<DbPool>, path: web::Path<Uuid>) -> Result<HttpResponse> {
+pub async fn get_payment(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
     let payment_id = path.into_inner();
     info!("Getting contribution {}", payment_id);
 
-    match Payment::find_by_id(&pool, payment_id).await {
-        Ok(Some(payment)) => Ok(HttpResponse::Ok().json(ApiResponse::success(payment))),
-        Ok(None) => Ok(HttpResponse::NotFound()
-            .json(ApiResponse::<()>::error("Payment not found".to_string()))),
-        Err(PaymentError::Database(e)) => {
-            error!("Database error getting payment: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to retrieve payment".to_string(),
-                )),
-            )
-        }
-        Err(e) => {
-            error!("Error getting payment: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
-        }
-    }
+    let payment = Payment::find_by_id(&pool, payment_id)
+        .await?
+        .ok_or(PaymentError::NotFound { id: payment_id })?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(payment)))
 }
 
+/// Takes the same filters as `all`, with the member fixed by the path.
 pub async fn get_user_payments(
     pool: web::Data<DbPool>,
     user_id: web::Path<Uuid>,
-) -> Result<HttpResponse> {
-    info!("Getting all contributions for user: {}", user_id);
+    query: ListQuery<PaymentsQuery>,
+) -> Result<HttpResponse, AppError> {
+    info!("Getting all payments for user: {}", user_id);
 
-    match Payment::find_by_user(&pool, *user_id).await {
-        Ok(payments) => Ok(HttpResponse::Ok().json(ApiResponse::success(payments))),
-        Err(PaymentError::Database(e)) => {
-            error!("Database error getting user payments: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to retrieve payments".to_string(),
-                )),
-            )
-        }
-        Err(e) => {
-            error!("Error getting user payments: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
-        }
-    }
+    let filter = PaymentFilter {
+        user_id: Some(*user_id),
+        ..payment_filter(&query.filter)?
+    };
+
+    let payments = Payment::find_page(&pool, &filter, &query.page).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::paginated(payments)))
 }
 
-pub async fn all(pool: web::Data<DbPool>) -> Result<HttpResponse> {
+pub async fn all(
+    pool: web::Data<DbPool>,
+    query: ListQuery<PaymentsQuery>,
+) -> Result<HttpResponse, AppError> {
     info!("Getting all payments");
 
-    match Payment::find_all(&pool).await {
-        Ok(payments) => Ok(HttpResponse::Ok().json(ApiResponse::success(payments))),
-        Err(PaymentError::Database(e)) => {
-            error!("Database error getting all payments: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to retrieve payments".to_string(),
-                )),
-            )
-        }
-        Err(e) => {
-            error!("Error getting all payments: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
-        }
-    }
+    let filter = payment_filter(&query.filter)?;
+    let payments = Payment::find_page(&pool, &filter, &query.page).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::paginated(payments)))
 }
 
 pub async fn update(
     pool: web::Data<DbPool>,
     path: web::Path<Uuid>,
-    request: web::Json<UpdatePaymentRequest>,
+    request: ValidJson<UpdatePaymentRequest>,
     user: AuthenticatedUser,
-) -> Result<HttpResponse> {
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
     let payment_id = path.into_inner();
     info!("Updating payment {} for user: {}", payment_id, user.user_id);
 
-    match Payment::find_by_id(&pool, payment_id).await {
-        Ok(Some(existing)) => {
-            if existing.user_id != user.user_id
-                && user.user_role != UserRole::Admin
-                && user.user_role != UserRole::SuperAdmin
-            {
-                return Ok(HttpResponse::Forbidden()
-                    .json(ApiResponse::<()>::error("Access denied".to_string())));
-            }
-        }
-        Ok(None) => {
-            return Ok(HttpResponse::NotFound()
-                .json(ApiResponse::<()>::error("Payment not found".to_string())));
-        }
-        Err(e) => {
-            error!("Error checking payment ownership: {}", e);
-            return Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to verify payment".to_string(),
-                )),
-            );
-        }
+    let existing = Payment::find_by_id(&pool, payment_id)
+        .await?
+        .ok_or(PaymentError::NotFound { id: payment_id })?;
+    if existing.user_id != user.user_id && !can_verify(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+    if request.status == Some(PaymentStatus::Verified) && !can_verify(&user) {
+        return Err(AppError::forbidden(
+            "Only treasurers and admins can verify payments",
+        ));
+    }
+    let reassigns = request.user_id.is_some_and(|id| id != existing.user_id)
+        || request
+            .contribution_id
+            .is_some_and(|id| existing.contribution_id != Some(id));
+    if reassigns && !can_reassign(&user) {
+        return Err(AppError::forbidden(
+            "Only admins can move a payment to another member or contribution",
+        ));
     }
 
     let update_data = UpdatePayment {
-        user_id: request.user_id.clone(),
-        contribution_id: request.contribution_id.clone(),
-        amount: request.amount.clone(),
+        user_id: request.user_id,
+        contribution_id: request.contribution_id,
+        amount: request.amount,
         receipt_url: request.receipt_url.as_ref().map(|url| Some(url.clone())),
         status: request.status.clone(),
+        verified_by: Some(user.user_id),
     };
 
-    match Payment::update(&pool, payment_id, update_data).await {
-        Ok(payment) => {
-            info!("Successfully updated payment: {}", payment_id);
-            Ok(HttpResponse::Ok().json(ApiResponse::success(payment)))
-        }
-        Err(PaymentError::NotFound { id }) => Ok(HttpResponse::NotFound().json(
-            ApiResponse::<()>::error(format!("Payment {} not found", id)),
-        )),
-        Err(PaymentError::NoUpdateFields) => Ok(HttpResponse::BadRequest().json(
-            ApiResponse::<()>::error("No fields provided for update".to_string()),
-        )),
-        Err(PaymentError::Database(e)) => {
-            error!("Database error updating payment: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to update payment".to_string(),
-                )),
+    let mut tx = pool.begin().await?;
+    let payment = Payment::update(&mut tx, payment_id, update_data).await?;
+    if let Some(updated) = &payment {
+        let action = if updated.status != existing.status {
+            status_action(&updated.status)
+        } else {
+            "payment.update"
+        };
+        audit
+            .record(
+                &mut tx,
+                action,
+                "payment",
+                payment_id,
+                snapshot(&existing),
+                snapshot(updated),
             )
+            .await?;
+    }
+    tx.commit().await?;
+
+    info!("Successfully updated payment: {}", payment_id);
+    if let Some(updated) = &payment {
+        if updated.status == PaymentStatus::Verified {
+            issue_receipt(&pool, payment_id).await;
         }
-        Err(e) => {
-            error!("Error updating payment: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
+        if updated.status != existing.status {
+            notifications::notify_payment_status(&pool, updated).await;
         }
     }
+    Ok(HttpResponse::Ok().json(ApiResponse::success(payment)))
 }
 
 pub async fn delete(
     pool: web::Data<DbPool>,
     path: web::Path<Uuid>,
     user: AuthenticatedUser,
-) -> Result<HttpResponse> {
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
     let payment_id = path.into_inner();
     info!("Deleting payment {} for user: {}", payment_id, user.user_id);
 
-    match Payment::find_by_id(&pool, payment_id).await {
-        Ok(Some(existing)) => {
-            if user.user_role != UserRole::Admin && user.user_role != UserRole::SuperAdmin {
-                return Ok(HttpResponse::Forbidden()
-                    .json(ApiResponse::<()>::error("Access denied".to_string())));
-            }
+    let existing = Payment::find_by_id(&pool, payment_id)
+        .await?
+        .ok_or(PaymentError::NotFound { id: payment_id })?;
+    if user.user_role != UserRole::Admin && user.user_role != UserRole::SuperAdmin {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let mut tx = pool.begin().await?;
+    Payment::delete(&mut tx, payment_id).await?;
+    info!("Successfully deleted payment: {}", payment_id);
+    audit
+        .record(
+            &mut tx,
+            "payment.delete",
+            "payment",
+            payment_id,
+            snapshot(&existing),
+            None,
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::<()>::success(())))
+}
+
+pub async fn deleted(
+    pool: web::Data<DbPool>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Getting deleted payments for user: {}", user.user_id);
+
+    if !can_manage_trash(&user) {
+        return Err(AppError::forbidden(
+            "Only admins can manage deleted payments",
+        ));
+    }
+
+    let payments = Payment::find_deleted(&pool).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(payments)))
+}
+
+pub async fn restore(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    let payment_id = path.into_inner();
+    info!(
+        "Restoring payment {} for user: {}",
+        payment_id, user.user_id
+    );
+
+    if !can_manage_trash(&user) {
+        return Err(AppError::forbidden("Only admins can restore payments"));
+    }
+
+    let mut tx = pool.begin().await?;
+    let payment = Payment::restore(&mut tx, payment_id).await?;
+    audit
+        .record(
+            &mut tx,
+            "payment.restore",
+            "payment",
+            payment_id,
+            None,
+            snapshot(&payment),
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(payment)))
+}
+
+/// Only payments that have already been deleted can be purged.
+pub async fn purge(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    let payment_id = path.into_inner();
+    info!("Purging payment {} for user: {}", payment_id, user.user_id);
+
+    if !can_manage_trash(&user) {
+        return Err(AppError::forbidden("Only admins can purge payments"));
+    }
+
+    let mut tx = pool.begin().await?;
+    Payment::purge(&mut tx, payment_id).await?;
+    audit
+        .record(&mut tx, "payment.purge", "payment", payment_id, None, None)
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::<()>::success(())))
+}
+
+pub async fn download_receipt(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let payment_id = path.into_inner();
+    info!(
+        "Downloading receipt for payment {} by user: {}",
+        payment_id, user.user_id
+    );
+
+    let payment = Payment::find_by_id(&pool, payment_id)
+        .await?
+        .ok_or(PaymentError::NotFound { id: payment_id })?;
+    if payment.user_id != user.user_id
+        && user.user_role != UserRole::Treasurer
+        && user.user_role != UserRole::Admin
+        && user.user_role != UserRole::SuperAdmin
+    {
+        return Err(AppError::forbidden("Access denied"));
+    }
+    if payment.status != PaymentStatus::Verified || payment.entry_type != PaymentEntryType::Payment
+    {
+        return Err(AppError::not_found(
+            "receipt_not_found",
+            "Receipts are only available for verified payments",
+        ));
+    }
+
+    // Payments verified before receipts existed get one issued on first download
+    PaymentReceipt::issue(&pool, payment_id).await?;
+    let details = PaymentReceipt::find_details(&pool, payment_id)
+        .await?
+        .ok_or(ReceiptError::NotFound { payment_id })?;
+
+    let pdf = ReceiptService::from_env().render_pdf(&details)?;
+    Ok(HttpResponse::Ok()
+        .content_type("application/pdf")
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"{}\"",
+                ReceiptService::filename(&details)
+            ),
+        ))
+        .body(pdf))
+}
+
+pub async fn initialize(
+    pool: web::Data<DbPool>,
+    request: ValidJson<InitializePaymentRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!(
+        "Initializing gateway payment for contribution {} by user: {}",
+        request.contribution_id, user.user_id
+    );
+
+    let gateway = PaymentGatewayService::new()?;
+
+    let contribution = Contribution::find_by_id(&pool, request.contribution_id)
+        .await?
+        .ok_or(ContributionError::NotFound {
+            id: request.contribution_id,
+        })?;
+
+    let amount = match request.amount.or(contribution.amount) {
+        Some(amount) if amount > Decimal::ZERO => amount,
+        _ => {
+            return Err(AppError::bad_request(
+                "invalid_amount",
+                "A positive payment amount is required",
+            ));
         }
-        Ok(None) => {
-            return Ok(HttpResponse::NotFound()
-                .json(ApiResponse::<()>::error("Payment not found".to_string())));
+    };
+
+    let currency = match request.currency.as_deref() {
+        Some(currency) => normalize_currency(currency)?,
+        None => contribution.currency,
+    };
+
+    start_checkout(
+        &pool,
+        &gateway,
+        &user,
+        amount,
+        currency,
+        PaymentTarget::Contribution(contribution.id),
+        false,
+    )
+    .await
+}
+
+/// What a gateway checkout pays towards.
+pub enum PaymentTarget {
+    Contribution(Uuid),
+    Campaign(Uuid),
+}
+
+/// Opens a gateway checkout session and records the pending payment it will settle.
+pub async fn start_checkout(
+    pool: &DbPool,
+    gateway: &PaymentGatewayService,
+    user: &AuthenticatedUser,
+    amount: Decimal,
+    currency: String,
+    target: PaymentTarget,
+    is_anonymous: bool,
+) -> Result<HttpResponse, AppError> {
+    let (contribution_id, campaign_id) = match target {
+        PaymentTarget::Contribution(id) => (Some(id), None),
+        PaymentTarget::Campaign(id) => (None, Some(id)),
+    };
+
+    // Check the rate before opening a session so a missing rate never strands a checkout.
+    let mut conn = pool.acquire().await?;
+    ExchangeRate::rate_on(&mut conn, &currency, Utc::now().date_naive()).await?;
+
+    let reference = PaymentGatewayService::generate_reference();
+    let metadata = json!({
+        "user_id": user.user_id,
+        "contribution_id": contribution_id,
+        "campaign_id": campaign_id,
+    });
+
+    let session = gateway
+        .initialize_transaction(&user.email, amount, &currency, &reference, metadata)
+        .await?;
+
+    let create_payment = CreatePayment {
+        user_id: user.user_id,
+        contribution_id,
+        campaign_id,
+        is_anonymous,
+        amount: Some(amount),
+        currency,
+        receipt_url: None,
+        status: PaymentStatus::Pending,
+        reference: Some(session.reference),
+        gateway: Some(gateway.name().to_string()),
+        verified_by: None,
+    };
+
+    let payment = Payment::create(&mut conn, create_payment).await?;
+    info!("Created pending gateway payment with ID: {}", payment.id);
+    Ok(
+        HttpResponse::Created().json(ApiResponse::success(PaymentCheckout {
+            payment,
+            authorization_url: session.authorization_url,
+        })),
+    )
+}
+
+pub async fn webhook(
+    pool: web::Data<DbPool>,
+    req: HttpRequest,
+    body: web::Bytes,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    let gateway = PaymentGatewayService::new()?;
+
+    let signature = req
+        .headers()
+        .get(gateway.signature_header())
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or_default();
+
+    if !gateway.verify_signature(&body, signature) {
+        warn!("Rejected payment webhook with invalid signature");
+        return Err(AppError::unauthorized(
+            "invalid_signature",
+            "Invalid webhook signature",
+        ));
+    }
+
+    let payload: serde_json::Value = serde_json::from_slice(&body).map_err(|e| {
+        AppError::bad_request("invalid_json", format!("Malformed webhook payload: {}", e))
+    })?;
+
+    let event: GatewayEvent = serde_json::from_value(payload.clone()).map_err(|e| {
+        AppError::bad_request(
+            "invalid_webhook_payload",
+            format!("Unrecognised webhook payload: {}", e),
+        )
+    })?;
+
+    info!(
+        "Received {} webhook for reference: {}",
+        event.event, event.data.reference
+    );
+
+    // Unknown references are acknowledged, as the gateway would otherwise keep retrying them
+    let Some(payment) = Payment::find_by_reference(&pool, &event.data.reference).await? else {
+        warn!(
+            "Ignoring {} webhook for unknown reference: {}",
+            event.event, event.data.reference
+        );
+        return Ok(
+            HttpResponse::Ok().json(ApiResponse::<()>::success_with_message(
+                (),
+                "Unknown payment reference".to_string(),
+            )),
+        );
+    };
+
+    let status = match event.event.as_str() {
+        "charge.success" => {
+            if !payment
+                .amount
+                .is_some_and(|amount| event.data.settles(amount, &payment.currency))
+            {
+                // Left pending and unrecorded so a treasurer can reconcile it manually
+                warn!(
+                    "Charge for payment {} does not match: expected {:?} {}, received {} {:?}",
+                    payment.id,
+                    payment.amount,
+                    payment.currency,
+                    event.data.amount,
+                    event.data.currency
+                );
+                return Ok(
+                    HttpResponse::Ok().json(ApiResponse::<()>::success_with_message(
+                        (),
+                        "Charge does not match the payment and was left for review".to_string(),
+                    )),
+                );
+            }
+            PaymentStatus::Verified
         }
-        Err(e) => {
-            error!("Error checking payment ownership: {}", e);
-            return Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to verify payment".to_string(),
-                )),
-            );
+        "charge.failed" => PaymentStatus::Failed,
+        other => {
+            info!("Ignoring unsupported payment webhook event: {}", other);
+            return Ok(HttpResponse::Ok().json(ApiResponse::<()>::success(())));
         }
-    }
+    };
 
-    match Payment::delete(&pool, payment_id).await {
-        Ok(()) => {
-            info!("Successfully deleted payment: {}", payment_id);
-            Ok(HttpResponse::Ok().json(ApiResponse::<()>::success(())))
+    let mut tx = pool.begin().await?;
+    match Payment::apply_gateway_event(
+        &mut tx,
+        gateway.name(),
+        &event.event,
+        &event.data.reference,
+        payload,
+        status,
+    )
+    .await
+    {
+        Ok(Some(updated)) => {
+            audit
+                .record(
+                    &mut tx,
+                    status_action(&updated.status),
+                    "payment",
+                    updated.id,
+                    snapshot(&payment),
+                    snapshot(&updated),
+                )
+                .await?;
+            tx.commit().await?;
+
+            info!("Payment {} is now {:?}", updated.id, updated.status);
+            if updated.status == PaymentStatus::Verified {
+                issue_receipt(&pool, updated.id).await;
+            }
+            notifications::notify_payment_status(&pool, &updated).await;
+            Ok(HttpResponse::Ok().json(ApiResponse::success(updated)))
         }
-        Err(PaymentError::NotFound { id }) => Ok(HttpResponse::NotFound().json(
-            ApiResponse::<()>::error(format!("Payment {} not found", id)),
-        )),
-        Err(PaymentError::Database(e)) => {
-            error!("Database error deleting payment: {}", e);
+        Ok(None) => {
+            tx.commit().await?;
             Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to delete payment".to_string(),
+                HttpResponse::Ok().json(ApiResponse::<()>::success_with_message(
+                    (),
+                    "Payment already settled".to_string(),
                 )),
             )
         }
-        Err(e) => {
-            error!("Error deleting payment: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
+        // Gateways retry deliveries, so a repeat is acknowledged rather than an error
+        Err(PaymentError::DuplicateGatewayEvent { event, reference }) => {
+            info!("Skipping duplicate {} webhook for: {}", event, reference);
+            Ok(
+                HttpResponse::Ok().json(ApiResponse::<()>::success_with_message(
+                    (),
+                    "Event already processed".to_string(),
+                )),
+            )
         }
+        Err(e) => Err(e.into()),
     }
 }
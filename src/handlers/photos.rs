@@ -1,207 +1,292 @@
-use crate::models::photo::{CreatePhoto, Photo, PhotoError, UpdatePhoto};
-use crate::models::user::UserRole;
-use crate::requests::photo::{CreatePhotoRequest, UpdatePhotoRequest};
+use crate::errors::AppError;
+use crate::models::photo::{CreatePhoto, Photo, PhotoError, PhotoFilter, UpdatePhoto};
+use crate::models::photo_tag::{PhotoTag, PhotoTagError};
+use crate::models::user::{User, UserRole};
+use crate::requests::photo::{
+    CreatePhotoRequest, PhotoTagRequest, PhotosQuery, UpdatePhotoRequest,
+};
+use crate::services::audit::{AuditContext, snapshot};
+use crate::services::notifications;
+use crate::utils::pagination::ListQuery;
+use crate::utils::validation::ValidJson;
 use crate::{
     database::connection::DbPool, middleware::auth::AuthenticatedUser, utils::helpers::ApiResponse,
 };
-use actix_web::{HttpResponse, Result, web};
-use chrono::{DateTime, TimeZone, Utc};
-use tracing::{error, info};
+use actix_web::{HttpResponse, web};
+use tracing::info;
 use uuid::Uuid;
 
+fn can_manage_trash(user: &AuthenticatedUser) -> bool {
+    matches!(user.user_role, UserRole::Admin | UserRole::SuperAdmin)
+}
+
+/// Loads a photo the user may change: its poster or an admin.
+async fn find_managed(
+    pool: &DbPool,
+    photo_id: Uuid,
+    user: &AuthenticatedUser,
+) -> Result<Photo, AppError> {
+    let existing = Photo::find_by_id(pool, photo_id)
+        .await?
+        .ok_or(PhotoError::NotFound { id: photo_id })?;
+    if existing.posted_by != user.user_id
+        && user.user_role != UserRole::Admin
+        && user.user_role != UserRole::SuperAdmin
+    {
+        return Err(AppError::forbidden("Access denied"));
+    }
+    Ok(existing)
+}
+
 pub async fn create(
     pool: web::Data<DbPool>,
-    request: web::Json<CreatePhotoRequest>,
+    request: ValidJson<CreatePhotoRequest>,
     user: AuthenticatedUser,
-) -> Result<HttpResponse> {
+) -> Result<HttpResponse, AppError> {
     info!("Creating photo for user: {}", user.user_id);
 
     let create_photo = CreatePhoto {
         caption: request.caption.clone(),
         url: request.url.clone(),
-        event_id: request.event_id.clone(),
+        event_id: request.event_id,
         posted_by: user.user_id,
     };
 
-    match Photo::create(&pool, create_photo).await {
-        Ok(photo) => {
-            info!("Successfully created photo with ID: {}", photo.id);
-            Ok(HttpResponse::Created().json(ApiResponse::success(photo)))
-        }
-        Err(PhotoError::Database(e)) => {
-            error!("Database error creating photo: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to create photo".to_string(),
-                )),
-            )
-        }
-        Err(e) => {
-            error!("Error creating photo: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
-        }
-    }
+    let photo = Photo::create(&pool, create_photo).await?;
+    info!("Successfully created photo with ID: {}", photo.id);
+    Ok(HttpResponse::Created().json(ApiResponse::success(photo)))
 }
 
-pub async fn get_photo(pool: web::Data<DbPool>, path: web::Path<Uuid>) -> Result<HttpResponse> {
+pub async fn get_photo(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
     let photo_id = path.into_inner();
     info!("Getting photo {}", photo_id);
 
-    match Photo::find_by_id(&pool, photo_id).await {
-        Ok(Some(photo)) => Ok(HttpResponse::Ok().json(ApiResponse::success(photo))),
-        Ok(None) => {
-            Ok(HttpResponse::NotFound()
-                .json(ApiResponse::<()>::error("Photo not found".to_string())))
-        }
-        Err(PhotoError::Database(e)) => {
-            error!("Database error getting photo: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to retrieve photo".to_string(),
-                )),
-            )
-        }
-        Err(e) => {
-            error!("Error getting photo: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
-        }
-    }
+    let photo = Photo::find_by_id(&pool, photo_id)
+        .await?
+        .ok_or(PhotoError::NotFound { id: photo_id })?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(photo)))
 }
 
-pub async fn all(pool: web::Data<DbPool>) -> Result<HttpResponse> {
+pub async fn all(
+    pool: web::Data<DbPool>,
+    query: ListQuery<PhotosQuery>,
+) -> Result<HttpResponse, AppError> {
     info!("Getting all photos");
 
-    match Photo::find_all(&pool).await {
-        Ok(photos) => Ok(HttpResponse::Ok().json(ApiResponse::success(photos))),
-        Err(PhotoError::Database(e)) => {
-            error!("Database error getting all photos: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to retrieve photos".to_string(),
-                )),
-            )
-        }
-        Err(e) => {
-            error!("Error getting all photos: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
-        }
-    }
+    let filter = PhotoFilter {
+        event_id: query.filter.event_id,
+        posted_by: query.filter.posted_by,
+    };
+
+    let photos = Photo::find_page(&pool, &filter, &query.page).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::paginated(photos)))
 }
 
 pub async fn update(
     pool: web::Data<DbPool>,
     path: web::Path<Uuid>,
-    request: web::Json<UpdatePhotoRequest>,
+    request: ValidJson<UpdatePhotoRequest>,
     user: AuthenticatedUser,
-) -> Result<HttpResponse> {
+) -> Result<HttpResponse, AppError> {
     let photo_id = path.into_inner();
     info!("Updating photo {} for user: {}", photo_id, user.user_id);
 
-    match Photo::find_by_id(&pool, photo_id).await {
-        Ok(Some(existing)) => {
-            if existing.posted_by != user.user_id
-                && user.user_role != UserRole::Admin
-                && user.user_role != UserRole::SuperAdmin
-            {
-                return Ok(HttpResponse::Forbidden()
-                    .json(ApiResponse::<()>::error("Access denied".to_string())));
-            }
-        }
-        Ok(None) => {
-            return Ok(HttpResponse::NotFound()
-                .json(ApiResponse::<()>::error("Photo not found".to_string())));
-        }
-        Err(e) => {
-            error!("Error checking photo ownership: {}", e);
-            return Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to verify photo".to_string(),
-                )),
-            );
-        }
-    }
+    find_managed(&pool, photo_id, &user).await?;
 
     let update_data = UpdatePhoto {
         caption: Some(request.caption.clone()),
         url: request.url.clone(),
-        event_id: Some(request.event_id.clone()),
+        event_id: Some(request.event_id),
     };
 
-    match Photo::update(&pool, photo_id, update_data).await {
-        Ok(photo) => {
-            info!("Successfully updated photo: {}", photo_id);
-            Ok(HttpResponse::Ok().json(ApiResponse::success(photo)))
-        }
-        Err(PhotoError::NotFound { id }) => Ok(HttpResponse::NotFound()
-            .json(ApiResponse::<()>::error(format!("Photo {} not found", id)))),
-        Err(PhotoError::NoUpdateFields) => Ok(HttpResponse::BadRequest().json(
-            ApiResponse::<()>::error("No fields provided for update".to_string()),
-        )),
-        Err(PhotoError::Database(e)) => {
-            error!("Database error updating photo: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to update photo".to_string(),
-                )),
-            )
-        }
-        Err(e) => {
-            error!("Error updating photo: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
-        }
-    }
+    let photo = Photo::update(&pool, photo_id, update_data).await?;
+    info!("Successfully updated photo: {}", photo_id);
+    Ok(HttpResponse::Ok().json(ApiResponse::success(photo)))
 }
 
 pub async fn delete(
     pool: web::Data<DbPool>,
     path: web::Path<Uuid>,
     user: AuthenticatedUser,
-) -> Result<HttpResponse> {
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
     let photo_id = path.into_inner();
     info!("Deleting photo {} for user: {}", photo_id, user.user_id);
 
-    match Photo::find_by_id(&pool, photo_id).await {
-        Ok(Some(existing)) => {
-            if existing.posted_by != user.user_id
-                && user.user_role != UserRole::Admin
-                && user.user_role != UserRole::SuperAdmin
-            {
-                return Ok(HttpResponse::Forbidden()
-                    .json(ApiResponse::<()>::error("Access denied".to_string())));
-            }
-        }
-        Ok(None) => {
-            return Ok(HttpResponse::NotFound()
-                .json(ApiResponse::<()>::error("Photo not found".to_string())));
-        }
-        Err(e) => {
-            error!("Error checking photo ownership: {}", e);
-            return Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to verify photo".to_string(),
-                )),
-            );
-        }
+    let existing = find_managed(&pool, photo_id, &user).await?;
+
+    let mut tx = pool.begin().await?;
+    Photo::delete(&mut tx, photo_id).await?;
+    info!("Successfully deleted photo: {}", photo_id);
+    audit
+        .record(
+            &mut tx,
+            "photo.delete",
+            "photo",
+            photo_id,
+            snapshot(&existing),
+            None,
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::<()>::success(())))
+}
+
+pub async fn deleted(
+    pool: web::Data<DbPool>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Getting deleted photos for user: {}", user.user_id);
+
+    if !can_manage_trash(&user) {
+        return Err(AppError::forbidden("Only admins can manage deleted photos"));
     }
 
-    match Photo::delete(&pool, photo_id).await {
-        Ok(()) => {
-            info!("Successfully deleted photo: {}", photo_id);
-            Ok(HttpResponse::Ok().json(ApiResponse::<()>::success(())))
-        }
-        Err(PhotoError::NotFound { id }) => Ok(HttpResponse::NotFound()
-            .json(ApiResponse::<()>::error(format!("Photo {} not found", id)))),
-        Err(PhotoError::Database(e)) => {
-            error!("Database error deleting photo: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to delete photo".to_string(),
-                )),
-            )
-        }
-        Err(e) => {
-            error!("Error deleting photo: {}", e);
-            Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
-        }
+    let photos = Photo::find_deleted(&pool).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(photos)))
+}
+
+pub async fn restore(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    let photo_id = path.into_inner();
+    info!("Restoring photo {} for user: {}", photo_id, user.user_id);
+
+    if !can_manage_trash(&user) {
+        return Err(AppError::forbidden("Only admins can restore photos"));
     }
+
+    let mut tx = pool.begin().await?;
+    let photo = Photo::restore(&mut tx, photo_id).await?;
+    audit
+        .record(
+            &mut tx,
+            "photo.restore",
+            "photo",
+            photo_id,
+            None,
+            snapshot(&photo),
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(photo)))
+}
+
+pub async fn purge(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    let photo_id = path.into_inner();
+    info!("Purging photo {} for user: {}", photo_id, user.user_id);
+
+    if !can_manage_trash(&user) {
+        return Err(AppError::forbidden("Only admins can purge photos"));
+    }
+
+    let mut tx = pool.begin().await?;
+    Photo::purge(&mut tx, photo_id).await?;
+    audit
+        .record(&mut tx, "photo.purge", "photo", photo_id, None, None)
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::<()>::success(())))
+}
+
+pub async fn tags(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, AppError> {
+    let photo_id = path.into_inner();
+    info!("Getting tags for photo {}", photo_id);
+
+    Photo::find_by_id(&pool, photo_id)
+        .await?
+        .ok_or(PhotoError::NotFound { id: photo_id })?;
+
+    let tags = PhotoTag::find_by_photo(&pool, photo_id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(tags)))
+}
+
+/// Any member can tag someone; the tagged member is notified unless they tagged themselves.
+pub async fn add_tag(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    request: ValidJson<PhotoTagRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let photo_id = path.into_inner();
+    info!(
+        "Tagging user {} in photo {} by user: {}",
+        request.user_id, photo_id, user.user_id
+    );
+
+    Photo::find_by_id(&pool, photo_id)
+        .await?
+        .ok_or(PhotoError::NotFound { id: photo_id })?;
+
+    let tag = PhotoTag::add(&pool, photo_id, request.user_id, user.user_id)
+        .await?
+        .ok_or_else(|| {
+            AppError::conflict("already_tagged", "User is already tagged in this photo")
+        })?;
+    if tag.user_id != user.user_id {
+        let tagged_by = match User::find_by_id(&pool, user.user_id).await {
+            Ok(Some(tagger)) => tagger.fullname,
+            _ => "Someone".to_string(),
+        };
+        notifications::notify_photo_tag(&pool, photo_id, tag.user_id, &tagged_by).await;
+    }
+    Ok(HttpResponse::Created().json(ApiResponse::success(tag)))
+}
+
+/// The tagged member, whoever tagged them, the photo's poster and admins can remove a tag.
+pub async fn remove_tag(
+    pool: web::Data<DbPool>,
+    path: web::Path<(Uuid, Uuid)>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let (photo_id, tagged_user_id) = path.into_inner();
+    info!(
+        "Removing tag of user {} from photo {} by user: {}",
+        tagged_user_id, photo_id, user.user_id
+    );
+
+    let tag_not_found = || PhotoTagError::NotFound {
+        photo_id,
+        user_id: tagged_user_id,
+    };
+    let photo = Photo::find_by_id(&pool, photo_id)
+        .await?
+        .ok_or_else(tag_not_found)?;
+    let tag = PhotoTag::find(&pool, photo_id, tagged_user_id)
+        .await?
+        .ok_or_else(tag_not_found)?;
+
+    if tag.user_id != user.user_id
+        && tag.tagged_by != Some(user.user_id)
+        && photo.posted_by != user.user_id
+        && user.user_role != UserRole::Admin
+        && user.user_role != UserRole::SuperAdmin
+    {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    PhotoTag::remove(&pool, photo_id, tagged_user_id).await?;
+    Ok(
+        HttpResponse::Ok().json(ApiResponse::<()>::success_with_message(
+            (),
+            "Photo tag removed".to_string(),
+        )),
+    )
 }
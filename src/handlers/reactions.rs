@@ -0,0 +1,132 @@
+use crate::errors::AppError;
+use crate::handlers::comments::check_target;
+use crate::models::comment::ContentTarget;
+use crate::models::reaction::Reaction;
+use crate::requests::reaction::ReactionRequest;
+use crate::utils::validation::ValidJson;
+use crate::{
+    database::connection::DbPool, middleware::auth::AuthenticatedUser, utils::helpers::ApiResponse,
+};
+use actix_web::{HttpResponse, web};
+use tracing::info;
+use uuid::Uuid;
+
+async fn summary(
+    pool: &DbPool,
+    target: ContentTarget,
+    user: &AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let summary = Reaction::summary(pool, target, user.user_id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(summary)))
+}
+
+async fn list(
+    pool: &DbPool,
+    target: ContentTarget,
+    user: &AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!(
+        "Getting reactions on {:?} for user: {}",
+        target, user.user_id
+    );
+
+    check_target(pool, target, user).await?;
+
+    summary(pool, target, user).await
+}
+
+/// Returns the updated summary so clients can redraw the reaction bar.
+async fn add(
+    pool: &DbPool,
+    target: ContentTarget,
+    emoji: &str,
+    user: &AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!(
+        "Reacting {} to {:?} for user: {}",
+        emoji, target, user.user_id
+    );
+
+    check_target(pool, target, user).await?;
+
+    Reaction::add(pool, target, user.user_id, emoji.trim()).await?;
+    summary(pool, target, user).await
+}
+
+async fn remove(
+    pool: &DbPool,
+    target: ContentTarget,
+    emoji: &str,
+    user: &AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!(
+        "Removing reaction {} from {:?} for user: {}",
+        emoji, target, user.user_id
+    );
+
+    check_target(pool, target, user).await?;
+
+    if !Reaction::remove(pool, target, user.user_id, emoji).await? {
+        return Err(AppError::not_found(
+            "reaction_not_found",
+            "Reaction not found",
+        ));
+    }
+    summary(pool, target, user).await
+}
+
+pub async fn announcement_reactions(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    list(&pool, ContentTarget::Announcement(path.into_inner()), &user).await
+}
+
+pub async fn add_announcement_reaction(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    request: ValidJson<ReactionRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let target = ContentTarget::Announcement(path.into_inner());
+    add(&pool, target, &request.emoji, &user).await
+}
+
+pub async fn remove_announcement_reaction(
+    pool: web::Data<DbPool>,
+    path: web::Path<(Uuid, String)>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let (announcement_id, emoji) = path.into_inner();
+    let target = ContentTarget::Announcement(announcement_id);
+    remove(&pool, target, &emoji, &user).await
+}
+
+pub async fn photo_reactions(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    list(&pool, ContentTarget::Photo(path.into_inner()), &user).await
+}
+
+pub async fn add_photo_reaction(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    request: ValidJson<ReactionRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let target = ContentTarget::Photo(path.into_inner());
+    add(&pool, target, &request.emoji, &user).await
+}
+
+pub async fn remove_photo_reaction(
+    pool: web::Data<DbPool>,
+    path: web::Path<(Uuid, String)>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let (photo_id, emoji) = path.into_inner();
+    let target = ContentTarget::Photo(photo_id);
+    remove(&pool, target, &emoji, &user).await
+}
@@ -0,0 +1,120 @@
+use crate::errors::AppError;
+use crate::handlers::announcements::can_see_all_announcements;
+use crate::models::user::User;
+use crate::services::realtime::{PushEvent, RealtimeHub};
+use crate::{database::connection::DbPool, middleware::auth::AuthenticatedUser};
+use actix_web::{HttpResponse, http::header, web, web::Bytes};
+use futures_util::stream;
+use serde_json::json;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Idle streams get a comment this often so proxies keep them open and dead ones are noticed.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// Whether the account behind a stream may keep it open. A failed lookup keeps it open; the
+/// next check will try again.
+async fn still_active(pool: &DbPool, user_id: Uuid) -> bool {
+    match User::find_by_id(pool, user_id).await {
+        Ok(user) => user.is_some_and(|user| user.is_active),
+        Err(e) => {
+            warn!("Failed to check real-time stream user {}: {}", user_id, e);
+            true
+        }
+    }
+}
+
+fn frame(event: &str, data: &str) -> Bytes {
+    Bytes::from(format!("event: {}\ndata: {}\n\n", event, data))
+}
+
+fn event_frame(event: &PushEvent) -> Option<Bytes> {
+    match serde_json::to_string(event) {
+        Ok(data) => Some(frame(event.name(), &data)),
+        Err(e) => {
+            error!("Failed to serialise {} event: {}", event.name(), e);
+            None
+        }
+    }
+}
+
+/// Server-sent events for the caller: `notification`, `payment` and `announcement` carry the
+/// same JSON as the REST endpoints, and `resync` means events were dropped and the client
+/// should refetch. The stream closes once the account is deactivated or deleted.
+pub async fn stream(
+    pool: web::Data<DbPool>,
+    hub: web::Data<RealtimeHub>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Opening real-time stream for user: {}", user.user_id);
+
+    let all_audiences = can_see_all_announcements(&user);
+    let state = (hub.subscribe(), pool, user, Instant::now());
+    let events = stream::unfold(
+        state,
+        move |(mut receiver, pool, user, mut checked_at)| async move {
+            loop {
+                if checked_at.elapsed() >= KEEP_ALIVE {
+                    if !still_active(&pool, user.user_id).await {
+                        info!(
+                            "Closing real-time stream for inactive user: {}",
+                            user.user_id
+                        );
+                        return None;
+                    }
+                    checked_at = Instant::now();
+                }
+
+                let bytes = match tokio::time::timeout(KEEP_ALIVE, receiver.recv()).await {
+                    Err(_) => Bytes::from_static(b": keep-alive\n\n"),
+                    Ok(Ok(signal)) => {
+                        match signal.resolve(&pool, user.user_id, all_audiences).await {
+                            Ok(Some(event)) => match event_frame(&event) {
+                                Some(bytes) => bytes,
+                                None => continue,
+                            },
+                            Ok(None) => continue,
+                            Err(e) => {
+                                error!("Failed to load real-time event {:?}: {}", signal, e);
+                                continue;
+                            }
+                        }
+                    }
+                    Ok(Err(RecvError::Lagged(skipped))) => {
+                        warn!(
+                            "Real-time stream for user {} dropped {} events",
+                            user.user_id, skipped
+                        );
+                        frame("resync", &json!({ "skipped": skipped }).to_string())
+                    }
+                    Ok(Err(RecvError::Closed)) => return None,
+                };
+                return Some((
+                    Ok::<_, actix_web::Error>(bytes),
+                    (receiver, pool, user, checked_at),
+                ));
+            }
+        },
+    );
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(events))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_follow_the_event_stream_format() {
+        assert_eq!(
+            frame("resync", "{\"skipped\":3}"),
+            Bytes::from_static(b"event: resync\ndata: {\"skipped\":3}\n\n")
+        );
+    }
+}
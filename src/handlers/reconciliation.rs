@@ -0,0 +1,313 @@
+use crate::errors::AppError;
+use crate::models::bank_statement::{
+    BankStatement, BankStatementError, BankStatementLine, CreateStatementLine, StatementImport,
+    StatementLineStatus, StatementMatchMethod,
+};
+use crate::models::payment::Payment;
+use crate::models::user::{User, UserRole};
+use crate::requests::reconciliation::{
+    ImportStatementQuery, MatchStatementLineRequest, StatementLinesQuery,
+};
+use crate::services::audit::{AuditContext, snapshot};
+use crate::services::notifications;
+use crate::services::receipt::issue_receipt;
+use crate::services::reconciliation::{LineMatch, find_match, parse_statement};
+use crate::utils::validation::{ValidJson, ValidQuery};
+use crate::{
+    database::connection::DbPool, middleware::auth::AuthenticatedUser, utils::helpers::ApiResponse,
+};
+use actix_web::{HttpResponse, web};
+use std::collections::HashSet;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+fn can_reconcile(user: &AuthenticatedUser) -> bool {
+    matches!(
+        user.user_role,
+        UserRole::Treasurer | UserRole::Admin | UserRole::SuperAdmin
+    )
+}
+
+/// Issues the receipt and tells the member once a statement line's match has verified their
+/// payment and been committed.
+async fn payment_verified(pool: &DbPool, payment: &Payment) {
+    issue_receipt(pool, payment.id).await;
+    notifications::notify_payment_status(pool, payment).await;
+}
+
+pub async fn import_statement(
+    pool: web::Data<DbPool>,
+    query: ValidQuery<ImportStatementQuery>,
+    body: web::Bytes,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    info!("Importing bank statement for user: {}", user.user_id);
+
+    if !can_reconcile(&user) {
+        return Err(AppError::forbidden(
+            "Only treasurers and admins can import bank statements",
+        ));
+    }
+
+    let transactions = parse_statement(&body)?;
+
+    let pending = Payment::find_pending(&pool).await?;
+    let users = User::find_all(&pool).await?;
+
+    let mut claimed = HashSet::new();
+    let mut lines = Vec::with_capacity(transactions.len());
+
+    for transaction in transactions {
+        let line_match = find_match(&transaction, &pending, &users, &claimed);
+
+        if let LineMatch::Payment { payment_id, .. } = line_match {
+            claimed.insert(payment_id);
+        }
+
+        lines.push(CreateStatementLine {
+            transaction_date: transaction.transaction_date,
+            narration: transaction.narration,
+            reference: transaction.reference,
+            amount: transaction.amount,
+            suggested_user_id: match line_match {
+                LineMatch::Member { user_id } => Some(user_id),
+                _ => None,
+            },
+            payment_match: match line_match {
+                LineMatch::Payment { payment_id, method } => Some((payment_id, method)),
+                _ => None,
+            },
+        });
+    }
+
+    let planned: Vec<Option<Uuid>> = lines
+        .iter()
+        .map(|line| line.payment_match.map(|(payment_id, _)| payment_id))
+        .collect();
+    let mut tx = pool.begin().await?;
+    let (statement, lines, verified) =
+        BankStatement::create(&mut tx, user.user_id, query.filename.clone(), lines).await?;
+
+    for payment in &verified {
+        let before = pending.iter().find(|pending| pending.id == payment.id);
+        audit
+            .record(
+                &mut tx,
+                "payment.verify",
+                "payment",
+                payment.id,
+                before.and_then(snapshot),
+                snapshot(payment),
+            )
+            .await?;
+    }
+    tx.commit().await?;
+
+    for (line, planned) in lines.iter().zip(planned) {
+        if let (None, Some(payment_id)) = (line.payment_id, planned) {
+            warn!(
+                "Left statement line {} unmatched: payment {} is no longer pending",
+                line.id, payment_id
+            );
+        }
+    }
+    for payment in &verified {
+        payment_verified(&pool, payment).await;
+    }
+
+    let matched = lines
+        .iter()
+        .filter(|line| line.status == StatementLineStatus::Matched)
+        .count();
+
+    info!(
+        "Imported bank statement {} with {} of {} lines matched",
+        statement.id,
+        matched,
+        lines.len()
+    );
+
+    Ok(
+        HttpResponse::Created().json(ApiResponse::success(StatementImport {
+            statement,
+            matched,
+            unmatched: lines.len() - matched,
+            lines,
+        })),
+    )
+}
+
+pub async fn all(
+    pool: web::Data<DbPool>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Getting all bank statements");
+
+    if !can_reconcile(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let statements = BankStatement::find_all(&pool).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(statements)))
+}
+
+pub async fn get_statement_lines(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    query: ValidQuery<StatementLinesQuery>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let statement_id = path.into_inner();
+    info!("Getting lines for bank statement {}", statement_id);
+
+    if !can_reconcile(&user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let status = query
+        .status
+        .as_deref()
+        .map(str::parse::<StatementLineStatus>)
+        .transpose()
+        .map_err(|()| {
+            AppError::bad_request(
+                "invalid_status",
+                "Invalid status. Valid values: unmatched, matched, ignored",
+            )
+        })?;
+
+    if BankStatement::find_by_id(&pool, statement_id)
+        .await?
+        .is_none()
+    {
+        return Err(AppError::not_found(
+            "statement_not_found",
+            "Bank statement not found",
+        ));
+    }
+
+    let lines = BankStatementLine::find_by_statement(&pool, statement_id, status).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(lines)))
+}
+
+pub async fn match_line(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    request: ValidJson<MatchStatementLineRequest>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    let line_id = path.into_inner();
+    info!(
+        "Matching statement line {} by user: {}",
+        line_id, user.user_id
+    );
+
+    if !can_reconcile(&user) {
+        return Err(AppError::forbidden(
+            "Only treasurers and admins can reconcile payments",
+        ));
+    }
+
+    let line = BankStatementLine::find_by_id(&pool, line_id)
+        .await?
+        .ok_or(BankStatementError::LineNotFound { id: line_id })?;
+
+    let mut before = None;
+    let mut tx = pool.begin().await?;
+    let (line, payment) = match (request.payment_id, request.user_id, request.contribution_id) {
+        (Some(payment_id), None, None) => {
+            before = Payment::find_by_id(&pool, payment_id).await?;
+            BankStatementLine::match_payment(
+                &mut tx,
+                line.id,
+                payment_id,
+                user.user_id,
+                StatementMatchMethod::Manual,
+            )
+            .await?
+        }
+        (None, user_id, Some(contribution_id)) => {
+            let Some(member_id) = user_id.or(line.suggested_user_id) else {
+                return Err(AppError::bad_request(
+                    "member_required",
+                    "A member is required to match this line",
+                ));
+            };
+
+            BankStatementLine::match_member(
+                &mut tx,
+                line.id,
+                member_id,
+                contribution_id,
+                user.user_id,
+            )
+            .await?
+        }
+        _ => {
+            return Err(AppError::bad_request(
+                "invalid_match",
+                "Provide either payment_id, or contribution_id with an optional user_id",
+            ));
+        }
+    };
+
+    audit
+        .record(
+            &mut tx,
+            "payment.verify",
+            "payment",
+            payment.id,
+            before.as_ref().and_then(snapshot),
+            snapshot(&payment),
+        )
+        .await?;
+    tx.commit().await?;
+
+    info!(
+        "Statement line {} matched to payment {}",
+        line.id, payment.id
+    );
+    payment_verified(&pool, &payment).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(line)))
+}
+
+pub async fn ignore_line(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    let line_id = path.into_inner();
+    info!(
+        "Ignoring statement line {} by user: {}",
+        line_id, user.user_id
+    );
+
+    if !can_reconcile(&user) {
+        return Err(AppError::forbidden(
+            "Only treasurers and admins can reconcile payments",
+        ));
+    }
+
+    let existing = BankStatementLine::find_by_id(&pool, line_id)
+        .await?
+        .ok_or(BankStatementError::LineNotFound { id: line_id })?;
+
+    let mut tx = pool.begin().await?;
+    let line = BankStatementLine::ignore(&mut tx, line_id, user.user_id).await?;
+    audit
+        .record(
+            &mut tx,
+            "bank_statement_line.ignore",
+            "bank_statement_line",
+            line_id,
+            snapshot(&existing),
+            snapshot(&line),
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(line)))
+}
@@ -0,0 +1,172 @@
+use crate::errors::AppError;
+use crate::models::report::{Report, ReportPeriod};
+use crate::models::user::UserRole;
+use crate::requests::report::ReportQuery;
+use crate::services::report_export::{
+    CollectionsReport, DefaultersReport, ExportFormat, MonthlyTrendReport, ReportTable, to_csv,
+    to_xlsx,
+};
+use crate::utils::validation::ValidQuery;
+use crate::{
+    database::connection::DbPool, middleware::auth::AuthenticatedUser, utils::helpers::ApiResponse,
+};
+use actix_web::{HttpResponse, http::header, web};
+use chrono::{Datelike, NaiveDate, Utc};
+use serde::Serialize;
+use tracing::info;
+
+const DEFAULT_DEFAULTERS_LIMIT: i64 = 10;
+
+fn can_view_reports(user: &AuthenticatedUser) -> bool {
+    matches!(
+        user.user_role,
+        UserRole::Treasurer | UserRole::Admin | UserRole::SuperAdmin
+    )
+}
+
+/// Resolves the requested period (the current calendar year by default) and format.
+fn parse_query(
+    query: &ReportQuery,
+    user: &AuthenticatedUser,
+) -> Result<(ReportPeriod, ExportFormat), AppError> {
+    if !can_view_reports(user) {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let year = Utc::now().year();
+    let from = query
+        .from
+        .or_else(|| NaiveDate::from_ymd_opt(year, 1, 1))
+        .unwrap_or_default();
+    let to = query
+        .to
+        .or_else(|| NaiveDate::from_ymd_opt(year, 12, 31))
+        .unwrap_or_default();
+
+    let period = ReportPeriod::new(from, to)?;
+
+    let format = query
+        .format
+        .as_deref()
+        .map(str::parse::<ExportFormat>)
+        .transpose()
+        .map_err(|()| {
+            AppError::bad_request(
+                "invalid_format",
+                "Invalid format. Valid values: json, csv, xlsx",
+            )
+        })?
+        .unwrap_or(ExportFormat::Json);
+
+    Ok((period, format))
+}
+
+fn report_response<T: Serialize>(
+    name: &str,
+    period: ReportPeriod,
+    format: ExportFormat,
+    data: &T,
+    table: &impl ReportTable,
+) -> Result<HttpResponse, AppError> {
+    let content = match format {
+        ExportFormat::Json => return Ok(HttpResponse::Ok().json(ApiResponse::success(data))),
+        ExportFormat::Csv => to_csv(table)?,
+        ExportFormat::Xlsx => to_xlsx(table)?,
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"{}-{}-{}.{}\"",
+                name,
+                period.from,
+                period.to,
+                format.extension()
+            ),
+        ))
+        .body(content))
+}
+
+pub async fn collections(
+    pool: web::Data<DbPool>,
+    query: ValidQuery<ReportQuery>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let (period, format) = parse_query(&query, &user)?;
+    info!(
+        "Generating collections report for {} to {}",
+        period.from, period.to
+    );
+
+    let rows = Report::collections(&pool, period).await?;
+    report_response(
+        "collections",
+        period,
+        format,
+        &rows,
+        &CollectionsReport(period, &rows),
+    )
+}
+
+pub async fn defaulters(
+    pool: web::Data<DbPool>,
+    query: ValidQuery<ReportQuery>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let (period, format) = parse_query(&query, &user)?;
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_DEFAULTERS_LIMIT)
+        .clamp(1, 500);
+    info!(
+        "Generating defaulters report for {} to {}",
+        period.from, period.to
+    );
+
+    let rows = Report::defaulters(&pool, period, limit).await?;
+    report_response(
+        "defaulters",
+        period,
+        format,
+        &rows,
+        &DefaultersReport(period, &rows),
+    )
+}
+
+pub async fn monthly_trend(
+    pool: web::Data<DbPool>,
+    query: ValidQuery<ReportQuery>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let (period, format) = parse_query(&query, &user)?;
+    info!(
+        "Generating monthly trend report for {} to {}",
+        period.from, period.to
+    );
+
+    let rows = Report::monthly_trend(&pool, period).await?;
+    report_response(
+        "monthly-trend",
+        period,
+        format,
+        &rows,
+        &MonthlyTrendReport(period, &rows),
+    )
+}
+
+pub async fn income_statement(
+    pool: web::Data<DbPool>,
+    query: ValidQuery<ReportQuery>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let (period, format) = parse_query(&query, &user)?;
+    info!(
+        "Generating income statement for {} to {}",
+        period.from, period.to
+    );
+
+    let statement = Report::income_statement(&pool, period).await?;
+    report_response("income-statement", period, format, &statement, &statement)
+}
@@ -1,57 +1,360 @@
+use crate::errors::AppError;
 use crate::middleware::auth::AuthenticatedUser;
-use crate::models::user::{UserError, UserRole};
+use crate::models::email_unsubscribe::{ANNOUNCEMENTS_LIST, EmailUnsubscribe};
+use crate::models::user::{UserError, UserFilter, UserRole};
+use crate::requests::user::{
+    AnnouncementEmailPreferenceRequest, DuesReminderPreferenceRequest, PhoneRequest,
+    UpdateRoleRequest, UsersQuery,
+};
+use crate::services::audit::{AuditContext, snapshot};
+use crate::services::notification_channels::normalize_phone;
+use crate::services::notifications;
+use crate::utils::pagination::ListQuery;
+use crate::utils::validation::ValidJson;
 use crate::{database::connection::DbPool, models::user::User, utils::helpers::ApiResponse};
-use actix_web::{HttpResponse, Result, web};
-use tracing::error;
+use actix_web::{HttpResponse, web};
 use tracing::log::info;
 use uuid::Uuid;
 
-pub async fn index(pool: web::Data<DbPool>) -> Result<HttpResponse> {
-    let users = User::find_all(&pool).await.map_err(|e| {
-        error!("Failed to fetch users: {}", e);
-        actix_web::error::ErrorInternalServerError("Failed to fetch users")
-    })?;
+fn can_manage_trash(user: &AuthenticatedUser) -> bool {
+    matches!(user.user_role, UserRole::Admin | UserRole::SuperAdmin)
+}
 
-    Ok(HttpResponse::Ok().json(ApiResponse::success(users)))
+fn invalid_role() -> AppError {
+    AppError::bad_request(
+        "invalid_role",
+        "Invalid role. Valid values: super_admin, admin, member, treasurer",
+    )
+}
+
+pub async fn index(
+    pool: web::Data<DbPool>,
+    query: ListQuery<UsersQuery>,
+) -> Result<HttpResponse, AppError> {
+    let role = query
+        .filter
+        .role
+        .as_deref()
+        .map(str::parse::<UserRole>)
+        .transpose()
+        .map_err(|()| invalid_role())?;
+
+    let filter = UserFilter {
+        role,
+        is_active: query.filter.active,
+        search: query.filter.search.clone(),
+    };
+
+    let users = User::find_page(&pool, &filter, &query.page).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::paginated(users)))
 }
 
 pub async fn toggle_user_active(
     pool: web::Data<DbPool>,
     path: web::Path<Uuid>,
     user: AuthenticatedUser,
-) -> Result<HttpResponse> {
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
     let target_user_id = path.into_inner();
     info!("Toggling active status for user: {}", target_user_id);
 
     if user.user_role != UserRole::SuperAdmin && user.user_role != UserRole::Admin {
-        return Ok(HttpResponse::Forbidden().json(ApiResponse::<()>::error(
-            "You don't have permission to perform this action".to_string(),
-        )));
+        return Err(AppError::forbidden(
+            "You don't have permission to perform this action",
+        ));
+    }
+
+    if target_user_id == user.user_id {
+        return Err(AppError::bad_request(
+            "cannot_modify_self",
+            "You cannot deactivate your own account",
+        ));
+    }
+
+    let before = User::find_by_id(&pool, target_user_id).await.ok().flatten();
+
+    let mut tx = pool.begin().await?;
+    let updated_user = User::toggle_active(&mut tx, target_user_id).await?;
+    info!(
+        "User {} active status toggled to {} by {}",
+        target_user_id, updated_user.is_active, user.user_id
+    );
+    audit
+        .record(
+            &mut tx,
+            if updated_user.is_active {
+                "user.activate"
+            } else {
+                "user.deactivate"
+            },
+            "user",
+            target_user_id,
+            before.as_ref().and_then(snapshot),
+            snapshot(&updated_user),
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(updated_user)))
+}
+
+/// Admins manage members and treasurers; only super admins can grant or take away admin roles.
+pub async fn update_role(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    request: ValidJson<UpdateRoleRequest>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    let target_user_id = path.into_inner();
+    info!(
+        "Changing role of user {} to {} by {}",
+        target_user_id, request.role, user.user_id
+    );
+
+    if user.user_role != UserRole::SuperAdmin && user.user_role != UserRole::Admin {
+        return Err(AppError::forbidden(
+            "You don't have permission to perform this action",
+        ));
+    }
+
+    if target_user_id == user.user_id {
+        return Err(AppError::bad_request(
+            "cannot_modify_self",
+            "You cannot change your own role",
+        ));
+    }
+
+    let role = request
+        .role
+        .parse::<UserRole>()
+        .map_err(|()| invalid_role())?;
+
+    let existing = User::find_by_id(&pool, target_user_id)
+        .await?
+        .ok_or(UserError::NotFound { id: target_user_id })?;
+
+    let is_admin_role = |role: &UserRole| matches!(role, UserRole::Admin | UserRole::SuperAdmin);
+    if user.user_role != UserRole::SuperAdmin
+        && (is_admin_role(&role) || is_admin_role(&existing.user_role))
+    {
+        return Err(AppError::forbidden(
+            "Only super admins can grant or remove admin roles",
+        ));
+    }
+
+    if existing.user_role == role {
+        return Ok(HttpResponse::Ok().json(ApiResponse::success(existing)));
+    }
+
+    let mut tx = pool.begin().await?;
+    let updated_user = User::set_role(&mut tx, target_user_id, role).await?;
+    info!(
+        "User {} role changed to {:?} by {}",
+        target_user_id, updated_user.user_role, user.user_id
+    );
+    audit
+        .record(
+            &mut tx,
+            "user.role_change",
+            "user",
+            target_user_id,
+            snapshot(&existing),
+            snapshot(&updated_user),
+        )
+        .await?;
+    tx.commit().await?;
+
+    notifications::notify_role_change(&pool, target_user_id, updated_user.user_role.clone()).await;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(updated_user)))
+}
+
+/// Admins remove members and treasurers; only super admins can remove admins.
+pub async fn delete(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    let target_user_id = path.into_inner();
+    info!("Deleting user {} by {}", target_user_id, user.user_id);
+
+    if !can_manage_trash(&user) {
+        return Err(AppError::forbidden(
+            "You don't have permission to perform this action",
+        ));
     }
 
     if target_user_id == user.user_id {
-        return Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(
-            "You cannot deactivate your own account".to_string(),
-        )));
-    }
-
-    match User::toggle_active(&pool, target_user_id).await {
-        Ok(updated_user) => {
-            info!(
-                "User {} active status toggled to {} by {}",
-                target_user_id, updated_user.is_active, user.user_id
-            );
-            Ok(HttpResponse::Ok().json(ApiResponse::success(updated_user)))
-        }
-        Err(UserError::NotFound { id }) => Ok(HttpResponse::NotFound()
-            .json(ApiResponse::<()>::error(format!("User {} not found", id)))),
-        Err(e) => {
-            error!("Failed to toggle active status: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                    "Failed to update user status".to_string(),
-                )),
+        return Err(AppError::bad_request(
+            "cannot_modify_self",
+            "You cannot delete your own account",
+        ));
+    }
+
+    let existing = User::find_by_id(&pool, target_user_id)
+        .await?
+        .ok_or(UserError::NotFound { id: target_user_id })?;
+
+    if user.user_role != UserRole::SuperAdmin
+        && matches!(existing.user_role, UserRole::Admin | UserRole::SuperAdmin)
+    {
+        return Err(AppError::forbidden("Only super admins can delete admins"));
+    }
+
+    let mut tx = pool.begin().await?;
+    let deleted_user = User::delete(&mut tx, target_user_id).await?;
+    info!("User {} deleted by {}", target_user_id, user.user_id);
+    audit
+        .record(
+            &mut tx,
+            "user.delete",
+            "user",
+            target_user_id,
+            snapshot(&existing),
+            snapshot(&deleted_user),
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::<()>::success(())))
+}
+
+pub async fn deleted(
+    pool: web::Data<DbPool>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Getting deleted users for user: {}", user.user_id);
+
+    if !can_manage_trash(&user) {
+        return Err(AppError::forbidden("Only admins can manage deleted users"));
+    }
+
+    let users = User::find_deleted(&pool).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(users)))
+}
+
+/// Restored accounts are active again only if they were active when deleted.
+pub async fn restore(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    let target_user_id = path.into_inner();
+    info!(
+        "Restoring user {} for user: {}",
+        target_user_id, user.user_id
+    );
+
+    if !can_manage_trash(&user) {
+        return Err(AppError::forbidden("Only admins can restore users"));
+    }
+
+    let mut tx = pool.begin().await?;
+    let restored_user = User::restore(&mut tx, target_user_id).await?;
+    audit
+        .record(
+            &mut tx,
+            "user.restore",
+            "user",
+            target_user_id,
+            None,
+            snapshot(&restored_user),
+        )
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(restored_user)))
+}
+
+/// Only deleted accounts without payments on record can be purged.
+pub async fn purge(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    audit: AuditContext,
+) -> Result<HttpResponse, AppError> {
+    let target_user_id = path.into_inner();
+    info!("Purging user {} for user: {}", target_user_id, user.user_id);
+
+    if !can_manage_trash(&user) {
+        return Err(AppError::forbidden("Only admins can purge users"));
+    }
+
+    let mut tx = pool.begin().await?;
+    User::purge(&mut tx, target_user_id).await?;
+    audit
+        .record(&mut tx, "user.purge", "user", target_user_id, None, None)
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiResponse::<()>::success(())))
+}
+
+/// Stored in international format, which SMS and messaging notifications need.
+pub async fn update_phone(
+    pool: web::Data<DbPool>,
+    request: ValidJson<PhoneRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!("Updating phone number for user: {}", user.user_id);
+
+    let phone = match request.phone.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(phone) => Some(normalize_phone(phone).ok_or_else(|| {
+            AppError::bad_request(
+                "invalid_phone",
+                "Invalid phone number. Use international format, e.g. +2348012345678",
             )
-        }
+        })?),
+    };
+
+    let updated_user = User::set_phone(&pool, user.user_id, phone).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(updated_user)))
+}
+
+pub async fn update_dues_reminders(
+    pool: web::Data<DbPool>,
+    request: ValidJson<DuesReminderPreferenceRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!(
+        "Setting dues reminders to {} for user: {}",
+        request.enabled, user.user_id
+    );
+
+    let updated_user = User::set_dues_reminders(&pool, user.user_id, request.enabled).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(updated_user)))
+}
+
+pub async fn update_announcement_emails(
+    pool: web::Data<DbPool>,
+    request: ValidJson<AnnouncementEmailPreferenceRequest>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    info!(
+        "Setting announcement emails to {} for user: {}",
+        request.enabled, user.user_id
+    );
+
+    if request.enabled {
+        EmailUnsubscribe::resubscribe(&pool, user.user_id, ANNOUNCEMENTS_LIST).await?;
+    } else {
+        EmailUnsubscribe::unsubscribe(&pool, user.user_id, ANNOUNCEMENTS_LIST).await?;
     }
+
+    Ok(
+        HttpResponse::Ok().json(ApiResponse::<()>::success_with_message(
+            (),
+            format!(
+                "Announcement emails {}",
+                if request.enabled {
+                    "enabled"
+                } else {
+                    "disabled"
+                }
+            ),
+        )),
+    )
 }
@@ -1,15 +1,14 @@
 // use crate::services::auth::AuthService;
-use actix::Actor;
 use actix_cors::Cors;
 use actix_web::{App, HttpServer, http, middleware::Logger, web};
 use config::settings::AppConfig;
 use database::connection::{create_pool, run_migrations};
 use dotenv::dotenv;
 use tracing::{error, info};
-use tracing_subscriber;
 
 mod config;
 mod database;
+mod errors;
 mod handlers;
 
 mod middleware;
@@ -29,7 +28,10 @@ async fn main() -> std::io::Result<()> {
         std::process::exit(1);
     });
 
-    info!("Starting server with config: {:?}", config);
+    info!(
+        "Starting server in {} environment with config: {:?}",
+        config.environment, config
+    );
 
     let pool = create_pool(&config.database).await.unwrap_or_else(|e| {
         error!("Failed to create database pool: {}", e);
@@ -45,6 +47,17 @@ async fn main() -> std::io::Result<()> {
 
     info!("Database migrations completed successfully");
 
+    services::jobs::JobRunner::new(pool.clone()).start();
+
+    // Emails stay queued in the outbox until the transport is configured
+    match services::email_outbox::OutboxDispatcher::new(pool.clone()) {
+        Ok(dispatcher) => dispatcher.start(),
+        Err(e) => error!("Email outbox dispatcher not started: {}", e),
+    }
+
+    let realtime_hub = services::realtime::RealtimeHub::new();
+    realtime_hub.start(pool.clone());
+
     let server_host = config.server.host.clone();
     let server_port = config.server.port;
 
@@ -74,8 +87,13 @@ async fn main() -> std::io::Result<()> {
             .wrap(cors)
             .app_data(web::Data::new(pool.clone()))
             .app_data(web::Data::new(config.clone()))
+            .app_data(web::Data::new(realtime_hub.clone()))
+            .app_data(web::JsonConfig::default().error_handler(errors::json_error_handler))
+            .app_data(web::QueryConfig::default().error_handler(errors::query_error_handler))
+            .app_data(web::PathConfig::default().error_handler(errors::path_error_handler))
             // .app_data(web::Data::from(auth_service.clone()))
             .wrap(Logger::default())
+            .wrap(middleware::request_id::RequestIdMiddleware)
             .service(web::scope("/api/v1").configure(routes::api::scoped_config))
     })
     .bind((server_host, server_port))?
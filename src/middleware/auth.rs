@@ -1,9 +1,11 @@
-use crate::models::user::UserRole;
+use crate::database::connection::DbPool;
+use crate::errors::AppError;
+use crate::models::user::{User, UserRole};
 use crate::services::auth::AuthService;
 use actix_web::{
     Error, HttpMessage,
     dev::{Service, ServiceRequest, ServiceResponse, Transform, forward_ready},
-    error::ErrorUnauthorized,
+    web,
 };
 use futures_util::future::{LocalBoxFuture, Ready, ready};
 use std::rc::Rc;
@@ -77,21 +79,43 @@ where
             let token = match auth_header {
                 Some(token) => token,
                 None => {
-                    return Err(ErrorUnauthorized("Missing or invalid authorization header"));
+                    return Err(AppError::unauthorized(
+                        "missing_token",
+                        "Missing or invalid authorization header",
+                    )
+                    .into());
                 }
             };
 
             // Validate the token
             let auth_service = AuthService::new()
-                .map_err(|_| ErrorUnauthorized("Authentication service error"))?;
+                .map_err(|e| AppError::internal(format!("Authentication service error: {}", e)))?;
 
             let claims = auth_service
                 .validate_token(token)
-                .map_err(|_| ErrorUnauthorized("Invalid token"))?;
+                .map_err(|_| AppError::unauthorized("invalid_token", "Invalid token"))?;
+
+            // Tokens outlive deactivation, deletion and role changes, so check the account as
+            // it is now rather than trusting the claims
+            let pool = req
+                .app_data::<web::Data<DbPool>>()
+                .ok_or_else(|| AppError::internal("Database pool not configured"))?;
+            let user = User::find_by_id(pool, claims.sub)
+                .await
+                .map_err(AppError::from)?
+                .ok_or_else(|| {
+                    AppError::unauthorized("invalid_token", "Account no longer exists")
+                })?;
+            if !user.is_active {
+                return Err(AppError::Forbidden {
+                    code: "account_inactive",
+                    message: "Account is not active".to_string(),
+                }
+                .into());
+            }
 
             // Create authenticated user and add to request extensions
-            let authenticated_user =
-                AuthenticatedUser::new(claims.sub, claims.email, claims.user_role);
+            let authenticated_user = AuthenticatedUser::new(user.id, user.email, user.user_role);
             req.extensions_mut().insert(authenticated_user);
 
             // Continue with the request
@@ -101,13 +125,16 @@ where
 }
 
 impl actix_web::FromRequest for AuthenticatedUser {
-    type Error = Error;
+    type Error = AppError;
     type Future = futures_util::future::Ready<Result<Self, Self::Error>>;
 
     fn from_request(req: &actix_web::HttpRequest, _: &mut actix_web::dev::Payload) -> Self::Future {
         match req.extensions().get::<AuthenticatedUser>() {
             Some(user) => futures_util::future::ready(Ok(user.clone())),
-            None => futures_util::future::ready(Err(ErrorUnauthorized("User not authenticated"))),
+            None => futures_util::future::ready(Err(AppError::unauthorized(
+                "missing_token",
+                "User not authenticated",
+            ))),
         }
     }
 }
@@ -1,7 +1,12 @@
 use crate::database::connection::DbPool;
+use crate::models::announcement_attachment::AnnouncementAttachment;
+use crate::models::user::UserRole;
+use crate::utils::markdown;
+use crate::utils::pagination::{Page, PageRequest};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{FromRow, PgConnection, Type};
+use std::str::FromStr;
 use thiserror::Error;
 use uuid::Uuid;
 
@@ -13,6 +18,31 @@ pub enum AnnouncementError {
     Database(#[from] sqlx::Error),
     #[error("No fields provided for update")]
     NoUpdateFields,
+    #[error("An announcement must expire after it is published")]
+    InvalidSchedule,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[sqlx(type_name = "announcement_audience", rename_all = "lowercase")]
+pub enum AnnouncementAudience {
+    Everyone,
+    Role,
+    Users,
+    Committee,
+}
+
+impl FromStr for AnnouncementAudience {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "everyone" => Ok(AnnouncementAudience::Everyone),
+            "role" => Ok(AnnouncementAudience::Role),
+            "users" => Ok(AnnouncementAudience::Users),
+            "committee" => Ok(AnnouncementAudience::Committee),
+            _ => Err(()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -20,9 +50,80 @@ pub struct Announcement {
     pub id: Uuid,
     pub posted_by: Uuid,
     pub title: String,
+    /// Markdown.
     pub body: Option<String>,
+    /// `body` rendered to sanitised HTML.
+    pub body_html: Option<String>,
+    pub audience: AnnouncementAudience,
+    pub audience_role: Option<UserRole>,
+    pub audience_user_ids: Vec<Uuid>,
+    pub committee_id: Option<Uuid>,
+    pub publish_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub is_draft: bool,
+    pub is_pinned: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// An announcement as listed to a user, with whether they have read it and how much
+/// discussion it has.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct AnnouncementListing {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub announcement: Announcement,
+    pub is_read: bool,
+    pub comment_count: i64,
+    pub reaction_count: i64,
+}
+
+/// A single announcement with its attachments.
+#[derive(Debug, Clone, Serialize)]
+pub struct AnnouncementDetail {
+    #[serde(flatten)]
+    pub announcement: Announcement,
+    pub attachments: Vec<AnnouncementAttachment>,
+}
+
+/// Which announcements to list. Unpublished ones are drafts, scheduled or expired posts.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AnnouncementFilter {
+    /// Skip the audience check, for admins.
+    pub all_audiences: bool,
+    /// Include unpublished posts: every one when `all_audiences`, otherwise the user's own.
+    pub include_unpublished: bool,
+    pub pinned: Option<bool>,
+}
+
+/// Who an announcement is for. Only the field matching `audience` is used.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnouncementTarget {
+    pub audience: AnnouncementAudience,
+    pub audience_role: Option<UserRole>,
+    pub audience_user_ids: Vec<Uuid>,
+    pub committee_id: Option<Uuid>,
+}
+
+impl Default for AnnouncementTarget {
+    fn default() -> Self {
+        Self {
+            audience: AnnouncementAudience::Everyone,
+            audience_role: None,
+            audience_user_ids: Vec::new(),
+            committee_id: None,
+        }
+    }
+}
+
+/// An active, verified member in an announcement's audience who has not opted out of
+/// announcement emails and has not been emailed it yet.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct AnnouncementRecipient {
+    pub user_id: Uuid,
+    pub fullname: String,
+    pub email: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,30 +131,69 @@ pub struct CreateAnnouncement {
     pub posted_by: Uuid,
     pub title: String,
     pub body: Option<String>,
+    pub target: AnnouncementTarget,
+    pub publish_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub is_draft: bool,
+    pub is_pinned: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateAnnouncement {
     pub title: Option<String>,
-    pub body: Option<String>,
+    pub body: Option<Option<String>>,
+    pub target: Option<AnnouncementTarget>,
+    pub publish_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<Option<DateTime<Utc>>>,
+    pub is_draft: Option<bool>,
+    pub is_pinned: Option<bool>,
+}
+
+fn check_schedule(
+    publish_at: DateTime<Utc>,
+    expires_at: Option<DateTime<Utc>>,
+) -> Result<(), AnnouncementError> {
+    match expires_at {
+        Some(expires_at) if expires_at <= publish_at => Err(AnnouncementError::InvalidSchedule),
+        _ => Ok(()),
+    }
 }
 
+/// SQL for whether an announcement is live right now.
+const LIVE: &str =
+    "(NOT is_draft AND publish_at <= NOW() AND (expires_at IS NULL OR expires_at > NOW())
+     AND deleted_at IS NULL)";
+
 impl Announcement {
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
     pub async fn create(
         pool: &DbPool,
         announcement: CreateAnnouncement,
     ) -> Result<Self, AnnouncementError> {
+        check_schedule(announcement.publish_at, announcement.expires_at)?;
         let now = Utc::now();
 
         let announcement = sqlx::query_as::<_, Announcement>(
-            "INSERT INTO announcements (id, title, body, posted_by, created_at, updated_at) 
-             VALUES ($1, $2, $3, $4, $5, $6) 
+            "INSERT INTO announcements (id, title, body, body_html, posted_by, audience, audience_role, audience_user_ids, committee_id, publish_at, expires_at, is_draft, is_pinned, created_at, updated_at) 
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15) 
              RETURNING *",
         )
         .bind(Uuid::new_v4())
         .bind(announcement.title)
-        .bind(announcement.body)
+        .bind(announcement.body.clone())
+        .bind(announcement.body.as_deref().map(markdown::render))
         .bind(announcement.posted_by)
+        .bind(announcement.target.audience)
+        .bind(announcement.target.audience_role)
+        .bind(announcement.target.audience_user_ids)
+        .bind(announcement.target.committee_id)
+        .bind(announcement.publish_at)
+        .bind(announcement.expires_at)
+        .bind(announcement.is_draft)
+        .bind(announcement.is_pinned)
         .bind(now)
         .bind(now)
         .fetch_one(pool)
@@ -63,23 +203,155 @@ impl Announcement {
     }
 
     pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, AnnouncementError> {
-        let announcement =
-            sqlx::query_as::<_, Announcement>("SELECT * FROM announcements WHERE id = $1")
-                .bind(id)
-                .fetch_optional(pool)
-                .await?;
+        let announcement = sqlx::query_as::<_, Announcement>(
+            "SELECT * FROM announcements WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
 
         Ok(announcement)
     }
 
-    pub async fn find_all(pool: &DbPool) -> Result<Vec<Self>, AnnouncementError> {
-        let announcements = sqlx::query_as::<_, Announcement>(
-            "SELECT * FROM announcements ORDER BY created_at DESC",
+    /// Live announcements `user_id` is in the audience for or posted, a page at a time.
+    pub async fn find_visible(
+        pool: &DbPool,
+        user_id: Uuid,
+        filter: &AnnouncementFilter,
+        page: &PageRequest,
+    ) -> Result<Page<AnnouncementListing>, AnnouncementError> {
+        let announcements = page
+            .fetch(pool, |query| {
+                query
+                    .push(
+                        "SELECT a.*, EXISTS (
+                             SELECT 1 FROM announcement_reads r
+                             WHERE r.announcement_id = a.id AND r.user_id = ",
+                    )
+                    .push_bind(user_id)
+                    .push(
+                        ") AS is_read,
+                         (SELECT COUNT(*) FROM comments c
+                          WHERE c.announcement_id = a.id AND c.deleted_at IS NULL AND c.hidden_at IS NULL
+                         ) AS comment_count,
+                         (SELECT COUNT(*) FROM reactions x WHERE x.announcement_id = a.id) AS reaction_count
+                         FROM announcements a
+                         WHERE deleted_at IS NULL",
+                    );
+                if !filter.all_audiences {
+                    query
+                        .push(" AND (posted_by = ")
+                        .push_bind(user_id)
+                        .push(" OR in_announcement_audience(id, ")
+                        .push_bind(user_id)
+                        .push("))");
+                }
+                match (filter.include_unpublished, filter.all_audiences) {
+                    (true, true) => {}
+                    (true, false) => {
+                        query
+                            .push(format!(" AND ({LIVE} OR posted_by = "))
+                            .push_bind(user_id)
+                            .push(")");
+                    }
+                    (false, _) => {
+                        query.push(format!(" AND {LIVE}"));
+                    }
+                }
+                if let Some(pinned) = filter.pinned {
+                    query.push(" AND is_pinned = ").push_bind(pinned);
+                }
+            })
+            .await?;
+
+        Ok(announcements)
+    }
+
+    pub async fn is_visible_to(
+        pool: &DbPool,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<bool, AnnouncementError> {
+        let visible: Option<bool> = sqlx::query_scalar(&format!(
+            "SELECT posted_by = $2 OR ({LIVE} AND COALESCE(in_announcement_audience(id, $2), false))
+             FROM announcements WHERE id = $1 AND deleted_at IS NULL"
+        ))
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(visible.unwrap_or(false))
+    }
+
+    /// Live announcements in the user's audience, other than their own, they have not read.
+    pub async fn unread_count(pool: &DbPool, user_id: Uuid) -> Result<i64, AnnouncementError> {
+        let count: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM announcements a
+             WHERE {LIVE} AND posted_by <> $1 AND in_announcement_audience(id, $1)
+               AND NOT EXISTS (
+                   SELECT 1 FROM announcement_reads r
+                   WHERE r.announcement_id = a.id AND r.user_id = $1
+               )"
+        ))
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Marks every unread announcement counted by `unread_count` as read. Returns how many.
+    pub async fn mark_all_read(pool: &DbPool, user_id: Uuid) -> Result<u64, AnnouncementError> {
+        let result = sqlx::query(&format!(
+            "INSERT INTO announcement_reads (announcement_id, user_id, read_at)
+             SELECT id, $1, $2 FROM announcements
+             WHERE {LIVE} AND posted_by <> $1 AND in_announcement_audience(id, $1)
+             ON CONFLICT (announcement_id, user_id) DO NOTHING"
+        ))
+        .bind(user_id)
+        .bind(Utc::now())
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// The next `limit` recipients after `after`, in id order, so a broadcast can walk the
+    /// audience in batches.
+    pub async fn find_email_recipients(
+        pool: &DbPool,
+        id: Uuid,
+        after: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<AnnouncementRecipient>, AnnouncementError> {
+        let recipients = sqlx::query_as::<_, AnnouncementRecipient>(
+            r#"
+            SELECT u.id AS user_id, u.fullname, u.email
+            FROM users u
+            WHERE u.is_active
+              AND u.is_email_verified
+              AND in_announcement_audience($1, u.id)
+              AND ($2::uuid IS NULL OR u.id > $2)
+              AND NOT EXISTS (
+                  SELECT 1 FROM email_unsubscribes x
+                  WHERE x.user_id = u.id AND x.list = 'announcements'
+              )
+              AND NOT EXISTS (
+                  SELECT 1 FROM announcement_deliveries d
+                  WHERE d.announcement_id = $1 AND d.user_id = u.id
+              )
+            ORDER BY u.id ASC
+            LIMIT $3
+            "#,
         )
+        .bind(id)
+        .bind(after)
+        .bind(limit)
         .fetch_all(pool)
         .await?;
 
-        Ok(announcements)
+        Ok(recipients)
     }
 
     pub async fn update(
@@ -87,7 +359,14 @@ impl Announcement {
         id: Uuid,
         update_data: UpdateAnnouncement,
     ) -> Result<Option<Self>, AnnouncementError> {
-        if update_data.title.is_none() && update_data.body.is_none() {
+        if update_data.title.is_none()
+            && update_data.body.is_none()
+            && update_data.target.is_none()
+            && update_data.publish_at.is_none()
+            && update_data.expires_at.is_none()
+            && update_data.is_draft.is_none()
+            && update_data.is_pinned.is_none()
+        {
             return Err(AnnouncementError::NoUpdateFields);
         }
 
@@ -97,16 +376,37 @@ impl Announcement {
         };
 
         let now = Utc::now();
+        let target = update_data.target.unwrap_or(AnnouncementTarget {
+            audience: existing.audience,
+            audience_role: existing.audience_role,
+            audience_user_ids: existing.audience_user_ids,
+            committee_id: existing.committee_id,
+        });
+        let body = update_data.body.unwrap_or(existing.body);
+        let publish_at = update_data.publish_at.unwrap_or(existing.publish_at);
+        let expires_at = update_data.expires_at.unwrap_or(existing.expires_at);
+        check_schedule(publish_at, expires_at)?;
 
         let updated_announcement = sqlx::query_as::<_, Announcement>(
             "UPDATE announcements 
-             SET title = $2, body = $3, updated_at = $4
-             WHERE id = $1 
+             SET title = $2, body = $3, body_html = $4, audience = $5, audience_role = $6,
+                 audience_user_ids = $7, committee_id = $8, publish_at = $9, expires_at = $10,
+                 is_draft = $11, is_pinned = $12, updated_at = $13
+             WHERE id = $1 AND deleted_at IS NULL
              RETURNING *",
         )
         .bind(id)
         .bind(update_data.title.unwrap_or(existing.title))
-        .bind(update_data.body.or(existing.body))
+        .bind(body.clone())
+        .bind(body.as_deref().map(markdown::render))
+        .bind(target.audience)
+        .bind(target.audience_role)
+        .bind(target.audience_user_ids)
+        .bind(target.committee_id)
+        .bind(publish_at)
+        .bind(expires_at)
+        .bind(update_data.is_draft.unwrap_or(existing.is_draft))
+        .bind(update_data.is_pinned.unwrap_or(existing.is_pinned))
         .bind(now)
         .fetch_optional(pool)
         .await?;
@@ -114,11 +414,54 @@ impl Announcement {
         Ok(updated_announcement)
     }
 
-    pub async fn delete(pool: &DbPool, id: Uuid) -> Result<(), AnnouncementError> {
-        let result = sqlx::query("DELETE FROM announcements WHERE id = $1")
-            .bind(id)
-            .execute(pool)
-            .await?;
+    /// Takes the announcement down; attachments, comments and reads are kept until it is
+    /// purged.
+    pub async fn delete(conn: &mut PgConnection, id: Uuid) -> Result<(), AnnouncementError> {
+        let result = sqlx::query(
+            "UPDATE announcements SET deleted_at = $2, updated_at = $2
+             WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .bind(Utc::now())
+        .execute(&mut *conn)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AnnouncementError::NotFound { id });
+        }
+
+        Ok(())
+    }
+
+    pub async fn find_deleted(pool: &DbPool) -> Result<Vec<Self>, AnnouncementError> {
+        let announcements = sqlx::query_as::<_, Announcement>(
+            "SELECT * FROM announcements WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(announcements)
+    }
+
+    pub async fn restore(conn: &mut PgConnection, id: Uuid) -> Result<Self, AnnouncementError> {
+        sqlx::query_as::<_, Announcement>(
+            "UPDATE announcements SET deleted_at = NULL, updated_at = $2
+             WHERE id = $1 AND deleted_at IS NOT NULL
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(Utc::now())
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(AnnouncementError::NotFound { id })
+    }
+
+    pub async fn purge(conn: &mut PgConnection, id: Uuid) -> Result<(), AnnouncementError> {
+        let result =
+            sqlx::query("DELETE FROM announcements WHERE id = $1 AND deleted_at IS NOT NULL")
+                .bind(id)
+                .execute(&mut *conn)
+                .await?;
 
         if result.rows_affected() == 0 {
             return Err(AnnouncementError::NotFound { id });
@@ -127,3 +470,57 @@ impl Announcement {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn announcement(expires_at: Option<DateTime<Utc>>) -> Announcement {
+        let now = Utc::now();
+        Announcement {
+            id: Uuid::new_v4(),
+            posted_by: Uuid::new_v4(),
+            title: "AGM".to_string(),
+            body: None,
+            body_html: None,
+            audience: AnnouncementAudience::Everyone,
+            audience_role: None,
+            audience_user_ids: Vec::new(),
+            committee_id: None,
+            publish_at: now,
+            expires_at,
+            is_draft: false,
+            is_pinned: false,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        }
+    }
+
+    #[test]
+    fn schedule_must_expire_after_publishing() {
+        let publish_at = Utc::now();
+
+        assert!(check_schedule(publish_at, None).is_ok());
+        assert!(check_schedule(publish_at, Some(publish_at + Duration::hours(1))).is_ok());
+        assert!(matches!(
+            check_schedule(publish_at, Some(publish_at)),
+            Err(AnnouncementError::InvalidSchedule)
+        ));
+        assert!(matches!(
+            check_schedule(publish_at, Some(publish_at - Duration::hours(1))),
+            Err(AnnouncementError::InvalidSchedule)
+        ));
+    }
+
+    #[test]
+    fn expires_at_the_expiry_time() {
+        let now = Utc::now();
+
+        assert!(!announcement(None).is_expired_at(now));
+        assert!(!announcement(Some(now + Duration::minutes(1))).is_expired_at(now));
+        assert!(announcement(Some(now)).is_expired_at(now));
+        assert!(announcement(Some(now - Duration::minutes(1))).is_expired_at(now));
+    }
+}
@@ -0,0 +1,112 @@
+use crate::database::connection::DbPool;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum AnnouncementAttachmentError {
+    #[error("Attachment with ID {id} not found")]
+    NotFound { id: Uuid },
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AnnouncementAttachment {
+    pub id: Uuid,
+    pub announcement_id: Uuid,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    #[serde(skip_serializing)]
+    pub storage_key: String,
+    pub uploaded_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateAnnouncementAttachment {
+    pub announcement_id: Uuid,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub storage_key: String,
+    pub uploaded_by: Uuid,
+}
+
+impl AnnouncementAttachment {
+    pub async fn create(
+        pool: &DbPool,
+        attachment: CreateAnnouncementAttachment,
+    ) -> Result<Self, AnnouncementAttachmentError> {
+        let attachment = sqlx::query_as::<_, AnnouncementAttachment>(
+            "INSERT INTO announcement_attachments (id, announcement_id, filename, content_type, size_bytes, storage_key, uploaded_by, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(attachment.announcement_id)
+        .bind(attachment.filename)
+        .bind(attachment.content_type)
+        .bind(attachment.size_bytes)
+        .bind(attachment.storage_key)
+        .bind(attachment.uploaded_by)
+        .bind(Utc::now())
+        .fetch_one(pool)
+        .await?;
+
+        Ok(attachment)
+    }
+
+    pub async fn find_by_id(
+        pool: &DbPool,
+        announcement_id: Uuid,
+        id: Uuid,
+    ) -> Result<Option<Self>, AnnouncementAttachmentError> {
+        let attachment = sqlx::query_as::<_, AnnouncementAttachment>(
+            "SELECT * FROM announcement_attachments WHERE id = $1 AND announcement_id = $2",
+        )
+        .bind(id)
+        .bind(announcement_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(attachment)
+    }
+
+    pub async fn find_by_announcement(
+        pool: &DbPool,
+        announcement_id: Uuid,
+    ) -> Result<Vec<Self>, AnnouncementAttachmentError> {
+        let attachments = sqlx::query_as::<_, AnnouncementAttachment>(
+            "SELECT * FROM announcement_attachments
+             WHERE announcement_id = $1
+             ORDER BY created_at ASC",
+        )
+        .bind(announcement_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(attachments)
+    }
+
+    /// Returns the deleted row so its stored file can be removed.
+    pub async fn delete(
+        pool: &DbPool,
+        announcement_id: Uuid,
+        id: Uuid,
+    ) -> Result<Self, AnnouncementAttachmentError> {
+        sqlx::query_as::<_, AnnouncementAttachment>(
+            "DELETE FROM announcement_attachments
+             WHERE id = $1 AND announcement_id = $2
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(announcement_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AnnouncementAttachmentError::NotFound { id })
+    }
+}
@@ -0,0 +1,124 @@
+use crate::database::connection::DbPool;
+use crate::models::email_outbox::EmailStatus;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum AnnouncementDeliveryError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AnnouncementDelivery {
+    pub id: Uuid,
+    pub announcement_id: Uuid,
+    pub user_id: Uuid,
+    pub email_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Where each recipient's copy of an announcement email has got to.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct RecipientDelivery {
+    pub user_id: Uuid,
+    pub fullname: String,
+    pub email: String,
+    pub status: Option<EmailStatus>,
+    pub attempts: Option<i32>,
+    pub last_error: Option<String>,
+    pub queued_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DeliveryStats {
+    pub recipients: i64,
+    pub pending: i64,
+    pub sent: i64,
+    pub failed: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeliveryReport {
+    pub stats: DeliveryStats,
+    pub recipients: Vec<RecipientDelivery>,
+}
+
+impl AnnouncementDelivery {
+    /// Claims the recipient before queueing. Returns `None` if they were already emailed.
+    pub async fn reserve(
+        conn: &mut PgConnection,
+        announcement_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<Self>, AnnouncementDeliveryError> {
+        let delivery = sqlx::query_as::<_, AnnouncementDelivery>(
+            "INSERT INTO announcement_deliveries (id, announcement_id, user_id, created_at)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (announcement_id, user_id) DO NOTHING
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(announcement_id)
+        .bind(user_id)
+        .bind(Utc::now())
+        .fetch_optional(conn)
+        .await?;
+
+        Ok(delivery)
+    }
+
+    pub async fn mark_queued(
+        conn: &mut PgConnection,
+        id: Uuid,
+        email_id: Uuid,
+    ) -> Result<(), AnnouncementDeliveryError> {
+        sqlx::query("UPDATE announcement_deliveries SET email_id = $2 WHERE id = $1")
+            .bind(id)
+            .bind(email_id)
+            .execute(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn report(
+        pool: &DbPool,
+        announcement_id: Uuid,
+    ) -> Result<DeliveryReport, AnnouncementDeliveryError> {
+        let stats = sqlx::query_as::<_, DeliveryStats>(
+            r#"
+            SELECT COUNT(*) AS recipients,
+                   COUNT(*) FILTER (WHERE o.status IN ('pending', 'sending')) AS pending,
+                   COUNT(*) FILTER (WHERE o.status = 'sent') AS sent,
+                   COUNT(*) FILTER (WHERE o.status = 'failed') AS failed
+            FROM announcement_deliveries d
+            LEFT JOIN email_outbox o ON o.id = d.email_id
+            WHERE d.announcement_id = $1
+            "#,
+        )
+        .bind(announcement_id)
+        .fetch_one(pool)
+        .await?;
+
+        let recipients = sqlx::query_as::<_, RecipientDelivery>(
+            r#"
+            SELECT u.id AS user_id, u.fullname, u.email, o.status, o.attempts, o.last_error,
+                   d.created_at AS queued_at, o.sent_at
+            FROM announcement_deliveries d
+            JOIN users u ON u.id = d.user_id
+            LEFT JOIN email_outbox o ON o.id = d.email_id
+            WHERE d.announcement_id = $1
+            ORDER BY u.fullname ASC
+            "#,
+        )
+        .bind(announcement_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(DeliveryReport { stats, recipients })
+    }
+}
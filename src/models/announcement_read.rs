@@ -0,0 +1,111 @@
+use crate::database::connection::DbPool;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum AnnouncementReadError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AnnouncementRead {
+    pub announcement_id: Uuid,
+    pub user_id: Uuid,
+    pub read_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct AnnouncementReader {
+    pub user_id: Uuid,
+    pub fullname: String,
+    pub email: String,
+    pub read_at: DateTime<Utc>,
+}
+
+/// How much of an announcement's audience (active members) has read it.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ReadStats {
+    pub audience: i64,
+    pub read: i64,
+    pub read_percent: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UnreadCount {
+    pub unread: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MarkedRead {
+    pub marked: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadReport {
+    pub stats: ReadStats,
+    pub readers: Vec<AnnouncementReader>,
+}
+
+impl AnnouncementRead {
+    /// Records the first time `user_id` read the announcement; later reads keep that time.
+    pub async fn mark_read(
+        pool: &DbPool,
+        announcement_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Self, AnnouncementReadError> {
+        let read = sqlx::query_as::<_, AnnouncementRead>(
+            "INSERT INTO announcement_reads (announcement_id, user_id, read_at)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (announcement_id, user_id)
+             DO UPDATE SET read_at = announcement_reads.read_at
+             RETURNING *",
+        )
+        .bind(announcement_id)
+        .bind(user_id)
+        .bind(Utc::now())
+        .fetch_one(pool)
+        .await?;
+
+        Ok(read)
+    }
+
+    /// Readers in the announcement's audience, so admins reading outside it do not count.
+    pub async fn report(
+        pool: &DbPool,
+        announcement_id: Uuid,
+    ) -> Result<ReadReport, AnnouncementReadError> {
+        let stats = sqlx::query_as::<_, ReadStats>(
+            r#"
+            SELECT COUNT(*) AS audience,
+                   COUNT(r.user_id) AS read,
+                   COALESCE(ROUND(100.0 * COUNT(r.user_id) / NULLIF(COUNT(*), 0), 2), 0) AS read_percent
+            FROM users u
+            LEFT JOIN announcement_reads r ON r.announcement_id = $1 AND r.user_id = u.id
+            WHERE u.is_active AND in_announcement_audience($1, u.id)
+            "#,
+        )
+        .bind(announcement_id)
+        .fetch_one(pool)
+        .await?;
+
+        let readers = sqlx::query_as::<_, AnnouncementReader>(
+            r#"
+            SELECT u.id AS user_id, u.fullname, u.email, r.read_at
+            FROM announcement_reads r
+            JOIN users u ON u.id = r.user_id
+            WHERE r.announcement_id = $1 AND u.is_active AND in_announcement_audience($1, u.id)
+            ORDER BY r.read_at ASC
+            "#,
+        )
+        .bind(announcement_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(ReadReport { stats, readers })
+    }
+}
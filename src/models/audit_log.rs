@@ -0,0 +1,121 @@
+use crate::database::connection::DbPool;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, PgConnection};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum AuditLogError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AuditLogEntry {
+    pub id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub actor_email: Option<String>,
+    pub actor_role: Option<String>,
+    pub action: String,
+    pub entity_type: String,
+    pub entity_id: Option<Uuid>,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub ip_address: Option<String>,
+    pub request_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewAuditLogEntry {
+    pub actor_id: Option<Uuid>,
+    pub actor_email: Option<String>,
+    pub actor_role: Option<String>,
+    pub action: String,
+    pub entity_type: String,
+    pub entity_id: Option<Uuid>,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub ip_address: Option<String>,
+    pub request_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AuditLogFilter {
+    pub actor_id: Option<Uuid>,
+    pub action: Option<String>,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<Uuid>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Only entries older than this, for paging back through the log.
+    pub before: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+}
+
+impl AuditLogEntry {
+    pub async fn record(
+        conn: &mut PgConnection,
+        entry: NewAuditLogEntry,
+    ) -> Result<Self, AuditLogError> {
+        let entry = sqlx::query_as::<_, AuditLogEntry>(
+            r#"
+            INSERT INTO audit_log (
+                id, actor_id, actor_email, actor_role, action, entity_type, entity_id,
+                before, after, ip_address, request_id, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(entry.actor_id)
+        .bind(entry.actor_email)
+        .bind(entry.actor_role)
+        .bind(entry.action)
+        .bind(entry.entity_type)
+        .bind(entry.entity_id)
+        .bind(entry.before)
+        .bind(entry.after)
+        .bind(entry.ip_address)
+        .bind(entry.request_id)
+        .bind(Utc::now())
+        .fetch_one(conn)
+        .await?;
+
+        Ok(entry)
+    }
+
+    /// Newest first, 100 at a time unless `limit` says otherwise. `action` matches a prefix,
+    /// so `payment.` finds every payment action.
+    pub async fn find(pool: &DbPool, filter: AuditLogFilter) -> Result<Vec<Self>, AuditLogError> {
+        let entries = sqlx::query_as::<_, AuditLogEntry>(
+            r#"
+            SELECT * FROM audit_log
+            WHERE ($1::uuid IS NULL OR actor_id = $1)
+              AND ($2::text IS NULL OR starts_with(action, $2))
+              AND ($3::text IS NULL OR entity_type = $3)
+              AND ($4::uuid IS NULL OR entity_id = $4)
+              AND ($5::timestamptz IS NULL OR created_at >= $5)
+              AND ($6::timestamptz IS NULL OR created_at < $6)
+              AND ($7::timestamptz IS NULL OR created_at < $7)
+            ORDER BY created_at DESC
+            LIMIT $8
+            "#,
+        )
+        .bind(filter.actor_id)
+        .bind(filter.action)
+        .bind(filter.entity_type)
+        .bind(filter.entity_id)
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(filter.before)
+        .bind(filter.limit.unwrap_or(100).clamp(1, 500))
+        .fetch_all(pool)
+        .await?;
+
+        Ok(entries)
+    }
+}
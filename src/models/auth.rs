@@ -1,4 +1,5 @@
 use crate::models::user::UserRole;
+use crate::utils::validation::{Validate, Validator};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -9,6 +10,13 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+impl Validate for LoginRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.field("email", self.email.as_str()).required();
+        v.field("password", self.password.as_str()).required();
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct AuthResponse {
     pub token: String,
@@ -36,8 +44,8 @@ impl Claims {
         let now = Utc::now().timestamp();
         Self {
             sub: user_id,
-            email: email,
-            user_role: user_role,
+            email,
+            user_role,
             exp: now + (24 * 60 * 60), // 24 hours
             iat: now,
         }
@@ -0,0 +1,366 @@
+use crate::database::connection::DbPool;
+use crate::models::payment::{Payment, PaymentStatus};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{Connection, FromRow, PgConnection, Type};
+use std::str::FromStr;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum BankStatementError {
+    #[error("Statement line with ID {id} not found")]
+    LineNotFound { id: Uuid },
+    #[error("Statement line {id} has already been reconciled")]
+    LineAlreadyReconciled { id: Uuid },
+    #[error("Payment {id} is not pending")]
+    PaymentNotPending { id: Uuid },
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[sqlx(type_name = "statement_line_status", rename_all = "lowercase")]
+pub enum StatementLineStatus {
+    Unmatched,
+    Matched,
+    Ignored,
+}
+
+impl FromStr for StatementLineStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "unmatched" => Ok(StatementLineStatus::Unmatched),
+            "matched" => Ok(StatementLineStatus::Matched),
+            "ignored" => Ok(StatementLineStatus::Ignored),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[sqlx(type_name = "statement_match_method", rename_all = "lowercase")]
+pub enum StatementMatchMethod {
+    Reference,
+    Narration,
+    Manual,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BankStatement {
+    pub id: Uuid,
+    pub filename: Option<String>,
+    pub uploaded_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BankStatementLine {
+    pub id: Uuid,
+    pub statement_id: Uuid,
+    pub transaction_date: NaiveDate,
+    pub narration: String,
+    pub reference: Option<String>,
+    pub amount: Decimal,
+    pub status: StatementLineStatus,
+    pub suggested_user_id: Option<Uuid>,
+    pub payment_id: Option<Uuid>,
+    pub match_method: Option<StatementMatchMethod>,
+    pub matched_by: Option<Uuid>,
+    pub matched_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateStatementLine {
+    pub transaction_date: NaiveDate,
+    pub narration: String,
+    pub reference: Option<String>,
+    pub amount: Decimal,
+    pub suggested_user_id: Option<Uuid>,
+    /// A pending payment the line settles, verified as the line is stored.
+    pub payment_match: Option<(Uuid, StatementMatchMethod)>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatementImport {
+    pub statement: BankStatement,
+    pub matched: usize,
+    pub unmatched: usize,
+    pub lines: Vec<BankStatementLine>,
+}
+
+/// Verifies `payment_id` if it is still pending, returning `None` when it is not.
+async fn verify_pending_payment(
+    conn: &mut PgConnection,
+    payment_id: Uuid,
+    verified_by: Uuid,
+    now: DateTime<Utc>,
+) -> Result<Option<Payment>, sqlx::Error> {
+    sqlx::query_as::<_, Payment>(
+        "UPDATE payments
+         SET status = $2, verified_by = $3, verified_at = $4, updated_at = $4
+         WHERE id = $1 AND status = 'pending' AND deleted_at IS NULL
+         RETURNING *",
+    )
+    .bind(payment_id)
+    .bind(PaymentStatus::Verified)
+    .bind(verified_by)
+    .bind(now)
+    .fetch_optional(conn)
+    .await
+}
+
+impl BankStatement {
+    /// Stores a statement and its lines in one transaction, verifying the payments lines were
+    /// matched to and returning them alongside the lines. A line whose payment is no longer
+    /// pending is stored unmatched instead.
+    pub async fn create(
+        conn: &mut PgConnection,
+        uploaded_by: Uuid,
+        filename: Option<String>,
+        lines: Vec<CreateStatementLine>,
+    ) -> Result<(Self, Vec<BankStatementLine>, Vec<Payment>), BankStatementError> {
+        let now = Utc::now();
+        let mut tx = conn.begin().await?;
+
+        let statement = sqlx::query_as::<_, BankStatement>(
+            "INSERT INTO bank_statements (id, filename, uploaded_by, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5)
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(filename)
+        .bind(uploaded_by)
+        .bind(now)
+        .bind(now)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let mut created = Vec::with_capacity(lines.len());
+        let mut verified = Vec::new();
+
+        for line in lines {
+            let payment_match = match line.payment_match {
+                Some((payment_id, method)) => {
+                    match verify_pending_payment(&mut tx, payment_id, uploaded_by, now).await? {
+                        Some(payment) => {
+                            verified.push(payment);
+                            Some((payment_id, method))
+                        }
+                        None => None,
+                    }
+                }
+                None => None,
+            };
+            let status = match payment_match {
+                Some(_) => StatementLineStatus::Matched,
+                None => StatementLineStatus::Unmatched,
+            };
+
+            let line = sqlx::query_as::<_, BankStatementLine>(
+                "INSERT INTO bank_statement_lines (id, statement_id, transaction_date, narration, reference, amount, status, suggested_user_id, payment_id, match_method, matched_by, matched_at, created_at, updated_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+                 RETURNING *",
+            )
+                .bind(Uuid::new_v4())
+                .bind(statement.id)
+                .bind(line.transaction_date)
+                .bind(line.narration)
+                .bind(line.reference)
+                .bind(line.amount)
+                .bind(status)
+                .bind(line.suggested_user_id)
+                .bind(payment_match.map(|(payment_id, _)| payment_id))
+                .bind(payment_match.map(|(_, method)| method))
+                .bind(payment_match.map(|_| uploaded_by))
+                .bind(payment_match.map(|_| now))
+                .bind(now)
+                .bind(now)
+                .fetch_one(&mut *tx)
+                .await?;
+
+            created.push(line);
+        }
+
+        tx.commit().await?;
+
+        Ok((statement, created, verified))
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, BankStatementError> {
+        let statement =
+            sqlx::query_as::<_, BankStatement>("SELECT * FROM bank_statements WHERE id = $1")
+                .bind(id)
+                .fetch_optional(pool)
+                .await?;
+
+        Ok(statement)
+    }
+
+    pub async fn find_all(pool: &DbPool) -> Result<Vec<Self>, BankStatementError> {
+        let statements = sqlx::query_as::<_, BankStatement>(
+            "SELECT * FROM bank_statements ORDER BY created_at DESC",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(statements)
+    }
+}
+
+impl BankStatementLine {
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, BankStatementError> {
+        let line = sqlx::query_as::<_, BankStatementLine>(
+            "SELECT * FROM bank_statement_lines WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(line)
+    }
+
+    pub async fn find_by_statement(
+        pool: &DbPool,
+        statement_id: Uuid,
+        status: Option<StatementLineStatus>,
+    ) -> Result<Vec<Self>, BankStatementError> {
+        let lines = sqlx::query_as::<_, BankStatementLine>(
+            "SELECT * FROM bank_statement_lines
+             WHERE statement_id = $1 AND ($2::statement_line_status IS NULL OR status = $2)
+             ORDER BY transaction_date ASC, created_at ASC",
+        )
+        .bind(statement_id)
+        .bind(status)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(lines)
+    }
+
+    /// Verifies a pending payment against this line, recording who matched it and how.
+    /// Returns the line and the verified payment.
+    pub async fn match_payment(
+        conn: &mut PgConnection,
+        id: Uuid,
+        payment_id: Uuid,
+        matched_by: Uuid,
+        method: StatementMatchMethod,
+    ) -> Result<(Self, Payment), BankStatementError> {
+        let now = Utc::now();
+        let mut tx = conn.begin().await?;
+
+        let line = sqlx::query_as::<_, BankStatementLine>(
+            "UPDATE bank_statement_lines
+             SET status = 'matched', payment_id = $2, match_method = $3, matched_by = $4, matched_at = $5, updated_at = $5
+             WHERE id = $1 AND status = 'unmatched'
+             RETURNING *",
+        )
+            .bind(id)
+            .bind(payment_id)
+            .bind(method)
+            .bind(matched_by)
+            .bind(now)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(BankStatementError::LineAlreadyReconciled { id })?;
+
+        let payment = verify_pending_payment(&mut tx, payment_id, matched_by, now)
+            .await?
+            .ok_or(BankStatementError::PaymentNotPending { id: payment_id })?;
+
+        tx.commit().await?;
+
+        Ok((line, payment))
+    }
+
+    /// Records a verified payment for a member straight from an unmatched line, returning the
+    /// line and the new payment. The bank's reference stays on the line: `payments.reference`
+    /// is the gateway's and must be unique.
+    pub async fn match_member(
+        conn: &mut PgConnection,
+        id: Uuid,
+        user_id: Uuid,
+        contribution_id: Uuid,
+        matched_by: Uuid,
+    ) -> Result<(Self, Payment), BankStatementError> {
+        let now = Utc::now();
+        let mut tx = conn.begin().await?;
+
+        let existing = sqlx::query_as::<_, BankStatementLine>(
+            "SELECT * FROM bank_statement_lines WHERE id = $1 FOR UPDATE",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(BankStatementError::LineNotFound { id })?;
+
+        if existing.status != StatementLineStatus::Unmatched {
+            return Err(BankStatementError::LineAlreadyReconciled { id });
+        }
+
+        let payment = sqlx::query_as::<_, Payment>(
+            "INSERT INTO payments (id, user_id, contribution_id, amount, status, verified_by, verified_at, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             RETURNING *",
+        )
+            .bind(Uuid::new_v4())
+            .bind(user_id)
+            .bind(Some(contribution_id))
+            .bind(existing.amount)
+            .bind(PaymentStatus::Verified)
+            .bind(matched_by)
+            .bind(now)
+            .bind(now)
+            .bind(now)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let line = sqlx::query_as::<_, BankStatementLine>(
+            "UPDATE bank_statement_lines
+             SET status = 'matched', payment_id = $2, match_method = $3, matched_by = $4, matched_at = $5, updated_at = $5
+             WHERE id = $1
+             RETURNING *",
+        )
+            .bind(id)
+            .bind(payment.id)
+            .bind(StatementMatchMethod::Manual)
+            .bind(matched_by)
+            .bind(now)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok((line, payment))
+    }
+
+    pub async fn ignore(
+        conn: &mut PgConnection,
+        id: Uuid,
+        ignored_by: Uuid,
+    ) -> Result<Self, BankStatementError> {
+        let now = Utc::now();
+
+        let line = sqlx::query_as::<_, BankStatementLine>(
+            "UPDATE bank_statement_lines
+             SET status = 'ignored', matched_by = $2, matched_at = $3, updated_at = $3
+             WHERE id = $1 AND status = 'unmatched'
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(ignored_by)
+        .bind(now)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(BankStatementError::LineAlreadyReconciled { id })?;
+
+        Ok(line)
+    }
+}
@@ -0,0 +1,230 @@
+use crate::database::connection::DbPool;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum BudgetError {
+    #[error("Budget with ID {id} not found")]
+    NotFound { id: Uuid },
+    #[error("Expense category with ID {id} not found")]
+    CategoryNotFound { id: Uuid },
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Budget {
+    pub id: Uuid,
+    pub category_id: Uuid,
+    pub year: i32,
+    pub amount: Decimal,
+    pub notes: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetBudget {
+    pub category_id: Uuid,
+    pub year: i32,
+    pub amount: Decimal,
+    pub notes: Option<String>,
+    pub created_by: Uuid,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct BudgetReportRow {
+    category_id: Uuid,
+    category_name: String,
+    budgeted: Decimal,
+    actual: Decimal,
+    pending: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetReportLine {
+    pub category_id: Uuid,
+    pub category_name: String,
+    pub budgeted: Decimal,
+    pub actual: Decimal,
+    pub pending: Decimal,
+    pub variance: Decimal,
+    /// Share of the budget spent so far, as a percentage. `None` when nothing was budgeted.
+    pub percent_used: Option<Decimal>,
+}
+
+impl From<BudgetReportRow> for BudgetReportLine {
+    fn from(row: BudgetReportRow) -> Self {
+        Self {
+            variance: row.budgeted - row.actual,
+            percent_used: (row.budgeted > Decimal::ZERO)
+                .then(|| (row.actual / row.budgeted * Decimal::ONE_HUNDRED).round_dp(2)),
+            category_id: row.category_id,
+            category_name: row.category_name,
+            budgeted: row.budgeted,
+            actual: row.actual,
+            pending: row.pending,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetReport {
+    pub year: i32,
+    pub lines: Vec<BudgetReportLine>,
+    pub total_budgeted: Decimal,
+    pub total_actual: Decimal,
+    pub total_variance: Decimal,
+}
+
+impl Budget {
+    /// Creates the budget for a category and year, or replaces the amount if one exists.
+    pub async fn set(conn: &mut PgConnection, budget: SetBudget) -> Result<Self, BudgetError> {
+        let category_exists: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM expense_categories WHERE id = $1)")
+                .bind(budget.category_id)
+                .fetch_one(&mut *conn)
+                .await?;
+
+        if !category_exists {
+            return Err(BudgetError::CategoryNotFound {
+                id: budget.category_id,
+            });
+        }
+
+        let now = Utc::now();
+
+        let budget = sqlx::query_as::<_, Budget>(
+            "INSERT INTO budgets (id, category_id, year, amount, notes, created_by, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+             ON CONFLICT (category_id, year)
+             DO UPDATE SET amount = EXCLUDED.amount, notes = EXCLUDED.notes, updated_at = EXCLUDED.updated_at
+             RETURNING *",
+        )
+            .bind(Uuid::new_v4())
+            .bind(budget.category_id)
+            .bind(budget.year)
+            .bind(budget.amount)
+            .bind(budget.notes)
+            .bind(budget.created_by)
+            .bind(now)
+            .bind(now)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        Ok(budget)
+    }
+
+    pub async fn find_by_category(
+        pool: &DbPool,
+        category_id: Uuid,
+        year: i32,
+    ) -> Result<Option<Self>, BudgetError> {
+        let budget = sqlx::query_as::<_, Budget>(
+            "SELECT * FROM budgets WHERE category_id = $1 AND year = $2",
+        )
+        .bind(category_id)
+        .bind(year)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(budget)
+    }
+
+    pub async fn find_by_year(pool: &DbPool, year: i32) -> Result<Vec<Self>, BudgetError> {
+        let budgets = sqlx::query_as::<_, Budget>(
+            "SELECT * FROM budgets WHERE year = $1 ORDER BY created_at ASC",
+        )
+        .bind(year)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(budgets)
+    }
+
+    /// Removes the budget, returning it as it was.
+    pub async fn delete(conn: &mut PgConnection, id: Uuid) -> Result<Self, BudgetError> {
+        sqlx::query_as::<_, Budget>("DELETE FROM budgets WHERE id = $1 RETURNING *")
+            .bind(id)
+            .fetch_optional(conn)
+            .await?
+            .ok_or(BudgetError::NotFound { id })
+    }
+
+    /// Compares each category's budget with its approved spending for the year.
+    pub async fn report(pool: &DbPool, year: i32) -> Result<BudgetReport, BudgetError> {
+        let rows = sqlx::query_as::<_, BudgetReportRow>(
+            r#"
+            SELECT c.id AS category_id, c.name AS category_name,
+                   COALESCE(b.amount, 0) AS budgeted,
+                   COALESCE(SUM(e.amount) FILTER (WHERE e.status = 'approved'), 0) AS actual,
+                   COALESCE(SUM(e.amount) FILTER (WHERE e.status = 'pending'), 0) AS pending
+            FROM expense_categories c
+            LEFT JOIN budgets b ON b.category_id = c.id AND b.year = $1
+            LEFT JOIN expenses e ON e.category_id = c.id
+                AND e.spent_on >= make_date($1, 1, 1) AND e.spent_on < make_date($1 + 1, 1, 1)
+            GROUP BY c.id, c.name, b.amount
+            HAVING b.amount IS NOT NULL OR COUNT(e.id) > 0
+            ORDER BY c.name ASC
+            "#,
+        )
+        .bind(year)
+        .fetch_all(pool)
+        .await?;
+
+        let lines: Vec<BudgetReportLine> = rows.into_iter().map(BudgetReportLine::from).collect();
+
+        let total_budgeted = lines.iter().map(|line| line.budgeted).sum();
+        let total_actual = lines.iter().map(|line| line.actual).sum();
+
+        Ok(BudgetReport {
+            year,
+            lines,
+            total_budgeted,
+            total_actual,
+            total_variance: total_budgeted - total_actual,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn line(budgeted: &str, actual: &str) -> BudgetReportLine {
+        BudgetReportLine::from(BudgetReportRow {
+            category_id: Uuid::nil(),
+            category_name: "Welfare".to_string(),
+            budgeted: Decimal::from_str(budgeted).unwrap(),
+            actual: Decimal::from_str(actual).unwrap(),
+            pending: Decimal::ZERO,
+        })
+    }
+
+    #[test]
+    fn reports_variance_and_share_used() {
+        let under = line("30000", "10000");
+        assert_eq!(under.variance, Decimal::from(20000));
+        assert_eq!(
+            under.percent_used,
+            Some(Decimal::from_str("33.33").unwrap())
+        );
+
+        let over = line("1000", "1500");
+        assert_eq!(over.variance, Decimal::from(-500));
+        assert_eq!(over.percent_used, Some(Decimal::from(150)));
+    }
+
+    #[test]
+    fn unbudgeted_spending_has_no_share_used() {
+        let unbudgeted = line("0", "250");
+        assert_eq!(unbudgeted.variance, Decimal::from(-250));
+        assert_eq!(unbudgeted.percent_used, None);
+    }
+}
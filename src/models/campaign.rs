@@ -0,0 +1,294 @@
+use crate::database::connection::DbPool;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum CampaignError {
+    #[error("Campaign with ID {id} not found")]
+    NotFound { id: Uuid },
+    #[error("Campaign {id} has payments on record and can only be closed")]
+    HasDonations { id: Uuid },
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("No fields provided for update")]
+    NoUpdateFields,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Campaign {
+    pub id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub target_amount: Decimal,
+    pub deadline: Option<NaiveDate>,
+    pub allow_anonymous: bool,
+    pub is_closed: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A campaign with its running totals in the base currency, net of refunds and reversals.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct CampaignProgress {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub campaign: Campaign,
+    pub raised: Decimal,
+    pub donor_count: i64,
+    pub percent_raised: Decimal,
+}
+
+impl CampaignProgress {
+    /// Whether the campaign still accepts donations on `today`.
+    pub fn is_open(&self, today: NaiveDate) -> bool {
+        !self.campaign.is_closed
+            && self
+                .campaign
+                .deadline
+                .is_none_or(|deadline| today <= deadline)
+    }
+}
+
+/// One entry on the donor wall. Anonymous donors have no name.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Donor {
+    pub donor_name: Option<String>,
+    pub is_anonymous: bool,
+    pub amount: Decimal,
+    pub donated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCampaign {
+    pub title: String,
+    pub description: Option<String>,
+    pub target_amount: Decimal,
+    pub deadline: Option<NaiveDate>,
+    pub allow_anonymous: bool,
+    pub created_by: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateCampaign {
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<Option<String>>,
+    pub target_amount: Option<Decimal>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<Option<NaiveDate>>,
+    pub allow_anonymous: Option<bool>,
+    pub is_closed: Option<bool>,
+}
+
+const PROGRESS_QUERY: &str = r#"
+    SELECT c.*,
+           COALESCE(SUM(p.base_amount) FILTER (WHERE p.status = 'verified'), 0) AS raised,
+           COUNT(DISTINCT p.user_id) FILTER (
+               WHERE p.status = 'verified' AND p.entry_type = 'payment'
+           ) AS donor_count,
+           ROUND(COALESCE(SUM(p.base_amount) FILTER (WHERE p.status = 'verified'), 0) * 100 / c.target_amount, 2)
+               AS percent_raised
+    FROM campaigns c
+    LEFT JOIN payments p ON p.campaign_id = c.id
+"#;
+
+impl Campaign {
+    pub async fn create(pool: &DbPool, campaign: CreateCampaign) -> Result<Self, CampaignError> {
+        let now = Utc::now();
+
+        let campaign = sqlx::query_as::<_, Campaign>(
+            "INSERT INTO campaigns (id, title, description, target_amount, deadline, allow_anonymous, created_by, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             RETURNING *",
+        )
+            .bind(Uuid::new_v4())
+            .bind(campaign.title)
+            .bind(campaign.description)
+            .bind(campaign.target_amount)
+            .bind(campaign.deadline)
+            .bind(campaign.allow_anonymous)
+            .bind(campaign.created_by)
+            .bind(now)
+            .bind(now)
+            .fetch_one(pool)
+            .await?;
+
+        Ok(campaign)
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, CampaignError> {
+        let campaign = sqlx::query_as::<_, Campaign>("SELECT * FROM campaigns WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(campaign)
+    }
+
+    pub async fn find_progress(
+        pool: &DbPool,
+        id: Uuid,
+    ) -> Result<Option<CampaignProgress>, CampaignError> {
+        let progress = sqlx::query_as::<_, CampaignProgress>(&format!(
+            "{PROGRESS_QUERY} WHERE c.id = $1 GROUP BY c.id"
+        ))
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(progress)
+    }
+
+    pub async fn find_all(pool: &DbPool) -> Result<Vec<CampaignProgress>, CampaignError> {
+        let campaigns = sqlx::query_as::<_, CampaignProgress>(&format!(
+            "{PROGRESS_QUERY} GROUP BY c.id ORDER BY c.is_closed ASC, c.created_at DESC"
+        ))
+        .fetch_all(pool)
+        .await?;
+
+        Ok(campaigns)
+    }
+
+    /// Verified donations in the base currency net of refunds, newest first. Names are
+    /// withheld for anonymous gifts.
+    pub async fn find_donors(pool: &DbPool, id: Uuid) -> Result<Vec<Donor>, CampaignError> {
+        let donors = sqlx::query_as::<_, Donor>(
+            r#"
+            SELECT CASE WHEN p.is_anonymous THEN NULL ELSE u.fullname END AS donor_name,
+                   p.is_anonymous,
+                   p.base_amount + COALESCE(SUM(r.base_amount), 0) AS amount,
+                   COALESCE(p.verified_at, p.created_at) AS donated_at
+            FROM payments p
+            JOIN users u ON u.id = p.user_id
+            LEFT JOIN payments r ON r.reverses_payment_id = p.id AND r.status = 'verified'
+            WHERE p.campaign_id = $1 AND p.status = 'verified' AND p.entry_type = 'payment'
+            GROUP BY p.id, u.fullname
+            HAVING p.base_amount + COALESCE(SUM(r.base_amount), 0) > 0
+            ORDER BY donated_at DESC
+            "#,
+        )
+        .bind(id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(donors)
+    }
+
+    pub async fn update(
+        pool: &DbPool,
+        id: Uuid,
+        update_data: UpdateCampaign,
+    ) -> Result<Option<Self>, CampaignError> {
+        if update_data.title.is_none()
+            && update_data.description.is_none()
+            && update_data.target_amount.is_none()
+            && update_data.deadline.is_none()
+            && update_data.allow_anonymous.is_none()
+            && update_data.is_closed.is_none()
+        {
+            return Err(CampaignError::NoUpdateFields);
+        }
+
+        let existing = match Self::find_by_id(pool, id).await? {
+            Some(campaign) => campaign,
+            None => return Err(CampaignError::NotFound { id }),
+        };
+
+        let now = Utc::now();
+
+        let updated_campaign = sqlx::query_as::<_, Campaign>(
+            "UPDATE campaigns
+             SET title = $2, description = $3, target_amount = $4, deadline = $5,
+                 allow_anonymous = $6, is_closed = $7, updated_at = $8
+             WHERE id = $1
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(update_data.title.unwrap_or(existing.title))
+        .bind(update_data.description.unwrap_or(existing.description))
+        .bind(update_data.target_amount.unwrap_or(existing.target_amount))
+        .bind(update_data.deadline.unwrap_or(existing.deadline))
+        .bind(
+            update_data
+                .allow_anonymous
+                .unwrap_or(existing.allow_anonymous),
+        )
+        .bind(update_data.is_closed.unwrap_or(existing.is_closed))
+        .bind(now)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(updated_campaign)
+    }
+
+    /// Deletes a campaign that no payment points at. Campaigns with any payments, whatever
+    /// their status, must be closed instead so the payments behind them are kept.
+    pub async fn delete(pool: &DbPool, id: Uuid) -> Result<(), CampaignError> {
+        let result = sqlx::query(
+            "DELETE FROM campaigns c
+             WHERE c.id = $1
+               AND NOT EXISTS (SELECT 1 FROM payments p WHERE p.campaign_id = c.id)",
+        )
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| match e.as_database_error() {
+            Some(db) if db.is_foreign_key_violation() => CampaignError::HasDonations { id },
+            _ => CampaignError::Database(e),
+        })?;
+
+        if result.rows_affected() == 0 {
+            return match Self::find_by_id(pool, id).await? {
+                Some(_) => Err(CampaignError::HasDonations { id }),
+                None => Err(CampaignError::NotFound { id }),
+            };
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(deadline: Option<NaiveDate>, is_closed: bool) -> CampaignProgress {
+        CampaignProgress {
+            campaign: Campaign {
+                id: Uuid::nil(),
+                title: "Classroom block".to_string(),
+                description: None,
+                target_amount: Decimal::from(1_000_000),
+                deadline,
+                allow_anonymous: true,
+                is_closed,
+                created_by: Some(Uuid::nil()),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            },
+            raised: Decimal::ZERO,
+            donor_count: 0,
+            percent_raised: Decimal::ZERO,
+        }
+    }
+
+    #[test]
+    fn open_until_the_end_of_its_deadline() {
+        let deadline = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+        let campaign = progress(Some(deadline), false);
+        assert!(campaign.is_open(deadline));
+        assert!(!campaign.is_open(deadline.succ_opt().unwrap()));
+    }
+
+    #[test]
+    fn open_without_a_deadline_until_closed() {
+        let today = NaiveDate::from_ymd_opt(2030, 1, 1).unwrap();
+        assert!(progress(None, false).is_open(today));
+        assert!(!progress(None, true).is_open(today));
+    }
+}
@@ -0,0 +1,370 @@
+use crate::database::connection::DbPool;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashMap;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum CommentError {
+    #[error("Comment with ID {id} not found")]
+    NotFound { id: Uuid },
+    #[error("Replies must be to a comment on the same post")]
+    InvalidParent,
+    #[error("Comment with ID {id} has been deleted")]
+    Deleted { id: Uuid },
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// What a comment or reaction is attached to.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", content = "id", rename_all = "snake_case")]
+pub enum ContentTarget {
+    Announcement(Uuid),
+    Photo(Uuid),
+}
+
+impl ContentTarget {
+    /// The column holding the target's ID in `comments` and `reactions`.
+    pub fn column(&self) -> &'static str {
+        match self {
+            ContentTarget::Announcement(_) => "announcement_id",
+            ContentTarget::Photo(_) => "photo_id",
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        match self {
+            ContentTarget::Announcement(id) | ContentTarget::Photo(id) => *id,
+        }
+    }
+
+    pub fn announcement_id(&self) -> Option<Uuid> {
+        match self {
+            ContentTarget::Announcement(id) => Some(*id),
+            ContentTarget::Photo(_) => None,
+        }
+    }
+
+    pub fn photo_id(&self) -> Option<Uuid> {
+        match self {
+            ContentTarget::Photo(id) => Some(*id),
+            ContentTarget::Announcement(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Comment {
+    pub id: Uuid,
+    pub announcement_id: Option<Uuid>,
+    pub photo_id: Option<Uuid>,
+    pub parent_id: Option<Uuid>,
+    pub author_id: Uuid,
+    pub author_name: String,
+    /// `None` once deleted, or while hidden from anyone but moderators.
+    pub body: Option<String>,
+    pub edited_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub hidden_at: Option<DateTime<Utc>>,
+    pub hidden_by: Option<Uuid>,
+    pub hidden_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A comment with its replies, oldest first.
+#[derive(Debug, Clone, Serialize)]
+pub struct CommentNode {
+    #[serde(flatten)]
+    pub comment: Comment,
+    pub replies: Vec<CommentNode>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateComment {
+    pub target: ContentTarget,
+    pub parent_id: Option<Uuid>,
+    pub author_id: Uuid,
+    pub body: String,
+}
+
+const SELECT_COMMENT: &str = "SELECT c.id, c.announcement_id, c.photo_id, c.parent_id, c.author_id,
+            u.fullname AS author_name, c.body, c.edited_at, c.deleted_at, c.hidden_at,
+            c.hidden_by, c.hidden_reason, c.created_at, c.updated_at
+     FROM comments c
+     JOIN users u ON u.id = c.author_id";
+
+impl Comment {
+    pub fn target(&self) -> Option<ContentTarget> {
+        self.announcement_id
+            .map(ContentTarget::Announcement)
+            .or(self.photo_id.map(ContentTarget::Photo))
+    }
+
+    /// Blanks what the viewer may not see: deleted bodies for everyone, and hidden bodies and
+    /// moderation details for anyone but moderators.
+    pub fn redact(mut self, is_moderator: bool) -> Self {
+        if self.deleted_at.is_some() {
+            self.body = None;
+        }
+        if self.hidden_at.is_some() && !is_moderator {
+            self.body = None;
+            self.hidden_by = None;
+            self.hidden_reason = None;
+        }
+        self
+    }
+
+    pub async fn create(pool: &DbPool, comment: CreateComment) -> Result<Self, CommentError> {
+        if let Some(parent_id) = comment.parent_id {
+            let parent = Self::find_by_id(pool, parent_id)
+                .await?
+                .ok_or(CommentError::InvalidParent)?;
+            if parent.target() != Some(comment.target) {
+                return Err(CommentError::InvalidParent);
+            }
+        }
+
+        let now = Utc::now();
+        let id: Uuid = sqlx::query_scalar(
+            "INSERT INTO comments (id, announcement_id, photo_id, parent_id, author_id, body, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+             RETURNING id",
+        )
+        .bind(Uuid::new_v4())
+        .bind(comment.target.announcement_id())
+        .bind(comment.target.photo_id())
+        .bind(comment.parent_id)
+        .bind(comment.author_id)
+        .bind(comment.body)
+        .bind(now)
+        .bind(now)
+        .fetch_one(pool)
+        .await?;
+
+        Self::find_by_id(pool, id)
+            .await?
+            .ok_or(CommentError::NotFound { id })
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, CommentError> {
+        let comment = sqlx::query_as::<_, Comment>(&format!("{SELECT_COMMENT} WHERE c.id = $1"))
+            .bind(id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(comment)
+    }
+
+    /// Every comment on `target` as a tree of top-level comments and their replies.
+    pub async fn find_thread(
+        pool: &DbPool,
+        target: ContentTarget,
+        is_moderator: bool,
+    ) -> Result<Vec<CommentNode>, CommentError> {
+        let comments = sqlx::query_as::<_, Comment>(&format!(
+            "{SELECT_COMMENT} WHERE c.{} = $1 ORDER BY c.created_at ASC",
+            target.column()
+        ))
+        .bind(target.id())
+        .fetch_all(pool)
+        .await?;
+
+        let mut children: HashMap<Option<Uuid>, Vec<Comment>> = HashMap::new();
+        for comment in comments {
+            children
+                .entry(comment.parent_id)
+                .or_default()
+                .push(comment.redact(is_moderator));
+        }
+
+        Ok(build_thread(None, &mut children))
+    }
+
+    pub async fn update_body(pool: &DbPool, id: Uuid, body: String) -> Result<Self, CommentError> {
+        let now = Utc::now();
+        let result = sqlx::query(
+            "UPDATE comments SET body = $2, edited_at = $3, updated_at = $3
+             WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .bind(body)
+        .bind(now)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(match Self::find_by_id(pool, id).await? {
+                Some(_) => CommentError::Deleted { id },
+                None => CommentError::NotFound { id },
+            });
+        }
+
+        Self::find_by_id(pool, id)
+            .await?
+            .ok_or(CommentError::NotFound { id })
+    }
+
+    /// Leaves a placeholder so replies stay in the thread.
+    pub async fn delete(pool: &DbPool, id: Uuid) -> Result<(), CommentError> {
+        let now = Utc::now();
+        let result = sqlx::query(
+            "UPDATE comments SET deleted_at = COALESCE(deleted_at, $2), updated_at = $2
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(now)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(CommentError::NotFound { id });
+        }
+
+        Ok(())
+    }
+
+    /// Hides the comment from everyone but moderators, or shows it again when `hidden_by` is
+    /// `None`.
+    pub async fn set_hidden(
+        pool: &DbPool,
+        id: Uuid,
+        hidden_by: Option<Uuid>,
+        reason: Option<String>,
+    ) -> Result<Self, CommentError> {
+        let now = Utc::now();
+        let result = sqlx::query(
+            "UPDATE comments
+             SET hidden_at = CASE WHEN $2::uuid IS NULL THEN NULL ELSE $4 END,
+                 hidden_by = $2, hidden_reason = $3, updated_at = $4
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(hidden_by)
+        .bind(reason)
+        .bind(now)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(CommentError::NotFound { id });
+        }
+
+        Self::find_by_id(pool, id)
+            .await?
+            .ok_or(CommentError::NotFound { id })
+    }
+}
+
+fn build_thread(
+    parent_id: Option<Uuid>,
+    children: &mut HashMap<Option<Uuid>, Vec<Comment>>,
+) -> Vec<CommentNode> {
+    children
+        .remove(&parent_id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|comment| {
+            let replies = build_thread(Some(comment.id), children);
+            CommentNode { comment, replies }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(parent_id: Option<Uuid>) -> Comment {
+        let now = Utc::now();
+        Comment {
+            id: Uuid::new_v4(),
+            announcement_id: Some(Uuid::nil()),
+            photo_id: None,
+            parent_id,
+            author_id: Uuid::new_v4(),
+            author_name: "Ada Obi".to_string(),
+            body: Some("Well done".to_string()),
+            edited_at: None,
+            deleted_at: None,
+            hidden_at: None,
+            hidden_by: None,
+            hidden_reason: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn hidden(mut comment: Comment) -> Comment {
+        comment.hidden_at = Some(Utc::now());
+        comment.hidden_by = Some(Uuid::new_v4());
+        comment.hidden_reason = Some("Off topic".to_string());
+        comment
+    }
+
+    #[test]
+    fn target_follows_the_set_column() {
+        let mut photo_comment = comment(None);
+        let photo_id = Uuid::new_v4();
+        photo_comment.announcement_id = None;
+        photo_comment.photo_id = Some(photo_id);
+
+        assert_eq!(
+            comment(None).target(),
+            Some(ContentTarget::Announcement(Uuid::nil()))
+        );
+        assert_eq!(photo_comment.target(), Some(ContentTarget::Photo(photo_id)));
+    }
+
+    #[test]
+    fn redact_blanks_deleted_bodies_for_everyone() {
+        let mut deleted = comment(None);
+        deleted.deleted_at = Some(Utc::now());
+
+        assert_eq!(deleted.clone().redact(true).body, None);
+        assert_eq!(deleted.redact(false).body, None);
+    }
+
+    #[test]
+    fn redact_keeps_hidden_comments_for_moderators_only() {
+        let for_moderator = hidden(comment(None)).redact(true);
+        assert_eq!(for_moderator.body.as_deref(), Some("Well done"));
+        assert_eq!(for_moderator.hidden_reason.as_deref(), Some("Off topic"));
+
+        let for_member = hidden(comment(None)).redact(false);
+        assert_eq!(for_member.body, None);
+        assert_eq!(for_member.hidden_by, None);
+        assert_eq!(for_member.hidden_reason, None);
+        assert!(for_member.hidden_at.is_some());
+    }
+
+    #[test]
+    fn builds_nested_threads_in_order() {
+        let first = comment(None);
+        let second = comment(None);
+        let reply = comment(Some(first.id));
+        let nested = comment(Some(reply.id));
+
+        let mut children: HashMap<Option<Uuid>, Vec<Comment>> = HashMap::new();
+        for comment in [&first, &second, &reply, &nested] {
+            children
+                .entry(comment.parent_id)
+                .or_default()
+                .push(comment.clone());
+        }
+
+        let thread = build_thread(None, &mut children);
+
+        assert_eq!(thread.len(), 2);
+        assert_eq!(thread[0].comment.id, first.id);
+        assert_eq!(thread[1].comment.id, second.id);
+        assert!(thread[1].replies.is_empty());
+        assert_eq!(thread[0].replies.len(), 1);
+        assert_eq!(thread[0].replies[0].comment.id, reply.id);
+        assert_eq!(thread[0].replies[0].replies[0].comment.id, nested.id);
+        assert!(children.is_empty());
+    }
+}
@@ -0,0 +1,161 @@
+use crate::database::connection::DbPool;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum CommitteeError {
+    #[error("Committee with ID {id} not found")]
+    NotFound { id: Uuid },
+    #[error("A committee named {name} already exists")]
+    NameTaken { name: String },
+    #[error("User {user_id} is not a member of committee {committee_id}")]
+    MemberNotFound { committee_id: Uuid, user_id: Uuid },
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Committee {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct CommitteeMember {
+    pub user_id: Uuid,
+    pub fullname: String,
+    pub email: String,
+    pub added_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitteeWithMembers {
+    #[serde(flatten)]
+    pub committee: Committee,
+    pub members: Vec<CommitteeMember>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCommittee {
+    pub name: String,
+    pub description: Option<String>,
+    pub created_by: Uuid,
+}
+
+impl Committee {
+    pub async fn create(pool: &DbPool, committee: CreateCommittee) -> Result<Self, CommitteeError> {
+        let now = Utc::now();
+
+        let created = sqlx::query_as::<_, Committee>(
+            "INSERT INTO committees (id, name, description, created_by, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $5)
+             ON CONFLICT (name) DO NOTHING
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(&committee.name)
+        .bind(committee.description)
+        .bind(committee.created_by)
+        .bind(now)
+        .fetch_optional(pool)
+        .await?;
+
+        created.ok_or(CommitteeError::NameTaken {
+            name: committee.name,
+        })
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, CommitteeError> {
+        let committee = sqlx::query_as::<_, Committee>("SELECT * FROM committees WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(committee)
+    }
+
+    pub async fn find_all(pool: &DbPool) -> Result<Vec<Self>, CommitteeError> {
+        let committees =
+            sqlx::query_as::<_, Committee>("SELECT * FROM committees ORDER BY name ASC")
+                .fetch_all(pool)
+                .await?;
+
+        Ok(committees)
+    }
+
+    pub async fn delete(pool: &DbPool, id: Uuid) -> Result<(), CommitteeError> {
+        let result = sqlx::query("DELETE FROM committees WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(CommitteeError::NotFound { id });
+        }
+
+        Ok(())
+    }
+
+    pub async fn find_members(
+        pool: &DbPool,
+        id: Uuid,
+    ) -> Result<Vec<CommitteeMember>, CommitteeError> {
+        let members = sqlx::query_as::<_, CommitteeMember>(
+            "SELECT u.id AS user_id, u.fullname, u.email, m.added_at
+             FROM committee_members m
+             JOIN users u ON u.id = m.user_id
+             WHERE m.committee_id = $1
+             ORDER BY u.fullname ASC",
+        )
+        .bind(id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(members)
+    }
+
+    /// Adds a member. Adding someone who is already a member is a no-op.
+    pub async fn add_member(pool: &DbPool, id: Uuid, user_id: Uuid) -> Result<(), CommitteeError> {
+        sqlx::query(
+            "INSERT INTO committee_members (committee_id, user_id, added_at)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (committee_id, user_id) DO NOTHING",
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(Utc::now())
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_member(
+        pool: &DbPool,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), CommitteeError> {
+        let result =
+            sqlx::query("DELETE FROM committee_members WHERE committee_id = $1 AND user_id = $2")
+                .bind(id)
+                .bind(user_id)
+                .execute(pool)
+                .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(CommitteeError::MemberNotFound {
+                committee_id: id,
+                user_id,
+            });
+        }
+
+        Ok(())
+    }
+}
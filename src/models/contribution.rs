@@ -1,8 +1,9 @@
 use crate::database::connection::DbPool;
+use crate::utils::pagination::{Page, PageRequest};
 use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{FromRow, PgConnection};
 use thiserror::Error;
 use uuid::Uuid;
 
@@ -14,6 +15,8 @@ pub enum ContributionError {
     Database(#[from] sqlx::Error),
     #[error("No fields provided for update")]
     NoUpdateFields,
+    #[error("Contribution {id} has payments and cannot be purged")]
+    HasPayments { id: Uuid },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -23,9 +26,11 @@ pub struct Contribution {
     pub title: String,
     pub description: Option<String>,
     pub amount: Option<Decimal>,
+    pub currency: String,
     pub due_date: NaiveDate,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +39,7 @@ pub struct CreateContribution {
     pub title: String,
     pub description: Option<String>,
     pub amount: Option<Decimal>,
+    pub currency: String,
     pub due_date: NaiveDate,
 }
 
@@ -42,79 +48,110 @@ pub struct UpdateContribution {
     pub title: Option<String>,
     pub description: Option<String>,
     pub amount: Option<Decimal>,
+    pub currency: Option<String>,
     pub due_date: Option<NaiveDate>,
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct ContributionFilter {
+    pub currency: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub due_from: Option<NaiveDate>,
+    pub due_to: Option<NaiveDate>,
+}
+
 impl Contribution {
     pub async fn create(
-        pool: &DbPool,
+        conn: &mut PgConnection,
         contribution: CreateContribution,
     ) -> Result<Self, ContributionError> {
         let now = Utc::now();
 
         let contribution = sqlx::query_as::<_, Contribution>(
-            "INSERT INTO contributions (id, title, description, amount, due_date, created_by, created_at, updated_at) 
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8) 
+            "INSERT INTO contributions (id, title, description, amount, currency, due_date, created_by, created_at, updated_at) 
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) 
              RETURNING *",
         )
             .bind(Uuid::new_v4())
             .bind(contribution.title)
             .bind(contribution.description)
             .bind(contribution.amount)
+            .bind(contribution.currency)
             .bind(contribution.due_date)
             .bind(contribution.created_by)
             .bind(now)
             .bind(now)
-            .fetch_one(pool)
+            .fetch_one(&mut *conn)
             .await?;
 
         Ok(contribution)
     }
 
     pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, ContributionError> {
-        let contribution =
-            sqlx::query_as::<_, Contribution>("SELECT * FROM contributions WHERE id = $1")
-                .bind(id)
-                .fetch_optional(pool)
-                .await?;
+        let contribution = sqlx::query_as::<_, Contribution>(
+            "SELECT * FROM contributions WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
 
         Ok(contribution)
     }
 
-    pub async fn find_all(pool: &DbPool) -> Result<Vec<Self>, ContributionError> {
-        let contributions = sqlx::query_as::<_, Contribution>(
-            "SELECT * FROM contributions ORDER BY created_at DESC",
-        )
-        .fetch_all(pool)
-        .await?;
+    pub async fn find_page(
+        pool: &DbPool,
+        filter: &ContributionFilter,
+        page: &PageRequest,
+    ) -> Result<Page<Self>, ContributionError> {
+        let contributions = page
+            .fetch(pool, |query| {
+                query.push("SELECT * FROM contributions WHERE deleted_at IS NULL");
+                if let Some(currency) = &filter.currency {
+                    query.push(" AND currency = ").push_bind(currency.clone());
+                }
+                if let Some(created_by) = filter.created_by {
+                    query.push(" AND created_by = ").push_bind(created_by);
+                }
+                if let Some(due_from) = filter.due_from {
+                    query.push(" AND due_date >= ").push_bind(due_from);
+                }
+                if let Some(due_to) = filter.due_to {
+                    query.push(" AND due_date <= ").push_bind(due_to);
+                }
+            })
+            .await?;
 
         Ok(contributions)
     }
 
     pub async fn update(
-        pool: &DbPool,
+        conn: &mut PgConnection,
         id: Uuid,
         update_data: UpdateContribution,
     ) -> Result<Option<Self>, ContributionError> {
         if update_data.title.is_none()
             && update_data.description.is_none()
             && update_data.amount.is_none()
+            && update_data.currency.is_none()
             && update_data.due_date.is_none()
         {
             return Err(ContributionError::NoUpdateFields);
         }
 
-        let existing = match Self::find_by_id(pool, id).await? {
-            Some(contribution) => contribution,
-            None => return Err(ContributionError::NotFound { id }),
-        };
+        let existing = sqlx::query_as::<_, Contribution>(
+            "SELECT * FROM contributions WHERE id = $1 AND deleted_at IS NULL FOR UPDATE",
+        )
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(ContributionError::NotFound { id })?;
 
         let now = Utc::now();
 
         let updated_contribution = sqlx::query_as::<_, Contribution>(
             "UPDATE contributions 
-             SET title = $2, description = $3, amount = $4, due_date = $5, updated_at = $6
-             WHERE id = $1 
+             SET title = $2, description = $3, amount = $4, due_date = $5, updated_at = $6, currency = $7
+             WHERE id = $1 AND deleted_at IS NULL
              RETURNING *",
         )
         .bind(id)
@@ -123,17 +160,67 @@ impl Contribution {
         .bind(update_data.amount.or(existing.amount))
         .bind(update_data.due_date.unwrap_or(existing.due_date))
         .bind(now)
-        .fetch_optional(pool)
+        .bind(update_data.currency.unwrap_or(existing.currency))
+        .fetch_optional(&mut *conn)
         .await?;
 
         Ok(updated_contribution)
     }
 
-    pub async fn delete(pool: &DbPool, id: Uuid) -> Result<(), ContributionError> {
-        let result = sqlx::query("DELETE FROM contributions WHERE id = $1")
-            .bind(id)
-            .execute(pool)
-            .await?;
+    /// Hides the contribution; its payments are kept. See `purge` for permanent removal.
+    pub async fn delete(conn: &mut PgConnection, id: Uuid) -> Result<(), ContributionError> {
+        let result = sqlx::query(
+            "UPDATE contributions SET deleted_at = $2, updated_at = $2
+             WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .bind(Utc::now())
+        .execute(&mut *conn)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ContributionError::NotFound { id });
+        }
+
+        Ok(())
+    }
+
+    pub async fn find_deleted(pool: &DbPool) -> Result<Vec<Self>, ContributionError> {
+        let contributions = sqlx::query_as::<_, Contribution>(
+            "SELECT * FROM contributions WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(contributions)
+    }
+
+    pub async fn restore(conn: &mut PgConnection, id: Uuid) -> Result<Self, ContributionError> {
+        sqlx::query_as::<_, Contribution>(
+            "UPDATE contributions SET deleted_at = NULL, updated_at = $2
+             WHERE id = $1 AND deleted_at IS NOT NULL
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(Utc::now())
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(ContributionError::NotFound { id })
+    }
+
+    /// Permanently removes a deleted contribution. Refused while payments still reference it.
+    pub async fn purge(conn: &mut PgConnection, id: Uuid) -> Result<(), ContributionError> {
+        let result =
+            sqlx::query("DELETE FROM contributions WHERE id = $1 AND deleted_at IS NOT NULL")
+                .bind(id)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| match e.as_database_error() {
+                    Some(db) if db.is_foreign_key_violation() => {
+                        ContributionError::HasPayments { id }
+                    }
+                    _ => ContributionError::Database(e),
+                })?;
 
         if result.rows_affected() == 0 {
             return Err(ContributionError::NotFound { id });
@@ -147,7 +234,9 @@ impl Contribution {
         created_by: Uuid,
     ) -> Result<Vec<Self>, ContributionError> {
         let contributions = sqlx::query_as::<_, Contribution>(
-            "SELECT * FROM contributions WHERE created_by = $1 ORDER BY created_at DESC",
+            "SELECT * FROM contributions
+             WHERE created_by = $1 AND deleted_at IS NULL
+             ORDER BY created_at DESC",
         )
         .bind(created_by)
         .fetch_all(pool)
@@ -158,10 +247,12 @@ impl Contribution {
 
     pub async fn find_due_before(
         pool: &DbPool,
-        before_date: DateTime<Utc>,
+        before_date: NaiveDate,
     ) -> Result<Vec<Self>, ContributionError> {
         let contributions = sqlx::query_as::<_, Contribution>(
-            "SELECT * FROM contributions WHERE due_date <= $1 ORDER BY due_date ASC",
+            "SELECT * FROM contributions
+             WHERE due_date <= $1 AND deleted_at IS NULL
+             ORDER BY due_date ASC",
         )
         .bind(before_date)
         .fetch_all(pool)
@@ -0,0 +1,118 @@
+use crate::database::connection::DbPool;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum DuesReminderError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DuesReminder {
+    pub id: Uuid,
+    pub contribution_id: Uuid,
+    pub user_id: Uuid,
+    /// Days relative to the due date: negative before, zero on the day, positive after.
+    pub offset_days: i32,
+    /// When the reminder was queued; delivery status is on the linked outbox email.
+    pub sent_at: Option<DateTime<Utc>>,
+    pub email_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An active member who still owes on a contribution and has not been reminded at this offset.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ReminderRecipient {
+    pub user_id: Uuid,
+    pub fullname: String,
+    pub email: String,
+    /// Remaining balance in the base currency; `None` for contributions without a fixed amount.
+    pub outstanding: Option<Decimal>,
+}
+
+impl DuesReminder {
+    pub async fn find_recipients(
+        pool: &DbPool,
+        contribution_id: Uuid,
+        offset_days: i32,
+    ) -> Result<Vec<ReminderRecipient>, DuesReminderError> {
+        let recipients = sqlx::query_as::<_, ReminderRecipient>(
+            r#"
+            WITH due AS (
+                SELECT ROUND(amount * exchange_rate_on(currency, due_date), 2) AS amount
+                FROM contributions WHERE id = $1
+            ),
+            paid AS (
+                SELECT user_id, SUM(base_amount) AS total
+                FROM payments
+                WHERE contribution_id = $1 AND status = 'verified'
+                GROUP BY user_id
+            )
+            SELECT u.id AS user_id, u.fullname, u.email,
+                   due.amount - COALESCE(paid.total, 0) AS outstanding
+            FROM users u
+            CROSS JOIN due
+            LEFT JOIN paid ON paid.user_id = u.id
+            WHERE u.is_active
+              AND u.dues_reminders_enabled
+              AND CASE WHEN due.amount IS NULL THEN COALESCE(paid.total, 0) <= 0
+                       ELSE COALESCE(paid.total, 0) < due.amount END
+              AND NOT EXISTS (
+                  SELECT 1 FROM dues_reminders r
+                  WHERE r.contribution_id = $1 AND r.user_id = u.id AND r.offset_days = $2
+              )
+            ORDER BY u.fullname ASC
+            "#,
+        )
+        .bind(contribution_id)
+        .bind(offset_days)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(recipients)
+    }
+
+    /// Claims the reminder slot before queueing. Returns `None` if another run already has it.
+    pub async fn reserve(
+        conn: &mut PgConnection,
+        contribution_id: Uuid,
+        user_id: Uuid,
+        offset_days: i32,
+    ) -> Result<Option<Self>, DuesReminderError> {
+        let reminder = sqlx::query_as::<_, DuesReminder>(
+            "INSERT INTO dues_reminders (id, contribution_id, user_id, offset_days, created_at)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (contribution_id, user_id, offset_days) DO NOTHING
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(contribution_id)
+        .bind(user_id)
+        .bind(offset_days)
+        .bind(Utc::now())
+        .fetch_optional(conn)
+        .await?;
+
+        Ok(reminder)
+    }
+
+    pub async fn mark_queued(
+        conn: &mut PgConnection,
+        id: Uuid,
+        email_id: Uuid,
+    ) -> Result<(), DuesReminderError> {
+        sqlx::query("UPDATE dues_reminders SET sent_at = $2, email_id = $3 WHERE id = $1")
+            .bind(id)
+            .bind(Utc::now())
+            .bind(email_id)
+            .execute(conn)
+            .await?;
+
+        Ok(())
+    }
+}
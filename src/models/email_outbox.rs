@@ -0,0 +1,268 @@
+use crate::database::connection::DbPool;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection, Type};
+use std::str::FromStr;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum OutboxEmailError {
+    #[error("Email with ID {id} not found")]
+    NotFound { id: Uuid },
+    #[error("Email {id} has not failed and cannot be retried")]
+    NotRetryable { id: Uuid },
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[sqlx(type_name = "email_status", rename_all = "lowercase")]
+pub enum EmailStatus {
+    Pending,
+    Sending,
+    Sent,
+    Failed,
+}
+
+impl FromStr for EmailStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(EmailStatus::Pending),
+            "sending" => Ok(EmailStatus::Sending),
+            "sent" => Ok(EmailStatus::Sent),
+            "failed" => Ok(EmailStatus::Failed),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OutboxEmail {
+    pub id: Uuid,
+    pub to_email: String,
+    pub to_name: Option<String>,
+    pub subject: String,
+    #[serde(skip_serializing)]
+    pub html_body: String,
+    #[serde(skip_serializing)]
+    pub text_body: Option<String>,
+    pub status: EmailStatus,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub locked_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct OutboxAttachment {
+    pub filename: String,
+    pub content_type: String,
+    pub content: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewOutboxEmail {
+    pub to_email: String,
+    pub to_name: Option<String>,
+    pub subject: String,
+    pub html_body: String,
+    pub text_body: Option<String>,
+    pub attachments: Vec<OutboxAttachment>,
+    pub max_attempts: i32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OutboxEmailFilter {
+    pub status: Option<EmailStatus>,
+    pub to_email: Option<String>,
+    pub limit: i64,
+}
+
+impl OutboxEmail {
+    /// Writes a message to the outbox. Takes a connection so callers can queue it inside the
+    /// transaction that makes the change the email is about.
+    pub async fn queue(
+        conn: &mut PgConnection,
+        email: NewOutboxEmail,
+    ) -> Result<Self, OutboxEmailError> {
+        let now = Utc::now();
+
+        let queued = sqlx::query_as::<_, OutboxEmail>(
+            "INSERT INTO email_outbox (id, to_email, to_name, subject, html_body, text_body, max_attempts, next_attempt_at, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $8, $8)
+             RETURNING *",
+        )
+            .bind(Uuid::new_v4())
+            .bind(email.to_email)
+            .bind(email.to_name)
+            .bind(email.subject)
+            .bind(email.html_body)
+            .bind(email.text_body)
+            .bind(email.max_attempts)
+            .bind(now)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        for attachment in email.attachments {
+            sqlx::query(
+                "INSERT INTO email_outbox_attachments (id, email_id, filename, content_type, content, created_at)
+                 VALUES ($1, $2, $3, $4, $5, $6)",
+            )
+                .bind(Uuid::new_v4())
+                .bind(queued.id)
+                .bind(attachment.filename)
+                .bind(attachment.content_type)
+                .bind(attachment.content)
+                .bind(now)
+                .execute(&mut *conn)
+                .await?;
+        }
+
+        Ok(queued)
+    }
+
+    /// Locks up to `limit` messages that are due for delivery. Messages left sending longer
+    /// than `stale_before` by a crashed dispatcher are picked up again.
+    pub async fn claim_batch(
+        pool: &DbPool,
+        limit: i64,
+        stale_before: DateTime<Utc>,
+    ) -> Result<Vec<Self>, OutboxEmailError> {
+        let now = Utc::now();
+
+        let emails = sqlx::query_as::<_, OutboxEmail>(
+            "UPDATE email_outbox
+             SET status = 'sending', attempts = attempts + 1, locked_at = $1, updated_at = $1
+             WHERE id IN (
+                 SELECT id FROM email_outbox
+                 WHERE (status = 'pending' AND next_attempt_at <= $1)
+                    OR (status = 'sending' AND locked_at < $3)
+                 ORDER BY next_attempt_at ASC
+                 LIMIT $2
+                 FOR UPDATE SKIP LOCKED
+             )
+             RETURNING *",
+        )
+        .bind(now)
+        .bind(limit)
+        .bind(stale_before)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(emails)
+    }
+
+    pub async fn find_attachments(
+        pool: &DbPool,
+        id: Uuid,
+    ) -> Result<Vec<OutboxAttachment>, OutboxEmailError> {
+        let attachments = sqlx::query_as::<_, OutboxAttachment>(
+            "SELECT filename, content_type, content FROM email_outbox_attachments
+             WHERE email_id = $1 ORDER BY created_at ASC, filename ASC",
+        )
+        .bind(id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(attachments)
+    }
+
+    pub async fn mark_sent(pool: &DbPool, id: Uuid) -> Result<(), OutboxEmailError> {
+        sqlx::query(
+            "UPDATE email_outbox
+             SET status = 'sent', sent_at = $2, locked_at = NULL, last_error = NULL, updated_at = $2
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(Utc::now())
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Records a failed delivery. The message is retried at `retry_at`, or marked failed when
+    /// `None`.
+    pub async fn mark_failed(
+        pool: &DbPool,
+        id: Uuid,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), OutboxEmailError> {
+        sqlx::query(
+            "UPDATE email_outbox
+             SET status = CASE WHEN $3::timestamptz IS NULL THEN 'failed'::email_status ELSE 'pending'::email_status END,
+                 next_attempt_at = COALESCE($3, next_attempt_at), last_error = $2,
+                 locked_at = NULL, updated_at = $4
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(error)
+        .bind(retry_at)
+        .bind(Utc::now())
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, OutboxEmailError> {
+        let email = sqlx::query_as::<_, OutboxEmail>("SELECT * FROM email_outbox WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(email)
+    }
+
+    pub async fn find_all(
+        pool: &DbPool,
+        filter: OutboxEmailFilter,
+    ) -> Result<Vec<Self>, OutboxEmailError> {
+        let emails = sqlx::query_as::<_, OutboxEmail>(
+            "SELECT * FROM email_outbox
+             WHERE ($1::email_status IS NULL OR status = $1)
+               AND ($2::varchar IS NULL OR to_email = $2)
+             ORDER BY created_at DESC
+             LIMIT $3",
+        )
+        .bind(filter.status)
+        .bind(filter.to_email)
+        .bind(filter.limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(emails)
+    }
+
+    /// Puts a failed message back in the outbox with a fresh set of attempts.
+    pub async fn retry(pool: &DbPool, id: Uuid) -> Result<Self, OutboxEmailError> {
+        let now = Utc::now();
+
+        let email = sqlx::query_as::<_, OutboxEmail>(
+            "UPDATE email_outbox
+             SET status = 'pending', attempts = 0, next_attempt_at = $2, updated_at = $2
+             WHERE id = $1 AND status = 'failed'
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(now)
+        .fetch_optional(pool)
+        .await?;
+
+        match email {
+            Some(email) => Ok(email),
+            None => match Self::find_by_id(pool, id).await? {
+                Some(_) => Err(OutboxEmailError::NotRetryable { id }),
+                None => Err(OutboxEmailError::NotFound { id }),
+            },
+        }
+    }
+}
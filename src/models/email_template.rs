@@ -0,0 +1,174 @@
+use crate::database::connection::DbPool;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum EmailTemplateError {
+    #[error("Email template {name} not found")]
+    NotFound { name: String },
+    #[error("Version {version} of email template {name} not found")]
+    VersionNotFound { name: String, version: i32 },
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// One saved revision of an email template. Bodies are Tera templates.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EmailTemplateVersion {
+    pub id: Uuid,
+    pub name: String,
+    pub version: i32,
+    pub subject: String,
+    pub html_body: String,
+    /// `None` when the plain-text alternative is generated from the HTML.
+    pub text_body: Option<String>,
+    pub is_active: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateEmailTemplateVersion {
+    pub name: String,
+    pub subject: String,
+    pub html_body: String,
+    pub text_body: Option<String>,
+    pub created_by: Uuid,
+}
+
+impl EmailTemplateVersion {
+    /// Takes a connection so emails rendered inside a transaction see the same templates.
+    pub async fn find_active(
+        conn: &mut PgConnection,
+        name: &str,
+    ) -> Result<Self, EmailTemplateError> {
+        let template = sqlx::query_as::<_, EmailTemplateVersion>(
+            "SELECT * FROM email_templates WHERE name = $1 AND is_active",
+        )
+        .bind(name)
+        .fetch_optional(conn)
+        .await?;
+
+        template.ok_or_else(|| EmailTemplateError::NotFound {
+            name: name.to_string(),
+        })
+    }
+
+    pub async fn find_all_active(pool: &DbPool) -> Result<Vec<Self>, EmailTemplateError> {
+        let templates = sqlx::query_as::<_, EmailTemplateVersion>(
+            "SELECT * FROM email_templates WHERE is_active ORDER BY name ASC",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(templates)
+    }
+
+    pub async fn find_versions(pool: &DbPool, name: &str) -> Result<Vec<Self>, EmailTemplateError> {
+        let versions = sqlx::query_as::<_, EmailTemplateVersion>(
+            "SELECT * FROM email_templates WHERE name = $1 ORDER BY version DESC",
+        )
+        .bind(name)
+        .fetch_all(pool)
+        .await?;
+
+        if versions.is_empty() {
+            return Err(EmailTemplateError::NotFound {
+                name: name.to_string(),
+            });
+        }
+
+        Ok(versions)
+    }
+
+    /// Saves a new version and makes it the active one.
+    pub async fn create_version(
+        pool: &DbPool,
+        template: CreateEmailTemplateVersion,
+    ) -> Result<Self, EmailTemplateError> {
+        let mut tx = pool.begin().await?;
+
+        // Lock the current versions so concurrent saves get consecutive numbers
+        let latest: Option<i32> = sqlx::query_scalar(
+            "SELECT version FROM email_templates WHERE name = $1
+             ORDER BY version DESC LIMIT 1 FOR UPDATE",
+        )
+        .bind(&template.name)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(latest) = latest else {
+            return Err(EmailTemplateError::NotFound {
+                name: template.name,
+            });
+        };
+
+        sqlx::query("UPDATE email_templates SET is_active = false WHERE name = $1 AND is_active")
+            .bind(&template.name)
+            .execute(&mut *tx)
+            .await?;
+
+        let created = sqlx::query_as::<_, EmailTemplateVersion>(
+            "INSERT INTO email_templates (id, name, version, subject, html_body, text_body, is_active, created_by, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, true, $7, $8)
+             RETURNING *",
+        )
+            .bind(Uuid::new_v4())
+            .bind(template.name)
+            .bind(latest + 1)
+            .bind(template.subject)
+            .bind(template.html_body)
+            .bind(template.text_body)
+            .bind(template.created_by)
+            .bind(Utc::now())
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(created)
+    }
+
+    /// Makes an earlier (or later) version the active one again.
+    pub async fn activate(
+        pool: &DbPool,
+        name: &str,
+        version: i32,
+    ) -> Result<Self, EmailTemplateError> {
+        let mut tx = pool.begin().await?;
+
+        let exists: Option<Uuid> =
+            sqlx::query_scalar("SELECT id FROM email_templates WHERE name = $1 AND version = $2")
+                .bind(name)
+                .bind(version)
+                .fetch_optional(&mut *tx)
+                .await?;
+        if exists.is_none() {
+            return Err(EmailTemplateError::VersionNotFound {
+                name: name.to_string(),
+                version,
+            });
+        }
+
+        sqlx::query("UPDATE email_templates SET is_active = false WHERE name = $1 AND is_active")
+            .bind(name)
+            .execute(&mut *tx)
+            .await?;
+
+        let activated = sqlx::query_as::<_, EmailTemplateVersion>(
+            "UPDATE email_templates SET is_active = true WHERE name = $1 AND version = $2
+             RETURNING *",
+        )
+        .bind(name)
+        .bind(version)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(activated)
+    }
+}
@@ -0,0 +1,53 @@
+use crate::database::connection::DbPool;
+use chrono::Utc;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// The mailing list announcement broadcasts go out on.
+pub const ANNOUNCEMENTS_LIST: &str = "announcements";
+
+/// Lists a member can opt out of. Transactional mail (verification, receipts) has no list.
+pub const MAILING_LISTS: &[&str] = &[ANNOUNCEMENTS_LIST];
+
+#[derive(Error, Debug)]
+pub enum EmailUnsubscribeError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+pub struct EmailUnsubscribe;
+
+impl EmailUnsubscribe {
+    pub async fn unsubscribe(
+        pool: &DbPool,
+        user_id: Uuid,
+        list: &str,
+    ) -> Result<(), EmailUnsubscribeError> {
+        sqlx::query(
+            "INSERT INTO email_unsubscribes (user_id, list, created_at)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (user_id, list) DO NOTHING",
+        )
+        .bind(user_id)
+        .bind(list)
+        .bind(Utc::now())
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn resubscribe(
+        pool: &DbPool,
+        user_id: Uuid,
+        list: &str,
+    ) -> Result<(), EmailUnsubscribeError> {
+        sqlx::query("DELETE FROM email_unsubscribes WHERE user_id = $1 AND list = $2")
+            .bind(user_id)
+            .bind(list)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+use crate::database::connection::DbPool;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Currency every ledger and report total is converted to. Matches the column defaults and
+/// the `exchange_rate_on` function in the migrations.
+pub const BASE_CURRENCY: &str = "NGN";
+
+#[derive(Error, Debug)]
+pub enum ExchangeRateError {
+    #[error("Exchange rate with ID {id} not found")]
+    NotFound { id: Uuid },
+    #[error("No {currency} exchange rate is in effect on {date}")]
+    Missing { currency: String, date: NaiveDate },
+    #[error("Invalid currency code: {0}")]
+    InvalidCurrency(String),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Units of the base currency one unit of `currency` was worth from `effective_date` on.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ExchangeRate {
+    pub id: Uuid,
+    pub currency: String,
+    pub rate: Decimal,
+    pub effective_date: NaiveDate,
+    pub notes: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetExchangeRate {
+    pub currency: String,
+    pub rate: Decimal,
+    pub effective_date: NaiveDate,
+    pub notes: Option<String>,
+    pub created_by: Uuid,
+}
+
+/// Upper-cases and checks an ISO 4217 style three letter code.
+pub fn normalize_currency(code: &str) -> Result<String, ExchangeRateError> {
+    let code = code.trim().to_uppercase();
+    if code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase()) {
+        Ok(code)
+    } else {
+        Err(ExchangeRateError::InvalidCurrency(code))
+    }
+}
+
+impl ExchangeRate {
+    /// Records the rate for a currency from a date, replacing any rate already entered for
+    /// that same date.
+    pub async fn set(
+        conn: &mut PgConnection,
+        rate: SetExchangeRate,
+    ) -> Result<Self, ExchangeRateError> {
+        let currency = normalize_currency(&rate.currency)?;
+        if currency == BASE_CURRENCY {
+            return Err(ExchangeRateError::InvalidCurrency(currency));
+        }
+
+        let now = Utc::now();
+
+        let rate = sqlx::query_as::<_, ExchangeRate>(
+            "INSERT INTO exchange_rates (id, currency, rate, effective_date, notes, created_by, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $7)
+             ON CONFLICT (currency, effective_date)
+             DO UPDATE SET rate = EXCLUDED.rate, notes = EXCLUDED.notes,
+                           created_by = EXCLUDED.created_by, updated_at = EXCLUDED.updated_at
+             RETURNING *",
+        )
+            .bind(Uuid::new_v4())
+            .bind(currency)
+            .bind(rate.rate)
+            .bind(rate.effective_date)
+            .bind(rate.notes)
+            .bind(rate.created_by)
+            .bind(now)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        Ok(rate)
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, ExchangeRateError> {
+        let rate = sqlx::query_as::<_, ExchangeRate>("SELECT * FROM exchange_rates WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(rate)
+    }
+
+    /// The rate entered for `currency` from exactly `effective_date`, which `set` replaces.
+    pub async fn find_by_date(
+        pool: &DbPool,
+        currency: &str,
+        effective_date: NaiveDate,
+    ) -> Result<Option<Self>, ExchangeRateError> {
+        let rate = sqlx::query_as::<_, ExchangeRate>(
+            "SELECT * FROM exchange_rates WHERE currency = $1 AND effective_date = $2",
+        )
+        .bind(currency)
+        .bind(effective_date)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(rate)
+    }
+
+    pub async fn find_all(
+        pool: &DbPool,
+        currency: Option<&str>,
+    ) -> Result<Vec<Self>, ExchangeRateError> {
+        let rates = sqlx::query_as::<_, ExchangeRate>(
+            "SELECT * FROM exchange_rates
+             WHERE ($1::varchar IS NULL OR currency = $1)
+             ORDER BY currency ASC, effective_date DESC",
+        )
+        .bind(currency)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rates)
+    }
+
+    /// The rate in effect for `currency` on `date`. The base currency is always 1.
+    pub async fn rate_on(
+        conn: &mut PgConnection,
+        currency: &str,
+        date: NaiveDate,
+    ) -> Result<Decimal, ExchangeRateError> {
+        let rate = sqlx::query_scalar::<_, Option<Decimal>>("SELECT exchange_rate_on($1, $2)")
+            .bind(currency)
+            .bind(date)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        rate.ok_or_else(|| ExchangeRateError::Missing {
+            currency: currency.to_string(),
+            date,
+        })
+    }
+
+    pub async fn delete(conn: &mut PgConnection, id: Uuid) -> Result<(), ExchangeRateError> {
+        let result = sqlx::query("DELETE FROM exchange_rates WHERE id = $1")
+            .bind(id)
+            .execute(&mut *conn)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ExchangeRateError::NotFound { id });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_currency_codes() {
+        assert_eq!(normalize_currency("usd").unwrap(), "USD");
+        assert_eq!(normalize_currency(" gbp ").unwrap(), "GBP");
+        assert_eq!(normalize_currency(BASE_CURRENCY).unwrap(), BASE_CURRENCY);
+    }
+
+    #[test]
+    fn rejects_codes_that_are_not_three_letters() {
+        for code in ["", "US", "USDT", "U5D", "$$$", "ÉUR"] {
+            assert!(
+                matches!(
+                    normalize_currency(code),
+                    Err(ExchangeRateError::InvalidCurrency(_))
+                ),
+                "{}",
+                code
+            );
+        }
+    }
+}
@@ -0,0 +1,374 @@
+use crate::database::connection::DbPool;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection, Type};
+use std::str::FromStr;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum ExpenseError {
+    #[error("Expense with ID {id} not found")]
+    NotFound { id: Uuid },
+    #[error("Expense category with ID {id} not found")]
+    CategoryNotFound { id: Uuid },
+    #[error("Expense category '{name}' already exists")]
+    CategoryAlreadyExists { name: String },
+    #[error("Expense {id} has already been reviewed")]
+    AlreadyReviewed { id: Uuid },
+    #[error("An expense cannot be approved by the person who recorded it")]
+    SelfApproval,
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("No fields provided for update")]
+    NoUpdateFields,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[sqlx(type_name = "expense_status", rename_all = "lowercase")]
+pub enum ExpenseStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl FromStr for ExpenseStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(ExpenseStatus::Pending),
+            "approved" => Ok(ExpenseStatus::Approved),
+            "rejected" => Ok(ExpenseStatus::Rejected),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ExpenseCategory {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Expense {
+    pub id: Uuid,
+    pub category_id: Uuid,
+    pub amount: Decimal,
+    pub payee: String,
+    pub description: Option<String>,
+    pub spent_on: NaiveDate,
+    pub event_id: Option<Uuid>,
+    pub project: Option<String>,
+    #[serde(skip_serializing)]
+    pub receipt_key: Option<String>,
+    pub receipt_content_type: Option<String>,
+    pub status: ExpenseStatus,
+    pub recorded_by: Option<Uuid>,
+    pub approved_by: Option<Uuid>,
+    pub approved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateExpense {
+    pub category_id: Uuid,
+    pub amount: Decimal,
+    pub payee: String,
+    pub description: Option<String>,
+    pub spent_on: NaiveDate,
+    pub event_id: Option<Uuid>,
+    pub project: Option<String>,
+    pub recorded_by: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateExpense {
+    pub category_id: Option<Uuid>,
+    pub amount: Option<Decimal>,
+    pub payee: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<Option<String>>,
+    pub spent_on: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<Option<Uuid>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<Option<String>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExpenseFilter {
+    pub status: Option<ExpenseStatus>,
+    pub category_id: Option<Uuid>,
+    pub year: Option<i32>,
+}
+
+impl ExpenseCategory {
+    pub async fn create(
+        pool: &DbPool,
+        name: String,
+        description: Option<String>,
+    ) -> Result<Self, ExpenseError> {
+        let existing = sqlx::query_as::<_, ExpenseCategory>(
+            "SELECT * FROM expense_categories WHERE LOWER(name) = LOWER($1)",
+        )
+        .bind(&name)
+        .fetch_optional(pool)
+        .await?;
+
+        if existing.is_some() {
+            return Err(ExpenseError::CategoryAlreadyExists { name });
+        }
+
+        let now = Utc::now();
+
+        let category = sqlx::query_as::<_, ExpenseCategory>(
+            "INSERT INTO expense_categories (id, name, description, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5)
+             RETURNING *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(name)
+        .bind(description)
+        .bind(now)
+        .bind(now)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(category)
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, ExpenseError> {
+        let category =
+            sqlx::query_as::<_, ExpenseCategory>("SELECT * FROM expense_categories WHERE id = $1")
+                .bind(id)
+                .fetch_optional(pool)
+                .await?;
+
+        Ok(category)
+    }
+
+    pub async fn find_all(pool: &DbPool) -> Result<Vec<Self>, ExpenseError> {
+        let categories = sqlx::query_as::<_, ExpenseCategory>(
+            "SELECT * FROM expense_categories ORDER BY name ASC",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(categories)
+    }
+}
+
+impl Expense {
+    pub async fn create(pool: &DbPool, expense: CreateExpense) -> Result<Self, ExpenseError> {
+        if ExpenseCategory::find_by_id(pool, expense.category_id)
+            .await?
+            .is_none()
+        {
+            return Err(ExpenseError::CategoryNotFound {
+                id: expense.category_id,
+            });
+        }
+
+        let now = Utc::now();
+
+        let expense = sqlx::query_as::<_, Expense>(
+            "INSERT INTO expenses (id, category_id, amount, payee, description, spent_on, event_id, project, recorded_by, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+             RETURNING *",
+        )
+            .bind(Uuid::new_v4())
+            .bind(expense.category_id)
+            .bind(expense.amount)
+            .bind(expense.payee)
+            .bind(expense.description)
+            .bind(expense.spent_on)
+            .bind(expense.event_id)
+            .bind(expense.project)
+            .bind(expense.recorded_by)
+            .bind(now)
+            .bind(now)
+            .fetch_one(pool)
+            .await?;
+
+        Ok(expense)
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, ExpenseError> {
+        let expense = sqlx::query_as::<_, Expense>("SELECT * FROM expenses WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(expense)
+    }
+
+    pub async fn find_all(pool: &DbPool, filter: ExpenseFilter) -> Result<Vec<Self>, ExpenseError> {
+        let expenses = sqlx::query_as::<_, Expense>(
+            "SELECT * FROM expenses
+             WHERE ($1::expense_status IS NULL OR status = $1)
+               AND ($2::uuid IS NULL OR category_id = $2)
+               AND ($3::int IS NULL OR (spent_on >= make_date($3, 1, 1) AND spent_on < make_date($3 + 1, 1, 1)))
+             ORDER BY spent_on DESC, created_at DESC",
+        )
+        .bind(filter.status)
+        .bind(filter.category_id)
+        .bind(filter.year)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(expenses)
+    }
+
+    /// Updates an expense that is still awaiting approval. Reviewed expenses are part of
+    /// the accounts and cannot be changed.
+    pub async fn update(
+        pool: &DbPool,
+        id: Uuid,
+        update_data: UpdateExpense,
+    ) -> Result<Option<Self>, ExpenseError> {
+        if update_data.category_id.is_none()
+            && update_data.amount.is_none()
+            && update_data.payee.is_none()
+            && update_data.description.is_none()
+            && update_data.spent_on.is_none()
+            && update_data.event_id.is_none()
+            && update_data.project.is_none()
+        {
+            return Err(ExpenseError::NoUpdateFields);
+        }
+
+        let existing = match Self::find_by_id(pool, id).await? {
+            Some(expense) => expense,
+            None => return Err(ExpenseError::NotFound { id }),
+        };
+
+        if existing.status != ExpenseStatus::Pending {
+            return Err(ExpenseError::AlreadyReviewed { id });
+        }
+
+        if let Some(category_id) = update_data.category_id
+            && ExpenseCategory::find_by_id(pool, category_id)
+                .await?
+                .is_none()
+        {
+            return Err(ExpenseError::CategoryNotFound { id: category_id });
+        }
+
+        let now = Utc::now();
+
+        let updated_expense = sqlx::query_as::<_, Expense>(
+            "UPDATE expenses
+             SET category_id = $2, amount = $3, payee = $4, description = $5, spent_on = $6,
+                 event_id = $7, project = $8, updated_at = $9
+             WHERE id = $1 AND status = 'pending'
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(update_data.category_id.unwrap_or(existing.category_id))
+        .bind(update_data.amount.unwrap_or(existing.amount))
+        .bind(update_data.payee.unwrap_or(existing.payee))
+        .bind(update_data.description.unwrap_or(existing.description))
+        .bind(update_data.spent_on.unwrap_or(existing.spent_on))
+        .bind(update_data.event_id.unwrap_or(existing.event_id))
+        .bind(update_data.project.unwrap_or(existing.project))
+        .bind(now)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(updated_expense)
+    }
+
+    /// Approves or rejects a pending expense on behalf of the approving officer.
+    pub async fn review(
+        conn: &mut PgConnection,
+        id: Uuid,
+        status: ExpenseStatus,
+        reviewed_by: Uuid,
+    ) -> Result<Self, ExpenseError> {
+        let existing =
+            sqlx::query_as::<_, Expense>("SELECT * FROM expenses WHERE id = $1 FOR UPDATE")
+                .bind(id)
+                .fetch_optional(&mut *conn)
+                .await?
+                .ok_or(ExpenseError::NotFound { id })?;
+
+        if existing.status != ExpenseStatus::Pending {
+            return Err(ExpenseError::AlreadyReviewed { id });
+        }
+
+        if status == ExpenseStatus::Approved && existing.recorded_by == Some(reviewed_by) {
+            return Err(ExpenseError::SelfApproval);
+        }
+
+        let now = Utc::now();
+
+        let expense = sqlx::query_as::<_, Expense>(
+            "UPDATE expenses
+             SET status = $2, approved_by = $3, approved_at = $4, updated_at = $4
+             WHERE id = $1 AND status = 'pending'
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(status)
+        .bind(reviewed_by)
+        .bind(now)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(ExpenseError::AlreadyReviewed { id })?;
+
+        Ok(expense)
+    }
+
+    /// Attaches an uploaded receipt and returns the key of any receipt it replaced.
+    pub async fn set_receipt(
+        pool: &DbPool,
+        id: Uuid,
+        receipt_key: String,
+        content_type: String,
+    ) -> Result<(Self, Option<String>), ExpenseError> {
+        let existing = match Self::find_by_id(pool, id).await? {
+            Some(expense) => expense,
+            None => return Err(ExpenseError::NotFound { id }),
+        };
+
+        let expense = sqlx::query_as::<_, Expense>(
+            "UPDATE expenses
+             SET receipt_key = $2, receipt_content_type = $3, updated_at = $4
+             WHERE id = $1
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(receipt_key)
+        .bind(content_type)
+        .bind(Utc::now())
+        .fetch_one(pool)
+        .await?;
+
+        Ok((expense, existing.receipt_key))
+    }
+
+    /// Deletes an expense that has not been approved yet.
+    pub async fn delete(pool: &DbPool, id: Uuid) -> Result<Option<String>, ExpenseError> {
+        let receipt_key: Option<Option<String>> = sqlx::query_scalar(
+            "DELETE FROM expenses WHERE id = $1 AND status <> 'approved' RETURNING receipt_key",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+        match receipt_key {
+            Some(receipt_key) => Ok(receipt_key),
+            None => match Self::find_by_id(pool, id).await? {
+                Some(_) => Err(ExpenseError::AlreadyReviewed { id }),
+                None => Err(ExpenseError::NotFound { id }),
+            },
+        }
+    }
+}
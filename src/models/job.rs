@@ -0,0 +1,337 @@
+use crate::database::connection::DbPool;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use std::str::FromStr;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum JobError {
+    #[error("Job with ID {id} not found")]
+    NotFound { id: Uuid },
+    #[error("Job {id} is not dead-lettered and cannot be retried")]
+    NotRetryable { id: Uuid },
+    #[error("Job {id} is no longer locked by worker {worker}")]
+    LockLost { id: Uuid, worker: String },
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[sqlx(type_name = "job_status", rename_all = "lowercase")]
+pub enum JobStatus {
+    Pending,
+    Running,
+    Completed,
+    Dead,
+}
+
+impl FromStr for JobStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(JobStatus::Pending),
+            "running" => Ok(JobStatus::Running),
+            "completed" => Ok(JobStatus::Completed),
+            "dead" => Ok(JobStatus::Dead),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Job {
+    pub id: Uuid,
+    pub job_type: String,
+    pub payload: serde_json::Value,
+    pub status: JobStatus,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub run_at: DateTime<Utc>,
+    pub locked_at: Option<DateTime<Utc>>,
+    pub locked_by: Option<String>,
+    pub last_error: Option<String>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateJob {
+    pub job_type: String,
+    pub payload: serde_json::Value,
+    pub run_at: DateTime<Utc>,
+    pub max_attempts: i32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct JobFilter {
+    pub status: Option<JobStatus>,
+    pub job_type: Option<String>,
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct JobSchedule {
+    pub name: String,
+    pub cron: String,
+    pub next_run_at: DateTime<Utc>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+const INSERT_JOB: &str =
+    "INSERT INTO jobs (id, job_type, payload, run_at, max_attempts, created_at, updated_at)
+     VALUES ($1, $2, $3, $4, $5, $6, $6)
+     RETURNING *";
+
+impl Job {
+    pub async fn enqueue(pool: &DbPool, job: CreateJob) -> Result<Self, JobError> {
+        let job = sqlx::query_as::<_, Job>(INSERT_JOB)
+            .bind(Uuid::new_v4())
+            .bind(job.job_type)
+            .bind(job.payload)
+            .bind(job.run_at)
+            .bind(job.max_attempts)
+            .bind(Utc::now())
+            .fetch_one(pool)
+            .await?;
+
+        Ok(job)
+    }
+
+    /// Locks the oldest runnable job for `worker`. Jobs left running longer than
+    /// `stale_before` by a crashed worker are picked up again, or dead-lettered once they have
+    /// used all their attempts so a job that takes the worker down is not retried forever.
+    pub async fn claim(
+        pool: &DbPool,
+        worker: &str,
+        stale_before: DateTime<Utc>,
+    ) -> Result<Option<Self>, JobError> {
+        let now = Utc::now();
+
+        sqlx::query(
+            "UPDATE jobs
+             SET status = 'dead', last_error = 'Worker stopped before the job finished',
+                 locked_at = NULL, locked_by = NULL, updated_at = $1
+             WHERE status = 'running' AND locked_at < $2 AND attempts >= max_attempts",
+        )
+        .bind(now)
+        .bind(stale_before)
+        .execute(pool)
+        .await?;
+
+        let job = sqlx::query_as::<_, Job>(
+            "UPDATE jobs
+             SET status = 'running', attempts = attempts + 1, locked_at = $1, locked_by = $2, updated_at = $1
+             WHERE id = (
+                 SELECT id FROM jobs
+                 WHERE (status = 'pending' AND run_at <= $1)
+                    OR (status = 'running' AND locked_at < $3 AND attempts < max_attempts)
+                 ORDER BY run_at ASC
+                 LIMIT 1
+                 FOR UPDATE SKIP LOCKED
+             )
+             RETURNING *",
+        )
+        .bind(now)
+        .bind(worker)
+        .bind(stale_before)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    /// Marks a job `worker` is running as done. Fails with `LockLost` if the lock went stale and
+    /// another worker took the job over, leaving that worker's outcome to stand.
+    pub async fn complete(pool: &DbPool, id: Uuid, worker: &str) -> Result<(), JobError> {
+        let result = sqlx::query(
+            "UPDATE jobs
+             SET status = 'completed', completed_at = $3, locked_at = NULL, locked_by = NULL, updated_at = $3
+             WHERE id = $1 AND locked_by = $2 AND status = 'running'",
+        )
+        .bind(id)
+        .bind(worker)
+        .bind(Utc::now())
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(JobError::LockLost {
+                id,
+                worker: worker.to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Records a failed attempt. The job is retried at `retry_at`, or dead-lettered when `None`.
+    /// Like `complete`, only the worker still holding the lock can record it.
+    pub async fn fail(
+        pool: &DbPool,
+        id: Uuid,
+        worker: &str,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), JobError> {
+        let result = sqlx::query(
+            "UPDATE jobs
+             SET status = CASE WHEN $4::timestamptz IS NULL THEN 'dead'::job_status ELSE 'pending'::job_status END,
+                 run_at = COALESCE($4, run_at), last_error = $3,
+                 locked_at = NULL, locked_by = NULL, updated_at = $5
+             WHERE id = $1 AND locked_by = $2 AND status = 'running'",
+        )
+        .bind(id)
+        .bind(worker)
+        .bind(error)
+        .bind(retry_at)
+        .bind(Utc::now())
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(JobError::LockLost {
+                id,
+                worker: worker.to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, JobError> {
+        let job = sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(job)
+    }
+
+    pub async fn find_all(pool: &DbPool, filter: JobFilter) -> Result<Vec<Self>, JobError> {
+        let jobs = sqlx::query_as::<_, Job>(
+            "SELECT * FROM jobs
+             WHERE ($1::job_status IS NULL OR status = $1)
+               AND ($2::varchar IS NULL OR job_type = $2)
+             ORDER BY created_at DESC
+             LIMIT $3",
+        )
+        .bind(filter.status)
+        .bind(filter.job_type)
+        .bind(filter.limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(jobs)
+    }
+
+    /// Puts a dead-lettered job back on the queue with a fresh set of attempts.
+    pub async fn retry(pool: &DbPool, id: Uuid) -> Result<Self, JobError> {
+        let now = Utc::now();
+
+        let job = sqlx::query_as::<_, Job>(
+            "UPDATE jobs
+             SET status = 'pending', attempts = 0, run_at = $2, updated_at = $2
+             WHERE id = $1 AND status = 'dead'
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(now)
+        .fetch_optional(pool)
+        .await?;
+
+        match job {
+            Some(job) => Ok(job),
+            None => match Self::find_by_id(pool, id).await? {
+                Some(_) => Err(JobError::NotRetryable { id }),
+                None => Err(JobError::NotFound { id }),
+            },
+        }
+    }
+}
+
+impl JobSchedule {
+    /// Registers a recurring job at startup. Changing the cron expression resets the next run.
+    pub async fn register(
+        pool: &DbPool,
+        name: &str,
+        cron: &str,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<Self, JobError> {
+        let now = Utc::now();
+
+        let schedule = sqlx::query_as::<_, JobSchedule>(
+            "INSERT INTO job_schedules (name, cron, next_run_at, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $4)
+             ON CONFLICT (name) DO UPDATE
+             SET next_run_at = CASE WHEN job_schedules.cron = EXCLUDED.cron
+                                    THEN job_schedules.next_run_at ELSE EXCLUDED.next_run_at END,
+                 cron = EXCLUDED.cron,
+                 updated_at = EXCLUDED.updated_at
+             RETURNING *",
+        )
+        .bind(name)
+        .bind(cron)
+        .bind(next_run_at)
+        .bind(now)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(schedule)
+    }
+
+    /// Enqueues `job` if the schedule is due and advances it to `next_run_at`, in one
+    /// transaction so concurrent instances enqueue each run once.
+    pub async fn enqueue_if_due(
+        pool: &DbPool,
+        name: &str,
+        next_run_at: DateTime<Utc>,
+        job: CreateJob,
+    ) -> Result<Option<Job>, JobError> {
+        let now = Utc::now();
+        let mut tx = pool.begin().await?;
+
+        let advanced = sqlx::query(
+            "UPDATE job_schedules
+             SET last_run_at = $2, next_run_at = $3, updated_at = $2
+             WHERE name = $1 AND next_run_at <= $2",
+        )
+        .bind(name)
+        .bind(now)
+        .bind(next_run_at)
+        .execute(&mut *tx)
+        .await?;
+
+        if advanced.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        let job = sqlx::query_as::<_, Job>(INSERT_JOB)
+            .bind(Uuid::new_v4())
+            .bind(job.job_type)
+            .bind(job.payload)
+            .bind(job.run_at)
+            .bind(job.max_attempts)
+            .bind(now)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(job))
+    }
+
+    pub async fn find_all(pool: &DbPool) -> Result<Vec<Self>, JobError> {
+        let schedules =
+            sqlx::query_as::<_, JobSchedule>("SELECT * FROM job_schedules ORDER BY name ASC")
+                .fetch_all(pool)
+                .await?;
+
+        Ok(schedules)
+    }
+}
@@ -1,7 +1,30 @@
 pub mod announcement;
+pub mod announcement_attachment;
+pub mod announcement_delivery;
+pub mod announcement_read;
+pub mod audit_log;
 pub mod auth;
+pub mod bank_statement;
+pub mod budget;
+pub mod campaign;
+pub mod comment;
+pub mod committee;
 pub mod contribution;
+pub mod dues_reminder;
+pub mod email_outbox;
+pub mod email_template;
+pub mod email_unsubscribe;
 pub mod event;
+pub mod exchange_rate;
+pub mod expense;
+pub mod job;
+pub mod notification;
+pub mod notification_channel;
 pub mod payment;
+pub mod payment_reversal;
 pub mod photo;
+pub mod photo_tag;
+pub mod reaction;
+pub mod receipt;
+pub mod report;
 pub mod user;
@@ -0,0 +1,344 @@
+use crate::database::connection::DbPool;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use std::str::FromStr;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum NotificationError {
+    #[error("Notification with ID {id} not found")]
+    NotFound { id: Uuid },
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq, Hash)]
+#[sqlx(type_name = "notification_kind", rename_all = "snake_case")]
+pub enum NotificationKind {
+    PaymentVerified,
+    PaymentRejected,
+    Announcement,
+    EventReminder,
+    PhotoTag,
+    RoleChange,
+}
+
+impl NotificationKind {
+    pub const ALL: [NotificationKind; 6] = [
+        NotificationKind::PaymentVerified,
+        NotificationKind::PaymentRejected,
+        NotificationKind::Announcement,
+        NotificationKind::EventReminder,
+        NotificationKind::PhotoTag,
+        NotificationKind::RoleChange,
+    ];
+}
+
+impl FromStr for NotificationKind {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "payment_verified" => Ok(NotificationKind::PaymentVerified),
+            "payment_rejected" => Ok(NotificationKind::PaymentRejected),
+            "announcement" => Ok(NotificationKind::Announcement),
+            "event_reminder" => Ok(NotificationKind::EventReminder),
+            "photo_tag" => Ok(NotificationKind::PhotoTag),
+            "role_change" => Ok(NotificationKind::RoleChange),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Notification {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: Option<String>,
+    pub link: Option<String>,
+    pub subject_id: Option<Uuid>,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewNotification {
+    pub user_id: Uuid,
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: Option<String>,
+    pub link: Option<String>,
+    pub subject_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NotificationFilter {
+    pub unread_only: bool,
+    pub kind: Option<NotificationKind>,
+    /// Only notifications older than this, for paging back through the list.
+    pub before: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct NotificationPreference {
+    pub kind: NotificationKind,
+    pub enabled: bool,
+}
+
+/// SQL for whether user `$1` still wants notifications of kind `$2`.
+const WANTS_KIND: &str = "NOT EXISTS (
+    SELECT 1 FROM notification_preferences p
+    WHERE p.user_id = $1 AND p.kind = $2 AND NOT p.enabled
+)";
+
+impl Notification {
+    /// Records a notification unless the user has turned its kind off, returning `None` then.
+    pub async fn create(
+        pool: &DbPool,
+        notification: NewNotification,
+    ) -> Result<Option<Self>, NotificationError> {
+        let notification = sqlx::query_as::<_, Notification>(&format!(
+            "INSERT INTO notifications (id, user_id, kind, title, body, link, subject_id, created_at)
+             SELECT $3, $1, $2, $4, $5, $6, $7, $8
+             WHERE {WANTS_KIND}
+             RETURNING *"
+        ))
+        .bind(notification.user_id)
+        .bind(notification.kind)
+        .bind(Uuid::new_v4())
+        .bind(notification.title)
+        .bind(notification.body)
+        .bind(notification.link)
+        .bind(notification.subject_id)
+        .bind(Utc::now())
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(notification)
+    }
+
+    /// Notifies every active member in the announcement's audience except its poster, once
+    /// each however often it is called.
+    pub async fn create_for_announcement(
+        pool: &DbPool,
+        announcement_id: Uuid,
+    ) -> Result<Vec<Self>, NotificationError> {
+        let notifications = sqlx::query_as::<_, Notification>(
+            r#"
+            INSERT INTO notifications (id, user_id, kind, title, body, link, subject_id, created_at)
+            SELECT gen_random_uuid(), u.id, 'announcement', a.title,
+                   'Posted by ' || poster.fullname, '/announcements/' || a.id, a.id, $2
+            FROM announcements a
+            JOIN users poster ON poster.id = a.posted_by
+            JOIN users u ON u.is_active AND u.id <> a.posted_by
+            WHERE a.id = $1 AND a.deleted_at IS NULL
+              AND in_announcement_audience(a.id, u.id)
+              AND NOT EXISTS (
+                  SELECT 1 FROM notification_preferences p
+                  WHERE p.user_id = u.id AND p.kind = 'announcement' AND NOT p.enabled
+              )
+              AND NOT EXISTS (
+                  SELECT 1 FROM notifications n
+                  WHERE n.user_id = u.id AND n.kind = 'announcement' AND n.subject_id = a.id
+              )
+            RETURNING *
+            "#,
+        )
+        .bind(announcement_id)
+        .bind(Utc::now())
+        .fetch_all(pool)
+        .await?;
+
+        Ok(notifications)
+    }
+
+    /// Reminds every active member of events starting before `starts_before`, once per event.
+    pub async fn create_event_reminders(
+        pool: &DbPool,
+        starts_before: DateTime<Utc>,
+    ) -> Result<Vec<Self>, NotificationError> {
+        let now = Utc::now();
+        let notifications = sqlx::query_as::<_, Notification>(
+            r#"
+            INSERT INTO notifications (id, user_id, kind, title, body, link, subject_id, created_at)
+            SELECT gen_random_uuid(), u.id, 'event_reminder', 'Upcoming event: ' || e.title,
+                   'Starts ' || to_char(e.starts_at AT TIME ZONE 'UTC', 'Dy DD Mon YYYY HH24:MI "UTC"'),
+                   '/events/' || e.id, e.id, $1
+            FROM events e
+            JOIN users u ON u.is_active
+            WHERE e.starts_at > $1 AND e.starts_at <= $2
+              AND NOT EXISTS (
+                  SELECT 1 FROM notification_preferences p
+                  WHERE p.user_id = u.id AND p.kind = 'event_reminder' AND NOT p.enabled
+              )
+              AND NOT EXISTS (
+                  SELECT 1 FROM notifications n
+                  WHERE n.user_id = u.id AND n.kind = 'event_reminder' AND n.subject_id = e.id
+              )
+            RETURNING *
+            "#,
+        )
+        .bind(now)
+        .bind(starts_before)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(notifications)
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, NotificationError> {
+        let notification =
+            sqlx::query_as::<_, Notification>("SELECT * FROM notifications WHERE id = $1")
+                .bind(id)
+                .fetch_optional(pool)
+                .await?;
+
+        Ok(notification)
+    }
+
+    /// Newest first, 50 at a time unless `limit` says otherwise.
+    pub async fn find_for_user(
+        pool: &DbPool,
+        user_id: Uuid,
+        filter: NotificationFilter,
+    ) -> Result<Vec<Self>, NotificationError> {
+        let notifications = sqlx::query_as::<_, Notification>(
+            "SELECT * FROM notifications
+             WHERE user_id = $1
+               AND (NOT $2 OR read_at IS NULL)
+               AND ($3::notification_kind IS NULL OR kind = $3)
+               AND ($4::timestamptz IS NULL OR created_at < $4)
+             ORDER BY created_at DESC
+             LIMIT $5",
+        )
+        .bind(user_id)
+        .bind(filter.unread_only)
+        .bind(filter.kind)
+        .bind(filter.before)
+        .bind(filter.limit.unwrap_or(50).clamp(1, 200))
+        .fetch_all(pool)
+        .await?;
+
+        Ok(notifications)
+    }
+
+    pub async fn unread_count(pool: &DbPool, user_id: Uuid) -> Result<i64, NotificationError> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND read_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Only the recipient can mark a notification read; anyone else gets `NotFound`.
+    pub async fn mark_read(
+        pool: &DbPool,
+        user_id: Uuid,
+        id: Uuid,
+    ) -> Result<Self, NotificationError> {
+        sqlx::query_as::<_, Notification>(
+            "UPDATE notifications SET read_at = COALESCE(read_at, $3)
+             WHERE id = $1 AND user_id = $2
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(Utc::now())
+        .fetch_optional(pool)
+        .await?
+        .ok_or(NotificationError::NotFound { id })
+    }
+
+    pub async fn mark_all_read(pool: &DbPool, user_id: Uuid) -> Result<u64, NotificationError> {
+        let result = sqlx::query(
+            "UPDATE notifications SET read_at = $2 WHERE user_id = $1 AND read_at IS NULL",
+        )
+        .bind(user_id)
+        .bind(Utc::now())
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+impl NotificationPreference {
+    /// Every kind, with those the user has not set reported as enabled.
+    pub async fn find_for_user(
+        pool: &DbPool,
+        user_id: Uuid,
+    ) -> Result<Vec<Self>, NotificationError> {
+        let saved = sqlx::query_as::<_, NotificationPreference>(
+            "SELECT kind, enabled FROM notification_preferences WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(NotificationKind::ALL
+            .into_iter()
+            .map(|kind| NotificationPreference {
+                kind,
+                enabled: saved
+                    .iter()
+                    .find(|preference| preference.kind == kind)
+                    .is_none_or(|preference| preference.enabled),
+            })
+            .collect())
+    }
+
+    pub async fn set(
+        pool: &DbPool,
+        user_id: Uuid,
+        preferences: &[NotificationPreference],
+    ) -> Result<Vec<Self>, NotificationError> {
+        let mut tx = pool.begin().await?;
+        let now = Utc::now();
+        for preference in preferences {
+            sqlx::query(
+                "INSERT INTO notification_preferences (user_id, kind, enabled, updated_at)
+                 VALUES ($1, $2, $3, $4)
+                 ON CONFLICT (user_id, kind)
+                 DO UPDATE SET enabled = EXCLUDED.enabled, updated_at = EXCLUDED.updated_at",
+            )
+            .bind(user_id)
+            .bind(preference.kind)
+            .bind(preference.enabled)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Self::find_for_user(pool, user_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds_parse_from_snake_case_names() {
+        let names = [
+            "payment_verified",
+            "payment_rejected",
+            "announcement",
+            "event_reminder",
+            "photo_tag",
+            "role_change",
+        ];
+        for (name, kind) in names.into_iter().zip(NotificationKind::ALL) {
+            assert_eq!(name.parse(), Ok(kind));
+        }
+        assert_eq!("PaymentVerified".parse::<NotificationKind>(), Err(()));
+    }
+}
@@ -0,0 +1,176 @@
+use crate::database::connection::DbPool;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection, Type};
+use std::str::FromStr;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum NotificationChannelError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq, Hash)]
+#[sqlx(type_name = "notification_channel", rename_all = "snake_case")]
+pub enum ChannelKind {
+    Email,
+    Sms,
+    Messaging,
+}
+
+impl ChannelKind {
+    pub const ALL: [ChannelKind; 3] =
+        [ChannelKind::Email, ChannelKind::Sms, ChannelKind::Messaging];
+
+    /// SMS and messaging are addressed to `users.phone`.
+    pub fn needs_phone(self) -> bool {
+        matches!(self, ChannelKind::Sms | ChannelKind::Messaging)
+    }
+}
+
+impl FromStr for ChannelKind {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "email" => Ok(ChannelKind::Email),
+            "sms" => Ok(ChannelKind::Sms),
+            "messaging" => Ok(ChannelKind::Messaging),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ChannelPreference {
+    pub channel: ChannelKind,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ChannelSubscription {
+    pub user_id: Uuid,
+    pub channel: ChannelKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct NotificationDelivery {
+    pub notification_id: Uuid,
+    pub channel: ChannelKind,
+    pub provider_reference: Option<String>,
+    pub sent_at: DateTime<Utc>,
+}
+
+impl ChannelPreference {
+    /// Every channel, with those the user has not opted into reported as disabled.
+    pub async fn find_for_user(
+        pool: &DbPool,
+        user_id: Uuid,
+    ) -> Result<Vec<Self>, NotificationChannelError> {
+        let saved = sqlx::query_as::<_, ChannelPreference>(
+            "SELECT channel, enabled FROM notification_channel_preferences WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(ChannelKind::ALL
+            .into_iter()
+            .map(|channel| ChannelPreference {
+                channel,
+                enabled: saved
+                    .iter()
+                    .any(|preference| preference.channel == channel && preference.enabled),
+            })
+            .collect())
+    }
+
+    pub async fn set(
+        pool: &DbPool,
+        user_id: Uuid,
+        preferences: &[ChannelPreference],
+    ) -> Result<Vec<Self>, NotificationChannelError> {
+        let mut tx = pool.begin().await?;
+        let now = Utc::now();
+        for preference in preferences {
+            sqlx::query(
+                "INSERT INTO notification_channel_preferences (user_id, channel, enabled, updated_at)
+                 VALUES ($1, $2, $3, $4)
+                 ON CONFLICT (user_id, channel)
+                 DO UPDATE SET enabled = EXCLUDED.enabled, updated_at = EXCLUDED.updated_at",
+            )
+            .bind(user_id)
+            .bind(preference.channel)
+            .bind(preference.enabled)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Self::find_for_user(pool, user_id).await
+    }
+}
+
+impl ChannelSubscription {
+    /// The channels each of `user_ids` has turned on.
+    pub async fn find_enabled(
+        pool: &DbPool,
+        user_ids: &[Uuid],
+    ) -> Result<Vec<Self>, NotificationChannelError> {
+        let subscriptions = sqlx::query_as::<_, ChannelSubscription>(
+            "SELECT user_id, channel FROM notification_channel_preferences
+             WHERE enabled AND user_id = ANY($1)",
+        )
+        .bind(user_ids)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(subscriptions)
+    }
+}
+
+impl NotificationDelivery {
+    pub async fn exists(
+        pool: &DbPool,
+        notification_id: Uuid,
+        channel: ChannelKind,
+    ) -> Result<bool, NotificationChannelError> {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS (
+                 SELECT 1 FROM notification_deliveries WHERE notification_id = $1 AND channel = $2
+             )",
+        )
+        .bind(notification_id)
+        .bind(channel)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    pub async fn record(
+        conn: &mut PgConnection,
+        notification_id: Uuid,
+        channel: ChannelKind,
+        provider_reference: Option<String>,
+    ) -> Result<Self, NotificationChannelError> {
+        let delivery = sqlx::query_as::<_, NotificationDelivery>(
+            "INSERT INTO notification_deliveries (notification_id, channel, provider_reference, sent_at)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (notification_id, channel)
+             DO UPDATE SET provider_reference = EXCLUDED.provider_reference
+             RETURNING *",
+        )
+        .bind(notification_id)
+        .bind(channel)
+        .bind(provider_reference)
+        .bind(Utc::now())
+        .fetch_one(conn)
+        .await?;
+
+        Ok(delivery)
+    }
+}
@@ -1,8 +1,10 @@
 use crate::database::connection::DbPool;
-use chrono::{DateTime, Utc};
+use crate::models::exchange_rate::{ExchangeRate, ExchangeRateError};
+use crate::utils::pagination::{Page, PageRequest};
+use chrono::{DateTime, Days, NaiveDate, NaiveTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, Type};
+use sqlx::{Connection, FromRow, PgConnection, Type};
 use std::str::FromStr;
 use thiserror::Error;
 use uuid::Uuid;
@@ -15,6 +17,14 @@ pub enum PaymentError {
     Database(#[from] sqlx::Error),
     #[error("No fields provided for update")]
     NoUpdateFields,
+    #[error("Payment {id} has been verified and can only be refunded or reversed")]
+    Immutable { id: Uuid },
+    #[error("Payment {id} has refunds or reversals on record")]
+    HasReversals { id: Uuid },
+    #[error("Gateway event {event} for {reference} already processed")]
+    DuplicateGatewayEvent { event: String, reference: String },
+    #[error(transparent)]
+    ExchangeRate(#[from] ExchangeRateError),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq, Eq)]
@@ -22,6 +32,7 @@ pub enum PaymentError {
 pub enum PaymentStatus {
     Pending,
     Verified,
+    Failed,
 }
 
 impl FromStr for PaymentStatus {
@@ -31,30 +42,64 @@ impl FromStr for PaymentStatus {
         match s {
             "pending" => Ok(PaymentStatus::Pending),
             "verified" => Ok(PaymentStatus::Verified),
+            "failed" => Ok(PaymentStatus::Failed),
             _ => Err(()),
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[sqlx(type_name = "payment_entry_type", rename_all = "lowercase")]
+pub enum PaymentEntryType {
+    Payment,
+    Refund,
+    Reversal,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Payment {
     pub id: Uuid,
     pub user_id: Uuid,
-    pub contribution_id: Uuid,
+    pub contribution_id: Option<Uuid>,
+    pub campaign_id: Option<Uuid>,
     pub amount: Option<Decimal>,
+    pub currency: String,
+    pub exchange_rate: Decimal,
+    /// `amount` converted at `exchange_rate`; computed by the database.
+    pub base_amount: Option<Decimal>,
     pub receipt_url: Option<String>,
     pub status: PaymentStatus,
+    pub reference: Option<String>,
+    pub gateway: Option<String>,
+    pub verified_by: Option<Uuid>,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub is_anonymous: bool,
+    pub entry_type: PaymentEntryType,
+    pub reverses_payment_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreatePayment {
     pub user_id: Uuid,
-    pub contribution_id: Uuid,
+    pub contribution_id: Option<Uuid>,
+    pub campaign_id: Option<Uuid>,
+    pub is_anonymous: bool,
     pub amount: Option<Decimal>,
+    pub currency: String,
     pub receipt_url: Option<String>,
     pub status: PaymentStatus,
+    pub reference: Option<String>,
+    pub gateway: Option<String>,
+    pub verified_by: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PaymentCheckout {
+    pub payment: Payment,
+    pub authorization_url: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -65,60 +110,135 @@ pub struct UpdatePayment {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub receipt_url: Option<Option<String>>,
     pub status: Option<PaymentStatus>,
+    #[serde(skip)]
+    pub verified_by: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PaymentFilter {
+    pub status: Option<PaymentStatus>,
+    pub user_id: Option<Uuid>,
+    pub contribution_id: Option<Uuid>,
+    pub campaign_id: Option<Uuid>,
+    /// Made on or after this date, in UTC.
+    pub from: Option<NaiveDate>,
+    /// Made on or before this date, in UTC.
+    pub to: Option<NaiveDate>,
 }
 
 impl Payment {
-    pub async fn create(pool: &DbPool, payment: CreatePayment) -> Result<Self, PaymentError> {
+    /// Records a payment at the exchange rate in effect today for its currency.
+    pub async fn create(
+        conn: &mut PgConnection,
+        payment: CreatePayment,
+    ) -> Result<Self, PaymentError> {
         let now = Utc::now();
+        let exchange_rate =
+            ExchangeRate::rate_on(&mut *conn, &payment.currency, now.date_naive()).await?;
 
         let payment = sqlx::query_as::<_, Payment>(
-            "INSERT INTO payments (id, user_id, contribution_id, amount, receipt_url, status, created_at, updated_at)
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "INSERT INTO payments (id, user_id, contribution_id, campaign_id, is_anonymous, amount, receipt_url, status, reference, gateway, verified_by, verified_at, currency, exchange_rate, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10,
+                     CASE WHEN $8 = 'verified'::payment_status THEN $11 END,
+                     CASE WHEN $8 = 'verified'::payment_status THEN $12 END,
+                     $13, $14, $12, $12)
              RETURNING *",
         )
             .bind(Uuid::new_v4())
             .bind(payment.user_id)
             .bind(payment.contribution_id)
+            .bind(payment.campaign_id)
+            .bind(payment.is_anonymous)
             .bind(payment.amount)
             .bind(payment.receipt_url)
             .bind(payment.status)
+            .bind(payment.reference)
+            .bind(payment.gateway)
+            .bind(payment.verified_by)
             .bind(now)
-            .bind(now)
-            .fetch_one(pool)
+            .bind(payment.currency)
+            .bind(exchange_rate)
+            .fetch_one(&mut *conn)
             .await?;
 
         Ok(payment)
     }
 
     pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, PaymentError> {
-        let payment = sqlx::query_as::<_, Payment>("SELECT * FROM payments WHERE id = $1")
-            .bind(id)
-            .fetch_optional(pool)
-            .await?;
+        let payment = sqlx::query_as::<_, Payment>(
+            "SELECT * FROM payments WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
 
         Ok(payment)
     }
 
-    pub async fn find_by_user(pool: &DbPool, user_id: Uuid) -> Result<Option<Self>, PaymentError> {
-        let payments = sqlx::query_as::<_, Payment>("SELECT * FROM payments WHERE user_id = $1")
-            .bind(user_id)
-            .fetch_optional(pool)
-            .await?;
+    pub async fn find_by_reference(
+        pool: &DbPool,
+        reference: &str,
+    ) -> Result<Option<Self>, PaymentError> {
+        let payment = sqlx::query_as::<_, Payment>(
+            "SELECT * FROM payments WHERE reference = $1 AND deleted_at IS NULL",
+        )
+        .bind(reference)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(payment)
+    }
+
+    pub async fn find_pending(pool: &DbPool) -> Result<Vec<Self>, PaymentError> {
+        let payments = sqlx::query_as::<_, Payment>(
+            "SELECT * FROM payments WHERE status = 'pending' AND deleted_at IS NULL ORDER BY created_at ASC",
+        )
+        .fetch_all(pool)
+        .await?;
 
         Ok(payments)
     }
 
-    pub async fn find_all(pool: &DbPool) -> Result<Vec<Self>, PaymentError> {
-        let payments =
-            sqlx::query_as::<_, Payment>("SELECT * FROM payments ORDER BY created_at DESC")
-                .fetch_all(pool)
-                .await?;
+    pub async fn find_page(
+        pool: &DbPool,
+        filter: &PaymentFilter,
+        page: &PageRequest,
+    ) -> Result<Page<Self>, PaymentError> {
+        let payments = page
+            .fetch(pool, |query| {
+                query.push("SELECT * FROM payments WHERE deleted_at IS NULL");
+                if let Some(status) = &filter.status {
+                    query.push(" AND status = ").push_bind(status.clone());
+                }
+                if let Some(user_id) = filter.user_id {
+                    query.push(" AND user_id = ").push_bind(user_id);
+                }
+                if let Some(contribution_id) = filter.contribution_id {
+                    query
+                        .push(" AND contribution_id = ")
+                        .push_bind(contribution_id);
+                }
+                if let Some(campaign_id) = filter.campaign_id {
+                    query.push(" AND campaign_id = ").push_bind(campaign_id);
+                }
+                if let Some(from) = filter.from {
+                    query
+                        .push(" AND created_at >= ")
+                        .push_bind(from.and_time(NaiveTime::MIN).and_utc());
+                }
+                if let Some(to) = filter.to.and_then(|to| to.checked_add_days(Days::new(1))) {
+                    query
+                        .push(" AND created_at < ")
+                        .push_bind(to.and_time(NaiveTime::MIN).and_utc());
+                }
+            })
+            .await?;
 
         Ok(payments)
     }
 
     pub async fn update(
-        pool: &DbPool,
+        conn: &mut PgConnection,
         id: Uuid,
         update_data: UpdatePayment,
     ) -> Result<Option<Self>, PaymentError> {
@@ -131,10 +251,17 @@ impl Payment {
             return Err(PaymentError::NoUpdateFields);
         }
 
-        let existing = match Self::find_by_id(pool, id).await? {
-            Some(payment) => payment,
-            None => return Err(PaymentError::NotFound { id }),
-        };
+        let existing = sqlx::query_as::<_, Payment>(
+            "SELECT * FROM payments WHERE id = $1 AND deleted_at IS NULL FOR UPDATE",
+        )
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(PaymentError::NotFound { id })?;
+
+        if existing.status == PaymentStatus::Verified {
+            return Err(PaymentError::Immutable { id });
+        }
 
         let now = Utc::now();
 
@@ -147,33 +274,89 @@ impl Payment {
                 amount = COALESCE($4, amount),
                 status = COALESCE($5, status),
                 receipt_url = COALESCE($6, receipt_url),
+                verified_by = CASE WHEN $5 = 'verified'::payment_status AND status <> 'verified' THEN $8 ELSE verified_by END,
+                verified_at = CASE WHEN $5 = 'verified'::payment_status AND status <> 'verified' THEN $7 ELSE verified_at END,
                 updated_at = $7
-            WHERE id = $1
+            WHERE id = $1 AND deleted_at IS NULL
             RETURNING *
             "#,
         )
         .bind(id)
         .bind(update_data.user_id.unwrap_or(existing.user_id))
-        .bind(
-            update_data
-                .contribution_id
-                .or(Some(existing.contribution_id)),
-        )
+        .bind(update_data.contribution_id.or(existing.contribution_id))
         .bind(update_data.amount.or(existing.amount))
         .bind(update_data.status.unwrap_or(existing.status))
         .bind(update_data.receipt_url.unwrap_or_default())
         .bind(now)
-        .fetch_optional(pool)
+        .bind(update_data.verified_by)
+        .fetch_optional(&mut *conn)
         .await?;
 
         Ok(updated_payment)
     }
 
-    pub async fn delete(pool: &DbPool, id: Uuid) -> Result<(), PaymentError> {
-        let result = sqlx::query("DELETE FROM payments WHERE id = $1")
+    /// Hides a payment that never settled. Verified payments are part of the ledger and
+    /// must be refunded or reversed instead.
+    pub async fn delete(conn: &mut PgConnection, id: Uuid) -> Result<(), PaymentError> {
+        let result = sqlx::query(
+            "UPDATE payments SET deleted_at = $2, updated_at = $2
+             WHERE id = $1 AND status <> 'verified' AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .bind(Utc::now())
+        .execute(&mut *conn)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            let exists: bool = sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM payments WHERE id = $1 AND deleted_at IS NULL)",
+            )
             .bind(id)
-            .execute(pool)
+            .fetch_one(&mut *conn)
             .await?;
+            return match exists {
+                true => Err(PaymentError::Immutable { id }),
+                false => Err(PaymentError::NotFound { id }),
+            };
+        }
+
+        Ok(())
+    }
+
+    pub async fn find_deleted(pool: &DbPool) -> Result<Vec<Self>, PaymentError> {
+        let payments = sqlx::query_as::<_, Payment>(
+            "SELECT * FROM payments WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(payments)
+    }
+
+    pub async fn restore(conn: &mut PgConnection, id: Uuid) -> Result<Self, PaymentError> {
+        sqlx::query_as::<_, Payment>(
+            "UPDATE payments SET deleted_at = NULL, updated_at = $2
+             WHERE id = $1 AND deleted_at IS NOT NULL
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(Utc::now())
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(PaymentError::NotFound { id })
+    }
+
+    /// Permanently removes a deleted payment. Only unsettled payments can be deleted, so the
+    /// ledger is never affected. Refused while refunds or reversals reference it.
+    pub async fn purge(conn: &mut PgConnection, id: Uuid) -> Result<(), PaymentError> {
+        let result = sqlx::query("DELETE FROM payments WHERE id = $1 AND deleted_at IS NOT NULL")
+            .bind(id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| match e.as_database_error() {
+                Some(db) if db.is_foreign_key_violation() => PaymentError::HasReversals { id },
+                _ => PaymentError::Database(e),
+            })?;
 
         if result.rows_affected() == 0 {
             return Err(PaymentError::NotFound { id });
@@ -181,4 +364,58 @@ impl Payment {
 
         Ok(())
     }
+
+    /// Records a gateway webhook event and settles the matching pending payment.
+    ///
+    /// Each (gateway, event, reference) is processed once; replays return
+    /// `DuplicateGatewayEvent`. Returns `None` when the payment was already settled.
+    pub async fn apply_gateway_event(
+        conn: &mut PgConnection,
+        gateway: &str,
+        event: &str,
+        reference: &str,
+        payload: serde_json::Value,
+        status: PaymentStatus,
+    ) -> Result<Option<Self>, PaymentError> {
+        let mut tx = conn.begin().await?;
+
+        let recorded = sqlx::query(
+            "INSERT INTO payment_webhook_events (id, gateway, event, reference, payload, received_at)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT (gateway, event, reference) DO NOTHING",
+        )
+        .bind(Uuid::new_v4())
+        .bind(gateway)
+        .bind(event)
+        .bind(reference)
+        .bind(payload)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        if recorded.rows_affected() == 0 {
+            return Err(PaymentError::DuplicateGatewayEvent {
+                event: event.to_string(),
+                reference: reference.to_string(),
+            });
+        }
+
+        let payment = sqlx::query_as::<_, Payment>(
+            "UPDATE payments
+             SET status = $2,
+                 verified_at = CASE WHEN $2 = 'verified'::payment_status THEN $3 ELSE verified_at END,
+                 updated_at = $3
+             WHERE reference = $1 AND status = 'pending' AND deleted_at IS NULL
+             RETURNING *",
+        )
+        .bind(reference)
+        .bind(status)
+        .bind(Utc::now())
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(payment)
+    }
 }
@@ -0,0 +1,405 @@
+use crate::database::connection::DbPool;
+use crate::models::payment::{Payment, PaymentEntryType, PaymentStatus};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{Connection, FromRow, PgConnection, Type};
+use std::str::FromStr;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum PaymentReversalError {
+    #[error("Reversal with ID {id} not found")]
+    NotFound { id: Uuid },
+    #[error("Payment with ID {id} not found")]
+    PaymentNotFound { id: Uuid },
+    #[error("Only verified payments can be refunded or reversed")]
+    PaymentNotVerified { id: Uuid },
+    #[error("Refunds and reversals cannot themselves be reversed")]
+    NotAnOriginalPayment { id: Uuid },
+    #[error("Amount exceeds the {remaining} still refundable on this payment")]
+    ExceedsRemaining { remaining: Decimal },
+    #[error("Reversal {id} has already been reviewed")]
+    AlreadyReviewed { id: Uuid },
+    #[error("A reversal cannot be approved by the person who requested it")]
+    SelfApproval,
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[sqlx(type_name = "reversal_status", rename_all = "lowercase")]
+pub enum ReversalStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl FromStr for ReversalStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(ReversalStatus::Pending),
+            "approved" => Ok(ReversalStatus::Approved),
+            "rejected" => Ok(ReversalStatus::Rejected),
+            _ => Err(()),
+        }
+    }
+}
+
+/// How much a request takes off the payment: a refund may be partial, a reversal undoes
+/// whatever is left. Either must fit within `remaining`.
+fn reversal_amount(
+    entry_type: PaymentEntryType,
+    requested: Option<Decimal>,
+    remaining: Decimal,
+) -> Result<Decimal, PaymentReversalError> {
+    let amount = match entry_type {
+        PaymentEntryType::Refund => requested.unwrap_or(remaining),
+        _ => remaining,
+    };
+
+    if amount <= Decimal::ZERO || amount > remaining {
+        return Err(PaymentReversalError::ExceedsRemaining { remaining });
+    }
+
+    Ok(amount)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PaymentReversal {
+    pub id: Uuid,
+    pub payment_id: Uuid,
+    pub entry_type: PaymentEntryType,
+    pub amount: Decimal,
+    pub reason: String,
+    pub status: ReversalStatus,
+    pub requested_by: Option<Uuid>,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub review_note: Option<String>,
+    pub compensating_payment_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreatePaymentReversal {
+    pub payment_id: Uuid,
+    pub entry_type: PaymentEntryType,
+    /// Ignored for full reversals, which always cover the remaining balance.
+    pub amount: Option<Decimal>,
+    pub reason: String,
+    pub requested_by: Uuid,
+}
+
+/// A payment together with its approved compensating entries.
+#[derive(Debug, Clone, Serialize)]
+pub struct PaymentLedger {
+    pub payment: Payment,
+    pub entries: Vec<Payment>,
+    pub reversals: Vec<PaymentReversal>,
+    pub net_amount: Decimal,
+    pub net_base_amount: Decimal,
+}
+
+impl PaymentReversal {
+    /// Records a refund or reversal request against a verified payment. Nothing is posted to
+    /// the ledger until a treasurer approves it.
+    pub async fn request(
+        conn: &mut PgConnection,
+        reversal: CreatePaymentReversal,
+    ) -> Result<Self, PaymentReversalError> {
+        let now = Utc::now();
+        let mut tx = conn.begin().await?;
+
+        let payment =
+            sqlx::query_as::<_, Payment>("SELECT * FROM payments WHERE id = $1 FOR UPDATE")
+                .bind(reversal.payment_id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .ok_or(PaymentReversalError::PaymentNotFound {
+                    id: reversal.payment_id,
+                })?;
+
+        if payment.entry_type != PaymentEntryType::Payment {
+            return Err(PaymentReversalError::NotAnOriginalPayment { id: payment.id });
+        }
+
+        if payment.status != PaymentStatus::Verified {
+            return Err(PaymentReversalError::PaymentNotVerified { id: payment.id });
+        }
+
+        // Pending requests count against the balance so two requests cannot both be approved
+        let claimed: Decimal = sqlx::query_scalar(
+            "SELECT COALESCE(SUM(amount), 0) FROM payment_reversals
+             WHERE payment_id = $1 AND status IN ('pending', 'approved')",
+        )
+        .bind(payment.id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let remaining = payment.amount.unwrap_or_default() - claimed;
+        let amount = reversal_amount(reversal.entry_type, reversal.amount, remaining)?;
+
+        let created = sqlx::query_as::<_, PaymentReversal>(
+            "INSERT INTO payment_reversals (id, payment_id, entry_type, amount, reason, requested_by, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+             RETURNING *",
+        )
+            .bind(Uuid::new_v4())
+            .bind(payment.id)
+            .bind(reversal.entry_type)
+            .bind(amount)
+            .bind(reversal.reason)
+            .bind(reversal.requested_by)
+            .bind(now)
+            .bind(now)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(created)
+    }
+
+    pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, PaymentReversalError> {
+        let reversal =
+            sqlx::query_as::<_, PaymentReversal>("SELECT * FROM payment_reversals WHERE id = $1")
+                .bind(id)
+                .fetch_optional(pool)
+                .await?;
+
+        Ok(reversal)
+    }
+
+    pub async fn find_all(
+        pool: &DbPool,
+        status: Option<ReversalStatus>,
+    ) -> Result<Vec<Self>, PaymentReversalError> {
+        let reversals = sqlx::query_as::<_, PaymentReversal>(
+            "SELECT * FROM payment_reversals
+             WHERE ($1::reversal_status IS NULL OR status = $1)
+             ORDER BY created_at DESC",
+        )
+        .bind(status)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(reversals)
+    }
+
+    pub async fn find_ledger(
+        pool: &DbPool,
+        payment_id: Uuid,
+    ) -> Result<Option<PaymentLedger>, PaymentReversalError> {
+        let Some(payment) = sqlx::query_as::<_, Payment>("SELECT * FROM payments WHERE id = $1")
+            .bind(payment_id)
+            .fetch_optional(pool)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let entries = sqlx::query_as::<_, Payment>(
+            "SELECT * FROM payments WHERE reverses_payment_id = $1 ORDER BY created_at ASC",
+        )
+        .bind(payment_id)
+        .fetch_all(pool)
+        .await?;
+
+        let reversals = sqlx::query_as::<_, PaymentReversal>(
+            "SELECT * FROM payment_reversals WHERE payment_id = $1 ORDER BY created_at ASC",
+        )
+        .bind(payment_id)
+        .fetch_all(pool)
+        .await?;
+
+        let net_amount = std::iter::once(&payment)
+            .chain(entries.iter())
+            .filter(|entry| entry.status == PaymentStatus::Verified)
+            .filter_map(|entry| entry.amount)
+            .sum();
+        let net_base_amount = std::iter::once(&payment)
+            .chain(entries.iter())
+            .filter(|entry| entry.status == PaymentStatus::Verified)
+            .filter_map(|entry| entry.base_amount)
+            .sum();
+
+        Ok(Some(PaymentLedger {
+            payment,
+            entries,
+            reversals,
+            net_amount,
+            net_base_amount,
+        }))
+    }
+
+    /// Approves a pending request and posts the compensating negative entry to the ledger.
+    pub async fn approve(
+        conn: &mut PgConnection,
+        id: Uuid,
+        approved_by: Uuid,
+        note: Option<String>,
+    ) -> Result<Self, PaymentReversalError> {
+        let now = Utc::now();
+        let mut tx = conn.begin().await?;
+
+        let reversal = sqlx::query_as::<_, PaymentReversal>(
+            "SELECT * FROM payment_reversals WHERE id = $1 FOR UPDATE",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(PaymentReversalError::NotFound { id })?;
+
+        if reversal.status != ReversalStatus::Pending {
+            return Err(PaymentReversalError::AlreadyReviewed { id });
+        }
+
+        if reversal.requested_by == Some(approved_by) {
+            return Err(PaymentReversalError::SelfApproval);
+        }
+
+        let payment =
+            sqlx::query_as::<_, Payment>("SELECT * FROM payments WHERE id = $1 FOR UPDATE")
+                .bind(reversal.payment_id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .ok_or(PaymentReversalError::PaymentNotFound {
+                    id: reversal.payment_id,
+                })?;
+
+        let entry = sqlx::query_as::<_, Payment>(
+            "INSERT INTO payments (id, user_id, contribution_id, campaign_id, is_anonymous, amount, currency, exchange_rate, status, gateway, entry_type, reverses_payment_id, verified_by, verified_at, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $14, $14)
+             RETURNING *",
+        )
+            .bind(Uuid::new_v4())
+            .bind(payment.user_id)
+            .bind(payment.contribution_id)
+            .bind(payment.campaign_id)
+            .bind(payment.is_anonymous)
+            .bind(-reversal.amount)
+            // Compensate at the original rate so the entries net to zero in the base currency.
+            .bind(payment.currency)
+            .bind(payment.exchange_rate)
+            .bind(PaymentStatus::Verified)
+            .bind(payment.gateway)
+            .bind(reversal.entry_type)
+            .bind(payment.id)
+            .bind(approved_by)
+            .bind(now)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let approved = sqlx::query_as::<_, PaymentReversal>(
+            "UPDATE payment_reversals
+             SET status = 'approved', reviewed_by = $2, reviewed_at = $3, review_note = $4,
+                 compensating_payment_id = $5, updated_at = $3
+             WHERE id = $1
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(approved_by)
+        .bind(now)
+        .bind(note)
+        .bind(entry.id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(approved)
+    }
+
+    pub async fn reject(
+        conn: &mut PgConnection,
+        id: Uuid,
+        rejected_by: Uuid,
+        note: Option<String>,
+    ) -> Result<Self, PaymentReversalError> {
+        let now = Utc::now();
+
+        let rejected = sqlx::query_as::<_, PaymentReversal>(
+            "UPDATE payment_reversals
+             SET status = 'rejected', reviewed_by = $2, reviewed_at = $3, review_note = $4, updated_at = $3
+             WHERE id = $1 AND status = 'pending'
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(rejected_by)
+        .bind(now)
+        .bind(note)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        match rejected {
+            Some(reversal) => Ok(reversal),
+            None => {
+                let exists: bool = sqlx::query_scalar(
+                    "SELECT EXISTS(SELECT 1 FROM payment_reversals WHERE id = $1)",
+                )
+                .bind(id)
+                .fetch_one(&mut *conn)
+                .await?;
+                match exists {
+                    true => Err(PaymentReversalError::AlreadyReviewed { id }),
+                    false => Err(PaymentReversalError::NotFound { id }),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refund_defaults_to_the_remaining_balance() {
+        let remaining = Decimal::from(5000);
+        assert_eq!(
+            reversal_amount(PaymentEntryType::Refund, None, remaining).unwrap(),
+            remaining
+        );
+        assert_eq!(
+            reversal_amount(
+                PaymentEntryType::Refund,
+                Some(Decimal::from(1500)),
+                remaining
+            )
+            .unwrap(),
+            Decimal::from(1500)
+        );
+    }
+
+    #[test]
+    fn reversal_always_takes_the_remaining_balance() {
+        let remaining = Decimal::from(3500);
+        assert_eq!(
+            reversal_amount(PaymentEntryType::Reversal, Some(Decimal::ONE), remaining).unwrap(),
+            remaining
+        );
+    }
+
+    #[test]
+    fn rejects_amounts_beyond_the_remaining_balance() {
+        let remaining = Decimal::from(1000);
+        for requested in [Decimal::from(1001), Decimal::ZERO, Decimal::NEGATIVE_ONE] {
+            assert!(matches!(
+                reversal_amount(PaymentEntryType::Refund, Some(requested), remaining),
+                Err(PaymentReversalError::ExceedsRemaining { .. })
+            ));
+        }
+        assert!(reversal_amount(PaymentEntryType::Reversal, None, Decimal::ZERO).is_err());
+    }
+
+    #[test]
+    fn parses_statuses() {
+        assert_eq!("approved".parse(), Ok(ReversalStatus::Approved));
+        assert_eq!("Approved".parse::<ReversalStatus>(), Err(()));
+    }
+}
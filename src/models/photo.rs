@@ -1,7 +1,8 @@
 use crate::database::connection::DbPool;
+use crate::utils::pagination::{Page, PageRequest};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{FromRow, PgConnection};
 use thiserror::Error;
 use uuid::Uuid;
 
@@ -24,6 +25,23 @@ pub struct Photo {
     pub caption: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// A photo with how many comments and reactions it has.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct PhotoListing {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub photo: Photo,
+    pub comment_count: i64,
+    pub reaction_count: i64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PhotoFilter {
+    pub event_id: Option<Uuid>,
+    pub posted_by: Option<Uuid>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,17 +85,38 @@ impl Photo {
     }
 
     pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, PhotoError> {
-        let photo = sqlx::query_as::<_, Photo>("SELECT * FROM photos WHERE id = $1")
-            .bind(id)
-            .fetch_optional(pool)
-            .await?;
+        let photo =
+            sqlx::query_as::<_, Photo>("SELECT * FROM photos WHERE id = $1 AND deleted_at IS NULL")
+                .bind(id)
+                .fetch_optional(pool)
+                .await?;
 
         Ok(photo)
     }
 
-    pub async fn find_all(pool: &DbPool) -> Result<Vec<Self>, PhotoError> {
-        let photos = sqlx::query_as::<_, Photo>("SELECT * FROM photos ORDER BY created_at DESC")
-            .fetch_all(pool)
+    pub async fn find_page(
+        pool: &DbPool,
+        filter: &PhotoFilter,
+        page: &PageRequest,
+    ) -> Result<Page<PhotoListing>, PhotoError> {
+        let photos = page
+            .fetch(pool, |query| {
+                query.push(
+                    "SELECT p.*,
+                            (SELECT COUNT(*) FROM comments c
+                             WHERE c.photo_id = p.id AND c.deleted_at IS NULL AND c.hidden_at IS NULL
+                            ) AS comment_count,
+                            (SELECT COUNT(*) FROM reactions x WHERE x.photo_id = p.id) AS reaction_count
+                     FROM photos p
+                     WHERE p.deleted_at IS NULL",
+                );
+                if let Some(event_id) = filter.event_id {
+                    query.push(" AND p.event_id = ").push_bind(event_id);
+                }
+                if let Some(posted_by) = filter.posted_by {
+                    query.push(" AND p.posted_by = ").push_bind(posted_by);
+                }
+            })
             .await?;
 
         Ok(photos)
@@ -105,7 +144,7 @@ impl Photo {
         let updated_photo = sqlx::query_as::<_, Photo>(
             "UPDATE photos 
              SET caption = $2, url = $3, event_id = $4, updated_at = $5
-             WHERE id = $1 
+             WHERE id = $1 AND deleted_at IS NULL
              RETURNING *",
         )
         .bind(id)
@@ -119,10 +158,51 @@ impl Photo {
         Ok(updated_photo)
     }
 
-    pub async fn delete(pool: &DbPool, id: Uuid) -> Result<(), PhotoError> {
-        let result = sqlx::query("DELETE FROM photos WHERE id = $1")
+    pub async fn delete(conn: &mut PgConnection, id: Uuid) -> Result<(), PhotoError> {
+        let result = sqlx::query(
+            "UPDATE photos SET deleted_at = $2, updated_at = $2
+             WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .bind(Utc::now())
+        .execute(&mut *conn)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(PhotoError::NotFound { id });
+        }
+
+        Ok(())
+    }
+
+    pub async fn find_deleted(pool: &DbPool) -> Result<Vec<Self>, PhotoError> {
+        let photos = sqlx::query_as::<_, Photo>(
+            "SELECT * FROM photos WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(photos)
+    }
+
+    pub async fn restore(conn: &mut PgConnection, id: Uuid) -> Result<Self, PhotoError> {
+        sqlx::query_as::<_, Photo>(
+            "UPDATE photos SET deleted_at = NULL, updated_at = $2
+             WHERE id = $1 AND deleted_at IS NOT NULL
+             RETURNING *",
+        )
+        .bind(id)
+        .bind(Utc::now())
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(PhotoError::NotFound { id })
+    }
+
+    /// Permanently removes a deleted photo along with its tags, comments and reactions.
+    pub async fn purge(conn: &mut PgConnection, id: Uuid) -> Result<(), PhotoError> {
+        let result = sqlx::query("DELETE FROM photos WHERE id = $1 AND deleted_at IS NOT NULL")
             .bind(id)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
 
         if result.rows_affected() == 0 {
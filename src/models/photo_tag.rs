@@ -0,0 +1,110 @@
+use crate::database::connection::DbPool;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum PhotoTagError {
+    #[error("User {user_id} is not tagged in photo {photo_id}")]
+    NotFound { photo_id: Uuid, user_id: Uuid },
+    #[error("User with ID {id} not found")]
+    UserNotFound { id: Uuid },
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// A member tagged in a photo.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PhotoTag {
+    pub photo_id: Uuid,
+    pub user_id: Uuid,
+    pub fullname: String,
+    pub tagged_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PhotoTag {
+    /// Returns `None` if the user was already tagged.
+    pub async fn add(
+        pool: &DbPool,
+        photo_id: Uuid,
+        user_id: Uuid,
+        tagged_by: Uuid,
+    ) -> Result<Option<Self>, PhotoTagError> {
+        let tag = sqlx::query_as::<_, PhotoTag>(
+            r#"
+            WITH inserted AS (
+                INSERT INTO photo_tags (photo_id, user_id, tagged_by, created_at)
+                SELECT $1, u.id, $3, $4 FROM users u WHERE u.id = $2 AND u.deleted_at IS NULL
+                ON CONFLICT (photo_id, user_id) DO NOTHING
+                RETURNING *
+            )
+            SELECT i.photo_id, i.user_id, u.fullname, i.tagged_by, i.created_at
+            FROM inserted i
+            JOIN users u ON u.id = i.user_id
+            "#,
+        )
+        .bind(photo_id)
+        .bind(user_id)
+        .bind(tagged_by)
+        .bind(Utc::now())
+        .fetch_optional(pool)
+        .await?;
+
+        if tag.is_none() && Self::find(pool, photo_id, user_id).await?.is_none() {
+            return Err(PhotoTagError::UserNotFound { id: user_id });
+        }
+
+        Ok(tag)
+    }
+
+    pub async fn find(
+        pool: &DbPool,
+        photo_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<Self>, PhotoTagError> {
+        let tag = sqlx::query_as::<_, PhotoTag>(
+            "SELECT t.photo_id, t.user_id, u.fullname, t.tagged_by, t.created_at
+             FROM photo_tags t
+             JOIN users u ON u.id = t.user_id
+             WHERE t.photo_id = $1 AND t.user_id = $2",
+        )
+        .bind(photo_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(tag)
+    }
+
+    pub async fn find_by_photo(pool: &DbPool, photo_id: Uuid) -> Result<Vec<Self>, PhotoTagError> {
+        let tags = sqlx::query_as::<_, PhotoTag>(
+            "SELECT t.photo_id, t.user_id, u.fullname, t.tagged_by, t.created_at
+             FROM photo_tags t
+             JOIN users u ON u.id = t.user_id
+             WHERE t.photo_id = $1
+             ORDER BY u.fullname ASC",
+        )
+        .bind(photo_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    pub async fn remove(pool: &DbPool, photo_id: Uuid, user_id: Uuid) -> Result<(), PhotoTagError> {
+        let result = sqlx::query("DELETE FROM photo_tags WHERE photo_id = $1 AND user_id = $2")
+            .bind(photo_id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(PhotoTagError::NotFound { photo_id, user_id });
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,141 @@
+use crate::database::connection::DbPool;
+use crate::models::comment::ContentTarget;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Longest emoji accepted, in characters; family and flag sequences need several.
+const MAX_EMOJI_CHARS: usize = 8;
+
+#[derive(Error, Debug)]
+pub enum ReactionError {
+    #[error("Reactions must be a single emoji")]
+    InvalidEmoji,
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Reaction {
+    pub id: Uuid,
+    pub announcement_id: Option<Uuid>,
+    pub photo_id: Option<Uuid>,
+    pub user_id: Uuid,
+    pub emoji: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// How many people reacted with an emoji, and whether the viewer is one of them.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ReactionSummary {
+    pub emoji: String,
+    pub count: i64,
+    pub reacted: bool,
+}
+
+/// Emoji are all non-ASCII, including their joiners and variation selectors, so anything
+/// with letters, digits, punctuation or spaces is rejected.
+pub fn is_valid_emoji(emoji: &str) -> bool {
+    let chars = emoji.chars().count();
+    (1..=MAX_EMOJI_CHARS).contains(&chars) && emoji.chars().all(|c| !c.is_ascii())
+}
+
+impl Reaction {
+    /// Adding the same reaction twice keeps the first.
+    pub async fn add(
+        pool: &DbPool,
+        target: ContentTarget,
+        user_id: Uuid,
+        emoji: &str,
+    ) -> Result<Self, ReactionError> {
+        if !is_valid_emoji(emoji) {
+            return Err(ReactionError::InvalidEmoji);
+        }
+
+        let column = target.column();
+        let reaction = sqlx::query_as::<_, Reaction>(&format!(
+            "INSERT INTO reactions (id, announcement_id, photo_id, user_id, emoji, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT ({column}, user_id, emoji) WHERE {column} IS NOT NULL
+             DO UPDATE SET emoji = EXCLUDED.emoji
+             RETURNING *"
+        ))
+        .bind(Uuid::new_v4())
+        .bind(target.announcement_id())
+        .bind(target.photo_id())
+        .bind(user_id)
+        .bind(emoji)
+        .bind(Utc::now())
+        .fetch_one(pool)
+        .await?;
+
+        Ok(reaction)
+    }
+
+    /// Returns whether there was a reaction to remove.
+    pub async fn remove(
+        pool: &DbPool,
+        target: ContentTarget,
+        user_id: Uuid,
+        emoji: &str,
+    ) -> Result<bool, ReactionError> {
+        let result = sqlx::query(&format!(
+            "DELETE FROM reactions WHERE {} = $1 AND user_id = $2 AND emoji = $3",
+            target.column()
+        ))
+        .bind(target.id())
+        .bind(user_id)
+        .bind(emoji)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Reactions grouped by emoji, most used first.
+    pub async fn summary(
+        pool: &DbPool,
+        target: ContentTarget,
+        user_id: Uuid,
+    ) -> Result<Vec<ReactionSummary>, ReactionError> {
+        let summary = sqlx::query_as::<_, ReactionSummary>(&format!(
+            "SELECT emoji, COUNT(*) AS count, BOOL_OR(user_id = $2) AS reacted
+             FROM reactions
+             WHERE {} = $1
+             GROUP BY emoji
+             ORDER BY count DESC, MIN(created_at) ASC",
+            target.column()
+        ))
+        .bind(target.id())
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_single_emoji_including_sequences() {
+        assert!(is_valid_emoji("👍"));
+        assert!(is_valid_emoji("❤️"));
+        assert!(is_valid_emoji("👍🏽"));
+        assert!(is_valid_emoji("👨‍👩‍👧"));
+        assert!(is_valid_emoji("🇳🇬"));
+    }
+
+    #[test]
+    fn rejects_text_and_oversized_input() {
+        assert!(!is_valid_emoji(""));
+        assert!(!is_valid_emoji("ok"));
+        assert!(!is_valid_emoji("👍 "));
+        assert!(!is_valid_emoji(":)"));
+        assert!(!is_valid_emoji(&"👍".repeat(MAX_EMOJI_CHARS + 1)));
+    }
+}
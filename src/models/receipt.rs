@@ -0,0 +1,142 @@
+use crate::database::connection::DbPool;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum ReceiptError {
+    #[error("Receipt for payment {payment_id} not found")]
+    NotFound { payment_id: Uuid },
+    #[error("Payment {payment_id} has not been verified")]
+    PaymentNotVerified { payment_id: Uuid },
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("PDF rendering failed: {0}")]
+    Render(String),
+    #[error("Receipt email could not be queued: {0}")]
+    Email(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PaymentReceipt {
+    pub id: Uuid,
+    pub payment_id: Uuid,
+    pub receipt_number: String,
+    pub issued_at: DateTime<Utc>,
+    pub emailed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Everything printed on a receipt, gathered in one query.
+#[derive(Debug, Clone, FromRow)]
+pub struct ReceiptDetails {
+    pub receipt_number: String,
+    pub issued_at: DateTime<Utc>,
+    pub amount: Decimal,
+    pub currency: String,
+    pub exchange_rate: Decimal,
+    pub base_amount: Decimal,
+    pub reference: Option<String>,
+    pub gateway: Option<String>,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub member_name: String,
+    pub member_email: String,
+    pub contribution_title: String,
+    pub verifier_name: Option<String>,
+}
+
+impl PaymentReceipt {
+    /// Issues the next receipt number for a verified payment.
+    ///
+    /// Returns the existing receipt and `false` if one was already issued.
+    pub async fn issue(pool: &DbPool, payment_id: Uuid) -> Result<(Self, bool), ReceiptError> {
+        if let Some(existing) = Self::find_by_payment(pool, payment_id).await? {
+            return Ok((existing, false));
+        }
+
+        let now = Utc::now();
+
+        let receipt = sqlx::query_as::<_, PaymentReceipt>(
+            "INSERT INTO payment_receipts (id, payment_id, receipt_number, issued_at, created_at, updated_at)
+             SELECT $1, p.id, 'RCT-' || LPAD(nextval('payment_receipt_number_seq')::text, 6, '0'), $3, $3, $3
+             FROM payments p
+             WHERE p.id = $2 AND p.status = 'verified' AND p.entry_type = 'payment'
+             ON CONFLICT (payment_id) DO NOTHING
+             RETURNING *",
+        )
+            .bind(Uuid::new_v4())
+            .bind(payment_id)
+            .bind(now)
+            .fetch_optional(pool)
+            .await?;
+
+        match receipt {
+            Some(receipt) => Ok((receipt, true)),
+            None => match Self::find_by_payment(pool, payment_id).await? {
+                Some(existing) => Ok((existing, false)),
+                None => Err(ReceiptError::PaymentNotVerified { payment_id }),
+            },
+        }
+    }
+
+    pub async fn find_by_payment(
+        pool: &DbPool,
+        payment_id: Uuid,
+    ) -> Result<Option<Self>, ReceiptError> {
+        let receipt = sqlx::query_as::<_, PaymentReceipt>(
+            "SELECT * FROM payment_receipts WHERE payment_id = $1",
+        )
+        .bind(payment_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(receipt)
+    }
+
+    pub async fn find_details(
+        pool: &DbPool,
+        payment_id: Uuid,
+    ) -> Result<Option<ReceiptDetails>, ReceiptError> {
+        let details = sqlx::query_as::<_, ReceiptDetails>(
+            r#"
+            SELECT r.receipt_number, r.issued_at, p.amount, p.currency, p.exchange_rate,
+                   p.base_amount, p.reference,
+                   p.gateway, p.verified_at, m.fullname AS member_name, m.email AS member_email,
+                   COALESCE(c.title, 'Donation: ' || cp.title) AS contribution_title,
+                   v.fullname AS verifier_name
+            FROM payment_receipts r
+            JOIN payments p ON p.id = r.payment_id
+            JOIN users m ON m.id = p.user_id
+            LEFT JOIN contributions c ON c.id = p.contribution_id
+            LEFT JOIN campaigns cp ON cp.id = p.campaign_id
+            LEFT JOIN users v ON v.id = p.verified_by
+            WHERE r.payment_id = $1
+            "#,
+        )
+        .bind(payment_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(details)
+    }
+
+    /// Marks the receipt as emailed, returning `false` if another caller got there first.
+    pub async fn mark_emailed(conn: &mut PgConnection, id: Uuid) -> Result<bool, ReceiptError> {
+        let now = Utc::now();
+
+        let result = sqlx::query(
+            "UPDATE payment_receipts SET emailed_at = $2, updated_at = $2
+             WHERE id = $1 AND emailed_at IS NULL",
+        )
+        .bind(id)
+        .bind(now)
+        .execute(conn)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
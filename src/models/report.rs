@@ -0,0 +1,254 @@
+use crate::database::connection::DbPool;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::FromRow;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum ReportError {
+    #[error("Report period starts after it ends")]
+    InvalidPeriod,
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ReportPeriod {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl ReportPeriod {
+    pub fn new(from: NaiveDate, to: NaiveDate) -> Result<Self, ReportError> {
+        if from > to {
+            return Err(ReportError::InvalidPeriod);
+        }
+
+        Ok(Self { from, to })
+    }
+}
+
+/// Collection figures for one contribution. Every active member counts as liable.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ContributionCollection {
+    pub contribution_id: Uuid,
+    pub title: String,
+    pub due_date: NaiveDate,
+    pub amount_due: Option<Decimal>,
+    pub liable_members: i64,
+    pub paid_members: i64,
+    pub expected: Option<Decimal>,
+    pub collected: Decimal,
+    pub collection_rate: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Defaulter {
+    pub user_id: Uuid,
+    pub fullname: String,
+    pub email: String,
+    pub contributions_owed: i64,
+    pub outstanding: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct MonthlyTotal {
+    pub month: String,
+    pub income: Decimal,
+    pub expenses: Decimal,
+    pub net: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct LineTotal {
+    pub name: String,
+    pub amount: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IncomeStatement {
+    pub period: ReportPeriod,
+    pub income: Vec<LineTotal>,
+    pub total_income: Decimal,
+    pub expenses: Vec<LineTotal>,
+    pub total_expenses: Decimal,
+    pub surplus: Decimal,
+}
+
+// Net verified amount per member and contribution in the base currency; refunds and
+// reversals are negative.
+const PAID_PER_MEMBER: &str = "SELECT contribution_id, user_id, SUM(base_amount) AS total
+     FROM payments WHERE status = 'verified'
+     GROUP BY contribution_id, user_id";
+
+// Contributions with the amount due converted to the base currency at the rate in effect on
+// the due date.
+const DUES: &str =
+    "SELECT id, title, due_date, ROUND(amount * exchange_rate_on(currency, due_date), 2) AS amount
+     FROM contributions WHERE deleted_at IS NULL";
+
+pub struct Report;
+
+impl Report {
+    pub async fn collections(
+        pool: &DbPool,
+        period: ReportPeriod,
+    ) -> Result<Vec<ContributionCollection>, ReportError> {
+        let collections = sqlx::query_as::<_, ContributionCollection>(&format!(
+            r#"
+            WITH paid AS ({PAID_PER_MEMBER}),
+            dues AS ({DUES}),
+            liable AS (SELECT COUNT(*) AS members FROM users WHERE is_active)
+            SELECT c.id AS contribution_id, c.title, c.due_date, c.amount AS amount_due,
+                   liable.members AS liable_members,
+                   COUNT(u.id) FILTER (
+                       WHERE paid.total > 0 AND (c.amount IS NULL OR paid.total >= c.amount)
+                   ) AS paid_members,
+                   c.amount * liable.members AS expected,
+                   COALESCE(SUM(paid.total), 0) AS collected,
+                   CASE WHEN liable.members > 0 THEN ROUND(
+                       COUNT(u.id) FILTER (
+                           WHERE paid.total > 0 AND (c.amount IS NULL OR paid.total >= c.amount)
+                       ) * 100.0 / liable.members, 2)
+                   END AS collection_rate
+            FROM dues c
+            CROSS JOIN liable
+            LEFT JOIN paid ON paid.contribution_id = c.id
+            LEFT JOIN users u ON u.id = paid.user_id AND u.is_active
+            WHERE c.due_date BETWEEN $1 AND $2
+            GROUP BY c.id, c.title, c.due_date, c.amount, liable.members
+            ORDER BY c.due_date ASC, c.title ASC
+            "#
+        ))
+        .bind(period.from)
+        .bind(period.to)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(collections)
+    }
+
+    /// Active members with the largest unpaid balance on contributions already past due.
+    pub async fn defaulters(
+        pool: &DbPool,
+        period: ReportPeriod,
+        limit: i64,
+    ) -> Result<Vec<Defaulter>, ReportError> {
+        let defaulters = sqlx::query_as::<_, Defaulter>(&format!(
+            r#"
+            WITH paid AS ({PAID_PER_MEMBER}),
+            dues AS ({DUES})
+            SELECT u.id AS user_id, u.fullname, u.email,
+                   COUNT(*) AS contributions_owed,
+                   SUM(c.amount - COALESCE(paid.total, 0)) AS outstanding
+            FROM users u
+            CROSS JOIN dues c
+            LEFT JOIN paid ON paid.contribution_id = c.id AND paid.user_id = u.id
+            WHERE u.is_active
+              AND c.amount IS NOT NULL
+              AND c.due_date BETWEEN $1 AND $2
+              AND c.due_date < CURRENT_DATE
+              AND COALESCE(paid.total, 0) < c.amount
+            GROUP BY u.id, u.fullname, u.email
+            ORDER BY outstanding DESC, u.fullname ASC
+            LIMIT $3
+            "#
+        ))
+        .bind(period.from)
+        .bind(period.to)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(defaulters)
+    }
+
+    pub async fn monthly_trend(
+        pool: &DbPool,
+        period: ReportPeriod,
+    ) -> Result<Vec<MonthlyTotal>, ReportError> {
+        let months = sqlx::query_as::<_, MonthlyTotal>(
+            r#"
+            WITH months AS (
+                SELECT generate_series(
+                    date_trunc('month', $1::date)::date,
+                    date_trunc('month', $2::date)::date,
+                    interval '1 month'
+                )::date AS start
+            ),
+            totals AS (
+                SELECT m.start,
+                       COALESCE((
+                           SELECT SUM(p.base_amount) FROM payments p
+                           WHERE p.status = 'verified'
+                             AND COALESCE(p.verified_at, p.created_at)::date
+                                 BETWEEN GREATEST(m.start, $1) AND LEAST((m.start + interval '1 month')::date - 1, $2)
+                       ), 0) AS income,
+                       COALESCE((
+                           SELECT SUM(e.amount) FROM expenses e
+                           WHERE e.status = 'approved'
+                             AND e.spent_on
+                                 BETWEEN GREATEST(m.start, $1) AND LEAST((m.start + interval '1 month')::date - 1, $2)
+                       ), 0) AS expenses
+                FROM months m
+            )
+            SELECT to_char(start, 'YYYY-MM') AS month, income, expenses, income - expenses AS net
+            FROM totals
+            ORDER BY start ASC
+            "#,
+        )
+        .bind(period.from)
+        .bind(period.to)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(months)
+    }
+
+    pub async fn income_statement(
+        pool: &DbPool,
+        period: ReportPeriod,
+    ) -> Result<IncomeStatement, ReportError> {
+        let income = sqlx::query_as::<_, LineTotal>(
+            "SELECT COALESCE(c.title, 'Donations: ' || cp.title) AS name, SUM(p.base_amount) AS amount
+             FROM payments p
+             LEFT JOIN contributions c ON c.id = p.contribution_id
+             LEFT JOIN campaigns cp ON cp.id = p.campaign_id
+             WHERE p.status = 'verified'
+               AND COALESCE(p.verified_at, p.created_at)::date BETWEEN $1 AND $2
+             GROUP BY c.id, c.title, cp.id, cp.title
+             ORDER BY amount DESC, name ASC",
+        )
+        .bind(period.from)
+        .bind(period.to)
+        .fetch_all(pool)
+        .await?;
+
+        let expenses = sqlx::query_as::<_, LineTotal>(
+            "SELECT ec.name, SUM(e.amount) AS amount
+             FROM expenses e
+             JOIN expense_categories ec ON ec.id = e.category_id
+             WHERE e.status = 'approved' AND e.spent_on BETWEEN $1 AND $2
+             GROUP BY ec.id, ec.name
+             ORDER BY amount DESC, ec.name ASC",
+        )
+        .bind(period.from)
+        .bind(period.to)
+        .fetch_all(pool)
+        .await?;
+
+        let total_income: Decimal = income.iter().map(|line| line.amount).sum();
+        let total_expenses: Decimal = expenses.iter().map(|line| line.amount).sum();
+
+        Ok(IncomeStatement {
+            period,
+            income,
+            total_income,
+            expenses,
+            total_expenses,
+            surplus: total_income - total_expenses,
+        })
+    }
+}
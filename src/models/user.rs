@@ -1,10 +1,11 @@
 use crate::database::connection::DbPool;
+use crate::utils::pagination::{Page, PageRequest};
 use bcrypt::{DEFAULT_COST, hash, verify};
 use chrono::{DateTime, Duration, Utc};
 use rand::Rng;
 use rand::distributions::Alphanumeric;
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, Type};
+use sqlx::{FromRow, PgConnection, Type};
 use std::str::FromStr;
 use thiserror::Error;
 use uuid::Uuid;
@@ -25,6 +26,12 @@ pub enum UserError {
     Database(#[from] sqlx::Error),
     #[error("Password hashing error")]
     PasswordHash,
+    #[error("User {id} has payments and cannot be purged")]
+    HasPayments { id: Uuid },
+    #[error("User {id} created contributions that have payments and cannot be purged")]
+    HasContributionPayments { id: Uuid },
+    #[error("User {id} is still referenced by other records and cannot be purged")]
+    InUse { id: Uuid },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq, Eq)]
@@ -64,8 +71,10 @@ pub struct User {
     pub email_verification_expires_at: Option<DateTime<Utc>>,
     pub is_email_verified: bool,
     pub is_active: bool,
+    pub dues_reminders_enabled: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,6 +89,13 @@ pub struct CreateUser {
     // pub photo_url: Option<String>,
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct UserFilter {
+    pub role: Option<UserRole>,
+    pub is_active: Option<bool>,
+    pub search: Option<String>,
+}
+
 impl User {
     fn generate_verification_code() -> String {
         rand::thread_rng()
@@ -89,8 +105,14 @@ impl User {
             .collect()
     }
 
-    pub async fn create(pool: &DbPool, user: CreateUser) -> Result<Self, UserError> {
-        if let Ok(Some(_)) = Self::find_by_email(pool, &user.email).await {
+    /// Takes a connection so the welcome and verification emails can be queued in the same
+    /// transaction.
+    pub async fn create(conn: &mut PgConnection, user: CreateUser) -> Result<Self, UserError> {
+        let existing = sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1")
+            .bind(&user.email)
+            .fetch_optional(&mut *conn)
+            .await?;
+        if existing.is_some() {
             return Err(UserError::EmailAlreadyExists { email: user.email });
         }
 
@@ -117,32 +139,35 @@ impl User {
             .bind(user.is_active)
             .bind(now)
             .bind(now)
-            .fetch_one(pool)
+            .fetch_one(&mut *conn)
             .await?;
 
         Ok(user)
     }
 
     pub async fn find_by_id(pool: &DbPool, id: Uuid) -> Result<Option<Self>, UserError> {
-        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
-            .bind(id)
-            .fetch_optional(pool)
-            .await?;
+        let user =
+            sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1 AND deleted_at IS NULL")
+                .bind(id)
+                .fetch_optional(pool)
+                .await?;
 
         Ok(user)
     }
 
     pub async fn find_by_email(pool: &DbPool, email: &str) -> Result<Option<Self>, UserError> {
-        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1")
-            .bind(email)
-            .fetch_optional(pool)
-            .await?;
+        let user = sqlx::query_as::<_, User>(
+            "SELECT * FROM users WHERE email = $1 AND deleted_at IS NULL",
+        )
+        .bind(email)
+        .fetch_optional(pool)
+        .await?;
 
         Ok(user)
     }
 
     pub async fn find_by_verification_code(
-        pool: &DbPool,
+        conn: &mut PgConnection,
         code: &str,
     ) -> Result<Option<Self>, UserError> {
         let user = sqlx::query_as::<_, User>(
@@ -150,26 +175,30 @@ impl User {
             SELECT id, fullname, email, password_hash, phone, dob, photo_url, 
                    user_role, email_verification_code, 
                    email_verification_expires_at, is_email_verified, is_active, 
-                   created_at, updated_at
-            FROM users WHERE email_verification_code = $1
+                   dues_reminders_enabled, created_at, updated_at, deleted_at
+            FROM users WHERE email_verification_code = $1 AND deleted_at IS NULL
+            FOR UPDATE
             "#,
         )
         .bind(code)
-        .fetch_optional(pool)
+        .fetch_optional(conn)
         .await?;
 
         Ok(user)
     }
 
-    pub async fn verify_email(pool: &DbPool, verification_code: &str) -> Result<Self, UserError> {
-        let user = Self::find_by_verification_code(pool, verification_code)
+    pub async fn verify_email(
+        conn: &mut PgConnection,
+        verification_code: &str,
+    ) -> Result<Self, UserError> {
+        let user = Self::find_by_verification_code(&mut *conn, verification_code)
             .await?
             .ok_or(UserError::InvalidVerificationCode)?;
 
-        if let Some(expires_at) = user.email_verification_expires_at {
-            if Utc::now() > expires_at {
-                return Err(UserError::VerificationCodeExpired);
-            }
+        if let Some(expires_at) = user.email_verification_expires_at
+            && Utc::now() > expires_at
+        {
+            return Err(UserError::VerificationCodeExpired);
         }
 
         // Update user to mark email as verified
@@ -184,23 +213,30 @@ impl User {
             RETURNING id, fullname, email, password_hash, phone, dob, photo_url, 
                       user_role, email_verification_code, 
                       email_verification_expires_at, is_email_verified, is_active, 
-                      created_at, updated_at
+                      dues_reminders_enabled, created_at, updated_at, deleted_at
             "#,
         )
         .bind(user.id)
         .bind(Utc::now())
-        .fetch_one(pool)
+        .fetch_one(conn)
         .await?;
 
         Ok(updated_user)
     }
 
-    pub async fn resend_verification_code(pool: &DbPool, email: &str) -> Result<Self, UserError> {
-        let user = Self::find_by_email(pool, email)
-            .await?
-            .ok_or(UserError::NotFoundByEmail {
-                email: email.to_string(),
-            })?;
+    pub async fn resend_verification_code(
+        conn: &mut PgConnection,
+        email: &str,
+    ) -> Result<Self, UserError> {
+        let user = sqlx::query_as::<_, User>(
+            "SELECT * FROM users WHERE email = $1 AND deleted_at IS NULL FOR UPDATE",
+        )
+        .bind(email)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(UserError::NotFoundByEmail {
+            email: email.to_string(),
+        })?;
 
         if user.is_email_verified {
             return Ok(user); // Already verified, no need to resend
@@ -217,16 +253,16 @@ impl User {
                 updated_at = $4
             WHERE id = $1 
             RETURNING id, fullname, email, password_hash, phone, dob, photo_url, 
-                      user_role as "user_role: UserRole", email_verification_code, 
+                      user_role, email_verification_code, 
                       email_verification_expires_at, is_email_verified, is_active, 
-                      created_at, updated_at
+                      dues_reminders_enabled, created_at, updated_at, deleted_at
             "#,
         )
         .bind(user.id)
         .bind(new_verification_code)
         .bind(new_expires_at)
         .bind(Utc::now())
-        .fetch_one(pool)
+        .fetch_one(conn)
         .await?;
 
         Ok(updated_user)
@@ -238,8 +274,8 @@ impl User {
                 SELECT id, fullname, email, password_hash, phone, dob, photo_url, 
                        user_role, email_verification_code, 
                        email_verification_expires_at, is_email_verified, is_active, 
-                       created_at, updated_at
-                FROM users ORDER BY created_at DESC
+                       dues_reminders_enabled, created_at, updated_at, deleted_at
+                FROM users WHERE deleted_at IS NULL ORDER BY created_at DESC
                 "#,
         )
         .fetch_all(pool)
@@ -248,6 +284,31 @@ impl User {
         Ok(users)
     }
 
+    pub async fn find_page(
+        pool: &DbPool,
+        filter: &UserFilter,
+        page: &PageRequest,
+    ) -> Result<Page<Self>, UserError> {
+        let users = page
+            .fetch(pool, |query| {
+                query.push("SELECT * FROM users WHERE deleted_at IS NULL");
+                if let Some(role) = &filter.role {
+                    query.push(" AND user_role = ").push_bind(role.clone());
+                }
+                if let Some(is_active) = filter.is_active {
+                    query.push(" AND is_active = ").push_bind(is_active);
+                }
+                if let Some(search) = &filter.search {
+                    query
+                        .push(" AND (fullname || ' ' || email) ILIKE ")
+                        .push_bind(format!("%{}%", search));
+                }
+            })
+            .await?;
+
+        Ok(users)
+    }
+
     pub fn verify_password(&self, password: &str) -> Result<bool, bcrypt::BcryptError> {
         verify(password, &self.password_hash)
     }
@@ -257,34 +318,160 @@ impl User {
         email: &str,
         password: &str,
     ) -> Result<Option<Self>, UserError> {
-        if let Some(user) = Self::find_by_email(pool, email).await? {
-            if user.verify_password(password).unwrap_or(false) {
-                return Ok(Some(user));
-            }
+        if let Some(user) = Self::find_by_email(pool, email).await?
+            && user.verify_password(password).unwrap_or(false)
+        {
+            return Ok(Some(user));
         }
         Ok(None)
     }
 
-    pub async fn toggle_active(pool: &DbPool, user_id: Uuid) -> Result<Self, UserError> {
-        let user = Self::find_by_id(pool, user_id)
-            .await?
-            .ok_or(UserError::NotFound { id: user_id })?;
-
+    pub async fn toggle_active(conn: &mut PgConnection, user_id: Uuid) -> Result<Self, UserError> {
         let updated_user = sqlx::query_as::<_, User>(
             r#"
             UPDATE users 
-            SET is_active = $2, 
+            SET is_active = NOT is_active, 
+                updated_at = $2
+            WHERE id = $1 AND deleted_at IS NULL
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(Utc::now())
+        .fetch_optional(conn)
+        .await?
+        .ok_or(UserError::NotFound { id: user_id })?;
+
+        Ok(updated_user)
+    }
+
+    pub async fn set_role(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        role: UserRole,
+    ) -> Result<Self, UserError> {
+        sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users 
+            SET user_role = $2, 
                 updated_at = $3
-            WHERE id = $1 
+            WHERE id = $1 AND deleted_at IS NULL
             RETURNING *
             "#,
         )
-        .bind(user.id)
-        .bind(!user.is_active)
+        .bind(user_id)
+        .bind(role)
+        .bind(Utc::now())
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(UserError::NotFound { id: user_id })
+    }
+
+    pub async fn set_phone(
+        pool: &DbPool,
+        user_id: Uuid,
+        phone: Option<String>,
+    ) -> Result<Self, UserError> {
+        let updated_user = sqlx::query_as::<_, User>(
+            "UPDATE users SET phone = $2, updated_at = $3
+             WHERE id = $1 AND deleted_at IS NULL
+             RETURNING *",
+        )
+        .bind(user_id)
+        .bind(phone)
         .bind(Utc::now())
-        .fetch_one(pool)
+        .fetch_optional(pool)
         .await?;
 
-        Ok(updated_user)
+        updated_user.ok_or(UserError::NotFound { id: user_id })
+    }
+
+    pub async fn set_dues_reminders(
+        pool: &DbPool,
+        user_id: Uuid,
+        enabled: bool,
+    ) -> Result<Self, UserError> {
+        let updated_user = sqlx::query_as::<_, User>(
+            "UPDATE users SET dues_reminders_enabled = $2, updated_at = $3
+             WHERE id = $1 AND deleted_at IS NULL
+             RETURNING *",
+        )
+        .bind(user_id)
+        .bind(enabled)
+        .bind(Utc::now())
+        .fetch_optional(pool)
+        .await?;
+
+        updated_user.ok_or(UserError::NotFound { id: user_id })
+    }
+
+    /// Deletes the account: the member can no longer sign in and drops out of every
+    /// audience, but their payment history is kept. `restore` puts back whether the account
+    /// was active.
+    pub async fn delete(conn: &mut PgConnection, user_id: Uuid) -> Result<Self, UserError> {
+        let now = Utc::now();
+
+        sqlx::query_as::<_, User>(
+            "UPDATE users
+             SET deleted_at = $2, active_before_delete = is_active, is_active = false, updated_at = $2
+             WHERE id = $1 AND deleted_at IS NULL
+             RETURNING *",
+        )
+        .bind(user_id)
+        .bind(now)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(UserError::NotFound { id: user_id })
+    }
+
+    pub async fn find_deleted(pool: &DbPool) -> Result<Vec<Self>, UserError> {
+        let users = sqlx::query_as::<_, User>(
+            "SELECT * FROM users WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(users)
+    }
+
+    pub async fn restore(conn: &mut PgConnection, user_id: Uuid) -> Result<Self, UserError> {
+        sqlx::query_as::<_, User>(
+            "UPDATE users
+             SET deleted_at = NULL, is_active = COALESCE(active_before_delete, is_active),
+                 active_before_delete = NULL, updated_at = $2
+             WHERE id = $1 AND deleted_at IS NOT NULL
+             RETURNING *",
+        )
+        .bind(user_id)
+        .bind(Utc::now())
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(UserError::NotFound { id: user_id })
+    }
+
+    /// Permanently removes a deleted account. Refused while the member has payments on record
+    /// or created contributions that others have paid towards, since their contributions go
+    /// with them.
+    pub async fn purge(conn: &mut PgConnection, user_id: Uuid) -> Result<(), UserError> {
+        let result = sqlx::query("DELETE FROM users WHERE id = $1 AND deleted_at IS NOT NULL")
+            .bind(user_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| match e.as_database_error() {
+                Some(db) if db.is_foreign_key_violation() => match db.constraint() {
+                    Some("payments_user_id_fkey") => UserError::HasPayments { id: user_id },
+                    Some("payments_contribution_id_fkey") => {
+                        UserError::HasContributionPayments { id: user_id }
+                    }
+                    _ => UserError::InUse { id: user_id },
+                },
+                _ => UserError::Database(e),
+            })?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserError::NotFound { id: user_id });
+        }
+
+        Ok(())
     }
 }
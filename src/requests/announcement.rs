@@ -1,14 +1,320 @@
+use crate::models::announcement::AnnouncementAudience;
+use crate::models::user::UserRole;
+use crate::utils::helpers::double_option;
+use crate::utils::pagination::{LeadingSort, SortField, Sortable};
+use crate::utils::validation::{Validate, Validator};
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use uuid::Uuid;
 
+/// Who an announcement is for: `everyone`, a `role`, a set of `users` or a `committee`.
+#[derive(Debug, Deserialize)]
+pub struct AnnouncementAudienceRequest {
+    pub audience: String,
+    pub role: Option<String>,
+    pub user_ids: Option<Vec<Uuid>>,
+    pub committee_id: Option<Uuid>,
+}
+
+impl Validate for AnnouncementAudienceRequest {
+    fn validate(&self, v: &mut Validator) {
+        let audience = self.audience.parse::<AnnouncementAudience>().ok();
+        v.check(
+            "audience",
+            audience.is_some(),
+            "invalid_audience",
+            "must be one of everyone, role, users, committee",
+        );
+        match audience {
+            Some(AnnouncementAudience::Role) => {
+                v.check(
+                    "role",
+                    self.role.is_some(),
+                    "required",
+                    "is required for a role audience",
+                );
+                v.check(
+                    "role",
+                    self.role
+                        .as_deref()
+                        .is_none_or(|role| role.parse::<UserRole>().is_ok()),
+                    "invalid_role",
+                    "must be one of super_admin, admin, member, treasurer",
+                );
+            }
+            Some(AnnouncementAudience::Users) => v.check(
+                "user_ids",
+                self.user_ids.as_ref().is_some_and(|ids| !ids.is_empty()),
+                "required",
+                "must list at least one user for a users audience",
+            ),
+            Some(AnnouncementAudience::Committee) => v.check(
+                "committee_id",
+                self.committee_id.is_some(),
+                "required",
+                "is required for a committee audience",
+            ),
+            Some(AnnouncementAudience::Everyone) | None => {}
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CreateAnnouncementRequest {
     pub title: String,
     pub body: Option<String>,
+    /// Defaults to everyone.
+    pub audience: Option<AnnouncementAudienceRequest>,
+    /// Defaults to now; a later time schedules the post.
+    pub publish_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub is_draft: Option<bool>,
+    pub is_pinned: Option<bool>,
+    /// Email the announcement to its audience once it is published.
+    pub send_email: Option<bool>,
+}
+
+impl Validate for CreateAnnouncementRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.field("title", self.title.as_str())
+            .required()
+            .max_chars(200);
+        if let Some(audience) = &self.audience {
+            audience.validate(v);
+        }
+        if let (Some(publish_at), Some(expires_at)) = (self.publish_at, self.expires_at) {
+            v.check(
+                "expires_at",
+                expires_at > publish_at,
+                "invalid_range",
+                "must be after publish_at",
+            );
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateAnnouncementRequest {
     pub title: Option<String>,
-    pub body: Option<String>,
+    /// `null` clears the body.
+    #[serde(default, deserialize_with = "double_option")]
+    pub body: Option<Option<String>>,
+    pub audience: Option<AnnouncementAudienceRequest>,
+    pub publish_at: Option<DateTime<Utc>>,
+    /// `null` removes the expiry, so the announcement stays up.
+    #[serde(default, deserialize_with = "double_option")]
+    pub expires_at: Option<Option<DateTime<Utc>>>,
+    pub is_draft: Option<bool>,
+    pub is_pinned: Option<bool>,
+    /// Email the announcement to its audience, once it is published if it is still scheduled.
+    pub send_email: Option<bool>,
+}
+
+impl Validate for UpdateAnnouncementRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.optional("title", self.title.as_deref())
+            .required()
+            .max_chars(200);
+        if let Some(audience) = &self.audience {
+            audience.validate(v);
+        }
+        if let (Some(publish_at), Some(Some(expires_at))) = (self.publish_at, self.expires_at) {
+            v.check(
+                "expires_at",
+                expires_at > publish_at,
+                "invalid_range",
+                "must be after publish_at",
+            );
+        }
+    }
+}
+
+/// The file itself is the request body, typed by its Content-Type header.
+#[derive(Debug, Deserialize)]
+pub struct AttachmentUploadQuery {
+    pub filename: Option<String>,
+}
+
+impl Validate for AttachmentUploadQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.optional("filename", self.filename.as_deref())
+            .max_chars(255);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnnouncementQuery {
+    /// Also list drafts, scheduled and expired posts the user can manage.
+    pub include_unpublished: Option<bool>,
+    /// `true` for just the pinned posts, `false` for just the rest. Pinned posts otherwise
+    /// come first whatever the sort.
+    pub pinned: Option<bool>,
+}
+
+impl Sortable for AnnouncementQuery {
+    const SORT_FIELDS: &'static [SortField] = &[
+        SortField {
+            name: "publish_at",
+            sql_type: "timestamptz",
+        },
+        SortField {
+            name: "created_at",
+            sql_type: "timestamptz",
+        },
+        SortField {
+            name: "title",
+            sql_type: "text",
+        },
+    ];
+    const DEFAULT_SORT: &'static str = "-publish_at";
+    const LEADING_SORT: Option<&'static LeadingSort> = Some(&LeadingSort {
+        field: SortField {
+            name: "is_pinned",
+            sql_type: "boolean",
+        },
+        descending: true,
+    });
+}
+
+impl Validate for AnnouncementQuery {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::AppError;
+    use chrono::Duration;
+
+    fn audience(
+        audience: &str,
+        role: Option<&str>,
+        user_ids: Option<Vec<Uuid>>,
+        committee_id: Option<Uuid>,
+    ) -> AnnouncementAudienceRequest {
+        AnnouncementAudienceRequest {
+            audience: audience.to_string(),
+            role: role.map(str::to_string),
+            user_ids,
+            committee_id,
+        }
+    }
+
+    fn create(audience: Option<AnnouncementAudienceRequest>) -> CreateAnnouncementRequest {
+        CreateAnnouncementRequest {
+            title: "Annual general meeting".to_string(),
+            body: None,
+            audience,
+            publish_at: None,
+            expires_at: None,
+            is_draft: None,
+            is_pinned: None,
+            send_email: None,
+        }
+    }
+
+    fn errors(validate: &impl Validate) -> Vec<(String, &'static str)> {
+        match validate.check() {
+            Ok(()) => Vec::new(),
+            Err(AppError::Validation(errors)) => errors
+                .into_iter()
+                .map(|error| (error.field, error.code))
+                .collect(),
+            Err(e) => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn accepts_complete_audiences() {
+        for request in [
+            audience("everyone", None, None, None),
+            audience("role", Some("treasurer"), None, None),
+            audience("users", None, Some(vec![Uuid::nil()]), None),
+            audience("committee", None, None, Some(Uuid::nil())),
+        ] {
+            assert!(errors(&request).is_empty(), "{:?}", request);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_audience() {
+        assert_eq!(
+            errors(&audience("alumni", None, None, None)),
+            vec![("audience".to_string(), "invalid_audience")]
+        );
+    }
+
+    #[test]
+    fn role_audience_needs_a_valid_role() {
+        assert_eq!(
+            errors(&audience("role", None, None, None)),
+            vec![("role".to_string(), "required")]
+        );
+        assert_eq!(
+            errors(&audience("role", Some("chairman"), None, None)),
+            vec![("role".to_string(), "invalid_role")]
+        );
+    }
+
+    #[test]
+    fn users_audience_needs_users() {
+        for user_ids in [None, Some(Vec::new())] {
+            assert_eq!(
+                errors(&audience("users", None, user_ids, None)),
+                vec![("user_ids".to_string(), "required")]
+            );
+        }
+    }
+
+    #[test]
+    fn committee_audience_needs_a_committee() {
+        assert_eq!(
+            errors(&audience("committee", None, None, None)),
+            vec![("committee_id".to_string(), "required")]
+        );
+    }
+
+    #[test]
+    fn announcement_checks_its_audience() {
+        assert!(errors(&create(None)).is_empty());
+        assert_eq!(
+            errors(&create(Some(audience("role", None, None, None)))),
+            vec![("role".to_string(), "required")]
+        );
+    }
+
+    #[test]
+    fn announcement_must_expire_after_publishing() {
+        let now = Utc::now();
+        let mut request = create(None);
+        request.title = " ".to_string();
+        request.publish_at = Some(now);
+        request.expires_at = Some(now - Duration::hours(1));
+        assert_eq!(
+            errors(&request),
+            vec![
+                ("title".to_string(), "required"),
+                ("expires_at".to_string(), "invalid_range"),
+            ]
+        );
+    }
+
+    #[test]
+    fn update_tells_a_cleared_field_from_a_missing_one() {
+        let request: UpdateAnnouncementRequest =
+            serde_json::from_str(r#"{"body":null,"expires_at":null}"#).unwrap();
+        assert_eq!(request.body, Some(None));
+        assert_eq!(request.expires_at, Some(None));
+
+        let request: UpdateAnnouncementRequest =
+            serde_json::from_str(r#"{"title":"AGM","expires_at":"2026-12-01T00:00:00Z"}"#).unwrap();
+        assert_eq!(request.body, None);
+        assert!(matches!(request.expires_at, Some(Some(_))));
+    }
+
+    #[test]
+    fn update_can_clear_the_expiry_while_rescheduling() {
+        let request: UpdateAnnouncementRequest =
+            serde_json::from_str(r#"{"publish_at":"2026-12-01T00:00:00Z","expires_at":null}"#)
+                .unwrap();
+        assert!(errors(&request).is_empty());
+    }
 }
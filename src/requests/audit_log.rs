@@ -0,0 +1,30 @@
+use crate::utils::validation::{Validate, Validator};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct AuditLogQuery {
+    pub actor_id: Option<Uuid>,
+    /// An action such as `payment.verify`, or a prefix such as `payment.`
+    pub action: Option<String>,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<Uuid>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Only entries older than this, for paging back through the log.
+    pub before: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+}
+
+impl Validate for AuditLogQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.optional("action", self.action.as_deref()).max_chars(100);
+        v.optional("entity_type", self.entity_type.as_deref())
+            .max_chars(50);
+        v.optional("limit", self.limit.as_ref()).range(1..=500);
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            v.check("to", from <= to, "invalid_range", "must not be before from");
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use crate::utils::validation::{Validate, Validator};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct SetBudgetRequest {
+    pub category_id: Uuid,
+    pub year: i32,
+    pub amount: Decimal,
+    pub notes: Option<String>,
+}
+
+impl Validate for SetBudgetRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.field("year", &self.year).range(2000..=2100);
+        v.field("amount", &self.amount).non_negative();
+        v.optional("notes", self.notes.as_deref()).max_chars(2000);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BudgetYearQuery {
+    pub year: Option<i32>,
+}
+
+impl Validate for BudgetYearQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.optional("year", self.year.as_ref()).range(2000..=2100);
+    }
+}
@@ -0,0 +1,60 @@
+use crate::utils::helpers::double_option;
+use crate::utils::validation::{Validate, Validator};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCampaignRequest {
+    pub title: String,
+    pub description: Option<String>,
+    pub target_amount: Decimal,
+    pub deadline: Option<NaiveDate>,
+    pub allow_anonymous: Option<bool>,
+}
+
+impl Validate for CreateCampaignRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.field("title", self.title.as_str())
+            .required()
+            .max_chars(200);
+        v.field("target_amount", &self.target_amount).positive();
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateCampaignRequest {
+    pub title: Option<String>,
+    /// `null` clears the description.
+    #[serde(default, deserialize_with = "double_option")]
+    pub description: Option<Option<String>>,
+    pub target_amount: Option<Decimal>,
+    /// `null` removes the deadline.
+    #[serde(default, deserialize_with = "double_option")]
+    pub deadline: Option<Option<NaiveDate>>,
+    pub allow_anonymous: Option<bool>,
+    pub is_closed: Option<bool>,
+}
+
+impl Validate for UpdateCampaignRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.optional("title", self.title.as_deref())
+            .required()
+            .max_chars(200);
+        v.optional("target_amount", self.target_amount.as_ref())
+            .positive();
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DonateRequest {
+    pub amount: Decimal,
+    pub currency: Option<String>,
+    pub anonymous: Option<bool>,
+}
+
+impl Validate for DonateRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.field("amount", &self.amount).positive();
+    }
+}
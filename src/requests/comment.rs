@@ -0,0 +1,44 @@
+use crate::utils::validation::{Validate, Validator};
+use serde::Deserialize;
+use uuid::Uuid;
+
+pub const MAX_COMMENT_LENGTH: usize = 5000;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCommentRequest {
+    pub body: String,
+    /// The comment being replied to.
+    pub parent_id: Option<Uuid>,
+}
+
+impl Validate for CreateCommentRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.field("body", self.body.as_str())
+            .required()
+            .max_chars(MAX_COMMENT_LENGTH);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateCommentRequest {
+    pub body: String,
+}
+
+impl Validate for UpdateCommentRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.field("body", self.body.as_str())
+            .required()
+            .max_chars(MAX_COMMENT_LENGTH);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HideCommentRequest {
+    pub reason: Option<String>,
+}
+
+impl Validate for HideCommentRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.optional("reason", self.reason.as_deref()).max_chars(500);
+    }
+}
@@ -0,0 +1,24 @@
+use crate::utils::validation::{Validate, Validator};
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCommitteeRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl Validate for CreateCommitteeRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.field("name", self.name.as_str())
+            .required()
+            .max_chars(255);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommitteeMemberRequest {
+    pub user_id: Uuid,
+}
+
+impl Validate for CommitteeMemberRequest {}
@@ -1,13 +1,26 @@
+use crate::utils::pagination::{SortField, Sortable};
+use crate::utils::validation::{Validate, Validator};
 use chrono::NaiveDate;
 use rust_decimal::Decimal;
 use serde::Deserialize;
+use uuid::Uuid;
 
 #[derive(Debug, Deserialize)]
 pub struct ContributionRequest {
     pub title: String,
     pub description: Option<String>,
     pub amount: Option<Decimal>,
-    pub due_date: Option<NaiveDate>,
+    pub currency: Option<String>,
+    pub due_date: NaiveDate,
+}
+
+impl Validate for ContributionRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.field("title", self.title.as_str())
+            .required()
+            .max_chars(200);
+        v.optional("amount", self.amount.as_ref()).non_negative();
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -15,5 +28,56 @@ pub struct UpdateContributionRequest {
     pub title: Option<String>,
     pub description: Option<String>,
     pub amount: Option<Decimal>,
+    pub currency: Option<String>,
     pub due_date: Option<NaiveDate>,
 }
+
+impl Validate for UpdateContributionRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.optional("title", self.title.as_deref())
+            .required()
+            .max_chars(200);
+        v.optional("amount", self.amount.as_ref()).non_negative();
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ContributionsQuery {
+    pub currency: Option<String>,
+    pub created_by: Option<Uuid>,
+    /// Due on or after this date.
+    pub due_from: Option<NaiveDate>,
+    /// Due on or before this date.
+    pub due_to: Option<NaiveDate>,
+}
+
+impl Sortable for ContributionsQuery {
+    const SORT_FIELDS: &'static [SortField] = &[
+        SortField {
+            name: "created_at",
+            sql_type: "timestamptz",
+        },
+        SortField {
+            name: "due_date",
+            sql_type: "date",
+        },
+        SortField {
+            name: "title",
+            sql_type: "text",
+        },
+    ];
+    const DEFAULT_SORT: &'static str = "-created_at";
+}
+
+impl Validate for ContributionsQuery {
+    fn validate(&self, v: &mut Validator) {
+        if let (Some(due_from), Some(due_to)) = (self.due_from, self.due_to) {
+            v.check(
+                "due_to",
+                due_from <= due_to,
+                "invalid_range",
+                "must not be before due_from",
+            );
+        }
+    }
+}
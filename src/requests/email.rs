@@ -0,0 +1,31 @@
+use crate::utils::validation::{Validate, Validator};
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct EmailOutboxQuery {
+    pub status: Option<String>,
+    pub to_email: Option<String>,
+    pub limit: Option<i64>,
+}
+
+impl Validate for EmailOutboxQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.optional("limit", self.limit.as_ref()).range(1..=500);
+    }
+}
+
+/// The query string of a signed unsubscribe link.
+#[derive(Debug, Deserialize)]
+pub struct UnsubscribeQuery {
+    pub user: Uuid,
+    pub list: String,
+    pub token: String,
+}
+
+impl Validate for UnsubscribeQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.field("list", self.list.as_str()).required();
+        v.field("token", self.token.as_str()).required();
+    }
+}
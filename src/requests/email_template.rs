@@ -0,0 +1,31 @@
+use crate::utils::validation::{Validate, Validator};
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+pub struct EmailTemplateRequest {
+    /// Ignored for the layout, which takes its title from the rendered subject.
+    pub subject: String,
+    pub html_body: String,
+    /// Leave out to generate the plain-text alternative from the HTML.
+    pub text_body: Option<String>,
+}
+
+impl Validate for EmailTemplateRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.field("subject", self.subject.as_str()).required();
+        v.field("html_body", self.html_body.as_str()).required();
+    }
+}
+
+/// Unsaved changes to preview. Anything left out falls back to the active version, and
+/// `context` is merged over the sample data.
+#[derive(Debug, Default, Deserialize)]
+pub struct PreviewEmailTemplateRequest {
+    pub subject: Option<String>,
+    pub html_body: Option<String>,
+    pub text_body: Option<String>,
+    pub context: Option<Value>,
+}
+
+impl Validate for PreviewEmailTemplateRequest {}
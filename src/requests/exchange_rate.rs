@@ -0,0 +1,27 @@
+use crate::utils::validation::{Validate, Validator};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct SetExchangeRateRequest {
+    pub currency: String,
+    pub rate: Decimal,
+    pub effective_date: Option<NaiveDate>,
+    pub notes: Option<String>,
+}
+
+impl Validate for SetExchangeRateRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.field("currency", self.currency.as_str()).required();
+        v.field("rate", &self.rate).positive();
+        v.optional("notes", self.notes.as_deref()).max_chars(2000);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExchangeRateQuery {
+    pub currency: Option<String>,
+}
+
+impl Validate for ExchangeRateQuery {}
@@ -0,0 +1,72 @@
+use crate::utils::validation::{Validate, Validator};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateExpenseCategoryRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl Validate for CreateExpenseCategoryRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.field("name", self.name.as_str())
+            .required()
+            .max_chars(100);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateExpenseRequest {
+    pub category_id: Uuid,
+    pub amount: Decimal,
+    pub payee: String,
+    pub description: Option<String>,
+    pub spent_on: NaiveDate,
+    pub event_id: Option<Uuid>,
+    pub project: Option<String>,
+}
+
+impl Validate for CreateExpenseRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.field("amount", &self.amount).positive();
+        v.field("payee", self.payee.as_str())
+            .required()
+            .max_chars(255);
+        v.optional("project", self.project.as_deref())
+            .max_chars(255);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateExpenseRequest {
+    pub category_id: Option<Uuid>,
+    pub amount: Option<Decimal>,
+    pub payee: Option<String>,
+    pub description: Option<String>,
+    pub spent_on: Option<NaiveDate>,
+    pub event_id: Option<Uuid>,
+    pub project: Option<String>,
+}
+
+impl Validate for UpdateExpenseRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.optional("amount", self.amount.as_ref()).positive();
+        v.optional("payee", self.payee.as_deref())
+            .required()
+            .max_chars(255);
+        v.optional("project", self.project.as_deref())
+            .max_chars(255);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExpensesQuery {
+    pub status: Option<String>,
+    pub category_id: Option<Uuid>,
+    pub year: Option<i32>,
+}
+
+impl Validate for ExpensesQuery {}
@@ -0,0 +1,15 @@
+use crate::utils::validation::{Validate, Validator};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct JobQuery {
+    pub status: Option<String>,
+    pub job_type: Option<String>,
+    pub limit: Option<i64>,
+}
+
+impl Validate for JobQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.optional("limit", self.limit.as_ref()).range(1..=500);
+    }
+}
@@ -1,8 +1,23 @@
 pub mod announcement;
+pub mod audit_log;
+pub mod budget;
+pub mod campaign;
+pub mod comment;
+pub mod committee;
 pub mod contribution;
+pub mod email;
+pub mod email_template;
+pub mod exchange_rate;
+pub mod expense;
+pub mod job;
+pub mod notification;
 pub mod payment;
+pub mod payment_reversal;
 pub mod photo;
+pub mod reaction;
+pub mod reconciliation;
 pub mod register;
+pub mod report;
 pub mod resend_email_verification;
 pub mod user;
 pub mod verify_email;
@@ -0,0 +1,37 @@
+use crate::utils::validation::{Validate, Validator};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+pub struct NotificationQuery {
+    pub unread: Option<bool>,
+    pub kind: Option<String>,
+    /// Only notifications older than this, for paging back through the list.
+    pub before: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+}
+
+impl Validate for NotificationQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.optional("limit", self.limit.as_ref()).range(1..=100);
+    }
+}
+
+/// Kinds to turn on or off, e.g. `{"preferences": {"event_reminder": false}}`. Kinds left out
+/// keep their current setting.
+#[derive(Debug, Deserialize)]
+pub struct NotificationPreferencesRequest {
+    pub preferences: HashMap<String, bool>,
+}
+
+impl Validate for NotificationPreferencesRequest {}
+
+/// Channels to turn on or off, e.g. `{"channels": {"sms": true}}`. Channels left out keep
+/// their current setting.
+#[derive(Debug, Deserialize)]
+pub struct NotificationChannelsRequest {
+    pub channels: HashMap<String, bool>,
+}
+
+impl Validate for NotificationChannelsRequest {}
@@ -1,4 +1,7 @@
 use crate::models::payment::PaymentStatus;
+use crate::utils::pagination::{SortField, Sortable};
+use crate::utils::validation::{Validate, Validator};
+use chrono::NaiveDate;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -6,12 +9,28 @@ use uuid::Uuid;
 #[derive(Debug, Deserialize)]
 pub struct PaymentRequest {
     pub user_id: Uuid,
-    pub contribution_id: Uuid,
+    pub contribution_id: Option<Uuid>,
+    pub campaign_id: Option<Uuid>,
+    pub is_anonymous: Option<bool>,
     pub amount: Option<Decimal>,
+    pub currency: Option<String>,
     pub receipt_url: Option<String>,
     pub status: PaymentStatus,
 }
 
+impl Validate for PaymentRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.check(
+            "contribution_id",
+            self.contribution_id.is_some() != self.campaign_id.is_some(),
+            "invalid_payment_target",
+            "provide exactly one of contribution_id or campaign_id",
+        );
+        v.optional("amount", self.amount.as_ref()).non_negative();
+        v.optional("receipt_url", self.receipt_url.as_deref()).url();
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct UpdatePaymentRequest {
     pub user_id: Option<Uuid>,
@@ -20,3 +39,109 @@ pub struct UpdatePaymentRequest {
     pub receipt_url: Option<String>,
     pub status: Option<PaymentStatus>,
 }
+
+impl Validate for UpdatePaymentRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.optional("amount", self.amount.as_ref()).non_negative();
+        v.optional("receipt_url", self.receipt_url.as_deref()).url();
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InitializePaymentRequest {
+    pub contribution_id: Uuid,
+    pub amount: Option<Decimal>,
+    pub currency: Option<String>,
+}
+
+impl Validate for InitializePaymentRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.optional("amount", self.amount.as_ref()).positive();
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PaymentsQuery {
+    /// `pending`, `verified` or `failed`.
+    pub status: Option<String>,
+    pub user_id: Option<Uuid>,
+    pub contribution_id: Option<Uuid>,
+    pub campaign_id: Option<Uuid>,
+    /// Made on or after this date.
+    pub from: Option<NaiveDate>,
+    /// Made on or before this date.
+    pub to: Option<NaiveDate>,
+}
+
+impl Sortable for PaymentsQuery {
+    const SORT_FIELDS: &'static [SortField] = &[
+        SortField {
+            name: "created_at",
+            sql_type: "timestamptz",
+        },
+        SortField {
+            name: "updated_at",
+            sql_type: "timestamptz",
+        },
+    ];
+    const DEFAULT_SORT: &'static str = "-created_at";
+}
+
+impl Validate for PaymentsQuery {
+    fn validate(&self, v: &mut Validator) {
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            v.check("to", from <= to, "invalid_range", "must not be before from");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(contribution_id: Option<Uuid>, campaign_id: Option<Uuid>) -> PaymentRequest {
+        PaymentRequest {
+            user_id: Uuid::nil(),
+            contribution_id,
+            campaign_id,
+            is_anonymous: None,
+            amount: Some(Decimal::ONE_HUNDRED),
+            currency: None,
+            receipt_url: None,
+            status: PaymentStatus::Pending,
+        }
+    }
+
+    #[test]
+    fn needs_exactly_one_target() {
+        assert!(request(Some(Uuid::nil()), None).check().is_ok());
+        assert!(request(None, Some(Uuid::nil())).check().is_ok());
+        assert!(request(None, None).check().is_err());
+        assert!(
+            request(Some(Uuid::nil()), Some(Uuid::nil()))
+                .check()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn rejects_negative_amounts_and_bad_receipt_urls() {
+        let mut payment = request(Some(Uuid::nil()), None);
+        payment.amount = Some(Decimal::NEGATIVE_ONE);
+        assert!(payment.check().is_err());
+
+        let mut payment = request(Some(Uuid::nil()), None);
+        payment.receipt_url = Some("receipt.pdf".to_string());
+        assert!(payment.check().is_err());
+    }
+
+    #[test]
+    fn checkout_amount_must_be_positive() {
+        let checkout = InitializePaymentRequest {
+            contribution_id: Uuid::nil(),
+            amount: Some(Decimal::ZERO),
+            currency: None,
+        };
+        assert!(checkout.check().is_err());
+    }
+}
@@ -0,0 +1,44 @@
+use crate::models::payment::PaymentEntryType;
+use crate::utils::validation::{Validate, Validator};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct PaymentReversalRequest {
+    pub entry_type: PaymentEntryType,
+    pub amount: Option<Decimal>,
+    pub reason: String,
+}
+
+impl Validate for PaymentReversalRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.check(
+            "entry_type",
+            self.entry_type != PaymentEntryType::Payment,
+            "invalid_entry_type",
+            "must be Refund or Reversal",
+        );
+        v.optional("amount", self.amount.as_ref()).positive();
+        v.field("reason", self.reason.as_str())
+            .required()
+            .max_chars(500);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewReversalRequest {
+    pub note: Option<String>,
+}
+
+impl Validate for ReviewReversalRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.optional("note", self.note.as_deref()).max_chars(500);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReversalsQuery {
+    pub status: Option<String>,
+}
+
+impl Validate for ReversalsQuery {}
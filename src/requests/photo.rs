@@ -1,3 +1,5 @@
+use crate::utils::pagination::{SortField, Sortable};
+use crate::utils::validation::{Validate, Validator};
 use serde::Deserialize;
 use uuid::Uuid;
 
@@ -8,9 +10,58 @@ pub struct CreatePhotoRequest {
     pub caption: Option<String>,
 }
 
+impl Validate for CreatePhotoRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.field("url", self.url.as_str()).url();
+        v.optional("caption", self.caption.as_deref())
+            .max_chars(2000);
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct UpdatePhotoRequest {
     pub event_id: Option<Uuid>,
     pub url: Option<String>,
     pub caption: Option<String>,
 }
+
+impl Validate for UpdatePhotoRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.optional("url", self.url.as_deref()).url();
+        v.optional("caption", self.caption.as_deref())
+            .max_chars(2000);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PhotoTagRequest {
+    pub user_id: Uuid,
+}
+
+impl Validate for PhotoTagRequest {}
+
+#[derive(Debug, Deserialize)]
+pub struct PhotosQuery {
+    pub event_id: Option<Uuid>,
+    pub posted_by: Option<Uuid>,
+}
+
+impl Sortable for PhotosQuery {
+    const SORT_FIELDS: &'static [SortField] = &[
+        SortField {
+            name: "created_at",
+            sql_type: "timestamptz",
+        },
+        SortField {
+            name: "comment_count",
+            sql_type: "bigint",
+        },
+        SortField {
+            name: "reaction_count",
+            sql_type: "bigint",
+        },
+    ];
+    const DEFAULT_SORT: &'static str = "-created_at";
+}
+
+impl Validate for PhotosQuery {}
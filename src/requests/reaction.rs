@@ -0,0 +1,15 @@
+use crate::utils::validation::{Validate, Validator};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct ReactionRequest {
+    pub emoji: String,
+}
+
+impl Validate for ReactionRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.field("emoji", self.emoji.as_str())
+            .required()
+            .max_chars(32);
+    }
+}
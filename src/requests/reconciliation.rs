@@ -0,0 +1,31 @@
+use crate::utils::validation::{Validate, Validator};
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct ImportStatementQuery {
+    pub filename: Option<String>,
+}
+
+impl Validate for ImportStatementQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.optional("filename", self.filename.as_deref())
+            .max_chars(255);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatementLinesQuery {
+    pub status: Option<String>,
+}
+
+impl Validate for StatementLinesQuery {}
+
+#[derive(Debug, Deserialize)]
+pub struct MatchStatementLineRequest {
+    pub payment_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub contribution_id: Option<Uuid>,
+}
+
+impl Validate for MatchStatementLineRequest {}
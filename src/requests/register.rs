@@ -1,3 +1,4 @@
+use crate::utils::validation::{Validate, Validator};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
@@ -8,3 +9,13 @@ pub struct RegisterRequest {
     pub user_role: Option<String>,
     pub is_active: Option<bool>,
 }
+
+impl Validate for RegisterRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.field("fullname", self.fullname.as_str())
+            .required()
+            .max_chars(255);
+        v.field("email", self.email.as_str()).email();
+        v.field("password", self.password.as_str()).password();
+    }
+}
@@ -0,0 +1,20 @@
+use crate::utils::validation::{Validate, Validator};
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct ReportQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub format: Option<String>,
+    pub limit: Option<i64>,
+}
+
+impl Validate for ReportQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.optional("limit", self.limit.as_ref()).range(1..=500);
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            v.check("to", from <= to, "invalid_range", "must not be before from");
+        }
+    }
+}
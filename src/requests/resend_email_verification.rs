@@ -1,6 +1,13 @@
+use crate::utils::validation::{Validate, Validator};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
 pub struct ResendVerificationRequest {
     pub email: String,
 }
+
+impl Validate for ResendVerificationRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.field("email", self.email.as_str()).email();
+    }
+}
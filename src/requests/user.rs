@@ -1 +1,72 @@
+use crate::utils::pagination::{SortField, Sortable};
+use crate::utils::validation::{Validate, Validator};
+use serde::Deserialize;
 
+#[derive(Debug, Deserialize)]
+pub struct UsersQuery {
+    pub role: Option<String>,
+    pub active: Option<bool>,
+    /// Matches part of the name or email.
+    pub search: Option<String>,
+}
+
+impl Sortable for UsersQuery {
+    const SORT_FIELDS: &'static [SortField] = &[
+        SortField {
+            name: "created_at",
+            sql_type: "timestamptz",
+        },
+        SortField {
+            name: "fullname",
+            sql_type: "text",
+        },
+        SortField {
+            name: "email",
+            sql_type: "text",
+        },
+    ];
+    const DEFAULT_SORT: &'static str = "-created_at";
+}
+
+impl Validate for UsersQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.optional("search", self.search.as_deref()).max_chars(255);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DuesReminderPreferenceRequest {
+    pub enabled: bool,
+}
+
+impl Validate for DuesReminderPreferenceRequest {}
+
+#[derive(Debug, Deserialize)]
+pub struct AnnouncementEmailPreferenceRequest {
+    pub enabled: bool,
+}
+
+impl Validate for AnnouncementEmailPreferenceRequest {}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateRoleRequest {
+    pub role: String,
+}
+
+impl Validate for UpdateRoleRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.field("role", self.role.as_str()).required();
+    }
+}
+
+/// `None` removes the number.
+#[derive(Debug, Deserialize)]
+pub struct PhoneRequest {
+    pub phone: Option<String>,
+}
+
+impl Validate for PhoneRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.optional("phone", self.phone.as_deref()).max_chars(25);
+    }
+}
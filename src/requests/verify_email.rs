@@ -1,6 +1,15 @@
+use crate::utils::validation::{Validate, Validator};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
 pub struct VerifyEmailRequest {
     pub code: String,
 }
+
+impl Validate for VerifyEmailRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.field("code", self.code.as_str())
+            .required()
+            .max_chars(255);
+    }
+}
@@ -20,10 +20,51 @@ pub fn scoped_config(cfg: &mut web::ServiceConfig) {
                     .route(web::get().to(handlers::users::index))
                     .route(web::head().to(HttpResponse::MethodNotAllowed)),
             )
+            .service(
+                web::resource("/me/dues-reminders")
+                    .wrap(AuthMiddleware)
+                    .route(web::put().to(handlers::users::update_dues_reminders)),
+            )
+            .service(
+                web::resource("/me/phone")
+                    .wrap(AuthMiddleware)
+                    .route(web::put().to(handlers::users::update_phone)),
+            )
+            .service(
+                web::resource("/me/announcement-emails")
+                    .wrap(AuthMiddleware)
+                    .route(web::put().to(handlers::users::update_announcement_emails)),
+            )
+            .service(
+                web::resource("/deleted")
+                    .wrap(AuthMiddleware)
+                    .route(web::get().to(handlers::users::deleted))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}")
+                    .wrap(AuthMiddleware)
+                    .route(web::delete().to(handlers::users::delete)),
+            )
             .service(
                 web::resource("/{id}/toggle-active")
                     .wrap(AuthMiddleware)
                     .route(web::post().to(handlers::users::toggle_user_active)),
+            )
+            .service(
+                web::resource("/{id}/role")
+                    .wrap(AuthMiddleware)
+                    .route(web::put().to(handlers::users::update_role)),
+            )
+            .service(
+                web::resource("/{id}/restore")
+                    .wrap(AuthMiddleware)
+                    .route(web::post().to(handlers::users::restore)),
+            )
+            .service(
+                web::resource("/{id}/purge")
+                    .wrap(AuthMiddleware)
+                    .route(web::delete().to(handlers::users::purge)),
             ),
     )
     .service(
@@ -47,6 +88,15 @@ pub fn scoped_config(cfg: &mut web::ServiceConfig) {
                     )
                     .route(web::head().to(HttpResponse::MethodNotAllowed)),
             )
+            .service(
+                web::resource("/deleted")
+                    .route(
+                        web::get()
+                            .to(handlers::contributions::deleted)
+                            .wrap(AuthMiddleware),
+                    )
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
             .service(
                 web::resource("/{id}")
                     .route(web::get().to(handlers::contributions::get_contribution))
@@ -61,6 +111,58 @@ pub fn scoped_config(cfg: &mut web::ServiceConfig) {
                             .wrap(AuthMiddleware),
                     )
                     .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/restore")
+                    .wrap(AuthMiddleware)
+                    .route(web::post().to(handlers::contributions::restore)),
+            )
+            .service(
+                web::resource("/{id}/purge")
+                    .wrap(AuthMiddleware)
+                    .route(web::delete().to(handlers::contributions::purge)),
+            ),
+    )
+    .service(
+        web::scope("/campaigns")
+            .service(
+                web::resource("")
+                    .route(
+                        web::post()
+                            .to(handlers::campaigns::create)
+                            .wrap(AuthMiddleware),
+                    )
+                    .route(web::get().to(handlers::campaigns::all))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}")
+                    .route(web::get().to(handlers::campaigns::get_campaign))
+                    .route(
+                        web::delete()
+                            .to(handlers::campaigns::delete)
+                            .wrap(AuthMiddleware),
+                    )
+                    .route(
+                        web::put()
+                            .to(handlers::campaigns::update)
+                            .wrap(AuthMiddleware),
+                    )
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/donors")
+                    .route(web::get().to(handlers::campaigns::donors))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/donate")
+                    .route(
+                        web::post()
+                            .to(handlers::campaigns::donate)
+                            .wrap(AuthMiddleware),
+                    )
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
             ),
     )
     .service(
@@ -72,6 +174,16 @@ pub fn scoped_config(cfg: &mut web::ServiceConfig) {
                     .route(web::post().to(handlers::payments::create))
                     .route(web::head().to(HttpResponse::MethodNotAllowed)),
             )
+            .service(
+                web::resource("/initialize")
+                    .route(web::post().to(handlers::payments::initialize))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/deleted")
+                    .route(web::get().to(handlers::payments::deleted))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
             .service(
                 web::resource("/{id}")
                     .route(web::get().to(handlers::payments::get_payment))
@@ -79,12 +191,252 @@ pub fn scoped_config(cfg: &mut web::ServiceConfig) {
                     .route(web::put().to(handlers::payments::update))
                     .route(web::head().to(HttpResponse::MethodNotAllowed)),
             )
+            .service(
+                web::resource("/{id}/restore").route(web::post().to(handlers::payments::restore)),
+            )
+            .service(
+                web::resource("/{id}/purge").route(web::delete().to(handlers::payments::purge)),
+            )
+            .service(
+                web::resource("/{id}/receipt")
+                    .route(web::get().to(handlers::payments::download_receipt))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/reversals")
+                    .route(web::post().to(handlers::payment_reversals::request_reversal)),
+            )
+            .service(
+                web::resource("/{id}/ledger")
+                    .route(web::get().to(handlers::payment_reversals::get_payment_ledger))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
             .service(
                 web::resource("/user/{user_id}")
                     .route(web::get().to(handlers::payments::get_user_payments))
                     .route(web::head().to(HttpResponse::MethodNotAllowed)),
             ),
     )
+    .service(
+        web::scope("/reversals")
+            .wrap(AuthMiddleware)
+            .service(
+                web::resource("")
+                    .route(web::get().to(handlers::payment_reversals::all))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/approve")
+                    .route(web::post().to(handlers::payment_reversals::approve)),
+            )
+            .service(
+                web::resource("/{id}/reject")
+                    .route(web::post().to(handlers::payment_reversals::reject)),
+            ),
+    )
+    .service(
+        web::scope("/expenses")
+            .wrap(AuthMiddleware)
+            .service(
+                web::resource("")
+                    .route(web::get().to(handlers::expenses::all))
+                    .route(web::post().to(handlers::expenses::create))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/categories")
+                    .route(web::get().to(handlers::expenses::categories))
+                    .route(web::post().to(handlers::expenses::create_category))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}")
+                    .route(web::get().to(handlers::expenses::get_expense))
+                    .route(web::put().to(handlers::expenses::update))
+                    .route(web::delete().to(handlers::expenses::delete))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/receipt")
+                    .app_data(web::PayloadConfig::new(10 * 1024 * 1024))
+                    .route(web::get().to(handlers::expenses::download_receipt))
+                    .route(web::put().to(handlers::expenses::upload_receipt))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/approve").route(web::post().to(handlers::expenses::approve)),
+            )
+            .service(
+                web::resource("/{id}/reject").route(web::post().to(handlers::expenses::reject)),
+            ),
+    )
+    .service(
+        web::scope("/budgets")
+            .wrap(AuthMiddleware)
+            .service(
+                web::resource("")
+                    .route(web::get().to(handlers::budgets::all))
+                    .route(web::put().to(handlers::budgets::set_budget))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/report")
+                    .route(web::get().to(handlers::budgets::report))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}")
+                    .route(web::delete().to(handlers::budgets::delete))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            ),
+    )
+    .service(
+        web::scope("/exchange-rates")
+            .wrap(AuthMiddleware)
+            .service(
+                web::resource("")
+                    .route(web::get().to(handlers::exchange_rates::all))
+                    .route(web::put().to(handlers::exchange_rates::set_rate))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}")
+                    .route(web::delete().to(handlers::exchange_rates::delete))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            ),
+    )
+    .service(
+        web::scope("/reports")
+            .wrap(AuthMiddleware)
+            .service(
+                web::resource("/collections")
+                    .route(web::get().to(handlers::reports::collections))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/defaulters")
+                    .route(web::get().to(handlers::reports::defaulters))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/monthly-trend")
+                    .route(web::get().to(handlers::reports::monthly_trend))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/income-statement")
+                    .route(web::get().to(handlers::reports::income_statement))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            ),
+    )
+    .service(
+        web::scope("/email-templates")
+            .wrap(AuthMiddleware)
+            .service(
+                web::resource("")
+                    .route(web::get().to(handlers::email_templates::all))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{name}")
+                    .route(web::get().to(handlers::email_templates::get_template))
+                    .route(web::put().to(handlers::email_templates::update))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{name}/preview")
+                    .route(web::get().to(handlers::email_templates::preview))
+                    .route(web::post().to(handlers::email_templates::preview_draft))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{name}/versions")
+                    .route(web::get().to(handlers::email_templates::versions))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{name}/versions/{version}/activate")
+                    .route(web::post().to(handlers::email_templates::activate))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            ),
+    )
+    .service(
+        web::scope("/emails")
+            .wrap(AuthMiddleware)
+            .service(
+                web::resource("")
+                    .route(web::get().to(handlers::emails::all))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}")
+                    .route(web::get().to(handlers::emails::get_email))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/retry")
+                    .route(web::post().to(handlers::emails::retry))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            ),
+    )
+    .service(
+        web::scope("/unsubscribe").service(
+            web::resource("")
+                .route(web::get().to(handlers::emails::unsubscribe))
+                .route(web::head().to(HttpResponse::MethodNotAllowed)),
+        ),
+    )
+    .service(
+        web::scope("/jobs")
+            .wrap(AuthMiddleware)
+            .service(
+                web::resource("")
+                    .route(web::get().to(handlers::jobs::all))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/schedules")
+                    .route(web::get().to(handlers::jobs::schedules))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}")
+                    .route(web::get().to(handlers::jobs::get_job))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/retry")
+                    .route(web::post().to(handlers::jobs::retry))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            ),
+    )
+    .service(
+        web::scope("/webhooks")
+            .service(web::resource("/payments").route(web::post().to(handlers::payments::webhook))),
+    )
+    .service(
+        web::scope("/reconciliation")
+            .wrap(AuthMiddleware)
+            .service(
+                web::resource("/statements")
+                    .route(web::get().to(handlers::reconciliation::all))
+                    .route(web::post().to(handlers::reconciliation::import_statement))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/statements/{id}/lines")
+                    .route(web::get().to(handlers::reconciliation::get_statement_lines))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/lines/{id}/match")
+                    .route(web::post().to(handlers::reconciliation::match_line)),
+            )
+            .service(
+                web::resource("/lines/{id}/ignore")
+                    .route(web::post().to(handlers::reconciliation::ignore_line)),
+            ),
+    )
     .service(
         web::scope("/announcements")
             .wrap(AuthMiddleware)
@@ -94,12 +446,111 @@ pub fn scoped_config(cfg: &mut web::ServiceConfig) {
                     .route(web::post().to(handlers::announcements::create))
                     .route(web::head().to(HttpResponse::MethodNotAllowed)),
             )
+            .service(
+                web::resource("/unread-count")
+                    .route(web::get().to(handlers::announcements::unread_count))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/read-all")
+                    .route(web::post().to(handlers::announcements::mark_all_read))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/deleted")
+                    .route(web::get().to(handlers::announcements::deleted))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
             .service(
                 web::resource("/{id}")
                     .route(web::get().to(handlers::announcements::get_announcement))
                     .route(web::delete().to(handlers::announcements::delete))
                     .route(web::put().to(handlers::announcements::update))
                     .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/restore")
+                    .route(web::post().to(handlers::announcements::restore)),
+            )
+            .service(
+                web::resource("/{id}/purge")
+                    .route(web::delete().to(handlers::announcements::purge)),
+            )
+            .service(
+                web::resource("/{id}/email")
+                    .route(web::post().to(handlers::announcements::send_email))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/deliveries")
+                    .route(web::get().to(handlers::announcements::deliveries))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/read")
+                    .route(web::post().to(handlers::announcements::mark_read))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/reads")
+                    .route(web::get().to(handlers::announcements::reads))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/comments")
+                    .route(web::get().to(handlers::comments::announcement_comments))
+                    .route(web::post().to(handlers::comments::create_announcement_comment))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/reactions")
+                    .route(web::get().to(handlers::reactions::announcement_reactions))
+                    .route(web::post().to(handlers::reactions::add_announcement_reaction))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/reactions/{emoji}")
+                    .route(web::delete().to(handlers::reactions::remove_announcement_reaction))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/attachments")
+                    .app_data(web::PayloadConfig::new(10 * 1024 * 1024))
+                    .route(web::get().to(handlers::announcements::attachments))
+                    .route(web::post().to(handlers::announcements::upload_attachment))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/attachments/{attachment_id}")
+                    .route(web::get().to(handlers::announcements::download_attachment))
+                    .route(web::delete().to(handlers::announcements::delete_attachment))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            ),
+    )
+    .service(
+        web::scope("/committees")
+            .wrap(AuthMiddleware)
+            .service(
+                web::resource("")
+                    .route(web::get().to(handlers::committees::all))
+                    .route(web::post().to(handlers::committees::create))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}")
+                    .route(web::get().to(handlers::committees::get_committee))
+                    .route(web::delete().to(handlers::committees::delete))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/members")
+                    .route(web::post().to(handlers::committees::add_member))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/members/{user_id}")
+                    .route(web::delete().to(handlers::committees::remove_member))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
             ),
     )
     .service(
@@ -111,12 +562,119 @@ pub fn scoped_config(cfg: &mut web::ServiceConfig) {
                     .route(web::post().to(handlers::photos::create))
                     .route(web::head().to(HttpResponse::MethodNotAllowed)),
             )
+            .service(
+                web::resource("/deleted")
+                    .route(web::get().to(handlers::photos::deleted))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
             .service(
                 web::resource("/{id}")
                     .route(web::get().to(handlers::photos::get_photo))
                     .route(web::delete().to(handlers::photos::delete))
                     .route(web::put().to(handlers::photos::update))
                     .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/restore").route(web::post().to(handlers::photos::restore)),
+            )
+            .service(web::resource("/{id}/purge").route(web::delete().to(handlers::photos::purge)))
+            .service(
+                web::resource("/{id}/comments")
+                    .route(web::get().to(handlers::comments::photo_comments))
+                    .route(web::post().to(handlers::comments::create_photo_comment))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/reactions")
+                    .route(web::get().to(handlers::reactions::photo_reactions))
+                    .route(web::post().to(handlers::reactions::add_photo_reaction))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/reactions/{emoji}")
+                    .route(web::delete().to(handlers::reactions::remove_photo_reaction))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/tags")
+                    .route(web::get().to(handlers::photos::tags))
+                    .route(web::post().to(handlers::photos::add_tag))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/tags/{user_id}")
+                    .route(web::delete().to(handlers::photos::remove_tag))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            ),
+    )
+    .service(
+        web::scope("/comments")
+            .wrap(AuthMiddleware)
+            .service(
+                web::resource("/{id}")
+                    .route(web::put().to(handlers::comments::update))
+                    .route(web::delete().to(handlers::comments::delete))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/hide")
+                    .route(web::post().to(handlers::comments::hide))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/unhide")
+                    .route(web::post().to(handlers::comments::unhide))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            ),
+    )
+    .service(
+        web::scope("/audit-log").wrap(AuthMiddleware).service(
+            web::resource("")
+                .route(web::get().to(handlers::audit_log::all))
+                .route(web::head().to(HttpResponse::MethodNotAllowed)),
+        ),
+    )
+    .service(
+        web::scope("/realtime").wrap(AuthMiddleware).service(
+            web::resource("/stream")
+                .route(web::get().to(handlers::realtime::stream))
+                .route(web::head().to(HttpResponse::MethodNotAllowed)),
+        ),
+    )
+    .service(
+        web::scope("/notifications")
+            .wrap(AuthMiddleware)
+            .service(
+                web::resource("")
+                    .route(web::get().to(handlers::notifications::all))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/unread-count")
+                    .route(web::get().to(handlers::notifications::unread_count))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/read-all")
+                    .route(web::post().to(handlers::notifications::mark_all_read))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/preferences")
+                    .route(web::get().to(handlers::notifications::preferences))
+                    .route(web::put().to(handlers::notifications::update_preferences))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/channels")
+                    .route(web::get().to(handlers::notifications::channels))
+                    .route(web::put().to(handlers::notifications::update_channels))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
+            )
+            .service(
+                web::resource("/{id}/read")
+                    .route(web::post().to(handlers::notifications::mark_read))
+                    .route(web::head().to(HttpResponse::MethodNotAllowed)),
             ),
     );
 }
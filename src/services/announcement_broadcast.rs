@@ -0,0 +1,176 @@
+use crate::database::connection::DbPool;
+use crate::models::announcement::{Announcement, AnnouncementError};
+use crate::models::announcement_attachment::{AnnouncementAttachment, AnnouncementAttachmentError};
+use crate::models::announcement_delivery::{AnnouncementDelivery, AnnouncementDeliveryError};
+use crate::models::email_unsubscribe::ANNOUNCEMENTS_LIST;
+use crate::models::job::JobError;
+use crate::models::user::{User, UserError};
+use crate::services::email::{EmailError, EmailService};
+use crate::services::jobs::{self, JobKind};
+use chrono::Utc;
+use std::env;
+use thiserror::Error;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum AnnouncementBroadcastError {
+    #[error(transparent)]
+    Announcement(#[from] AnnouncementError),
+    #[error(transparent)]
+    Delivery(#[from] AnnouncementDeliveryError),
+    #[error(transparent)]
+    Attachment(#[from] AnnouncementAttachmentError),
+    #[error(transparent)]
+    User(#[from] UserError),
+    #[error(transparent)]
+    Email(#[from] EmailError),
+    #[error(transparent)]
+    Job(#[from] JobError),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct AnnouncementBroadcastConfig {
+    /// Recipients queued per transaction.
+    pub batch_size: i64,
+}
+
+impl AnnouncementBroadcastConfig {
+    pub fn from_env() -> Self {
+        Self {
+            batch_size: env::var("ANNOUNCEMENT_EMAIL_BATCH_SIZE")
+                .ok()
+                .and_then(|size| size.parse().ok())
+                .unwrap_or(100_i64)
+                .max(1),
+        }
+    }
+}
+
+/// Emails an announcement to everyone in its audience who has not had it yet, so running it
+/// again after the audience grows only reaches the new members.
+pub struct AnnouncementBroadcastService {
+    config: AnnouncementBroadcastConfig,
+}
+
+impl AnnouncementBroadcastService {
+    pub fn from_env() -> Self {
+        Self {
+            config: AnnouncementBroadcastConfig::from_env(),
+        }
+    }
+
+    /// Returns how many emails were queued.
+    pub async fn run(
+        &self,
+        pool: &DbPool,
+        announcement_id: Uuid,
+    ) -> Result<usize, AnnouncementBroadcastError> {
+        let announcement = Announcement::find_by_id(pool, announcement_id)
+            .await?
+            .ok_or(AnnouncementError::NotFound {
+                id: announcement_id,
+            })?;
+
+        // Scheduled posts go out when they are published; drafts and expired posts never do
+        let now = Utc::now();
+        if announcement.is_draft || announcement.is_expired_at(now) {
+            info!(
+                "Not emailing announcement {}: it is a draft or has expired",
+                announcement_id
+            );
+            return Ok(0);
+        }
+        if announcement.publish_at > now {
+            jobs::enqueue_at(
+                pool,
+                JobKind::BroadcastAnnouncement { announcement_id },
+                announcement.publish_at,
+            )
+            .await?;
+            info!(
+                "Deferred announcement {} email until {}",
+                announcement_id, announcement.publish_at
+            );
+            return Ok(0);
+        }
+        let posted_by = User::find_by_id(pool, announcement.posted_by)
+            .await?
+            .map(|user| user.fullname)
+            .unwrap_or_default();
+        let attachment_count = AnnouncementAttachment::find_by_announcement(pool, announcement_id)
+            .await?
+            .len();
+        let email_service = EmailService::new()?;
+
+        let mut queued = 0;
+        let mut after = None;
+        loop {
+            let recipients = Announcement::find_email_recipients(
+                pool,
+                announcement_id,
+                after,
+                self.config.batch_size,
+            )
+            .await?;
+            let Some(last) = recipients.last() else {
+                break;
+            };
+            after = Some(last.user_id);
+
+            let mut tx = pool.begin().await?;
+            for recipient in recipients {
+                let Some(delivery) =
+                    AnnouncementDelivery::reserve(&mut tx, announcement_id, recipient.user_id)
+                        .await?
+                else {
+                    continue;
+                };
+
+                let unsubscribe_link =
+                    email_service.unsubscribe_link(recipient.user_id, ANNOUNCEMENTS_LIST);
+                let template = email_service
+                    .announcement_email(
+                        &mut tx,
+                        &recipient.fullname,
+                        &announcement,
+                        attachment_count,
+                        &posted_by,
+                        &unsubscribe_link,
+                    )
+                    .await?;
+
+                // A bad address is skipped rather than failing the whole batch
+                match email_service
+                    .queue(
+                        &mut tx,
+                        &recipient.email,
+                        Some(&recipient.fullname),
+                        template,
+                    )
+                    .await
+                {
+                    Ok(email) => {
+                        AnnouncementDelivery::mark_queued(&mut tx, delivery.id, email.id).await?;
+                        queued += 1;
+                    }
+                    Err(EmailError::Address(e)) => warn!(
+                        "Skipping announcement {} for {}: {}",
+                        announcement_id, recipient.email, e
+                    ),
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            tx.commit().await?;
+        }
+
+        info!(
+            "Queued announcement {} for {} recipients",
+            announcement_id, queued
+        );
+
+        Ok(queued)
+    }
+}
@@ -0,0 +1,123 @@
+use crate::middleware::auth::AuthenticatedUser;
+use crate::middleware::request_id::RequestId;
+use crate::models::audit_log::{AuditLogEntry, AuditLogError, NewAuditLogEntry};
+use actix_web::{Error, FromRequest, HttpMessage, HttpRequest, dev::Payload};
+use futures_util::future::{Ready, ready};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+/// Fields never copied into the audit log.
+const REDACTED_FIELDS: [&str; 3] = ["password_hash", "email_verification_code", "storage_key"];
+
+/// Who is acting and from where. Extracted from the request; requests without a signed-in
+/// user, such as gateway webhooks, are recorded as the system.
+#[derive(Debug, Clone)]
+pub struct AuditContext {
+    actor: Option<AuthenticatedUser>,
+    ip_address: Option<String>,
+    request_id: Option<String>,
+}
+
+impl FromRequest for AuditContext {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let extensions = req.extensions();
+        ready(Ok(AuditContext {
+            actor: extensions.get::<AuthenticatedUser>().cloned(),
+            // Honours X-Forwarded-For, so only trust it behind our own proxy
+            ip_address: req
+                .connection_info()
+                .realip_remote_addr()
+                .map(str::to_string),
+            request_id: extensions
+                .get::<RequestId>()
+                .map(|request_id| request_id.0.clone()),
+        }))
+    }
+}
+
+/// Serializes `value` for the log without the fields in `REDACTED_FIELDS`.
+pub fn snapshot<T: Serialize>(value: &T) -> Option<Value> {
+    let mut value = serde_json::to_value(value).ok()?;
+    if let Value::Object(fields) = &mut value {
+        for field in REDACTED_FIELDS {
+            fields.remove(field);
+        }
+    }
+    Some(value)
+}
+
+impl AuditContext {
+    /// Records `action` against the entity. Pass the transaction that made the change, so the
+    /// change and its entry are committed or rolled back together.
+    pub async fn record(
+        &self,
+        conn: &mut PgConnection,
+        action: &str,
+        entity_type: &str,
+        entity_id: Uuid,
+        before: Option<Value>,
+        after: Option<Value>,
+    ) -> Result<(), AuditLogError> {
+        let entry = NewAuditLogEntry {
+            actor_id: self.actor.as_ref().map(|actor| actor.user_id),
+            actor_email: self.actor.as_ref().map(|actor| actor.email.clone()),
+            actor_role: self
+                .actor
+                .as_ref()
+                .map(|actor| format!("{:?}", actor.user_role)),
+            action: action.to_string(),
+            entity_type: entity_type.to_string(),
+            entity_id: Some(entity_id),
+            before,
+            after,
+            ip_address: self.ip_address.clone(),
+            request_id: self.request_id.clone(),
+        };
+
+        AuditLogEntry::record(conn, entry).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Serialize)]
+    struct User {
+        email: &'static str,
+        password_hash: &'static str,
+        email_verification_code: Option<&'static str>,
+    }
+
+    #[test]
+    fn snapshot_strips_redacted_fields() {
+        let user = User {
+            email: "ada@example.com",
+            password_hash: "$argon2id$secret",
+            email_verification_code: Some("123456"),
+        };
+        assert_eq!(snapshot(&user), Some(json!({ "email": "ada@example.com" })));
+    }
+
+    #[test]
+    fn snapshot_strips_storage_keys() {
+        let photo = json!({ "id": 1, "storage_key": "photos/abc.jpg", "url": "/photos/1" });
+        assert_eq!(
+            snapshot(&photo),
+            Some(json!({ "id": 1, "url": "/photos/1" }))
+        );
+    }
+
+    #[test]
+    fn snapshot_keeps_values_that_are_not_objects() {
+        assert_eq!(snapshot(&"password_hash"), Some(json!("password_hash")));
+        assert_eq!(snapshot(&Option::<i32>::None), Some(Value::Null));
+    }
+}
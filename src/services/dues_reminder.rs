@@ -0,0 +1,203 @@
+use crate::database::connection::DbPool;
+use crate::models::contribution::{Contribution, ContributionError};
+use crate::models::dues_reminder::{DuesReminder, DuesReminderError};
+use crate::models::exchange_rate::BASE_CURRENCY;
+use crate::services::email::{EmailError, EmailService};
+use crate::services::receipt::ReceiptService;
+use chrono::{Duration, NaiveDate, Utc};
+use std::env;
+use thiserror::Error;
+use tracing::warn;
+
+#[derive(Error, Debug)]
+pub enum DuesReminderServiceError {
+    #[error(transparent)]
+    Contribution(#[from] ContributionError),
+    #[error(transparent)]
+    Reminder(#[from] DuesReminderError),
+    #[error(transparent)]
+    Email(#[from] EmailError),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct DuesReminderConfig {
+    /// Days relative to the due date to send reminders on, e.g. `-7,0,7`.
+    pub offsets: Vec<i32>,
+    /// How many days late a reminder may still go out if the scheduler was not running.
+    pub grace_days: i64,
+}
+
+impl DuesReminderConfig {
+    pub fn from_env() -> Self {
+        let mut offsets: Vec<i32> = env::var("DUES_REMINDER_OFFSETS")
+            .unwrap_or_else(|_| "-7,0,7".to_string())
+            .split(',')
+            .filter_map(|offset| offset.trim().parse().ok())
+            .collect();
+        offsets.sort_unstable();
+        offsets.dedup();
+
+        Self {
+            offsets,
+            grace_days: env::var("DUES_REMINDER_GRACE_DAYS")
+                .ok()
+                .and_then(|days| days.parse().ok())
+                .unwrap_or(1),
+        }
+    }
+}
+
+pub struct DuesReminderService {
+    config: DuesReminderConfig,
+}
+
+impl DuesReminderService {
+    pub fn from_env() -> Self {
+        Self {
+            config: DuesReminderConfig::from_env(),
+        }
+    }
+
+    /// The latest configured offset that falls due on `today`, allowing for `grace_days` of
+    /// downtime. Earlier offsets that were missed are skipped rather than sent late.
+    fn offset_for(&self, due_date: NaiveDate, today: NaiveDate) -> Option<i32> {
+        self.config.offsets.iter().rev().copied().find(|offset| {
+            let late_by = (today - (due_date + Duration::days(i64::from(*offset)))).num_days();
+            (0..=self.config.grace_days).contains(&late_by)
+        })
+    }
+
+    /// Queues every reminder that is due today. Returns how many emails were queued.
+    pub async fn run_once(&self, pool: &DbPool) -> Result<usize, DuesReminderServiceError> {
+        let Some(earliest) = self.config.offsets.first() else {
+            return Ok(0);
+        };
+
+        let today = Utc::now().date_naive();
+        let contributions =
+            Contribution::find_due_before(pool, today - Duration::days(i64::from(*earliest)))
+                .await?;
+
+        let due: Vec<(Contribution, i32)> = contributions
+            .into_iter()
+            .filter_map(|contribution| {
+                self.offset_for(contribution.due_date, today)
+                    .map(|offset| (contribution, offset))
+            })
+            .collect();
+        if due.is_empty() {
+            return Ok(0);
+        }
+
+        let email_service = EmailService::new()?;
+
+        let mut sent = 0;
+        for (contribution, offset) in due {
+            let recipients = DuesReminder::find_recipients(pool, contribution.id, offset).await?;
+
+            for recipient in recipients {
+                // The slot is only claimed if the email is queued with it
+                let mut tx = pool.begin().await?;
+                let Some(reminder) =
+                    DuesReminder::reserve(&mut tx, contribution.id, recipient.user_id, offset)
+                        .await?
+                else {
+                    continue;
+                };
+
+                let amount = recipient
+                    .outstanding
+                    .map(|amount| ReceiptService::format_amount(BASE_CURRENCY, amount));
+                let queued = match email_service
+                    .dues_reminder_email(
+                        &mut tx,
+                        &recipient.fullname,
+                        &contribution.title,
+                        amount.as_deref(),
+                        contribution.due_date,
+                        offset,
+                    )
+                    .await
+                {
+                    Ok(template) => {
+                        email_service
+                            .queue(
+                                &mut tx,
+                                &recipient.email,
+                                Some(&recipient.fullname),
+                                template,
+                            )
+                            .await
+                    }
+                    Err(e) => Err(e),
+                };
+
+                match queued {
+                    Ok(email) => {
+                        DuesReminder::mark_queued(&mut tx, reminder.id, email.id).await?;
+                        tx.commit().await?;
+                        sent += 1;
+                    }
+                    Err(e) => warn!(
+                        "Failed to queue dues reminder for {} to {}: {}",
+                        contribution.id, recipient.email, e
+                    ),
+                }
+            }
+        }
+
+        Ok(sent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service(offsets: &[i32], grace_days: i64) -> DuesReminderService {
+        DuesReminderService {
+            config: DuesReminderConfig {
+                offsets: offsets.to_vec(),
+                grace_days,
+            },
+        }
+    }
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    #[test]
+    fn picks_the_offset_falling_due_today() {
+        let reminders = service(&[-7, 0, 7], 0);
+        let due = day(15);
+        assert_eq!(reminders.offset_for(due, day(8)), Some(-7));
+        assert_eq!(reminders.offset_for(due, day(15)), Some(0));
+        assert_eq!(reminders.offset_for(due, day(22)), Some(7));
+        assert_eq!(reminders.offset_for(due, day(10)), None);
+        assert_eq!(reminders.offset_for(due, day(7)), None);
+    }
+
+    #[test]
+    fn sends_late_within_the_grace_period() {
+        let reminders = service(&[-7, 0, 7], 1);
+        let due = day(15);
+        assert_eq!(reminders.offset_for(due, day(9)), Some(-7));
+        assert_eq!(reminders.offset_for(due, day(16)), Some(0));
+        assert_eq!(reminders.offset_for(due, day(17)), None);
+    }
+
+    #[test]
+    fn skips_missed_offsets_for_the_latest_one() {
+        let reminders = service(&[-1, 0], 3);
+        assert_eq!(reminders.offset_for(day(15), day(15)), Some(0));
+        assert_eq!(reminders.offset_for(day(15), day(14)), Some(-1));
+    }
+
+    #[test]
+    fn no_offsets_means_no_reminders() {
+        assert_eq!(service(&[], 1).offset_for(day(15), day(15)), None);
+    }
+}
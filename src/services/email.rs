@@ -1,25 +1,46 @@
-use lettre::{
-    Message, SmtpTransport, Transport,
-    message::{MultiPart, SinglePart, header::ContentType},
-    transport::smtp::authentication::Credentials,
+use crate::models::announcement::Announcement;
+use crate::models::email_outbox::{
+    NewOutboxEmail, OutboxAttachment, OutboxEmail, OutboxEmailError,
 };
+use crate::models::email_template::{EmailTemplateError, EmailTemplateVersion};
+use crate::services::notification_channels::ChannelMessage;
+use chrono::{Datelike, NaiveDate, Utc};
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use sqlx::PgConnection;
 use std::env;
+use tera::{Context, Tera};
 use thiserror::Error;
-use tracing::{error, info};
+use tracing::info;
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// The shared layout every template extends as `layout.html`.
+pub const LAYOUT_TEMPLATE: &str = "layout";
+
+const TEXT_WIDTH: usize = 78;
 
 #[derive(Error, Debug)]
 pub enum EmailError {
-    #[error("SMTP configuration error: {0}")]
+    #[error("Email configuration error: {0}")]
     Config(String),
     #[error("Email sending failed: {0}")]
     Send(#[from] lettre::transport::smtp::Error),
+    #[error("Writing email failed: {0}")]
+    Io(#[from] std::io::Error),
     #[error("Message building failed: {0}")]
     Message(#[from] lettre::error::Error),
     #[error("Address parsing failed: {0}")]
     Address(#[from] lettre::address::AddressError),
     #[error("Template rendering failed: {0}")]
     Template(String),
+    #[error(transparent)]
+    TemplateStore(#[from] EmailTemplateError),
+    #[error("Email outbox error: {0}")]
+    Outbox(#[from] OutboxEmailError),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,41 +50,47 @@ pub struct EmailTemplate {
     pub text_body: Option<String>,
 }
 
+/// Unrendered subject and bodies, either loaded from `email_templates` or a draft.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateSource {
+    pub subject: String,
+    pub html_body: String,
+    pub text_body: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct EmailConfig {
-    pub smtp_server: String,
-    pub smtp_port: u16,
-    pub username: String,
-    pub password: String,
     pub from_email: String,
     pub from_name: String,
     pub base_url: String, // For generating verification links
+    pub max_attempts: i32,
+    /// Signs unsubscribe links; falls back to `JWT_SECRET`.
+    pub unsubscribe_secret: String,
 }
 
 impl EmailConfig {
     pub fn from_env() -> Result<Self, EmailError> {
         Ok(Self {
-            smtp_server: env::var("SMTP_SERVER")
-                .map_err(|_| EmailError::Config("SMTP_SERVER not set".to_string()))?,
-            smtp_port: env::var("SMTP_PORT")
-                .unwrap_or_else(|_| "587".to_string())
-                .parse()
-                .map_err(|_| EmailError::Config("Invalid SMTP_PORT".to_string()))?,
-            username: env::var("SMTP_USERNAME")
-                .map_err(|_| EmailError::Config("SMTP_USERNAME not set".to_string()))?,
-            password: env::var("SMTP_PASSWORD")
-                .map_err(|_| EmailError::Config("SMTP_PASSWORD not set".to_string()))?,
             from_email: env::var("FROM_EMAIL")
                 .map_err(|_| EmailError::Config("FROM_EMAIL not set".to_string()))?,
             from_name: env::var("FROM_NAME").unwrap_or_else(|_| "Portal".to_string()),
             base_url: env::var("BASE_URL")
                 .map_err(|_| EmailError::Config("BASE_URL not set".to_string()))?,
+            max_attempts: env::var("EMAIL_MAX_ATTEMPTS")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse::<i32>()
+                .map_err(|_| EmailError::Config("Invalid EMAIL_MAX_ATTEMPTS".to_string()))?
+                .max(1),
+            unsubscribe_secret: env::var("UNSUBSCRIBE_SECRET")
+                .or_else(|_| env::var("JWT_SECRET"))
+                .map_err(|_| EmailError::Config("UNSUBSCRIBE_SECRET not set".to_string()))?,
         })
     }
 }
 
+/// Renders emails and writes them to the outbox. Delivery happens in
+/// `services::email_outbox::OutboxDispatcher`.
 pub struct EmailService {
-    mailer: SmtpTransport,
     config: EmailConfig,
 }
 
@@ -71,194 +98,322 @@ impl EmailService {
     pub fn new() -> Result<Self, EmailError> {
         let config = EmailConfig::from_env()?;
 
-        let creds = Credentials::new(config.username.clone(), config.password.clone());
-
-        let mailer = SmtpTransport::relay(&config.smtp_server)
-            .map_err(|e| EmailError::Config(format!("SMTP relay error: {}", e)))?
-            .port(config.smtp_port)
-            .credentials(creds)
-            .build();
+        Ok(Self { config })
+    }
 
-        Ok(Self { mailer, config })
+    /// Queues an email on `conn`, so it is only sent if the surrounding transaction commits.
+    pub async fn queue(
+        &self,
+        conn: &mut PgConnection,
+        to_email: &str,
+        to_name: Option<&str>,
+        template: EmailTemplate,
+    ) -> Result<OutboxEmail, EmailError> {
+        self.queue_with_attachments(conn, to_email, to_name, template, Vec::new())
+            .await
     }
 
-    pub fn send_email(
+    pub async fn queue_with_attachments(
         &self,
+        conn: &mut PgConnection,
         to_email: &str,
         to_name: Option<&str>,
         template: EmailTemplate,
-    ) -> Result<(), EmailError> {
-        let to_address = match to_name {
-            Some(name) => format!("{} <{}>", name, to_email),
-            None => to_email.to_string(),
+        attachments: Vec<OutboxAttachment>,
+    ) -> Result<OutboxEmail, EmailError> {
+        // Reject bad addresses now rather than on every delivery attempt
+        to_email.parse::<lettre::Address>()?;
+
+        let email = OutboxEmail::queue(
+            conn,
+            NewOutboxEmail {
+                to_email: to_email.to_string(),
+                to_name: to_name.map(str::to_string),
+                subject: template.subject,
+                html_body: template.html_body,
+                text_body: template.text_body,
+                attachments,
+                max_attempts: self.config.max_attempts,
+            },
+        )
+        .await?;
+
+        info!("Queued email {} to: {}", email.id, to_email);
+
+        Ok(email)
+    }
+
+    /// Renders the active version of template `name` inside the active layout.
+    pub async fn render(
+        &self,
+        conn: &mut PgConnection,
+        name: &str,
+        context: Value,
+    ) -> Result<EmailTemplate, EmailError> {
+        let layout = EmailTemplateVersion::find_active(&mut *conn, LAYOUT_TEMPLATE).await?;
+        let template = EmailTemplateVersion::find_active(&mut *conn, name).await?;
+
+        self.render_source(
+            &layout.html_body,
+            &TemplateSource {
+                subject: template.subject,
+                html_body: template.html_body,
+                text_body: template.text_body,
+            },
+            context,
+        )
+    }
+
+    /// Renders `template` inside `layout` without touching the database, so unsaved edits
+    /// can be previewed and validated.
+    pub fn render_source(
+        &self,
+        layout: &str,
+        template: &TemplateSource,
+        context: Value,
+    ) -> Result<EmailTemplate, EmailError> {
+        let mut context = Context::from_value(context).map_err(template_error)?;
+        context.insert("app_name", &self.config.from_name);
+        context.insert("base_url", &self.config.base_url);
+        context.insert("year", &Utc::now().year());
+
+        let subject = Tera::one_off(&template.subject, &context, false)
+            .map_err(template_error)?
+            .trim()
+            .to_string();
+        context.insert("subject", &subject);
+
+        // The .html suffix turns on autoescaping for both templates
+        let mut tera = Tera::default();
+        tera.add_raw_templates(vec![
+            ("layout.html", layout),
+            ("email.html", template.html_body.as_str()),
+        ])
+        .map_err(template_error)?;
+        let html_body = tera
+            .render("email.html", &context)
+            .map_err(template_error)?;
+
+        let text_body = match &template.text_body {
+            Some(text_body) => Tera::one_off(text_body, &context, false).map_err(template_error)?,
+            // Links are left unwrapped so verification URLs stay usable
+            None => html2text::config::plain()
+                .no_link_wrapping()
+                .string_from_read(html_body.as_bytes(), TEXT_WIDTH)
+                .map_err(|e| EmailError::Template(e.to_string()))?,
         };
 
-        let from_address = format!("{} <{}>", self.config.from_name, self.config.from_email);
-
-        let mut message_builder = Message::builder()
-            .from(from_address.parse()?)
-            .to(to_address.parse()?)
-            .subject(&template.subject);
-
-        let message = if let Some(text_body) = &template.text_body {
-            // Multipart email with both HTML and text
-            message_builder.multipart(
-                MultiPart::alternative()
-                    .singlepart(
-                        SinglePart::builder()
-                            .header(ContentType::TEXT_PLAIN)
-                            .body(text_body.clone()),
-                    )
-                    .singlepart(
-                        SinglePart::builder()
-                            .header(ContentType::TEXT_HTML)
-                            .body(template.html_body.clone()),
-                    ),
-            )?
-        } else {
-            // HTML only
-            message_builder
-                .header(ContentType::TEXT_HTML)
-                .body(template.html_body.clone())?
+        Ok(EmailTemplate {
+            subject,
+            html_body,
+            text_body: Some(text_body.trim().to_string()),
+        })
+    }
+
+    /// A link that takes `user_id` off `list` without logging in.
+    pub fn unsubscribe_link(&self, user_id: Uuid, list: &str) -> String {
+        let token = self
+            .unsubscribe_mac(user_id, list)
+            .map(|mac| hex::encode(mac.finalize().into_bytes()))
+            .unwrap_or_default();
+
+        format!(
+            "{}/unsubscribe?user={}&list={}&token={}",
+            self.config.base_url, user_id, list, token
+        )
+    }
+
+    pub fn verify_unsubscribe_token(&self, user_id: Uuid, list: &str, token: &str) -> bool {
+        let Ok(expected) = hex::decode(token) else {
+            return false;
+        };
+        let Some(mac) = self.unsubscribe_mac(user_id, list) else {
+            return false;
         };
 
-        info!("Sending email to: {}", to_email);
-        self.mailer.send(&message)?;
-        info!("Email sent successfully to: {}", to_email);
+        mac.verify_slice(&expected).is_ok()
+    }
 
-        Ok(())
+    fn unsubscribe_mac(&self, user_id: Uuid, list: &str) -> Option<HmacSha256> {
+        let mut mac = HmacSha256::new_from_slice(self.config.unsubscribe_secret.as_bytes()).ok()?;
+        mac.update(format!("{}:{}", user_id, list).as_bytes());
+        Some(mac)
     }
 
-    pub fn generate_verification_template(
+    pub async fn verification_email(
         &self,
+        conn: &mut PgConnection,
         user_name: &str,
         verification_code: &str,
-    ) -> EmailTemplate {
+    ) -> Result<EmailTemplate, EmailError> {
         let verification_link = format!(
             "{}/auth/verify-email?code={}",
             self.config.base_url, verification_code
         );
 
-        let html_body = format!(
-            r#"
-            <!DOCTYPE html>
-            <html>
-            <head>
-                <meta charset="utf-8">
-                <title>Email Verification</title>
-                <style>
-                    body {{ font-family: Arial, sans-serif; line-height: 1.6; color: #333; }}
-                    .container {{ max-width: 600px; margin: 0 auto; padding: 20px; }}
-                    .header {{ background-color: #4CAF50; color: white; padding: 20px; text-align: center; }}
-                    .content {{ padding: 20px; background-color: #f9f9f9; }}
-                    .button {{ 
-                        display: inline-block; 
-                        background-color: #4CAF50; 
-                        color: white; 
-                        padding: 12px 24px; 
-                        text-decoration: none; 
-                        border-radius: 5px; 
-                        margin: 20px 0;
-                    }}
-                    .footer {{ padding: 20px; text-align: center; color: #666; font-size: 12px; }}
-                </style>
-            </head>
-            <body>
-                <div class="container">
-                    <div class="header">
-                        <h1>Welcome to VOBA 014 Portal!</h1>
-                    </div>
-                    <div class="content">
-                        <h2>Hi {}!</h2>
-                        <p>Thank you for registering with VOBA 014 Portal. To complete your registration, please verify your email address by clicking the button below:</p>
-                        
-                        <div style="text-align: center;">
-                            <a href="{}" class="button">Verify Email Address</a>
-                        </div>
-                        
-                        <p>If the button above doesn't work, you can also copy and paste the following link into your browser:</p>
-                        <p style="word-break: break-all;"><a href="{}">{}</a></p>
-                        
-                        <p>This verification link will expire in 24 hours for security reasons.</p>
-                        
-                        <p>If you didn't create an account with Portal, please ignore this email.</p>
-                    </div>
-                    <div class="footer">
-                        <p>&copy; 2025 Portal. All rights reserved.</p>
-                    </div>
-                </div>
-            </body>
-            </html>
-            "#,
-            user_name, verification_link, verification_link, verification_link
-        );
+        self.render(
+            conn,
+            "verification",
+            json!({ "user_name": user_name, "verification_link": verification_link }),
+        )
+        .await
+    }
 
-        let text_body = format!(
-            r#"
-Welcome to Portal!
+    pub async fn welcome_email(
+        &self,
+        conn: &mut PgConnection,
+        user_name: &str,
+    ) -> Result<EmailTemplate, EmailError> {
+        self.render(conn, "welcome", json!({ "user_name": user_name }))
+            .await
+    }
 
-Hi {}!
+    pub async fn receipt_email(
+        &self,
+        conn: &mut PgConnection,
+        user_name: &str,
+        receipt_number: &str,
+        contribution_title: &str,
+        amount: &str,
+    ) -> Result<EmailTemplate, EmailError> {
+        self.render(
+            conn,
+            "receipt",
+            json!({
+                "user_name": user_name,
+                "receipt_number": receipt_number,
+                "contribution_title": contribution_title,
+                "amount": amount,
+            }),
+        )
+        .await
+    }
 
-Thank you for registering with Portal. To complete your registration, please verify your email address by visiting the following link:
+    pub async fn dues_reminder_email(
+        &self,
+        conn: &mut PgConnection,
+        user_name: &str,
+        contribution_title: &str,
+        amount: Option<&str>,
+        due_date: NaiveDate,
+        offset_days: i32,
+    ) -> Result<EmailTemplate, EmailError> {
+        self.render(
+            conn,
+            "dues_reminder",
+            json!({
+                "user_name": user_name,
+                "contribution_title": contribution_title,
+                "amount": amount,
+                "due_date": due_date.format("%d %B %Y").to_string(),
+                "offset_days": offset_days,
+                "days": offset_days.abs(),
+                "payments_link": format!("{}/payments", self.config.base_url),
+            }),
+        )
+        .await
+    }
 
-{}
+    pub async fn announcement_email(
+        &self,
+        conn: &mut PgConnection,
+        user_name: &str,
+        announcement: &Announcement,
+        attachment_count: usize,
+        posted_by: &str,
+        unsubscribe_link: &str,
+    ) -> Result<EmailTemplate, EmailError> {
+        self.render(
+            conn,
+            "announcement",
+            json!({
+                "user_name": user_name,
+                "title": announcement.title,
+                "body": announcement.body,
+                "body_html": announcement.body_html,
+                "attachment_count": attachment_count,
+                "posted_by": posted_by,
+                "announcement_link": format!("{}/announcements/{}", self.config.base_url, announcement.id),
+                "unsubscribe_link": unsubscribe_link,
+            }),
+        )
+        .await
+    }
 
-This verification link will expire in 24 hours for security reasons.
+    pub async fn notification_email(
+        &self,
+        conn: &mut PgConnection,
+        user_name: &str,
+        message: &ChannelMessage,
+    ) -> Result<EmailTemplate, EmailError> {
+        self.render(
+            conn,
+            "notification",
+            json!({
+                "user_name": user_name,
+                "title": message.title,
+                "body": message.body,
+                "link": message.link,
+            }),
+        )
+        .await
+    }
+}
 
-If you didn't create an account with Portal, please ignore this email.
+/// Sample data for previewing and validating each template. `None` for unknown names.
+pub fn sample_context(name: &str) -> Option<Value> {
+    let context = match name {
+        LAYOUT_TEMPLATE | "welcome" => json!({ "user_name": "Ada Obi" }),
+        "verification" => json!({
+            "user_name": "Ada Obi",
+            "verification_link": "https://example.com/auth/verify-email?code=sample",
+        }),
+        "receipt" => json!({
+            "user_name": "Ada Obi",
+            "receipt_number": "RCT-000042",
+            "contribution_title": "Annual Dues 2026",
+            "amount": "NGN 25,000.00",
+        }),
+        "dues_reminder" => json!({
+            "user_name": "Ada Obi",
+            "contribution_title": "Annual Dues 2026",
+            "amount": "NGN 25,000.00",
+            "due_date": "31 January 2026",
+            "offset_days": -7,
+            "days": 7,
+            "payments_link": "https://example.com/payments",
+        }),
+        "announcement" => json!({
+            "user_name": "Ada Obi",
+            "title": "Annual General Meeting",
+            "body": "The AGM holds on **Saturday** at the school hall.\n\nRefreshments will be served.",
+            "body_html": "<p>The AGM holds on <strong>Saturday</strong> at the school hall.</p>\n<p>Refreshments will be served.</p>\n",
+            "attachment_count": 1,
+            "posted_by": "Chinedu Eze",
+            "announcement_link": "https://example.com/announcements/sample",
+            "unsubscribe_link": "https://example.com/unsubscribe?token=sample",
+        }),
+        "notification" => json!({
+            "user_name": "Ada Obi",
+            "title": "Payment verified",
+            "body": "NGN 25,000.00 has been verified. Thank you!",
+            "link": "https://example.com/payments/sample",
+        }),
+        _ => return None,
+    };
 
-© 2025 Portal. All rights reserved.
-            "#,
-            user_name, verification_link
-        );
+    Some(context)
+}
 
-        EmailTemplate {
-            subject: "Verify your Portal account".to_string(),
-            html_body,
-            text_body: Some(text_body),
-        }
+/// Tera reports the useful detail (line, missing variable) in the error's sources.
+fn template_error(e: tera::Error) -> EmailError {
+    let mut message = e.to_string();
+    let mut source = std::error::Error::source(&e);
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
     }
 
-    pub fn generate_welcome_template(&self, user_name: &str) -> EmailTemplate {
-        let html_body = format!(
-            r#"
-            <!DOCTYPE html>
-            <html>
-            <head>
-                <meta charset="utf-8">
-                <title>Welcome to Portal</title>
-                <style>
-                    body {{ font-family: Arial, sans-serif; line-height: 1.6; color: #333; }}
-                    .container {{ max-width: 600px; margin: 0 auto; padding: 20px; }}
-                    .header {{ background-color: #4CAF50; color: white; padding: 20px; text-align: center; }}
-                    .content {{ padding: 20px; background-color: #f9f9f9; }}
-                </style>
-            </head>
-            <body>
-                <div class="container">
-                    <div class="header">
-                        <h1>Welcome to Portal!</h1>
-                    </div>
-                    <div class="content">
-                        <h2>Hi {}!</h2>
-                        <p>Your email has been successfully verified. Welcome to Portal!</p>
-                        <p>You can now access all features of your account.</p>
-                        <p>If you have any questions, feel free to contact our support team.</p>
-                    </div>
-                </div>
-            </body>
-            </html>
-            "#,
-            user_name
-        );
-
-        EmailTemplate {
-            subject: "Welcome to Portal - Email Verified!".to_string(),
-            html_body,
-            text_body: Some(format!(
-                "Hi {}!\n\nYour email has been successfully verified. Welcome to Portal!\n\nYou can now access all features of your account.",
-                user_name
-            )),
-        }
-    }
+    EmailError::Template(message)
 }
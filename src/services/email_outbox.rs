@@ -0,0 +1,268 @@
+use crate::database::connection::DbPool;
+use crate::models::email_outbox::{OutboxAttachment, OutboxEmail, OutboxEmailError};
+use crate::services::email::{EmailConfig, EmailError};
+use crate::services::jobs::backoff;
+use chrono::{Duration, Utc};
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Attachment, MultiPart, SinglePart, header::ContentType},
+    transport::smtp::authentication::Credentials,
+};
+use std::env;
+use std::path::PathBuf;
+use std::str::FromStr;
+use tracing::{error, info, warn};
+
+/// Where queued emails are delivered, selected with `EMAIL_TRANSPORT`.
+pub enum EmailTransport {
+    Smtp(AsyncSmtpTransport<Tokio1Executor>),
+    /// Writes each message as an `.eml` file, for development and tests.
+    File(PathBuf),
+    /// Only logs the message.
+    Stub,
+}
+
+impl EmailTransport {
+    pub fn from_env() -> Result<Self, EmailError> {
+        let transport = env::var("EMAIL_TRANSPORT").unwrap_or_else(|_| "smtp".to_string());
+
+        match transport.as_str() {
+            "smtp" => {
+                let server = env::var("SMTP_SERVER")
+                    .map_err(|_| EmailError::Config("SMTP_SERVER not set".to_string()))?;
+                let port = env::var("SMTP_PORT")
+                    .unwrap_or_else(|_| "587".to_string())
+                    .parse()
+                    .map_err(|_| EmailError::Config("Invalid SMTP_PORT".to_string()))?;
+                let username = env::var("SMTP_USERNAME")
+                    .map_err(|_| EmailError::Config("SMTP_USERNAME not set".to_string()))?;
+                let password = env::var("SMTP_PASSWORD")
+                    .map_err(|_| EmailError::Config("SMTP_PASSWORD not set".to_string()))?;
+
+                let mailer = AsyncSmtpTransport::<Tokio1Executor>::relay(&server)
+                    .map_err(|e| EmailError::Config(format!("SMTP relay error: {}", e)))?
+                    .port(port)
+                    .credentials(Credentials::new(username, password))
+                    .build();
+
+                Ok(EmailTransport::Smtp(mailer))
+            }
+            "file" => Ok(EmailTransport::File(PathBuf::from(
+                env::var("EMAIL_FILE_DIR").unwrap_or_else(|_| "mail".to_string()),
+            ))),
+            "stub" => Ok(EmailTransport::Stub),
+            other => Err(EmailError::Config(format!(
+                "Unknown EMAIL_TRANSPORT '{}'. Valid values: smtp, file, stub",
+                other
+            ))),
+        }
+    }
+
+    async fn send(&self, email: &OutboxEmail, message: Message) -> Result<(), EmailError> {
+        match self {
+            EmailTransport::Smtp(mailer) => {
+                mailer.send(message).await?;
+            }
+            EmailTransport::File(dir) => {
+                tokio::fs::create_dir_all(dir).await?;
+                tokio::fs::write(dir.join(format!("{}.eml", email.id)), message.formatted())
+                    .await?;
+            }
+            EmailTransport::Stub => {
+                info!(
+                    "Stub transport dropped email {} to {}: {}",
+                    email.id, email.to_email, email.subject
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OutboxDispatcherConfig {
+    pub batch_size: i64,
+    pub poll_interval_secs: u64,
+    pub lock_timeout_secs: i64,
+}
+
+impl OutboxDispatcherConfig {
+    pub fn from_env() -> Self {
+        fn parse<T: FromStr>(key: &str, default: T) -> T {
+            env::var(key)
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(default)
+        }
+
+        Self {
+            batch_size: parse("EMAIL_OUTBOX_BATCH_SIZE", 20).max(1),
+            poll_interval_secs: parse("EMAIL_OUTBOX_POLL_INTERVAL_SECS", 5).max(1),
+            lock_timeout_secs: parse("EMAIL_OUTBOX_LOCK_TIMEOUT_SECS", 300),
+        }
+    }
+}
+
+/// Delivers queued emails, retrying failures with backoff until `max_attempts` is reached.
+pub struct OutboxDispatcher {
+    pool: DbPool,
+    transport: EmailTransport,
+    email_config: EmailConfig,
+    config: OutboxDispatcherConfig,
+}
+
+impl OutboxDispatcher {
+    pub fn new(pool: DbPool) -> Result<Self, EmailError> {
+        Ok(Self {
+            pool,
+            transport: EmailTransport::from_env()?,
+            email_config: EmailConfig::from_env()?,
+            config: OutboxDispatcherConfig::from_env(),
+        })
+    }
+
+    /// Spawns the delivery loop onto the tokio runtime.
+    pub fn start(self) {
+        tokio::spawn(async move { self.run().await });
+    }
+
+    async fn run(&self) {
+        let idle = std::time::Duration::from_secs(self.config.poll_interval_secs);
+
+        loop {
+            match self.deliver_batch().await {
+                // A full batch suggests more is waiting, so go again straight away
+                Ok(delivered) if delivered as i64 >= self.config.batch_size => {}
+                Ok(_) => tokio::time::sleep(idle).await,
+                Err(e) => {
+                    error!("Failed to claim outbox emails: {}", e);
+                    tokio::time::sleep(idle).await;
+                }
+            }
+        }
+    }
+
+    async fn deliver_batch(&self) -> Result<usize, OutboxEmailError> {
+        let stale_before = Utc::now() - Duration::seconds(self.config.lock_timeout_secs);
+        let emails =
+            OutboxEmail::claim_batch(&self.pool, self.config.batch_size, stale_before).await?;
+        let count = emails.len();
+
+        for email in emails {
+            self.deliver(email).await;
+        }
+
+        Ok(count)
+    }
+
+    async fn deliver(&self, email: OutboxEmail) {
+        let result = match OutboxEmail::find_attachments(&self.pool, email.id).await {
+            Ok(attachments) => match build_message(&self.email_config, &email, attachments) {
+                Ok(message) => self.transport.send(&email, message).await,
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e.into()),
+        };
+
+        let outcome = match result {
+            Ok(()) => {
+                info!("Email {} sent to: {}", email.id, email.to_email);
+                OutboxEmail::mark_sent(&self.pool, email.id).await
+            }
+            Err(e) => {
+                let retry_at = (email.attempts < email.max_attempts)
+                    .then(|| Utc::now() + backoff(email.attempts));
+                match retry_at {
+                    Some(at) => warn!(
+                        "Email {} to {} failed on attempt {}, retrying at {}: {}",
+                        email.id, email.to_email, email.attempts, at, e
+                    ),
+                    None => error!(
+                        "Email {} to {} failed on attempt {} and was given up: {}",
+                        email.id, email.to_email, email.attempts, e
+                    ),
+                }
+                OutboxEmail::mark_failed(&self.pool, email.id, &e.to_string(), retry_at).await
+            }
+        };
+
+        if let Err(e) = outcome {
+            error!("Failed to record delivery of email {}: {}", email.id, e);
+        }
+    }
+}
+
+fn build_message(
+    config: &EmailConfig,
+    email: &OutboxEmail,
+    attachments: Vec<OutboxAttachment>,
+) -> Result<Message, EmailError> {
+    let to_address = match &email.to_name {
+        Some(name) => format!("{} <{}>", name, email.to_email),
+        None => email.to_email.clone(),
+    };
+
+    let from_address = format!("{} <{}>", config.from_name, config.from_email);
+
+    let message_builder = Message::builder()
+        .from(from_address.parse()?)
+        .to(to_address.parse()?)
+        .subject(&email.subject);
+
+    let message = if !attachments.is_empty() {
+        // Mixed multipart: the message body followed by each attachment
+        let body = match &email.text_body {
+            Some(text_body) => MultiPart::alternative()
+                .singlepart(
+                    SinglePart::builder()
+                        .header(ContentType::TEXT_PLAIN)
+                        .body(text_body.clone()),
+                )
+                .singlepart(
+                    SinglePart::builder()
+                        .header(ContentType::TEXT_HTML)
+                        .body(email.html_body.clone()),
+                ),
+            None => MultiPart::alternative().singlepart(
+                SinglePart::builder()
+                    .header(ContentType::TEXT_HTML)
+                    .body(email.html_body.clone()),
+            ),
+        };
+
+        let mut mixed = MultiPart::mixed().multipart(body);
+        for attachment in attachments {
+            let content_type = ContentType::parse(&attachment.content_type).map_err(|e| {
+                EmailError::Config(format!("Invalid attachment content type: {}", e))
+            })?;
+            mixed = mixed.singlepart(
+                Attachment::new(attachment.filename).body(attachment.content, content_type),
+            );
+        }
+
+        message_builder.multipart(mixed)?
+    } else if let Some(text_body) = &email.text_body {
+        // Multipart email with both HTML and text
+        message_builder.multipart(
+            MultiPart::alternative()
+                .singlepart(
+                    SinglePart::builder()
+                        .header(ContentType::TEXT_PLAIN)
+                        .body(text_body.clone()),
+                )
+                .singlepart(
+                    SinglePart::builder()
+                        .header(ContentType::TEXT_HTML)
+                        .body(email.html_body.clone()),
+                ),
+        )?
+    } else {
+        // HTML only
+        message_builder
+            .header(ContentType::TEXT_HTML)
+            .body(email.html_body.clone())?
+    };
+
+    Ok(message)
+}
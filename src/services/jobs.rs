@@ -0,0 +1,392 @@
+use crate::database::connection::DbPool;
+use crate::models::job::{CreateJob, Job, JobError, JobSchedule};
+use crate::models::notification_channel::ChannelKind;
+use crate::models::receipt::ReceiptError;
+use crate::services::announcement_broadcast::{
+    AnnouncementBroadcastError, AnnouncementBroadcastService,
+};
+use crate::services::dues_reminder::{DuesReminderService, DuesReminderServiceError};
+use crate::services::notification_channels::{self, ChannelError};
+use crate::services::notifications::{self, NotificationFailure};
+use crate::services::receipt::ReceiptService;
+use chrono::{DateTime, Duration, Utc};
+use cron::Schedule;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::str::FromStr;
+use std::sync::LazyLock;
+use thiserror::Error;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Work that runs outside the request cycle. Serialized into `jobs.payload`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobKind {
+    IssueReceipt {
+        payment_id: Uuid,
+    },
+    DuesReminders,
+    BroadcastAnnouncement {
+        announcement_id: Uuid,
+    },
+    NotifyAnnouncement {
+        announcement_id: Uuid,
+    },
+    EventReminders,
+    DeliverNotification {
+        notification_id: Uuid,
+        channel: ChannelKind,
+    },
+}
+
+impl JobKind {
+    pub fn job_type(&self) -> &'static str {
+        match self {
+            JobKind::IssueReceipt { .. } => "issue_receipt",
+            JobKind::DuesReminders => "dues_reminders",
+            JobKind::BroadcastAnnouncement { .. } => "broadcast_announcement",
+            JobKind::NotifyAnnouncement { .. } => "notify_announcement",
+            JobKind::EventReminders => "event_reminders",
+            JobKind::DeliverNotification { .. } => "deliver_notification",
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum JobFailure {
+    #[error("Invalid job payload: {0}")]
+    Payload(#[from] serde_json::Error),
+    #[error(transparent)]
+    Receipt(#[from] ReceiptError),
+    #[error(transparent)]
+    DuesReminders(#[from] DuesReminderServiceError),
+    #[error(transparent)]
+    Broadcast(#[from] AnnouncementBroadcastError),
+    #[error(transparent)]
+    Notification(#[from] NotificationFailure),
+    #[error(transparent)]
+    Channel(#[from] ChannelError),
+    #[error("Job panicked: {0}")]
+    Panicked(String),
+}
+
+impl JobFailure {
+    /// A payload that cannot be decoded will never succeed, so it is dead-lettered at once,
+    /// as is a delivery that lacks configuration or a phone number.
+    fn is_retryable(&self) -> bool {
+        match self {
+            JobFailure::Payload(_) => false,
+            JobFailure::Channel(e) => e.is_retryable(),
+            _ => true,
+        }
+    }
+}
+
+fn parse_env<T: FromStr>(key: &str, default: T) -> T {
+    env::var(key)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Settings for queueing jobs, read once on first use.
+#[derive(Debug, Clone)]
+pub struct JobQueueConfig {
+    /// Attempts before a failing job is dead-lettered.
+    pub max_attempts: i32,
+}
+
+impl JobQueueConfig {
+    pub fn from_env() -> Self {
+        Self {
+            max_attempts: parse_env("JOB_MAX_ATTEMPTS", 5).max(1),
+        }
+    }
+}
+
+static QUEUE_CONFIG: LazyLock<JobQueueConfig> = LazyLock::new(JobQueueConfig::from_env);
+
+#[derive(Debug, Clone)]
+pub struct JobRunnerConfig {
+    pub workers: usize,
+    pub poll_interval_secs: u64,
+    pub lock_timeout_secs: i64,
+    pub dues_reminder_schedule: String,
+    pub event_reminder_schedule: String,
+}
+
+impl JobRunnerConfig {
+    pub fn from_env() -> Self {
+        Self {
+            workers: parse_env("JOB_WORKERS", 2).max(1),
+            poll_interval_secs: parse_env("JOB_POLL_INTERVAL_SECS", 5).max(1),
+            lock_timeout_secs: parse_env("JOB_LOCK_TIMEOUT_SECS", 600),
+            dues_reminder_schedule: env::var("DUES_REMINDER_SCHEDULE")
+                .unwrap_or_else(|_| "0 0 * * * *".to_string()),
+            event_reminder_schedule: env::var("EVENT_REMINDER_SCHEDULE")
+                .unwrap_or_else(|_| "0 */15 * * * *".to_string()),
+        }
+    }
+}
+
+/// Queues `kind` to run as soon as a worker is free.
+pub async fn enqueue(pool: &DbPool, kind: JobKind) -> Result<Job, JobError> {
+    enqueue_at(pool, kind, Utc::now()).await
+}
+
+/// Queues `kind` to run once `run_at` has passed.
+pub async fn enqueue_at(
+    pool: &DbPool,
+    kind: JobKind,
+    run_at: DateTime<Utc>,
+) -> Result<Job, JobError> {
+    Job::enqueue(pool, create_job(&kind, run_at, QUEUE_CONFIG.max_attempts)).await
+}
+
+/// Queues `kind`, logging rather than surfacing a failure so it never undoes the caller's work.
+pub async fn enqueue_logged(pool: &DbPool, kind: JobKind) {
+    if let Err(e) = enqueue(pool, kind.clone()).await {
+        error!("Failed to enqueue {} job: {}", kind.job_type(), e);
+    }
+}
+
+fn create_job(kind: &JobKind, run_at: DateTime<Utc>, max_attempts: i32) -> CreateJob {
+    CreateJob {
+        job_type: kind.job_type().to_string(),
+        payload: serde_json::to_value(kind).unwrap_or_default(),
+        run_at,
+        max_attempts,
+    }
+}
+
+/// Exponential backoff from 30 seconds, capped at an hour.
+pub fn backoff(attempts: i32) -> Duration {
+    let exponent = attempts.clamp(1, 8) as u32 - 1;
+    Duration::seconds((30 * 2_i64.pow(exponent)).min(3600))
+}
+
+async fn execute(pool: DbPool, kind: JobKind) -> Result<(), JobFailure> {
+    match kind {
+        JobKind::IssueReceipt { payment_id } => {
+            ReceiptService::from_env()
+                .issue_and_send(&pool, payment_id)
+                .await?;
+        }
+        JobKind::DuesReminders => {
+            let queued = DuesReminderService::from_env().run_once(&pool).await?;
+            if queued > 0 {
+                info!("Queued {} dues reminders", queued);
+            }
+        }
+        JobKind::BroadcastAnnouncement { announcement_id } => {
+            AnnouncementBroadcastService::from_env()
+                .run(&pool, announcement_id)
+                .await?;
+        }
+        JobKind::NotifyAnnouncement { announcement_id } => {
+            let notified = notifications::notify_announcement(&pool, announcement_id).await?;
+            if notified > 0 {
+                info!(
+                    "Notified {} members of announcement {}",
+                    notified, announcement_id
+                );
+            }
+        }
+        JobKind::EventReminders => {
+            let reminded = notifications::send_event_reminders(&pool).await?;
+            if reminded > 0 {
+                info!("Sent {} event reminders", reminded);
+            }
+        }
+        JobKind::DeliverNotification {
+            notification_id,
+            channel,
+        } => {
+            notification_channels::deliver(&pool, notification_id, channel).await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub struct JobRunner {
+    pool: DbPool,
+    config: JobRunnerConfig,
+}
+
+impl JobRunner {
+    pub fn new(pool: DbPool) -> Self {
+        Self {
+            pool,
+            config: JobRunnerConfig::from_env(),
+        }
+    }
+
+    /// Spawns the workers and the scheduler onto the tokio runtime.
+    pub fn start(self) {
+        let runner = std::sync::Arc::new(self);
+
+        for index in 0..runner.config.workers {
+            let runner = runner.clone();
+            let worker = format!("worker-{}-{}", std::process::id(), index);
+            tokio::spawn(async move { runner.work(worker).await });
+        }
+
+        tokio::spawn(async move { runner.schedule().await });
+    }
+
+    async fn work(&self, worker: String) {
+        let idle = std::time::Duration::from_secs(self.config.poll_interval_secs);
+
+        loop {
+            let stale_before = Utc::now() - Duration::seconds(self.config.lock_timeout_secs);
+            match Job::claim(&self.pool, &worker, stale_before).await {
+                Ok(Some(job)) => self.process(job, &worker).await,
+                Ok(None) => tokio::time::sleep(idle).await,
+                Err(e) => {
+                    error!("Worker {} failed to claim a job: {}", worker, e);
+                    tokio::time::sleep(idle).await;
+                }
+            }
+        }
+    }
+
+    async fn process(&self, job: Job, worker: &str) {
+        let result = match serde_json::from_value::<JobKind>(job.payload.clone()) {
+            // Run in its own task so a panic fails the job instead of killing the worker.
+            Ok(kind) => tokio::spawn(execute(self.pool.clone(), kind))
+                .await
+                .unwrap_or_else(|e| Err(JobFailure::Panicked(e.to_string()))),
+            Err(e) => Err(JobFailure::Payload(e)),
+        };
+
+        let outcome = match result {
+            Ok(()) => Job::complete(&self.pool, job.id, worker).await,
+            Err(e) => {
+                let retry_at = (e.is_retryable() && job.attempts < job.max_attempts)
+                    .then(|| Utc::now() + backoff(job.attempts));
+                match retry_at {
+                    Some(at) => warn!(
+                        "Job {} ({}) failed on attempt {}, retrying at {}: {}",
+                        job.id, job.job_type, job.attempts, at, e
+                    ),
+                    None => error!(
+                        "Job {} ({}) failed on attempt {} and was dead-lettered: {}",
+                        job.id, job.job_type, job.attempts, e
+                    ),
+                }
+                Job::fail(&self.pool, job.id, worker, &e.to_string(), retry_at).await
+            }
+        };
+
+        match outcome {
+            Ok(()) => {}
+            // Ran past the lock timeout and was claimed again; the new run records the outcome
+            Err(JobError::LockLost { .. }) => warn!(
+                "Job {} ({}) was taken over by another worker, discarding this run's outcome",
+                job.id, job.job_type
+            ),
+            Err(e) => error!("Failed to record outcome of job {}: {}", job.id, e),
+        }
+    }
+
+    fn recurring(&self) -> Vec<(&'static str, String, JobKind)> {
+        vec![
+            (
+                "dues_reminders",
+                self.config.dues_reminder_schedule.clone(),
+                JobKind::DuesReminders,
+            ),
+            (
+                "event_reminders",
+                self.config.event_reminder_schedule.clone(),
+                JobKind::EventReminders,
+            ),
+        ]
+    }
+
+    async fn schedule(&self) {
+        let mut schedules = Vec::new();
+        for (name, expression, kind) in self.recurring() {
+            let schedule = match Schedule::from_str(&expression) {
+                Ok(schedule) => schedule,
+                Err(e) => {
+                    error!("Invalid cron expression for {}: {}", name, e);
+                    continue;
+                }
+            };
+            let Some(next) = schedule.upcoming(Utc).next() else {
+                continue;
+            };
+            if let Err(e) = JobSchedule::register(&self.pool, name, &expression, next).await {
+                error!("Failed to register schedule {}: {}", name, e);
+                continue;
+            }
+            schedules.push((name, schedule, kind));
+        }
+
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+            self.config.poll_interval_secs,
+        ));
+        loop {
+            interval.tick().await;
+            for (name, schedule, kind) in &schedules {
+                let Some(next) = schedule.upcoming(Utc).next() else {
+                    continue;
+                };
+                let job = create_job(kind, Utc::now(), QUEUE_CONFIG.max_attempts);
+                match JobSchedule::enqueue_if_due(&self.pool, name, next, job).await {
+                    Ok(Some(job)) => info!("Enqueued scheduled {} job {}", name, job.id),
+                    Ok(None) => {}
+                    Err(e) => error!("Failed to enqueue scheduled {} job: {}", name, e),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_an_hour() {
+        assert_eq!(backoff(0), Duration::seconds(30));
+        assert_eq!(backoff(1), Duration::seconds(30));
+        assert_eq!(backoff(2), Duration::seconds(60));
+        assert_eq!(backoff(5), Duration::seconds(480));
+        assert_eq!(backoff(7), Duration::seconds(1920));
+        assert_eq!(backoff(8), Duration::seconds(3600));
+        assert_eq!(backoff(50), Duration::seconds(3600));
+    }
+
+    #[test]
+    fn payload_is_tagged_with_the_job_type() {
+        let id = Uuid::new_v4();
+        for kind in [
+            JobKind::IssueReceipt { payment_id: id },
+            JobKind::DuesReminders,
+            JobKind::NotifyAnnouncement {
+                announcement_id: id,
+            },
+            JobKind::EventReminders,
+            JobKind::DeliverNotification {
+                notification_id: id,
+                channel: ChannelKind::Sms,
+            },
+        ] {
+            let job = create_job(&kind, Utc::now(), 5);
+            assert_eq!(job.payload["type"], job.job_type);
+            let decoded: JobKind = serde_json::from_value(job.payload).unwrap();
+            assert_eq!(decoded.job_type(), kind.job_type());
+        }
+    }
+
+    #[test]
+    fn undecodable_payloads_are_not_retried() {
+        let error = serde_json::from_str::<JobKind>("{\"type\":\"unknown\"}").unwrap_err();
+        assert!(!JobFailure::Payload(error).is_retryable());
+        assert!(JobFailure::Panicked("boom".to_string()).is_retryable());
+    }
+}
@@ -1,2 +1,15 @@
+pub mod announcement_broadcast;
+pub mod audit;
 pub mod auth;
+pub mod dues_reminder;
 pub mod email;
+pub mod email_outbox;
+pub mod jobs;
+pub mod notification_channels;
+pub mod notifications;
+pub mod payment_gateway;
+pub mod realtime;
+pub mod receipt;
+pub mod reconciliation;
+pub mod report_export;
+pub mod storage;
@@ -0,0 +1,503 @@
+use crate::database::connection::DbPool;
+use crate::models::job::JobError;
+use crate::models::notification::{Notification, NotificationError};
+use crate::models::notification_channel::{
+    ChannelKind, ChannelSubscription, NotificationChannelError, NotificationDelivery,
+};
+use crate::models::user::{User, UserError};
+use crate::services::email::{EmailError, EmailService};
+use crate::services::jobs::{self, JobKind};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use std::env;
+use thiserror::Error;
+use tracing::{error, info};
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum ChannelError {
+    #[error("Notification channel configuration error: {0}")]
+    Config(String),
+    #[error("User {user_id} has no valid phone number for {channel:?}")]
+    NoPhone { user_id: Uuid, channel: ChannelKind },
+    #[error("Messaging provider request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Messaging provider rejected the message: {0}")]
+    Rejected(String),
+    #[error(transparent)]
+    Email(#[from] EmailError),
+    #[error(transparent)]
+    Channel(#[from] NotificationChannelError),
+    #[error(transparent)]
+    Notification(#[from] NotificationError),
+    #[error(transparent)]
+    User(#[from] UserError),
+    #[error(transparent)]
+    Job(#[from] JobError),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+impl ChannelError {
+    /// Retrying cannot fix missing configuration or a missing phone number.
+    pub fn is_retryable(&self) -> bool {
+        !matches!(self, ChannelError::Config(_) | ChannelError::NoPhone { .. })
+    }
+}
+
+/// A notification as sent outside the portal, with its link made absolute against the portal
+/// so members land on the page rather than the API.
+#[derive(Debug, Clone)]
+pub struct ChannelMessage {
+    pub title: String,
+    pub body: Option<String>,
+    pub link: Option<String>,
+}
+
+impl ChannelMessage {
+    pub fn from_notification(notification: &Notification, portal_url: &str) -> Self {
+        Self {
+            title: notification.title.clone(),
+            body: notification.body.clone(),
+            link: notification
+                .link
+                .as_ref()
+                .map(|link| format!("{}{}", portal_url.trim_end_matches('/'), link)),
+        }
+    }
+
+    /// Plain text for SMS and messaging apps: title, body and link on their own lines.
+    pub fn text(&self) -> String {
+        [Some(&self.title), self.body.as_ref(), self.link.as_ref()]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Somewhere notifications can be delivered besides the in-app centre.
+pub trait NotificationChannel: Send + Sync {
+    fn kind(&self) -> ChannelKind;
+
+    /// Sends `message` to `user`, returning the provider's reference for it if there is one.
+    /// Anything the channel writes goes through `conn`, the transaction that records the
+    /// delivery.
+    fn send<'a>(
+        &'a self,
+        conn: &'a mut PgConnection,
+        user: &'a User,
+        message: &'a ChannelMessage,
+    ) -> BoxFuture<'a, Result<Option<String>, ChannelError>>;
+}
+
+/// Queues the `notification` email template through the outbox.
+pub struct EmailChannel {
+    email: EmailService,
+}
+
+impl EmailChannel {
+    pub fn new() -> Result<Self, ChannelError> {
+        Ok(Self {
+            email: EmailService::new()?,
+        })
+    }
+}
+
+impl NotificationChannel for EmailChannel {
+    fn kind(&self) -> ChannelKind {
+        ChannelKind::Email
+    }
+
+    fn send<'a>(
+        &'a self,
+        conn: &'a mut PgConnection,
+        user: &'a User,
+        message: &'a ChannelMessage,
+    ) -> BoxFuture<'a, Result<Option<String>, ChannelError>> {
+        Box::pin(async move {
+            let template = self
+                .email
+                .notification_email(conn, &user.fullname, message)
+                .await?;
+            let email = self
+                .email
+                .queue(conn, &user.email, Some(&user.fullname), template)
+                .await?;
+            Ok(Some(email.id.to_string()))
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MessagingProviderConfig {
+    pub base_url: String, // Point at `cargo run --example mock_messaging_provider` in development
+    pub api_key: String,
+    pub sender: String,
+}
+
+impl MessagingProviderConfig {
+    /// Reads `{prefix}_PROVIDER_BASE_URL`, `{prefix}_PROVIDER_API_KEY` and `{prefix}_SENDER`.
+    pub fn from_env(prefix: &str) -> Result<Self, ChannelError> {
+        let var = |name: &str| {
+            let key = format!("{}_{}", prefix, name);
+            env::var(&key).map_err(|_| ChannelError::Config(format!("{} not set", key)))
+        };
+
+        Ok(Self {
+            base_url: var("PROVIDER_BASE_URL")?,
+            api_key: var("PROVIDER_API_KEY")?,
+            sender: var("SENDER")?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct OutboundMessage<'a> {
+    channel: &'a str,
+    from: &'a str,
+    to: &'a str,
+    text: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProviderResponse {
+    id: Option<String>,
+}
+
+/// The HTTP messaging API behind the SMS and messaging channels.
+pub struct MessagingProvider {
+    client: reqwest::Client,
+    config: MessagingProviderConfig,
+}
+
+impl MessagingProvider {
+    pub fn new(config: MessagingProviderConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            config,
+        }
+    }
+
+    async fn send(
+        &self,
+        channel: &str,
+        to: &str,
+        text: &str,
+    ) -> Result<Option<String>, ChannelError> {
+        let response = self
+            .client
+            .post(format!("{}/messages", self.config.base_url))
+            .bearer_auth(&self.config.api_key)
+            .json(&OutboundMessage {
+                channel,
+                from: &self.config.sender,
+                to,
+                text,
+            })
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let reason = response.text().await.unwrap_or_default();
+            return Err(ChannelError::Rejected(format!("{}: {}", status, reason)));
+        }
+
+        Ok(response.json::<ProviderResponse>().await?.id)
+    }
+}
+
+fn phone_for(user: &User, channel: ChannelKind) -> Result<String, ChannelError> {
+    user.phone
+        .as_deref()
+        .and_then(normalize_phone)
+        .ok_or(ChannelError::NoPhone {
+            user_id: user.id,
+            channel,
+        })
+}
+
+/// Text messages, configured with the `SMS_` variables.
+pub struct SmsChannel {
+    provider: MessagingProvider,
+}
+
+impl SmsChannel {
+    pub fn from_env() -> Result<Self, ChannelError> {
+        Ok(Self {
+            provider: MessagingProvider::new(MessagingProviderConfig::from_env("SMS")?),
+        })
+    }
+}
+
+impl NotificationChannel for SmsChannel {
+    fn kind(&self) -> ChannelKind {
+        ChannelKind::Sms
+    }
+
+    fn send<'a>(
+        &'a self,
+        _conn: &'a mut PgConnection,
+        user: &'a User,
+        message: &'a ChannelMessage,
+    ) -> BoxFuture<'a, Result<Option<String>, ChannelError>> {
+        Box::pin(async move {
+            let phone = phone_for(user, self.kind())?;
+            self.provider.send("sms", &phone, &message.text()).await
+        })
+    }
+}
+
+/// WhatsApp-style messaging apps, configured with the `MESSAGING_` variables.
+pub struct MessagingChannel {
+    provider: MessagingProvider,
+}
+
+impl MessagingChannel {
+    pub fn from_env() -> Result<Self, ChannelError> {
+        Ok(Self {
+            provider: MessagingProvider::new(MessagingProviderConfig::from_env("MESSAGING")?),
+        })
+    }
+}
+
+impl NotificationChannel for MessagingChannel {
+    fn kind(&self) -> ChannelKind {
+        ChannelKind::Messaging
+    }
+
+    fn send<'a>(
+        &'a self,
+        _conn: &'a mut PgConnection,
+        user: &'a User,
+        message: &'a ChannelMessage,
+    ) -> BoxFuture<'a, Result<Option<String>, ChannelError>> {
+        Box::pin(async move {
+            let phone = phone_for(user, self.kind())?;
+            self.provider
+                .send("messaging", &phone, &message.text())
+                .await
+        })
+    }
+}
+
+/// The configured implementation of `kind`.
+pub fn channel(kind: ChannelKind) -> Result<Box<dyn NotificationChannel>, ChannelError> {
+    Ok(match kind {
+        ChannelKind::Email => Box::new(EmailChannel::new()?),
+        ChannelKind::Sms => Box::new(SmsChannel::from_env()?),
+        ChannelKind::Messaging => Box::new(MessagingChannel::from_env()?),
+    })
+}
+
+/// Drops spaces, dashes, dots and brackets, accepting only international numbers such as
+/// +2348012345678.
+pub fn normalize_phone(phone: &str) -> Option<String> {
+    let phone: String = phone
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '.' | '(' | ')'))
+        .collect();
+    let digits = phone.strip_prefix('+')?;
+
+    (digits.len() >= 8
+        && digits.len() <= 15
+        && !digits.starts_with('0')
+        && digits.chars().all(|c| c.is_ascii_digit()))
+    .then_some(phone)
+}
+
+/// Queues a delivery for every channel each recipient has turned on.
+pub async fn dispatch(
+    pool: &DbPool,
+    notifications: &[Notification],
+) -> Result<usize, ChannelError> {
+    if notifications.is_empty() {
+        return Ok(0);
+    }
+
+    let user_ids: Vec<Uuid> = notifications.iter().map(|n| n.user_id).collect();
+    let subscriptions = ChannelSubscription::find_enabled(pool, &user_ids).await?;
+
+    let mut queued = 0;
+    for notification in notifications {
+        for subscription in subscriptions
+            .iter()
+            .filter(|subscription| subscription.user_id == notification.user_id)
+        {
+            jobs::enqueue(
+                pool,
+                JobKind::DeliverNotification {
+                    notification_id: notification.id,
+                    channel: subscription.channel,
+                },
+            )
+            .await?;
+            queued += 1;
+        }
+    }
+
+    Ok(queued)
+}
+
+/// Like `dispatch`, logging rather than surfacing a failure so it never undoes the caller's
+/// work.
+pub async fn dispatch_logged(pool: &DbPool, notifications: &[Notification]) {
+    if let Err(e) = dispatch(pool, notifications).await {
+        error!("Failed to queue notification deliveries: {}", e);
+    }
+}
+
+/// Sends one notification over one channel unless it already went out, returning whether
+/// anything was sent.
+pub async fn deliver(
+    pool: &DbPool,
+    notification_id: Uuid,
+    kind: ChannelKind,
+) -> Result<bool, ChannelError> {
+    if NotificationDelivery::exists(pool, notification_id, kind).await? {
+        return Ok(false);
+    }
+    let Some(notification) = Notification::find_by_id(pool, notification_id).await? else {
+        return Ok(false);
+    };
+    let Some(user) = User::find_by_id(pool, notification.user_id).await? else {
+        return Ok(false);
+    };
+    if !user.is_active {
+        return Ok(false);
+    }
+
+    let portal_url = env::var("PORTAL_URL")
+        .map_err(|_| ChannelError::Config("PORTAL_URL not set".to_string()))?;
+    let message = ChannelMessage::from_notification(&notification, &portal_url);
+
+    // A queued email and its delivery record commit together, so a retry never sends it twice
+    let mut tx = pool.begin().await?;
+    let reference = channel(kind)?.send(&mut tx, &user, &message).await?;
+    NotificationDelivery::record(&mut tx, notification_id, kind, reference).await?;
+    tx.commit().await?;
+
+    info!(
+        "Delivered notification {} to user {} by {:?}",
+        notification_id, user.id, kind
+    );
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::notification::NotificationKind;
+    use crate::models::user::UserRole;
+    use chrono::Utc;
+
+    fn user(phone: Option<&str>) -> User {
+        User {
+            id: Uuid::new_v4(),
+            fullname: "Ada Obi".to_string(),
+            email: "ada@example.com".to_string(),
+            password_hash: String::new(),
+            phone: phone.map(str::to_string),
+            dob: None,
+            photo_url: None,
+            user_role: UserRole::Member,
+            email_verification_code: None,
+            email_verification_expires_at: None,
+            is_email_verified: true,
+            is_active: true,
+            dues_reminders_enabled: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            deleted_at: None,
+        }
+    }
+
+    fn notification(body: Option<&str>, link: Option<&str>) -> Notification {
+        Notification {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            kind: NotificationKind::PaymentVerified,
+            title: "Payment verified".to_string(),
+            body: body.map(str::to_string),
+            link: link.map(str::to_string),
+            subject_id: None,
+            read_at: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn normalizes_international_numbers() {
+        assert_eq!(
+            normalize_phone("+234 801 234 5678").as_deref(),
+            Some("+2348012345678")
+        );
+        assert_eq!(
+            normalize_phone("+1 (415) 555-0100").as_deref(),
+            Some("+14155550100")
+        );
+        assert_eq!(
+            normalize_phone("+44.20.7946.0958").as_deref(),
+            Some("+442079460958")
+        );
+    }
+
+    #[test]
+    fn rejects_local_and_malformed_numbers() {
+        for phone in [
+            "08012345678",
+            "+08012345678",
+            "+1234567",
+            "+1234567890123456",
+            "+234801234567x",
+            "++2348012345678",
+            "",
+        ] {
+            assert_eq!(normalize_phone(phone), None, "{}", phone);
+        }
+    }
+
+    #[test]
+    fn phone_for_requires_a_valid_number() {
+        assert_eq!(
+            phone_for(&user(Some("+234 801 234 5678")), ChannelKind::Sms).unwrap(),
+            "+2348012345678"
+        );
+        for phone in [None, Some("0801 234 5678")] {
+            let error = phone_for(&user(phone), ChannelKind::Messaging).unwrap_err();
+            assert!(matches!(error, ChannelError::NoPhone { .. }));
+            assert!(!error.is_retryable());
+        }
+    }
+
+    #[test]
+    fn message_text_joins_title_body_and_absolute_link() {
+        let message = ChannelMessage::from_notification(
+            &notification(
+                Some("Your dues for March were verified."),
+                Some("/payments/1"),
+            ),
+            "https://portal.example.org/",
+        );
+
+        assert_eq!(
+            message.text(),
+            "Payment verified\nYour dues for March were verified.\nhttps://portal.example.org/payments/1"
+        );
+    }
+
+    #[test]
+    fn message_text_skips_missing_parts() {
+        let message = ChannelMessage::from_notification(
+            &notification(None, None),
+            "https://portal.example.org",
+        );
+
+        assert_eq!(message.link, None);
+        assert_eq!(message.text(), "Payment verified");
+    }
+}
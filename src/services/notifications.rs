@@ -0,0 +1,169 @@
+use crate::database::connection::DbPool;
+use crate::models::announcement::{Announcement, AnnouncementError};
+use crate::models::job::JobError;
+use crate::models::notification::{
+    NewNotification, Notification, NotificationError, NotificationKind,
+};
+use crate::models::payment::{Payment, PaymentStatus};
+use crate::models::user::UserRole;
+use crate::services::jobs::{self, JobKind};
+use crate::services::notification_channels;
+use crate::services::realtime::{self, PushSignal};
+use chrono::{Duration, Utc};
+use std::env;
+use thiserror::Error;
+use tracing::error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum NotificationFailure {
+    #[error(transparent)]
+    Notification(#[from] NotificationError),
+    #[error(transparent)]
+    Announcement(#[from] AnnouncementError),
+    #[error(transparent)]
+    Job(#[from] JobError),
+}
+
+/// Records `notification`, logging rather than surfacing a failure so it never undoes the
+/// caller's work.
+pub async fn notify_logged(pool: &DbPool, notification: NewNotification) {
+    let kind = notification.kind;
+    let user_id = notification.user_id;
+    match Notification::create(pool, notification).await {
+        Ok(Some(notification)) => {
+            notification_channels::dispatch_logged(pool, &[notification]).await
+        }
+        Ok(None) => {}
+        Err(e) => error!(
+            "Failed to record {:?} notification for user {}: {}",
+            kind, user_id, e
+        ),
+    }
+}
+
+/// Tells the payer their payment was verified or rejected. Pending payments are ignored.
+pub async fn notify_payment_status(pool: &DbPool, payment: &Payment) {
+    let (kind, title) = match payment.status {
+        PaymentStatus::Verified => (NotificationKind::PaymentVerified, "Payment verified"),
+        PaymentStatus::Failed => (NotificationKind::PaymentRejected, "Payment rejected"),
+        PaymentStatus::Pending => return,
+    };
+    let amount = payment
+        .amount
+        .map(|amount| format!("{} {}", payment.currency, amount.round_dp(2)))
+        .unwrap_or_else(|| "Your payment".to_string());
+    let body = match payment.status {
+        PaymentStatus::Verified => format!("{} has been verified. Thank you!", amount),
+        _ => format!(
+            "{} could not be verified. Contact the treasurer if you think this is a mistake.",
+            amount
+        ),
+    };
+
+    notify_logged(
+        pool,
+        NewNotification {
+            user_id: payment.user_id,
+            kind,
+            title: title.to_string(),
+            body: Some(body),
+            link: Some(format!("/payments/{}", payment.id)),
+            subject_id: Some(payment.id),
+        },
+    )
+    .await;
+}
+
+pub async fn notify_role_change(pool: &DbPool, user_id: Uuid, role: UserRole) {
+    let role = match role {
+        UserRole::SuperAdmin => "super admin",
+        UserRole::Admin => "admin",
+        UserRole::Member => "member",
+        UserRole::Treasurer => "treasurer",
+    };
+
+    notify_logged(
+        pool,
+        NewNotification {
+            user_id,
+            kind: NotificationKind::RoleChange,
+            title: "Your role has changed".to_string(),
+            body: Some(format!("You are now a {}.", role)),
+            link: None,
+            subject_id: Some(user_id),
+        },
+    )
+    .await;
+}
+
+pub async fn notify_photo_tag(pool: &DbPool, photo_id: Uuid, user_id: Uuid, tagged_by: &str) {
+    notify_logged(
+        pool,
+        NewNotification {
+            user_id,
+            kind: NotificationKind::PhotoTag,
+            title: "You were tagged in a photo".to_string(),
+            body: Some(format!("{} tagged you in a photo.", tagged_by)),
+            link: Some(format!("/photos/{}", photo_id)),
+            subject_id: Some(photo_id),
+        },
+    )
+    .await;
+}
+
+/// Notifies the audience of an announcement once it is live and pushes it to connected
+/// clients. Safe to repeat: members already notified are skipped, and a post that is not yet published is retried when it is.
+pub async fn notify_announcement(
+    pool: &DbPool,
+    announcement_id: Uuid,
+) -> Result<usize, NotificationFailure> {
+    let Some(announcement) = Announcement::find_by_id(pool, announcement_id).await? else {
+        return Ok(0);
+    };
+
+    let now = Utc::now();
+    if announcement.is_draft || announcement.is_expired_at(now) {
+        return Ok(0);
+    }
+    if announcement.publish_at > now {
+        jobs::enqueue_at(
+            pool,
+            JobKind::NotifyAnnouncement { announcement_id },
+            announcement.publish_at,
+        )
+        .await?;
+        return Ok(0);
+    }
+
+    let notifications = Notification::create_for_announcement(pool, announcement_id).await?;
+    notification_channels::dispatch_logged(pool, &notifications).await;
+    if let Err(e) = realtime::publish(
+        pool,
+        &PushSignal::Announcement {
+            id: announcement_id,
+        },
+    )
+    .await
+    {
+        error!("Failed to push announcement {}: {}", announcement_id, e);
+    }
+    Ok(notifications.len())
+}
+
+/// How far ahead event reminders go out, from `EVENT_REMINDER_HOURS`.
+fn event_reminder_window() -> Duration {
+    let hours = env::var("EVENT_REMINDER_HOURS")
+        .ok()
+        .and_then(|hours| hours.parse().ok())
+        .unwrap_or(24_i64)
+        .max(1);
+    Duration::hours(hours)
+}
+
+pub async fn send_event_reminders(pool: &DbPool) -> Result<usize, NotificationFailure> {
+    let starts_before = Utc::now() + event_reminder_window();
+    let notifications = Notification::create_event_reminders(pool, starts_before).await?;
+    notification_channels::dispatch_logged(pool, &notifications).await;
+    Ok(notifications.len())
+}
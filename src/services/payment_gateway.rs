@@ -0,0 +1,274 @@
+use hmac::{Hmac, Mac};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use sha2::Sha512;
+use std::env;
+use thiserror::Error;
+use tracing::info;
+use uuid::Uuid;
+
+type HmacSha512 = Hmac<Sha512>;
+
+#[derive(Error, Debug)]
+pub enum PaymentGatewayError {
+    #[error("Payment gateway configuration error: {0}")]
+    Config(String),
+    #[error("Payment gateway request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Payment gateway rejected the request: {0}")]
+    Rejected(String),
+    #[error("Invalid payment amount: {0}")]
+    InvalidAmount(Decimal),
+}
+
+#[derive(Debug, Clone)]
+pub struct PaymentGatewayConfig {
+    pub name: String,
+    pub base_url: String, // Point at a local mock server in development
+    pub secret_key: String,
+    pub signature_header: String,
+    pub callback_url: Option<String>,
+}
+
+impl PaymentGatewayConfig {
+    pub fn from_env() -> Result<Self, PaymentGatewayError> {
+        Ok(Self {
+            name: env::var("PAYMENT_GATEWAY_NAME").unwrap_or_else(|_| "paystack".to_string()),
+            base_url: env::var("PAYMENT_GATEWAY_BASE_URL")
+                .unwrap_or_else(|_| "https://api.paystack.co".to_string()),
+            secret_key: env::var("PAYMENT_GATEWAY_SECRET_KEY").map_err(|_| {
+                PaymentGatewayError::Config("PAYMENT_GATEWAY_SECRET_KEY not set".to_string())
+            })?,
+            signature_header: env::var("PAYMENT_GATEWAY_SIGNATURE_HEADER")
+                .unwrap_or_else(|_| "x-paystack-signature".to_string()),
+            callback_url: env::var("PAYMENT_GATEWAY_CALLBACK_URL").ok(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct InitializeTransaction<'a> {
+    email: &'a str,
+    amount: i64, // In minor units (kobo, cents, pence)
+    currency: &'a str,
+    reference: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    callback_url: Option<&'a str>,
+    metadata: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct GatewayResponse<T> {
+    status: bool,
+    message: String,
+    data: Option<T>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckoutSession {
+    pub authorization_url: String,
+    pub access_code: Option<String>,
+    pub reference: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GatewayEvent {
+    pub event: String,
+    pub data: GatewayEventData,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GatewayEventData {
+    pub reference: String,
+    pub amount: i64, // In minor units (kobo)
+    pub currency: Option<String>,
+}
+
+impl GatewayEventData {
+    /// Whether the charge was for exactly `amount` in `currency`.
+    pub fn settles(&self, amount: Decimal, currency: &str) -> bool {
+        PaymentGatewayService::to_minor_units(amount).is_ok_and(|expected| expected == self.amount)
+            && self
+                .currency
+                .as_deref()
+                .is_some_and(|charged| charged.eq_ignore_ascii_case(currency))
+    }
+}
+
+pub struct PaymentGatewayService {
+    client: reqwest::Client,
+    config: PaymentGatewayConfig,
+}
+
+impl PaymentGatewayService {
+    pub fn new() -> Result<Self, PaymentGatewayError> {
+        let config = PaymentGatewayConfig::from_env()?;
+
+        Ok(Self {
+            client: reqwest::Client::new(),
+            config,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    pub fn signature_header(&self) -> &str {
+        &self.config.signature_header
+    }
+
+    pub fn generate_reference() -> String {
+        format!("VOBA-{}", Uuid::new_v4().simple()).to_uppercase()
+    }
+
+    pub fn to_minor_units(amount: Decimal) -> Result<i64, PaymentGatewayError> {
+        (amount * Decimal::ONE_HUNDRED)
+            .round()
+            .to_i64()
+            .filter(|minor| *minor > 0)
+            .ok_or(PaymentGatewayError::InvalidAmount(amount))
+    }
+
+    pub async fn initialize_transaction(
+        &self,
+        email: &str,
+        amount: Decimal,
+        currency: &str,
+        reference: &str,
+        metadata: serde_json::Value,
+    ) -> Result<CheckoutSession, PaymentGatewayError> {
+        let body = InitializeTransaction {
+            email,
+            amount: Self::to_minor_units(amount)?,
+            currency,
+            reference,
+            callback_url: self.config.callback_url.as_deref(),
+            metadata,
+        };
+
+        info!(
+            "Initializing {} transaction: {}",
+            self.config.name, reference
+        );
+
+        let response = self
+            .client
+            .post(format!("{}/transaction/initialize", self.config.base_url))
+            .bearer_auth(&self.config.secret_key)
+            .json(&body)
+            .send()
+            .await?
+            .json::<GatewayResponse<CheckoutSession>>()
+            .await?;
+
+        match response.data {
+            Some(session) if response.status => Ok(session),
+            _ => Err(PaymentGatewayError::Rejected(response.message)),
+        }
+    }
+
+    /// Checks the hex encoded HMAC-SHA512 of the raw webhook body against the secret key.
+    pub fn verify_signature(&self, payload: &[u8], signature: &str) -> bool {
+        let Ok(expected) = hex::decode(signature) else {
+            return false;
+        };
+
+        let Ok(mut mac) = HmacSha512::new_from_slice(self.config.secret_key.as_bytes()) else {
+            return false;
+        };
+
+        mac.update(payload);
+        mac.verify_slice(&expected).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const SECRET: &str = "sk_test_secret";
+    const BODY: &[u8] =
+        br#"{"event":"charge.success","data":{"reference":"VOBA-1","amount":500000,"currency":"NGN"}}"#;
+
+    fn service() -> PaymentGatewayService {
+        PaymentGatewayService {
+            client: reqwest::Client::new(),
+            config: PaymentGatewayConfig {
+                name: "paystack".to_string(),
+                base_url: "http://localhost".to_string(),
+                secret_key: SECRET.to_string(),
+                signature_header: "x-paystack-signature".to_string(),
+                callback_url: None,
+            },
+        }
+    }
+
+    fn sign(secret: &str, payload: &[u8]) -> String {
+        let mut mac = HmacSha512::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(payload);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    #[test]
+    fn accepts_a_valid_signature() {
+        assert!(service().verify_signature(BODY, &sign(SECRET, BODY)));
+    }
+
+    #[test]
+    fn accepts_an_upper_case_signature() {
+        let signature = sign(SECRET, BODY).to_uppercase();
+        assert!(service().verify_signature(BODY, &signature));
+    }
+
+    #[test]
+    fn rejects_a_signature_from_another_key() {
+        assert!(!service().verify_signature(BODY, &sign("sk_test_other", BODY)));
+    }
+
+    #[test]
+    fn rejects_a_tampered_body() {
+        let signature = sign(SECRET, BODY);
+        let tampered = String::from_utf8_lossy(BODY).replace("500000", "5000000");
+        assert!(!service().verify_signature(tampered.as_bytes(), &signature));
+    }
+
+    #[test]
+    fn rejects_malformed_signatures() {
+        let signature = sign(SECRET, BODY);
+        assert!(!service().verify_signature(BODY, ""));
+        assert!(!service().verify_signature(BODY, "not hex"));
+        assert!(!service().verify_signature(BODY, &signature[..64]));
+    }
+
+    #[test]
+    fn converts_amounts_to_minor_units() {
+        let minor = |amount: &str| {
+            PaymentGatewayService::to_minor_units(Decimal::from_str(amount).unwrap()).ok()
+        };
+        assert_eq!(minor("5000"), Some(500000));
+        assert_eq!(minor("12.345"), Some(1234));
+        assert_eq!(minor("0.01"), Some(1));
+        assert_eq!(minor("0"), None);
+        assert_eq!(minor("-10"), None);
+    }
+
+    #[test]
+    fn charge_must_match_the_amount_and_currency() {
+        let event: GatewayEvent = serde_json::from_slice(BODY).unwrap();
+        let amount = Decimal::from(5000);
+
+        assert!(event.data.settles(amount, "NGN"));
+        assert!(event.data.settles(amount, "ngn"));
+        assert!(!event.data.settles(Decimal::from(4999), "NGN"));
+        assert!(!event.data.settles(amount, "USD"));
+
+        let without_currency = GatewayEventData {
+            currency: None,
+            ..event.data
+        };
+        assert!(!without_currency.settles(amount, "NGN"));
+    }
+}
@@ -0,0 +1,207 @@
+use crate::database::connection::DbPool;
+use crate::models::announcement::{Announcement, AnnouncementError};
+use crate::models::notification::{Notification, NotificationError};
+use crate::models::payment::{Payment, PaymentError};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgListener;
+use std::env;
+use thiserror::Error;
+use tokio::sync::broadcast;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Postgres channel every server instance listens on.
+pub const CHANNEL: &str = "portal_events";
+
+#[derive(Error, Debug)]
+pub enum RealtimeError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Invalid event payload: {0}")]
+    Payload(#[from] serde_json::Error),
+    #[error(transparent)]
+    Notification(#[from] NotificationError),
+    #[error(transparent)]
+    Payment(#[from] PaymentError),
+    #[error(transparent)]
+    Announcement(#[from] AnnouncementError),
+}
+
+/// What travels over `portal_events`. Only ids are sent; each instance loads the rows for
+/// the clients that should see them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PushSignal {
+    /// Raised by a trigger on `notifications`.
+    Notification { id: Uuid, user_id: Uuid },
+    /// Raised by a trigger whenever a payment's status changes.
+    Payment { id: Uuid, user_id: Uuid },
+    /// Published once an announcement goes live.
+    Announcement { id: Uuid },
+}
+
+/// An event as delivered to one client.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum PushEvent {
+    Notification(Notification),
+    Payment(Payment),
+    Announcement(Announcement),
+}
+
+impl PushEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PushEvent::Notification(_) => "notification",
+            PushEvent::Payment(_) => "payment",
+            PushEvent::Announcement(_) => "announcement",
+        }
+    }
+}
+
+impl PushSignal {
+    /// Loads what `user_id` should see of this signal, or `None` when it is not for them.
+    pub async fn resolve(
+        &self,
+        pool: &DbPool,
+        user_id: Uuid,
+        all_audiences: bool,
+    ) -> Result<Option<PushEvent>, RealtimeError> {
+        let event = match *self {
+            PushSignal::Notification { id, user_id: owner } if owner == user_id => {
+                Notification::find_by_id(pool, id)
+                    .await?
+                    .map(PushEvent::Notification)
+            }
+            PushSignal::Payment { id, user_id: owner } if owner == user_id => {
+                Payment::find_by_id(pool, id).await?.map(PushEvent::Payment)
+            }
+            PushSignal::Announcement { id }
+                if all_audiences || Announcement::is_visible_to(pool, id, user_id).await? =>
+            {
+                Announcement::find_by_id(pool, id)
+                    .await?
+                    .map(PushEvent::Announcement)
+            }
+            _ => None,
+        };
+
+        Ok(event)
+    }
+}
+
+/// Sends `signal` through Postgres so clients connected to any instance receive it.
+pub async fn publish(pool: &DbPool, signal: &PushSignal) -> Result<(), RealtimeError> {
+    sqlx::query("SELECT pg_notify($1, $2)")
+        .bind(CHANNEL)
+        .bind(serde_json::to_string(signal)?)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Fans signals from `portal_events` out to the streams connected to this instance.
+#[derive(Clone)]
+pub struct RealtimeHub {
+    sender: broadcast::Sender<PushSignal>,
+}
+
+impl RealtimeHub {
+    /// Slow clients that fall more than `REALTIME_BUFFER` signals behind are told to resync.
+    pub fn new() -> Self {
+        let capacity = env::var("REALTIME_BUFFER")
+            .ok()
+            .and_then(|capacity| capacity.parse().ok())
+            .unwrap_or(256);
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<PushSignal> {
+        self.sender.subscribe()
+    }
+
+    /// Spawns the LISTEN loop onto the tokio runtime.
+    pub fn start(&self, pool: DbPool) {
+        let sender = self.sender.clone();
+        tokio::spawn(async move { listen(pool, sender).await });
+    }
+}
+
+async fn listen(pool: DbPool, sender: broadcast::Sender<PushSignal>) {
+    let retry = std::time::Duration::from_secs(5);
+
+    loop {
+        let mut listener = match PgListener::connect_with(&pool).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!("Failed to connect real-time listener: {}", e);
+                tokio::time::sleep(retry).await;
+                continue;
+            }
+        };
+        if let Err(e) = listener.listen(CHANNEL).await {
+            error!("Failed to listen on {}: {}", CHANNEL, e);
+            tokio::time::sleep(retry).await;
+            continue;
+        }
+        info!("Listening for real-time events on {}", CHANNEL);
+
+        loop {
+            match listener.recv().await {
+                Ok(notification) => match serde_json::from_str(notification.payload()) {
+                    // Sending only fails when no client is connected
+                    Ok(signal) => {
+                        let _ = sender.send(signal);
+                    }
+                    Err(e) => warn!("Ignoring malformed real-time event: {}", e),
+                },
+                Err(e) => {
+                    error!("Real-time listener lost its connection: {}", e);
+                    break;
+                }
+            }
+        }
+
+        tokio::time::sleep(retry).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_trigger_payloads() {
+        let id = Uuid::new_v4();
+        let user_id = Uuid::new_v4();
+        // As built by json_build_object in the realtime triggers
+        let payload = format!(
+            "{{\"type\" : \"payment\", \"id\" : \"{}\", \"user_id\" : \"{}\"}}",
+            id, user_id
+        );
+
+        let signal: PushSignal = serde_json::from_str(&payload).unwrap();
+
+        assert!(matches!(
+            signal,
+            PushSignal::Payment { id: a, user_id: b } if a == id && b == user_id
+        ));
+    }
+
+    #[test]
+    fn published_signals_round_trip() {
+        let id = Uuid::new_v4();
+        let payload = serde_json::to_string(&PushSignal::Announcement { id }).unwrap();
+
+        assert_eq!(
+            payload,
+            format!("{{\"type\":\"announcement\",\"id\":\"{}\"}}", id)
+        );
+        assert!(matches!(
+            serde_json::from_str(&payload).unwrap(),
+            PushSignal::Announcement { id: a } if a == id
+        ));
+    }
+}
@@ -0,0 +1,275 @@
+use crate::database::connection::DbPool;
+use crate::models::email_outbox::OutboxAttachment;
+use crate::models::exchange_rate::BASE_CURRENCY;
+use crate::models::receipt::{PaymentReceipt, ReceiptDetails, ReceiptError};
+use crate::services::email::EmailService;
+use crate::services::jobs::{self, JobKind};
+use printpdf::{BuiltinFont, Line, Mm, PdfDocument, Point};
+use rust_decimal::Decimal;
+use std::env;
+use tracing::info;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct ReceiptConfig {
+    pub association_name: String,
+    pub association_address: Option<String>,
+}
+
+impl ReceiptConfig {
+    pub fn from_env() -> Self {
+        Self {
+            association_name: env::var("ASSOCIATION_NAME")
+                .unwrap_or_else(|_| "VOBA 014".to_string()),
+            association_address: env::var("ASSOCIATION_ADDRESS").ok(),
+        }
+    }
+}
+
+pub struct ReceiptService {
+    config: ReceiptConfig,
+}
+
+impl ReceiptService {
+    pub fn from_env() -> Self {
+        Self {
+            config: ReceiptConfig::from_env(),
+        }
+    }
+
+    /// Formats an amount as `NGN 12,500.00`. Built-in PDF fonts cannot print the naira sign.
+    pub fn format_amount(currency: &str, amount: Decimal) -> String {
+        let rounded = amount.round_dp(2);
+        let formatted = format!("{:.2}", rounded.abs());
+        let (whole, fraction) = formatted.split_once('.').unwrap_or((&formatted, "00"));
+
+        let mut grouped = String::with_capacity(whole.len() + whole.len() / 3);
+        for (index, digit) in whole.chars().enumerate() {
+            if index > 0 && (whole.len() - index) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(digit);
+        }
+
+        let sign = if rounded.is_sign_negative() { "-" } else { "" };
+        format!("{} {}{}.{}", currency, sign, grouped, fraction)
+    }
+
+    pub fn filename(details: &ReceiptDetails) -> String {
+        format!("receipt-{}.pdf", details.receipt_number)
+    }
+
+    pub fn render_pdf(&self, details: &ReceiptDetails) -> Result<Vec<u8>, ReceiptError> {
+        let (document, page, layer) = PdfDocument::new(
+            format!("Receipt {}", details.receipt_number),
+            Mm(210.0),
+            Mm(297.0),
+            "Receipt",
+        );
+        let layer = document.get_page(page).get_layer(layer);
+
+        let regular = document
+            .add_builtin_font(BuiltinFont::Helvetica)
+            .map_err(|e| ReceiptError::Render(e.to_string()))?;
+        let bold = document
+            .add_builtin_font(BuiltinFont::HelveticaBold)
+            .map_err(|e| ReceiptError::Render(e.to_string()))?;
+
+        // Letterhead
+        layer.use_text(
+            &self.config.association_name,
+            22.0,
+            Mm(20.0),
+            Mm(270.0),
+            &bold,
+        );
+        if let Some(address) = &self.config.association_address {
+            layer.use_text(address, 10.0, Mm(20.0), Mm(263.0), &regular);
+        }
+        layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(20.0), Mm(257.0)), false),
+                (Point::new(Mm(190.0), Mm(257.0)), false),
+            ],
+            is_closed: false,
+        });
+
+        layer.use_text("PAYMENT RECEIPT", 16.0, Mm(20.0), Mm(243.0), &bold);
+        layer.use_text(
+            format!("Receipt No: {}", details.receipt_number),
+            11.0,
+            Mm(20.0),
+            Mm(234.0),
+            &regular,
+        );
+        layer.use_text(
+            format!("Issued: {}", details.issued_at.format("%d %B %Y")),
+            11.0,
+            Mm(20.0),
+            Mm(228.0),
+            &regular,
+        );
+
+        let method = match &details.gateway {
+            Some(gateway) => format!("Online ({})", gateway),
+            None => "Bank transfer".to_string(),
+        };
+        let verifier = details
+            .verifier_name
+            .clone()
+            .unwrap_or_else(|| match details.gateway {
+                Some(_) => "Payment gateway".to_string(),
+                None => "-".to_string(),
+            });
+
+        let mut rows = vec![
+            ("Received from", details.member_name.clone()),
+            ("Email", details.member_email.clone()),
+            ("Contribution", details.contribution_title.clone()),
+            (
+                "Amount",
+                Self::format_amount(&details.currency, details.amount),
+            ),
+            (
+                "Payment date",
+                details
+                    .verified_at
+                    .unwrap_or(details.issued_at)
+                    .format("%d %B %Y")
+                    .to_string(),
+            ),
+            ("Method", method),
+            (
+                "Reference",
+                details.reference.clone().unwrap_or_else(|| "-".to_string()),
+            ),
+            ("Verified by", verifier),
+        ];
+        if details.currency != BASE_CURRENCY {
+            rows.insert(
+                4,
+                (
+                    "Equivalent",
+                    format!(
+                        "{} at {}",
+                        Self::format_amount(BASE_CURRENCY, details.base_amount),
+                        details.exchange_rate.normalize()
+                    ),
+                ),
+            );
+        }
+
+        let mut y = 212.0;
+        for (label, value) in rows {
+            layer.use_text(label, 11.0, Mm(20.0), Mm(y), &bold);
+            layer.use_text(value, 11.0, Mm(70.0), Mm(y), &regular);
+            y -= 9.0;
+        }
+
+        layer.use_text(
+            format!(
+                "This receipt was generated electronically by the {} portal.",
+                self.config.association_name
+            ),
+            9.0,
+            Mm(20.0),
+            Mm(20.0),
+            &regular,
+        );
+
+        document
+            .save_to_bytes()
+            .map_err(|e| ReceiptError::Render(e.to_string()))
+    }
+
+    /// Issues a receipt for a verified payment and queues it for the member if it has not been
+    /// emailed yet, so a retry picks up where a failed attempt left off.
+    pub async fn issue_and_send(
+        &self,
+        pool: &DbPool,
+        payment_id: Uuid,
+    ) -> Result<PaymentReceipt, ReceiptError> {
+        let (receipt, issued) = PaymentReceipt::issue(pool, payment_id).await?;
+        if issued {
+            info!(
+                "Issued receipt {} for payment {}",
+                receipt.receipt_number, payment_id
+            );
+        }
+        if receipt.emailed_at.is_some() {
+            return Ok(receipt);
+        }
+
+        let details = PaymentReceipt::find_details(pool, payment_id)
+            .await?
+            .ok_or(ReceiptError::NotFound { payment_id })?;
+        let pdf = self.render_pdf(&details)?;
+
+        let email_service = EmailService::new().map_err(|e| ReceiptError::Email(e.to_string()))?;
+
+        let attachment = OutboxAttachment {
+            filename: Self::filename(&details),
+            content_type: "application/pdf".to_string(),
+            content: pdf,
+        };
+
+        let mut tx = pool.begin().await?;
+        if !PaymentReceipt::mark_emailed(&mut tx, receipt.id).await? {
+            return Ok(receipt);
+        }
+        let template = email_service
+            .receipt_email(
+                &mut tx,
+                &details.member_name,
+                &details.receipt_number,
+                &details.contribution_title,
+                &Self::format_amount(&details.currency, details.amount),
+            )
+            .await
+            .map_err(|e| ReceiptError::Email(e.to_string()))?;
+        email_service
+            .queue_with_attachments(
+                &mut tx,
+                &details.member_email,
+                Some(&details.member_name),
+                template,
+                vec![attachment],
+            )
+            .await
+            .map_err(|e| ReceiptError::Email(e.to_string()))?;
+        tx.commit().await?;
+
+        Ok(receipt)
+    }
+}
+
+/// Queues receipt issuance once a payment has been verified. Failures are logged rather than
+/// surfaced so they never undo the verification itself.
+pub async fn issue_receipt(pool: &DbPool, payment_id: Uuid) {
+    jobs::enqueue_logged(pool, JobKind::IssueReceipt { payment_id }).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn format(currency: &str, amount: &str) -> String {
+        ReceiptService::format_amount(currency, Decimal::from_str(amount).unwrap())
+    }
+
+    #[test]
+    fn formats_amounts_with_grouping_and_two_decimals() {
+        assert_eq!(format("NGN", "0"), "NGN 0.00");
+        assert_eq!(format("NGN", "999.5"), "NGN 999.50");
+        assert_eq!(format("NGN", "12500"), "NGN 12,500.00");
+        assert_eq!(format("NGN", "1234567.891"), "NGN 1,234,567.89");
+        assert_eq!(format("NGN", "-2500"), "NGN -2,500.00");
+    }
+
+    #[test]
+    fn formats_amounts_in_the_payment_currency() {
+        assert_eq!(format("USD", "1250.005"), "USD 1,250.00");
+        assert_eq!(format("GBP", "0.015"), "GBP 0.02");
+    }
+}
@@ -0,0 +1,460 @@
+use crate::models::bank_statement::StatementMatchMethod;
+use crate::models::payment::Payment;
+use crate::models::user::User;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use std::collections::HashSet;
+use std::str::FromStr;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum StatementParseError {
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("Missing required column: {0}")]
+    MissingColumn(&'static str),
+    #[error("Invalid date '{value}' on row {row}")]
+    InvalidDate { row: usize, value: String },
+    #[error("Invalid amount '{value}' on row {row}")]
+    InvalidAmount { row: usize, value: String },
+    #[error("Statement contains no credit transactions")]
+    Empty,
+}
+
+const DATE_COLUMNS: &[&str] = &[
+    "date",
+    "transaction date",
+    "trans date",
+    "value date",
+    "posted date",
+];
+const NARRATION_COLUMNS: &[&str] = &[
+    "narration",
+    "description",
+    "details",
+    "remarks",
+    "particulars",
+];
+const CREDIT_COLUMNS: &[&str] = &[
+    "credit",
+    "credit amount",
+    "deposit",
+    "deposits",
+    "money in",
+    "amount",
+];
+const REFERENCE_COLUMNS: &[&str] = &[
+    "reference",
+    "ref",
+    "transaction reference",
+    "reference number",
+];
+const DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%d", "%d/%m/%Y", "%d-%m-%Y", "%d-%b-%Y", "%d %b %Y", "%d-%b-%y",
+];
+
+#[derive(Debug, Clone)]
+pub struct StatementTransaction {
+    pub transaction_date: NaiveDate,
+    pub narration: String,
+    pub reference: Option<String>,
+    pub amount: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LineMatch {
+    Payment {
+        payment_id: Uuid,
+        method: StatementMatchMethod,
+    },
+    Member {
+        user_id: Uuid,
+    },
+    Unmatched,
+}
+
+/// Parses the credit lines of a bank statement export. Debits and blank rows are skipped.
+pub fn parse_statement(data: &[u8]) -> Result<Vec<StatementTransaction>, StatementParseError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(data);
+
+    let headers = reader.headers()?.clone();
+    let find_column = |names: &[&str]| {
+        headers
+            .iter()
+            .position(|header| names.contains(&header.to_lowercase().as_str()))
+    };
+
+    let date_column =
+        find_column(DATE_COLUMNS).ok_or(StatementParseError::MissingColumn("date"))?;
+    let narration_column =
+        find_column(NARRATION_COLUMNS).ok_or(StatementParseError::MissingColumn("narration"))?;
+    let credit_column =
+        find_column(CREDIT_COLUMNS).ok_or(StatementParseError::MissingColumn("credit"))?;
+    let reference_column = find_column(REFERENCE_COLUMNS);
+
+    let mut transactions = Vec::new();
+
+    for (index, record) in reader.records().enumerate() {
+        let record = record?;
+        let row = index + 2; // Row 1 is the header
+
+        let raw_amount = record.get(credit_column).unwrap_or_default();
+        if raw_amount.is_empty() {
+            continue;
+        }
+
+        let amount =
+            parse_amount(raw_amount).ok_or_else(|| StatementParseError::InvalidAmount {
+                row,
+                value: raw_amount.to_string(),
+            })?;
+
+        if amount <= Decimal::ZERO {
+            continue;
+        }
+
+        let raw_date = record.get(date_column).unwrap_or_default();
+        let transaction_date =
+            parse_date(raw_date).ok_or_else(|| StatementParseError::InvalidDate {
+                row,
+                value: raw_date.to_string(),
+            })?;
+
+        transactions.push(StatementTransaction {
+            transaction_date,
+            narration: record.get(narration_column).unwrap_or_default().to_string(),
+            reference: reference_column
+                .and_then(|column| record.get(column))
+                .filter(|reference| !reference.is_empty())
+                .map(str::to_string),
+            amount,
+        });
+    }
+
+    if transactions.is_empty() {
+        return Err(StatementParseError::Empty);
+    }
+
+    Ok(transactions)
+}
+
+/// Ignores currency codes, symbols and thousands separators. Accounting negatives such as
+/// "(300.00)" and amounts marked "DR" are debits, so they come back negative.
+fn parse_amount(value: &str) -> Option<Decimal> {
+    let value = value.trim().to_uppercase();
+    let (value, debit) = match value.strip_suffix("DR") {
+        Some(value) => (value.trim_end(), true),
+        None => (value.strip_suffix("CR").unwrap_or(&value).trim_end(), false),
+    };
+    let (value, debit) = match value.strip_prefix('(').and_then(|v| v.strip_suffix(')')) {
+        Some(value) => (value, true),
+        None => (value, debit),
+    };
+
+    let cleaned: String = value
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '.' || *c == '-')
+        .collect();
+    let amount = Decimal::from_str(&cleaned).ok()?;
+
+    Some(if debit { -amount.abs() } else { amount })
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+}
+
+fn words(value: &str) -> HashSet<String> {
+    value
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_uppercase)
+        .collect()
+}
+
+/// Finds the pending payment (or, failing that, the member) a statement line most likely
+/// belongs to. Payments already in `claimed` are skipped so one credit settles one payment.
+pub fn find_match(
+    transaction: &StatementTransaction,
+    pending: &[Payment],
+    users: &[User],
+    claimed: &HashSet<Uuid>,
+) -> LineMatch {
+    let text = format!(
+        "{} {}",
+        transaction.reference.as_deref().unwrap_or_default(),
+        transaction.narration
+    )
+    .to_uppercase();
+
+    let available = || {
+        pending
+            .iter()
+            .filter(|payment| !claimed.contains(&payment.id))
+            // Statement lines are in the base currency.
+            .filter(|payment| payment.base_amount == Some(transaction.amount))
+    };
+
+    if let Some(payment) = available().find(|payment| {
+        payment
+            .reference
+            .as_ref()
+            .is_some_and(|reference| text.contains(&reference.to_uppercase()))
+    }) {
+        return LineMatch::Payment {
+            payment_id: payment.id,
+            method: StatementMatchMethod::Reference,
+        };
+    }
+
+    let narration_words = words(&transaction.narration);
+    let members: Vec<&User> = users
+        .iter()
+        .filter(|user| {
+            let name_words = words(&user.fullname);
+            !name_words.is_empty() && name_words.is_subset(&narration_words)
+        })
+        .collect();
+
+    let [member] = members.as_slice() else {
+        return LineMatch::Unmatched;
+    };
+
+    let candidates: Vec<&Payment> = available()
+        .filter(|payment| payment.user_id == member.id)
+        .collect();
+
+    match candidates.as_slice() {
+        [payment] => LineMatch::Payment {
+            payment_id: payment.id,
+            method: StatementMatchMethod::Narration,
+        },
+        _ => LineMatch::Member { user_id: member.id },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::payment::{PaymentEntryType, PaymentStatus};
+    use crate::models::user::UserRole;
+    use chrono::Utc;
+
+    fn payment(user_id: Uuid, amount: i64, reference: Option<&str>) -> Payment {
+        Payment {
+            id: Uuid::new_v4(),
+            user_id,
+            contribution_id: Some(Uuid::new_v4()),
+            campaign_id: None,
+            is_anonymous: false,
+            amount: Some(Decimal::from(amount)),
+            currency: "NGN".to_string(),
+            exchange_rate: Decimal::ONE,
+            base_amount: Some(Decimal::from(amount)),
+            receipt_url: None,
+            status: PaymentStatus::Pending,
+            reference: reference.map(str::to_string),
+            gateway: None,
+            verified_by: None,
+            verified_at: None,
+            entry_type: PaymentEntryType::Payment,
+            reverses_payment_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            deleted_at: None,
+        }
+    }
+
+    fn user(fullname: &str) -> User {
+        User {
+            id: Uuid::new_v4(),
+            fullname: fullname.to_string(),
+            email: "member@example.com".to_string(),
+            password_hash: String::new(),
+            phone: None,
+            dob: None,
+            photo_url: None,
+            user_role: UserRole::Member,
+            email_verification_code: None,
+            email_verification_expires_at: None,
+            is_email_verified: true,
+            is_active: true,
+            dues_reminders_enabled: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            deleted_at: None,
+        }
+    }
+
+    fn line(narration: &str, reference: Option<&str>, amount: i64) -> StatementTransaction {
+        StatementTransaction {
+            transaction_date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            narration: narration.to_string(),
+            reference: reference.map(str::to_string),
+            amount: Decimal::from(amount),
+        }
+    }
+
+    #[test]
+    fn parses_credit_lines_and_skips_debits() {
+        let csv = "Trans Date,Narration,Reference,Debit,Credit\n\
+                   01/03/2024,TRF FROM ADA LOVELACE,FT123,,\"5,000.00\"\n\
+                   02-Mar-2024,ATM WITHDRAWAL,,2000.00,\n\
+                   2024-03-03,REVERSAL,,,-300\n\
+                   04 Mar 2024,DUES JOHN DOE,,,NGN 2500.50\n\
+                   05/03/2024,CHARGES,,,(52.50)\n\
+                   06/03/2024,TRANSFER OUT,,,\"1,200.00 DR\"\n";
+        let lines = parse_statement(csv.as_bytes()).unwrap();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0].transaction_date,
+            NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()
+        );
+        assert_eq!(lines[0].narration, "TRF FROM ADA LOVELACE");
+        assert_eq!(lines[0].reference.as_deref(), Some("FT123"));
+        assert_eq!(lines[0].amount, Decimal::from(5000));
+        assert_eq!(lines[1].reference, None);
+        assert_eq!(lines[1].amount, Decimal::from_str("2500.50").unwrap());
+    }
+
+    #[test]
+    fn parses_debit_and_credit_markers() {
+        let amount = |value| parse_amount(value).unwrap();
+
+        assert_eq!(amount("(300.00)"), Decimal::from(-300));
+        assert_eq!(amount("1,200.00 DR"), Decimal::from(-1200));
+        assert_eq!(amount("450dr"), Decimal::from(-450));
+        assert_eq!(amount("750.00 CR"), Decimal::from(750));
+        assert_eq!(amount("NGN 2,500"), Decimal::from(2500));
+        assert_eq!(amount("-80"), Decimal::from(-80));
+        assert_eq!(parse_amount("DR"), None);
+        assert_eq!(parse_amount("()"), None);
+    }
+
+    #[test]
+    fn parse_reports_missing_columns_and_bad_values() {
+        assert!(matches!(
+            parse_statement(b"Date,Amount\n2024-03-01,100\n"),
+            Err(StatementParseError::MissingColumn("narration"))
+        ));
+        assert!(matches!(
+            parse_statement(b"Date,Narration,Credit\n2024-03-01,DUES,abc\n"),
+            Err(StatementParseError::InvalidAmount { row: 2, .. })
+        ));
+        assert!(matches!(
+            parse_statement(b"Date,Narration,Credit\n31/02/2024,DUES,100\n"),
+            Err(StatementParseError::InvalidDate { row: 2, .. })
+        ));
+        assert!(matches!(
+            parse_statement(b"Date,Narration,Credit\n2024-03-01,FEES,\n"),
+            Err(StatementParseError::Empty)
+        ));
+    }
+
+    #[test]
+    fn matches_on_payment_reference() {
+        let ada = user("Ada Lovelace");
+        let payments = [
+            payment(ada.id, 5000, Some("VOBA-1")),
+            payment(ada.id, 5000, Some("VOBA-2")),
+        ];
+        let found = find_match(
+            &line("transfer voba-2 dues", None, 5000),
+            &payments,
+            &[ada],
+            &HashSet::new(),
+        );
+        assert_eq!(
+            found,
+            LineMatch::Payment {
+                payment_id: payments[1].id,
+                method: StatementMatchMethod::Reference,
+            }
+        );
+    }
+
+    #[test]
+    fn reference_match_needs_the_same_amount() {
+        let payments = [payment(Uuid::new_v4(), 5000, Some("VOBA-1"))];
+        let found = find_match(
+            &line("DUES", Some("VOBA-1"), 4000),
+            &payments,
+            &[],
+            &HashSet::new(),
+        );
+        assert_eq!(found, LineMatch::Unmatched);
+    }
+
+    #[test]
+    fn matches_a_single_pending_payment_by_member_name() {
+        let ada = user("Ada Lovelace");
+        let grace = user("Grace Hopper");
+        let payments = [payment(grace.id, 5000, None), payment(ada.id, 5000, None)];
+        let found = find_match(
+            &line("TRF/LOVELACE ADA/DUES", None, 5000),
+            &payments,
+            &[ada, grace],
+            &HashSet::new(),
+        );
+        assert_eq!(
+            found,
+            LineMatch::Payment {
+                payment_id: payments[1].id,
+                method: StatementMatchMethod::Narration,
+            }
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_member_when_payments_are_ambiguous() {
+        let ada = user("Ada Lovelace");
+        let payments = [payment(ada.id, 5000, None), payment(ada.id, 5000, None)];
+        let found = find_match(
+            &line("ADA LOVELACE", None, 5000),
+            &payments,
+            std::slice::from_ref(&ada),
+            &HashSet::new(),
+        );
+        assert_eq!(found, LineMatch::Member { user_id: ada.id });
+    }
+
+    #[test]
+    fn skips_claimed_payments() {
+        let ada = user("Ada Lovelace");
+        let payments = [payment(ada.id, 5000, Some("VOBA-1"))];
+        let claimed = HashSet::from([payments[0].id]);
+        let found = find_match(
+            &line("VOBA-1 ADA LOVELACE", None, 5000),
+            &payments,
+            std::slice::from_ref(&ada),
+            &claimed,
+        );
+        assert_eq!(found, LineMatch::Member { user_id: ada.id });
+    }
+
+    #[test]
+    fn leaves_lines_for_unknown_or_shared_names_unmatched() {
+        let payments = [payment(Uuid::new_v4(), 5000, None)];
+        let users = [user("John Doe"), user("Jane Doe")];
+        assert_eq!(
+            find_match(&line("DOE", None, 5000), &payments, &users, &HashSet::new()),
+            LineMatch::Unmatched
+        );
+        assert_eq!(
+            find_match(
+                &line("ALAN TURING", None, 5000),
+                &payments,
+                &users,
+                &HashSet::new()
+            ),
+            LineMatch::Unmatched
+        );
+    }
+}
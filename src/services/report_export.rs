@@ -0,0 +1,348 @@
+use crate::models::report::{
+    ContributionCollection, Defaulter, IncomeStatement, MonthlyTotal, ReportPeriod,
+};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("CSV buffer error: {0}")]
+    Buffer(String),
+    #[error("XLSX error: {0}")]
+    Xlsx(#[from] XlsxError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Xlsx,
+}
+
+impl FromStr for ExportFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(ExportFormat::Json),
+            "csv" => Ok(ExportFormat::Csv),
+            "xlsx" => Ok(ExportFormat::Xlsx),
+            _ => Err(()),
+        }
+    }
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "application/json",
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Xlsx => {
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            }
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+pub enum ReportCell {
+    Text(String),
+    Amount(Decimal),
+    Count(i64),
+    Empty,
+}
+
+impl ReportCell {
+    fn to_csv_field(&self) -> String {
+        match self {
+            ReportCell::Text(text) => text.clone(),
+            ReportCell::Amount(amount) => amount.round_dp(2).to_string(),
+            ReportCell::Count(count) => count.to_string(),
+            ReportCell::Empty => String::new(),
+        }
+    }
+}
+
+impl From<Option<Decimal>> for ReportCell {
+    fn from(value: Option<Decimal>) -> Self {
+        value.map_or(ReportCell::Empty, ReportCell::Amount)
+    }
+}
+
+/// A report that can be flattened into a single table for CSV or XLSX export.
+pub trait ReportTable {
+    fn title(&self) -> String;
+    fn headers(&self) -> Vec<&'static str>;
+    fn rows(&self) -> Vec<Vec<ReportCell>>;
+}
+
+pub struct CollectionsReport<'a>(pub ReportPeriod, pub &'a [ContributionCollection]);
+pub struct DefaultersReport<'a>(pub ReportPeriod, pub &'a [Defaulter]);
+pub struct MonthlyTrendReport<'a>(pub ReportPeriod, pub &'a [MonthlyTotal]);
+
+fn period_title(name: &str, period: &ReportPeriod) -> String {
+    format!("{} {} to {}", name, period.from, period.to)
+}
+
+impl ReportTable for CollectionsReport<'_> {
+    fn title(&self) -> String {
+        period_title("Collections", &self.0)
+    }
+
+    fn headers(&self) -> Vec<&'static str> {
+        vec![
+            "Contribution",
+            "Due date",
+            "Amount due",
+            "Liable members",
+            "Paid members",
+            "Expected",
+            "Collected",
+            "Collection rate (%)",
+        ]
+    }
+
+    fn rows(&self) -> Vec<Vec<ReportCell>> {
+        self.1
+            .iter()
+            .map(|row| {
+                vec![
+                    ReportCell::Text(row.title.clone()),
+                    ReportCell::Text(row.due_date.to_string()),
+                    row.amount_due.into(),
+                    ReportCell::Count(row.liable_members),
+                    ReportCell::Count(row.paid_members),
+                    row.expected.into(),
+                    ReportCell::Amount(row.collected),
+                    row.collection_rate.into(),
+                ]
+            })
+            .collect()
+    }
+}
+
+impl ReportTable for DefaultersReport<'_> {
+    fn title(&self) -> String {
+        period_title("Defaulters", &self.0)
+    }
+
+    fn headers(&self) -> Vec<&'static str> {
+        vec!["Member", "Email", "Contributions owed", "Outstanding"]
+    }
+
+    fn rows(&self) -> Vec<Vec<ReportCell>> {
+        self.1
+            .iter()
+            .map(|row| {
+                vec![
+                    ReportCell::Text(row.fullname.clone()),
+                    ReportCell::Text(row.email.clone()),
+                    ReportCell::Count(row.contributions_owed),
+                    ReportCell::Amount(row.outstanding),
+                ]
+            })
+            .collect()
+    }
+}
+
+impl ReportTable for MonthlyTrendReport<'_> {
+    fn title(&self) -> String {
+        period_title("Monthly trend", &self.0)
+    }
+
+    fn headers(&self) -> Vec<&'static str> {
+        vec!["Month", "Income", "Expenses", "Net"]
+    }
+
+    fn rows(&self) -> Vec<Vec<ReportCell>> {
+        self.1
+            .iter()
+            .map(|row| {
+                vec![
+                    ReportCell::Text(row.month.clone()),
+                    ReportCell::Amount(row.income),
+                    ReportCell::Amount(row.expenses),
+                    ReportCell::Amount(row.net),
+                ]
+            })
+            .collect()
+    }
+}
+
+impl ReportTable for IncomeStatement {
+    fn title(&self) -> String {
+        period_title("Income statement", &self.period)
+    }
+
+    fn headers(&self) -> Vec<&'static str> {
+        vec!["Section", "Item", "Amount"]
+    }
+
+    fn rows(&self) -> Vec<Vec<ReportCell>> {
+        let line = |section: &str, name: &str, amount: Decimal| {
+            vec![
+                ReportCell::Text(section.to_string()),
+                ReportCell::Text(name.to_string()),
+                ReportCell::Amount(amount),
+            ]
+        };
+
+        let mut rows: Vec<Vec<ReportCell>> = self
+            .income
+            .iter()
+            .map(|item| line("Income", &item.name, item.amount))
+            .collect();
+        rows.push(line("Income", "Total income", self.total_income));
+        rows.extend(
+            self.expenses
+                .iter()
+                .map(|item| line("Expenses", &item.name, item.amount)),
+        );
+        rows.push(line("Expenses", "Total expenses", self.total_expenses));
+        rows.push(line("Summary", "Surplus / (deficit)", self.surplus));
+
+        rows
+    }
+}
+
+pub fn to_csv(report: &impl ReportTable) -> Result<Vec<u8>, ExportError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(report.headers())?;
+
+    for row in report.rows() {
+        writer.write_record(row.iter().map(ReportCell::to_csv_field))?;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|e| ExportError::Buffer(e.to_string()))
+}
+
+pub fn to_xlsx(report: &impl ReportTable) -> Result<Vec<u8>, ExportError> {
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+    let money = Format::new().set_num_format("#,##0.00");
+
+    let worksheet = workbook.add_worksheet();
+    // Sheet names are limited to 31 characters
+    worksheet.set_name(report.title().chars().take(31).collect::<String>())?;
+
+    for (col, header) in report.headers().iter().enumerate() {
+        worksheet.write_string_with_format(0, col as u16, *header, &bold)?;
+        worksheet.set_column_width(col as u16, 18)?;
+    }
+
+    for (index, row) in report.rows().iter().enumerate() {
+        let row_number = index as u32 + 1;
+
+        for (col, cell) in row.iter().enumerate() {
+            let col = col as u16;
+            match cell {
+                ReportCell::Text(text) => {
+                    worksheet.write_string(row_number, col, text)?;
+                }
+                ReportCell::Amount(amount) => {
+                    worksheet.write_number_with_format(
+                        row_number,
+                        col,
+                        amount.to_f64().unwrap_or_default(),
+                        &money,
+                    )?;
+                }
+                ReportCell::Count(count) => {
+                    worksheet.write_number(row_number, col, *count as f64)?;
+                }
+                ReportCell::Empty => {}
+            }
+        }
+    }
+
+    Ok(workbook.save_to_buffer()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn period() -> ReportPeriod {
+        ReportPeriod::new(
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn months() -> Vec<MonthlyTotal> {
+        vec![
+            MonthlyTotal {
+                month: "2024-01".to_string(),
+                income: Decimal::from_str("12500.456").unwrap(),
+                expenses: Decimal::from(2000),
+                net: Decimal::from_str("10500.456").unwrap(),
+            },
+            MonthlyTotal {
+                month: "2024-02, late".to_string(),
+                income: Decimal::ZERO,
+                expenses: Decimal::from(300),
+                net: Decimal::from(-300),
+            },
+        ]
+    }
+
+    #[test]
+    fn parses_export_formats() {
+        assert_eq!("csv".parse(), Ok(ExportFormat::Csv));
+        assert_eq!("xlsx".parse(), Ok(ExportFormat::Xlsx));
+        assert_eq!("pdf".parse::<ExportFormat>(), Err(()));
+        assert_eq!(ExportFormat::Xlsx.extension(), "xlsx");
+    }
+
+    #[test]
+    fn writes_csv_with_headers_and_rounded_amounts() {
+        let months = months();
+        let csv = to_csv(&MonthlyTrendReport(period(), &months)).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "Month,Income,Expenses,Net\n\
+             2024-01,12500.46,2000,10500.46\n\
+             \"2024-02, late\",0,300,-300\n"
+        );
+    }
+
+    #[test]
+    fn writes_empty_cells_for_missing_amounts() {
+        assert_eq!(ReportCell::from(None).to_csv_field(), "");
+        assert_eq!(ReportCell::from(Some(Decimal::ONE)).to_csv_field(), "1");
+    }
+
+    #[test]
+    fn writes_an_xlsx_workbook() {
+        let months = months();
+        let xlsx = to_xlsx(&MonthlyTrendReport(period(), &months)).unwrap();
+        assert!(xlsx.starts_with(b"PK"));
+    }
+
+    #[test]
+    fn period_must_not_end_before_it_starts() {
+        let from = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        assert!(ReportPeriod::new(from, from).is_ok());
+        assert!(ReportPeriod::new(from, from.pred_opt().unwrap()).is_err());
+        assert_eq!(
+            MonthlyTrendReport(period(), &[]).title(),
+            "Monthly trend 2024-01-01 to 2024-12-31"
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use std::env;
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+use tokio::fs;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("Storage I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid storage key: {0}")]
+    InvalidKey(String),
+    #[error("Unsupported file type: {0}")]
+    UnsupportedType(String),
+}
+
+/// Document types accepted as expense receipts.
+pub const RECEIPT_TYPES: &[&str] = &["application/pdf", "image/jpeg", "image/png", "image/webp"];
+
+/// Document types accepted as announcement attachments, such as minutes and flyers.
+pub const ATTACHMENT_TYPES: &[&str] = &[
+    "application/pdf",
+    "image/jpeg",
+    "image/png",
+    "image/webp",
+    "image/gif",
+    "text/plain",
+    "text/csv",
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+];
+
+#[derive(Debug, Clone)]
+pub struct StorageConfig {
+    pub upload_dir: PathBuf,
+}
+
+impl StorageConfig {
+    pub fn from_env() -> Self {
+        Self {
+            upload_dir: env::var("UPLOAD_DIR")
+                .unwrap_or_else(|_| "uploads".to_string())
+                .into(),
+        }
+    }
+}
+
+/// Stores uploaded files on local disk under `UPLOAD_DIR`, addressed by a relative key.
+pub struct StorageService {
+    config: StorageConfig,
+}
+
+impl StorageService {
+    pub fn from_env() -> Self {
+        Self {
+            config: StorageConfig::from_env(),
+        }
+    }
+
+    /// File extension for a content type in `allowed`.
+    pub fn extension_for(
+        allowed: &[&str],
+        content_type: &str,
+    ) -> Result<&'static str, StorageError> {
+        if !allowed.contains(&content_type) {
+            return Err(StorageError::UnsupportedType(content_type.to_string()));
+        }
+
+        match content_type {
+            "application/pdf" => Ok("pdf"),
+            "image/jpeg" => Ok("jpg"),
+            "image/png" => Ok("png"),
+            "image/webp" => Ok("webp"),
+            "image/gif" => Ok("gif"),
+            "text/plain" => Ok("txt"),
+            "text/csv" => Ok("csv"),
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => Ok("docx"),
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" => Ok("xlsx"),
+            "application/vnd.openxmlformats-officedocument.presentationml.presentation" => {
+                Ok("pptx")
+            }
+            other => Err(StorageError::UnsupportedType(other.to_string())),
+        }
+    }
+
+    fn resolve(&self, key: &str) -> Result<PathBuf, StorageError> {
+        let relative = Path::new(key);
+        if relative
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_)))
+        {
+            return Err(StorageError::InvalidKey(key.to_string()));
+        }
+
+        Ok(self.config.upload_dir.join(relative))
+    }
+
+    /// Saves `content` under `folder` with a generated name and returns its key. Content types
+    /// outside `allowed` are rejected.
+    pub async fn save(
+        &self,
+        folder: &str,
+        allowed: &[&str],
+        content_type: &str,
+        content: &[u8],
+    ) -> Result<String, StorageError> {
+        let extension = Self::extension_for(allowed, content_type)?;
+        let key = format!("{}/{}.{}", folder, Uuid::new_v4(), extension);
+        let path = self.resolve(&key)?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::write(&path, content).await?;
+
+        Ok(key)
+    }
+
+    pub async fn read(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        Ok(fs::read(self.resolve(key)?).await?)
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        match fs::remove_file(self.resolve(key)?).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service() -> StorageService {
+        StorageService {
+            config: StorageConfig {
+                upload_dir: "uploads".into(),
+            },
+        }
+    }
+
+    #[test]
+    fn accepts_receipt_document_types() {
+        assert_eq!(
+            StorageService::extension_for(RECEIPT_TYPES, "application/pdf").unwrap(),
+            "pdf"
+        );
+        assert_eq!(
+            StorageService::extension_for(RECEIPT_TYPES, "image/jpeg").unwrap(),
+            "jpg"
+        );
+        assert!(matches!(
+            StorageService::extension_for(RECEIPT_TYPES, "text/csv"),
+            Err(StorageError::UnsupportedType(_))
+        ));
+    }
+
+    #[test]
+    fn accepts_attachment_document_types() {
+        assert_eq!(
+            StorageService::extension_for(ATTACHMENT_TYPES, "text/csv").unwrap(),
+            "csv"
+        );
+        assert_eq!(
+            StorageService::extension_for(
+                ATTACHMENT_TYPES,
+                "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+            )
+            .unwrap(),
+            "docx"
+        );
+        assert!(matches!(
+            StorageService::extension_for(ATTACHMENT_TYPES, "text/html"),
+            Err(StorageError::UnsupportedType(_))
+        ));
+    }
+
+    #[test]
+    fn resolves_keys_under_the_upload_dir() {
+        assert_eq!(
+            service().resolve("expenses/receipt.pdf").unwrap(),
+            PathBuf::from("uploads/expenses/receipt.pdf")
+        );
+    }
+
+    #[test]
+    fn rejects_keys_that_escape_the_upload_dir() {
+        for key in [
+            "../secrets",
+            "expenses/../../etc/passwd",
+            "/etc/passwd",
+            "./receipt.pdf",
+        ] {
+            assert!(
+                matches!(service().resolve(key), Err(StorageError::InvalidKey(_))),
+                "{}",
+                key
+            );
+        }
+    }
+}
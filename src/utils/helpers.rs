@@ -1,5 +1,7 @@
+use crate::utils::pagination::{Page, Pagination};
+use crate::utils::validation::FieldError;
 use actix_web::HttpResponse;
-use serde::Serialize;
+use serde::{Deserialize, Deserializer, Serialize};
 
 #[derive(Serialize, Debug)]
 pub struct ApiResponse<T> {
@@ -10,6 +12,12 @@ pub struct ApiResponse<T> {
     data: Option<T>,
     #[serde(skip_serializing_if = "Option::is_none")]
     error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    errors: Option<Vec<FieldError>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pagination: Option<Pagination>,
 }
 
 impl<T> ApiResponse<T> {
@@ -19,6 +27,9 @@ impl<T> ApiResponse<T> {
             message: None,
             data: Some(data),
             error: None,
+            code: None,
+            errors: None,
+            pagination: None,
         }
     }
 
@@ -28,21 +39,57 @@ impl<T> ApiResponse<T> {
             message: Some(message),
             data: Some(data),
             error: None,
+            code: None,
+            errors: None,
+            pagination: None,
         }
     }
 
-    pub fn error(message: String) -> ApiResponse<()> {
+    pub fn error(code: &'static str, message: String) -> ApiResponse<()> {
         ApiResponse {
             success: false,
             message: None,
             data: None,
             error: Some(message),
+            code: Some(code),
+            errors: None,
+            pagination: None,
+        }
+    }
+}
+
+impl<T> ApiResponse<Vec<T>> {
+    pub fn paginated(page: Page<T>) -> Self {
+        Self {
+            success: true,
+            message: None,
+            data: Some(page.items),
+            error: None,
+            code: None,
+            errors: None,
+            pagination: Some(page.pagination),
         }
     }
 }
 
 impl ApiResponse<()> {
+    /// Lists the fields that failed validation alongside the error.
+    pub fn with_errors(mut self, errors: Vec<FieldError>) -> Self {
+        self.errors = Some(errors);
+        self
+    }
+
     pub fn to_response(&self, status: actix_web::http::StatusCode) -> HttpResponse {
         HttpResponse::build(status).json(self)
     }
 }
+
+/// For update fields that can be cleared: a missing field stays `None`, while `null` becomes
+/// `Some(None)`. Use with `#[serde(default, deserialize_with = "double_option")]`.
+pub fn double_option<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
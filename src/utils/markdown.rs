@@ -0,0 +1,73 @@
+use ammonia::Builder;
+use pulldown_cmark::{Options, Parser, html};
+use std::sync::LazyLock;
+
+/// Raw HTML in the Markdown is cleaned by the sanitiser rather than trusted, links open in a
+/// new tab without leaking the portal as referrer, and only http, https and mailto URLs survive.
+static SANITIZER: LazyLock<Builder<'static>> = LazyLock::new(|| {
+    let mut builder = Builder::default();
+    builder
+        .url_schemes(["http", "https", "mailto"].into())
+        .link_rel(Some("noopener noreferrer nofollow"))
+        .set_tag_attribute_value("a", "target", "_blank")
+        // Task list checkboxes, which can only ever be read-only checkboxes
+        .add_tags(["input"])
+        .add_tag_attributes("input", ["checked"])
+        .set_tag_attribute_value("input", "type", "checkbox")
+        .set_tag_attribute_value("input", "disabled", "");
+    builder
+});
+
+/// Renders CommonMark, with tables, strikethrough and task lists, to sanitised HTML.
+pub fn render(markdown: &str) -> String {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, Parser::new_ext(markdown, options));
+
+    SANITIZER.clean(&unsafe_html).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_commonmark_extensions() {
+        assert_eq!(
+            render("**AGM** on ~~Friday~~"),
+            "<p><strong>AGM</strong> on <del>Friday</del></p>\n"
+        );
+        assert!(render("| a |\n|---|\n| 1 |").contains("<table>"));
+
+        // The sanitiser does not keep attributes in a fixed order
+        let task = render("- [x] Book hall");
+        for attribute in [r#"type="checkbox""#, r#"disabled="""#, r#"checked="""#] {
+            assert!(task.contains(attribute), "{}", task);
+        }
+    }
+
+    #[test]
+    fn strips_scripts_and_event_handlers() {
+        let html = render("<script>alert(1)</script><img src=\"x.png\" onerror=\"alert(1)\">");
+
+        assert!(!html.contains("script"));
+        assert!(!html.contains("onerror"));
+        assert!(html.contains("<img src=\"x.png\">"));
+    }
+
+    #[test]
+    fn keeps_only_safe_links() {
+        let html = render("[site](https://example.org) [bad](javascript:alert(1))");
+
+        assert!(html.contains(r#"href="https://example.org""#));
+        assert_eq!(html.matches(r#"target="_blank""#).count(), 2);
+        assert_eq!(
+            html.matches(r#"rel="noopener noreferrer nofollow""#)
+                .count(),
+            2
+        );
+        assert_eq!(html.matches("href=").count(), 1);
+        assert!(!html.contains("javascript"));
+    }
+}
@@ -1 +1,4 @@
 pub mod helpers;
+pub mod markdown;
+pub mod pagination;
+pub mod validation;
@@ -0,0 +1,521 @@
+use crate::database::connection::DbPool;
+use crate::errors::AppError;
+use crate::utils::validation::Validate;
+use actix_web::dev::Payload;
+use actix_web::{FromRequest, HttpRequest, web};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Postgres, QueryBuilder, Row};
+use std::future::{Ready, ready};
+use uuid::Uuid;
+
+pub const DEFAULT_PER_PAGE: i64 = 25;
+pub const MAX_PER_PAGE: i64 = 100;
+
+/// A column a listing can be sorted by. Sort columns must be `NOT NULL` so keyset paging
+/// can compare against them; `sql_type` is what the cursor value is cast back to.
+#[derive(Debug)]
+pub struct SortField {
+    pub name: &'static str,
+    pub sql_type: &'static str,
+}
+
+/// A column a listing is always ordered by first, whatever sort the client asks for.
+#[derive(Debug)]
+pub struct LeadingSort {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+/// Implemented by the filter half of a list query to declare how it can be sorted.
+pub trait Sortable {
+    const SORT_FIELDS: &'static [SortField];
+    /// A field name, prefixed with `-` for descending.
+    const DEFAULT_SORT: &'static str;
+    /// Ordered on ahead of the requested sort, such as pinned posts before the rest.
+    const LEADING_SORT: Option<&'static LeadingSort> = None;
+}
+
+#[derive(Debug, Deserialize)]
+struct PageParams {
+    page: Option<i64>,
+    per_page: Option<i64>,
+    cursor: Option<String>,
+    sort: Option<String>,
+}
+
+/// Where the last page ended: the sort it was taken with and the last row's leading sort
+/// value, sort value and id.
+#[derive(Debug, Serialize, Deserialize)]
+struct Cursor {
+    sort: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    lead: Option<String>,
+    value: String,
+    id: Uuid,
+}
+
+impl Cursor {
+    fn encode(&self) -> String {
+        hex::encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    fn decode(cursor: &str) -> Option<Self> {
+        serde_json::from_slice(&hex::decode(cursor).ok()?).ok()
+    }
+}
+
+/// Which page to fetch and in what order.
+#[derive(Debug)]
+pub struct PageRequest {
+    leading: Option<&'static LeadingSort>,
+    sort: &'static SortField,
+    sort_param: String,
+    descending: bool,
+    per_page: i64,
+    page: i64,
+    cursor: Option<Cursor>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Pagination {
+    pub per_page: i64,
+    /// Set for offset paging; cursor paging skips the page number and count.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_pages: Option<i64>,
+    pub has_more: bool,
+    /// Pass as `cursor` to fetch the next page.
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub pagination: Pagination,
+}
+
+impl PageRequest {
+    fn parse<F: Sortable>(params: PageParams) -> Result<Self, String> {
+        let sort_param = params.sort.unwrap_or_else(|| F::DEFAULT_SORT.to_string());
+        let (name, descending) = match sort_param.strip_prefix('-') {
+            Some(name) => (name, true),
+            None => (sort_param.as_str(), false),
+        };
+        let Some(sort) = F::SORT_FIELDS.iter().find(|field| field.name == name) else {
+            let names: Vec<&str> = F::SORT_FIELDS.iter().map(|field| field.name).collect();
+            return Err(format!(
+                "Invalid sort. Valid values: {}, prefixed with - for descending",
+                names.join(", ")
+            ));
+        };
+
+        let per_page = params.per_page.unwrap_or(DEFAULT_PER_PAGE);
+        if !(1..=MAX_PER_PAGE).contains(&per_page) {
+            return Err(format!("per_page must be between 1 and {}", MAX_PER_PAGE));
+        }
+
+        let page = params.page.unwrap_or(1);
+        if page < 1 {
+            return Err("page must be 1 or more".to_string());
+        }
+
+        let cursor = match params.cursor.as_deref() {
+            None | Some("") => None,
+            Some(cursor) => match Cursor::decode(cursor) {
+                Some(cursor) if cursor.lead.is_some() != F::LEADING_SORT.is_some() => {
+                    return Err("Invalid cursor".to_string());
+                }
+                Some(cursor) if cursor.sort == sort_param => Some(cursor),
+                Some(_) => return Err("cursor was issued for a different sort".to_string()),
+                None => return Err("Invalid cursor".to_string()),
+            },
+        };
+        if cursor.is_some() && params.page.is_some() {
+            return Err("Use either page or cursor, not both".to_string());
+        }
+
+        Ok(Self {
+            leading: F::LEADING_SORT,
+            sort,
+            sort_param,
+            descending,
+            per_page,
+            page,
+            cursor,
+        })
+    }
+
+    /// The query for one page: `base` ordered by the sort columns then id, resuming after the
+    /// cursor when there is one. A row past the page is fetched to tell whether more follow.
+    fn page_query<'a>(
+        &self,
+        base: impl Fn(&mut QueryBuilder<'a, Postgres>),
+    ) -> QueryBuilder<'a, Postgres> {
+        let column = self.sort.name;
+        let direction = if self.descending { "DESC" } else { "ASC" };
+
+        let mut query = QueryBuilder::new("WITH base AS (");
+        base(&mut query);
+        query.push(format!(") SELECT t.*, t.{column}::text AS page_sort_value"));
+        if let Some(leading) = self.leading {
+            query.push(format!(
+                ", t.{}::text AS page_lead_value",
+                leading.field.name
+            ));
+        }
+        query.push(" FROM base t");
+        if let Some(cursor) = &self.cursor {
+            let comparison = if self.descending { "<" } else { ">" };
+            query.push(" WHERE ");
+            // Rows after the cursor within its leading group, then every later group
+            if let (Some(leading), Some(lead)) = (self.leading, &cursor.lead) {
+                let lead_column = leading.field.name;
+                let lead_comparison = if leading.descending { "<" } else { ">" };
+                let lead_type = leading.field.sql_type;
+                query.push(format!("(t.{lead_column} {lead_comparison} "));
+                query.push_bind(lead.clone());
+                query.push(format!("::{lead_type} OR (t.{lead_column} = "));
+                query.push_bind(lead.clone());
+                query.push(format!("::{lead_type} AND "));
+            }
+            query.push(format!("(t.{column}, t.id) {comparison} ("));
+            query.push_bind(cursor.value.clone());
+            query.push(format!("::{}, ", self.sort.sql_type));
+            query.push_bind(cursor.id);
+            query.push(")");
+            if self.leading.is_some() {
+                query.push("))");
+            }
+        }
+        query.push(" ORDER BY ");
+        if let Some(leading) = self.leading {
+            let lead_direction = if leading.descending { "DESC" } else { "ASC" };
+            query.push(format!("t.{} {lead_direction}, ", leading.field.name));
+        }
+        query.push(format!("t.{column} {direction}, t.id {direction} LIMIT "));
+        query.push_bind(self.per_page + 1);
+        if self.cursor.is_none() {
+            query.push(" OFFSET ");
+            query.push_bind((self.page - 1) * self.per_page);
+        }
+
+        query
+    }
+
+    /// Runs the query `base` pushes, which must select an `id` column and the sort columns,
+    /// one page at a time.
+    pub async fn fetch<'a, T>(
+        &self,
+        pool: &DbPool,
+        base: impl Fn(&mut QueryBuilder<'a, Postgres>),
+    ) -> Result<Page<T>, sqlx::Error>
+    where
+        T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+    {
+        let mut query = self.page_query(&base);
+        let mut rows = query.build().fetch_all(pool).await?;
+        let has_more = rows.len() as i64 > self.per_page;
+        rows.truncate(self.per_page as usize);
+
+        let next_cursor = match rows.last() {
+            Some(row) if has_more => Some(
+                Cursor {
+                    sort: self.sort_param.clone(),
+                    lead: match self.leading {
+                        Some(_) => Some(row.try_get("page_lead_value")?),
+                        None => None,
+                    },
+                    value: row.try_get("page_sort_value")?,
+                    id: row.try_get("id")?,
+                }
+                .encode(),
+            ),
+            _ => None,
+        };
+        let items = rows
+            .iter()
+            .map(T::from_row)
+            .collect::<Result<Vec<T>, _>>()?;
+
+        let (page, total, total_pages) = if self.cursor.is_none() {
+            let mut count = QueryBuilder::new("WITH base AS (");
+            base(&mut count);
+            count.push(") SELECT COUNT(*) FROM base");
+            let total: i64 = count.build_query_scalar().fetch_one(pool).await?;
+            let total_pages = (total + self.per_page - 1) / self.per_page;
+            (Some(self.page), Some(total), Some(total_pages))
+        } else {
+            (None, None, None)
+        };
+
+        Ok(Page {
+            items,
+            pagination: Pagination {
+                per_page: self.per_page,
+                page,
+                total,
+                total_pages,
+                has_more,
+                next_cursor,
+            },
+        })
+    }
+}
+
+/// Paging, sorting and field filters for a list endpoint, taken from the query string:
+/// `?page=2&per_page=50&sort=-created_at&status=pending`. Pass the `next_cursor` of a page
+/// as `cursor` instead of `page` to walk a listing that is changing underneath. The filters
+/// must pass their validation rules.
+pub struct ListQuery<F> {
+    pub page: PageRequest,
+    pub filter: F,
+}
+
+impl<F: Sortable + Validate + DeserializeOwned> FromRequest for ListQuery<F> {
+    type Error = AppError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let parsed = web::Query::<PageParams>::from_query(req.query_string())
+            .map_err(|e| e.to_string())
+            .and_then(|params| PageRequest::parse::<F>(params.into_inner()))
+            .and_then(|page| {
+                web::Query::<F>::from_query(req.query_string())
+                    .map(|filter| ListQuery {
+                        page,
+                        filter: filter.into_inner(),
+                    })
+                    .map_err(|e| e.to_string())
+            });
+
+        let parsed = parsed
+            .map_err(|message| AppError::bad_request("invalid_query", message))
+            .and_then(|query| {
+                query.filter.check()?;
+                Ok(query)
+            });
+        ready(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Plain;
+
+    impl Sortable for Plain {
+        const SORT_FIELDS: &'static [SortField] = &[
+            SortField {
+                name: "created_at",
+                sql_type: "timestamptz",
+            },
+            SortField {
+                name: "name",
+                sql_type: "text",
+            },
+        ];
+        const DEFAULT_SORT: &'static str = "-created_at";
+    }
+
+    struct Pinned;
+
+    impl Sortable for Pinned {
+        const SORT_FIELDS: &'static [SortField] = Plain::SORT_FIELDS;
+        const DEFAULT_SORT: &'static str = Plain::DEFAULT_SORT;
+        const LEADING_SORT: Option<&'static LeadingSort> = Some(&LeadingSort {
+            field: SortField {
+                name: "is_pinned",
+                sql_type: "boolean",
+            },
+            descending: true,
+        });
+    }
+
+    fn params(query: &str) -> PageParams {
+        web::Query::<PageParams>::from_query(query)
+            .unwrap()
+            .into_inner()
+    }
+
+    fn cursor(sort: &str, lead: Option<&str>) -> String {
+        Cursor {
+            sort: sort.to_string(),
+            lead: lead.map(str::to_string),
+            value: "2024-01-01T00:00:00Z".to_string(),
+            id: Uuid::nil(),
+        }
+        .encode()
+    }
+
+    fn page_sql(query: &str) -> String {
+        PageRequest::parse::<Plain>(params(query))
+            .unwrap()
+            .page_query(|query| {
+                query.push("SELECT id, name, created_at FROM users");
+            })
+            .sql()
+            .to_string()
+    }
+
+    #[test]
+    fn cursor_round_trips() {
+        let decoded = Cursor::decode(&cursor("-name", Some("true"))).unwrap();
+        assert_eq!(decoded.sort, "-name");
+        assert_eq!(decoded.lead.as_deref(), Some("true"));
+        assert_eq!(decoded.value, "2024-01-01T00:00:00Z");
+        assert_eq!(decoded.id, Uuid::nil());
+    }
+
+    #[test]
+    fn cursor_without_lead_leaves_it_out() {
+        let encoded = cursor("name", None);
+        let json = String::from_utf8(hex::decode(&encoded).unwrap()).unwrap();
+        assert!(!json.contains("lead"));
+        assert!(Cursor::decode(&encoded).unwrap().lead.is_none());
+    }
+
+    #[test]
+    fn cursor_rejects_garbage() {
+        assert!(Cursor::decode("not hex").is_none());
+        assert!(Cursor::decode(&hex::encode("{\"sort\":1}")).is_none());
+        assert!(Cursor::decode("").is_none());
+    }
+
+    #[test]
+    fn parse_uses_defaults() {
+        let page = PageRequest::parse::<Plain>(params("")).unwrap();
+        assert_eq!(page.sort.name, "created_at");
+        assert!(page.descending);
+        assert_eq!(page.per_page, DEFAULT_PER_PAGE);
+        assert_eq!(page.page, 1);
+        assert!(page.cursor.is_none());
+        assert!(page.leading.is_none());
+    }
+
+    #[test]
+    fn parse_reads_sort_direction() {
+        let page = PageRequest::parse::<Plain>(params("sort=name")).unwrap();
+        assert_eq!(page.sort.name, "name");
+        assert!(!page.descending);
+
+        let page = PageRequest::parse::<Plain>(params("sort=-name")).unwrap();
+        assert!(page.descending);
+    }
+
+    #[test]
+    fn parse_rejects_unknown_sort() {
+        let error = PageRequest::parse::<Plain>(params("sort=password_hash")).unwrap_err();
+        assert!(error.contains("created_at, name"));
+    }
+
+    #[test]
+    fn parse_bounds_per_page_and_page() {
+        assert!(PageRequest::parse::<Plain>(params("per_page=0")).is_err());
+        assert!(PageRequest::parse::<Plain>(params("per_page=101")).is_err());
+        assert!(PageRequest::parse::<Plain>(params("per_page=100")).is_ok());
+        assert!(PageRequest::parse::<Plain>(params("page=0")).is_err());
+        assert_eq!(
+            PageRequest::parse::<Plain>(params("page=3")).unwrap().page,
+            3
+        );
+    }
+
+    #[test]
+    fn parse_accepts_a_matching_cursor() {
+        let query = format!("sort=name&cursor={}", cursor("name", None));
+        let page = PageRequest::parse::<Plain>(params(&query)).unwrap();
+        assert_eq!(page.cursor.unwrap().sort, "name");
+
+        let page = PageRequest::parse::<Plain>(params("cursor=")).unwrap();
+        assert!(page.cursor.is_none());
+    }
+
+    #[test]
+    fn parse_rejects_cursor_for_another_sort() {
+        let query = format!("sort=-name&cursor={}", cursor("name", None));
+        assert_eq!(
+            PageRequest::parse::<Plain>(params(&query)).unwrap_err(),
+            "cursor was issued for a different sort"
+        );
+    }
+
+    #[test]
+    fn parse_rejects_cursor_and_page_together() {
+        let query = format!("page=2&cursor={}", cursor("-created_at", None));
+        assert!(PageRequest::parse::<Plain>(params(&query)).is_err());
+    }
+
+    #[test]
+    fn parse_rejects_bad_cursor() {
+        assert_eq!(
+            PageRequest::parse::<Plain>(params("cursor=zz")).unwrap_err(),
+            "Invalid cursor"
+        );
+    }
+
+    #[test]
+    fn parse_checks_cursor_lead_matches_leading_sort() {
+        let unpinned = format!("cursor={}", cursor("-created_at", None));
+        let pinned = format!("cursor={}", cursor("-created_at", Some("true")));
+
+        assert!(PageRequest::parse::<Pinned>(params(&unpinned)).is_err());
+        assert!(PageRequest::parse::<Plain>(params(&pinned)).is_err());
+
+        let page = PageRequest::parse::<Pinned>(params(&pinned)).unwrap();
+        assert_eq!(page.leading.unwrap().field.name, "is_pinned");
+        assert_eq!(page.cursor.unwrap().lead.as_deref(), Some("true"));
+    }
+
+    #[test]
+    fn offset_pages_fetch_one_extra_row() {
+        assert_eq!(
+            page_sql("sort=name&page=2"),
+            "WITH base AS (SELECT id, name, created_at FROM users) \
+             SELECT t.*, t.name::text AS page_sort_value FROM base t \
+             ORDER BY t.name ASC, t.id ASC LIMIT $1 OFFSET $2"
+        );
+    }
+
+    #[test]
+    fn cursor_pages_resume_after_the_last_row() {
+        assert_eq!(
+            page_sql(&format!("cursor={}", cursor("-created_at", None))),
+            "WITH base AS (SELECT id, name, created_at FROM users) \
+             SELECT t.*, t.created_at::text AS page_sort_value FROM base t \
+             WHERE (t.created_at, t.id) < ($1::timestamptz, $2) \
+             ORDER BY t.created_at DESC, t.id DESC LIMIT $3"
+        );
+        assert!(
+            page_sql(&format!("sort=name&cursor={}", cursor("name", None)))
+                .contains("WHERE (t.name, t.id) > ($1::text, $2)")
+        );
+    }
+
+    #[test]
+    fn leading_sort_orders_first_and_bounds_the_cursor() {
+        let query = format!("sort=name&cursor={}", cursor("name", Some("true")));
+        let sql = PageRequest::parse::<Pinned>(params(&query))
+            .unwrap()
+            .page_query(|query| {
+                query.push("SELECT id, name, is_pinned FROM announcements");
+            })
+            .sql()
+            .to_string();
+
+        assert_eq!(
+            sql,
+            "WITH base AS (SELECT id, name, is_pinned FROM announcements) \
+             SELECT t.*, t.name::text AS page_sort_value, t.is_pinned::text AS page_lead_value \
+             FROM base t \
+             WHERE (t.is_pinned < $1::boolean OR (t.is_pinned = $2::boolean \
+             AND (t.name, t.id) > ($3::text, $4))) \
+             ORDER BY t.is_pinned DESC, t.name ASC, t.id ASC LIMIT $5"
+        );
+    }
+}
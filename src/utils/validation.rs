@@ -0,0 +1,469 @@
+use crate::errors::AppError;
+use actix_web::dev::Payload;
+use actix_web::{FromRequest, HttpRequest, web};
+use futures_util::future::LocalBoxFuture;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fmt::Display;
+use std::future::{Ready, ready};
+use std::ops::{Deref, RangeInclusive};
+
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+pub const MAX_PASSWORD_LENGTH: usize = 128;
+const MAX_EMAIL_LENGTH: usize = 254;
+
+/// One field that failed a rule. `code` is stable for clients; `message` is for people.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub code: &'static str,
+    pub message: String,
+}
+
+/// Declares the rules a request must pass before its handler runs. DTOs with nothing to
+/// check implement it with no body.
+pub trait Validate {
+    fn validate(&self, _v: &mut Validator) {}
+
+    /// Runs the rules, failing with every invalid field at once.
+    fn check(&self) -> Result<(), AppError> {
+        let mut validator = Validator::default();
+        self.validate(&mut validator);
+        validator.finish()
+    }
+}
+
+/// Collects the errors from a request's rules.
+#[derive(Debug, Default)]
+pub struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    /// Starts the rules for a required field.
+    pub fn field<'v, 'a, T: ?Sized>(
+        &'v mut self,
+        name: &'static str,
+        value: &'a T,
+    ) -> Field<'v, 'a, T> {
+        Field {
+            validator: self,
+            name,
+            value: Some(value),
+        }
+    }
+
+    /// Starts the rules for an optional field; they are skipped when it is absent.
+    pub fn optional<'v, 'a, T: ?Sized>(
+        &'v mut self,
+        name: &'static str,
+        value: Option<&'a T>,
+    ) -> Field<'v, 'a, T> {
+        Field {
+            validator: self,
+            name,
+            value,
+        }
+    }
+
+    /// Records a rule that spans fields, such as a date range, against `field`.
+    pub fn check(
+        &mut self,
+        field: &'static str,
+        valid: bool,
+        code: &'static str,
+        message: impl Into<String>,
+    ) {
+        if !valid {
+            self.add(field, code, message);
+        }
+    }
+
+    fn add(&mut self, field: &str, code: &'static str, message: impl Into<String>) {
+        // One reason per field is enough; the first rule to fail is the most basic
+        if self.errors.iter().any(|error| error.field == field) {
+            return;
+        }
+        self.errors.push(FieldError {
+            field: field.to_string(),
+            code,
+            message: message.into(),
+        });
+    }
+
+    pub fn finish(self) -> Result<(), AppError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Validation(self.errors))
+        }
+    }
+}
+
+/// The rules for one field, chained off [`Validator::field`] or [`Validator::optional`].
+pub struct Field<'v, 'a, T: ?Sized> {
+    validator: &'v mut Validator,
+    name: &'static str,
+    value: Option<&'a T>,
+}
+
+impl<T: ?Sized> Field<'_, '_, T> {
+    fn rule(self, valid: impl FnOnce(&T) -> bool, code: &'static str, message: String) -> Self {
+        if let Some(value) = self.value
+            && !valid(value)
+        {
+            self.validator.add(self.name, code, message);
+        }
+        self
+    }
+}
+
+impl Field<'_, '_, str> {
+    /// Not empty or only whitespace.
+    pub fn required(self) -> Self {
+        self.rule(
+            |value| !value.trim().is_empty(),
+            "required",
+            "must not be empty".to_string(),
+        )
+    }
+
+    pub fn max_chars(self, max: usize) -> Self {
+        self.rule(
+            |value| value.chars().count() <= max,
+            "too_long",
+            format!("must be at most {} characters", max),
+        )
+    }
+
+    pub fn email(self) -> Self {
+        self.rule(
+            is_email,
+            "invalid_email",
+            "must be a valid email address".to_string(),
+        )
+    }
+
+    /// At least [`MIN_PASSWORD_LENGTH`] characters with upper and lower case letters and a digit.
+    pub fn password(self) -> Self {
+        self.rule(
+            |value| {
+                (MIN_PASSWORD_LENGTH..=MAX_PASSWORD_LENGTH).contains(&value.chars().count())
+                    && value.chars().any(char::is_lowercase)
+                    && value.chars().any(char::is_uppercase)
+                    && value.chars().any(|c| c.is_ascii_digit())
+            },
+            "weak_password",
+            format!(
+                "must be {} to {} characters and include upper and lower case letters and a digit",
+                MIN_PASSWORD_LENGTH, MAX_PASSWORD_LENGTH
+            ),
+        )
+    }
+
+    /// An absolute `http` or `https` URL.
+    pub fn url(self) -> Self {
+        self.rule(
+            |value| {
+                value
+                    .strip_prefix("https://")
+                    .or_else(|| value.strip_prefix("http://"))
+                    .is_some_and(|rest| {
+                        !rest.is_empty()
+                            && !rest.starts_with('/')
+                            && !rest.contains(char::is_whitespace)
+                    })
+            },
+            "invalid_url",
+            "must be an http or https URL".to_string(),
+        )
+    }
+}
+
+impl Field<'_, '_, Decimal> {
+    pub fn non_negative(self) -> Self {
+        self.rule(
+            |value| *value >= Decimal::ZERO,
+            "negative",
+            "must not be negative".to_string(),
+        )
+    }
+
+    pub fn positive(self) -> Self {
+        self.rule(
+            |value| *value > Decimal::ZERO,
+            "not_positive",
+            "must be greater than zero".to_string(),
+        )
+    }
+}
+
+impl<T: PartialOrd + Display> Field<'_, '_, T> {
+    pub fn range(self, range: RangeInclusive<T>) -> Self {
+        let message = format!("must be between {} and {}", range.start(), range.end());
+        self.rule(|value| range.contains(value), "out_of_range", message)
+    }
+}
+
+fn is_email(value: &str) -> bool {
+    let Some((local, domain)) = value.rsplit_once('@') else {
+        return false;
+    };
+    value.len() <= MAX_EMAIL_LENGTH
+        && !local.is_empty()
+        && !local.contains('@')
+        && !value.contains(char::is_whitespace)
+        && domain.contains('.')
+        && domain.split('.').all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+        })
+}
+
+/// `web::Json` whose body has passed its [`Validate`] rules.
+#[derive(Debug)]
+pub struct ValidJson<T>(pub T);
+
+impl<T> ValidJson<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for ValidJson<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: DeserializeOwned + Validate + 'static> FromRequest for ValidJson<T> {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let json = web::Json::<T>::from_request(req, payload);
+        Box::pin(async move {
+            let body = json.await?.into_inner();
+            body.check()?;
+            Ok(ValidJson(body))
+        })
+    }
+}
+
+/// `web::Query` whose parameters have passed their [`Validate`] rules.
+#[derive(Debug)]
+pub struct ValidQuery<T>(pub T);
+
+impl<T> ValidQuery<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for ValidQuery<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: DeserializeOwned + Validate> FromRequest for ValidQuery<T> {
+    type Error = AppError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let query = web::Query::<T>::from_query(req.query_string())
+            .map_err(|e| AppError::bad_request("invalid_query", e.to_string()))
+            .and_then(|query| {
+                let query = query.into_inner();
+                query.check()?;
+                Ok(ValidQuery(query))
+            });
+        ready(query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::ResponseError;
+    use actix_web::http::StatusCode;
+
+    struct Signup {
+        name: &'static str,
+        email: &'static str,
+        password: &'static str,
+        website: Option<&'static str>,
+    }
+
+    impl Validate for Signup {
+        fn validate(&self, v: &mut Validator) {
+            v.field("name", self.name).required().max_chars(5);
+            v.field("email", self.email).email();
+            v.field("password", self.password).password();
+            v.optional("website", self.website).url();
+        }
+    }
+
+    fn valid() -> Signup {
+        Signup {
+            name: "Ada",
+            email: "ada@example.com",
+            password: "Passw0rdx",
+            website: None,
+        }
+    }
+
+    fn errors(result: Result<(), AppError>) -> Vec<(String, &'static str)> {
+        match result {
+            Ok(()) => Vec::new(),
+            Err(AppError::Validation(errors)) => errors
+                .into_iter()
+                .map(|error| (error.field, error.code))
+                .collect(),
+            Err(e) => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    fn codes(
+        value: &str,
+        rule: for<'v, 'a> fn(Field<'v, 'a, str>) -> Field<'v, 'a, str>,
+    ) -> Vec<&'static str> {
+        let mut v = Validator::default();
+        rule(v.field("value", value));
+        errors(v.finish())
+            .into_iter()
+            .map(|(_, code)| code)
+            .collect()
+    }
+
+    #[test]
+    fn passes_a_valid_request() {
+        assert!(valid().check().is_ok());
+    }
+
+    #[test]
+    fn reports_every_invalid_field_once() {
+        let request = Signup {
+            name: " ",
+            email: "ada",
+            password: "short",
+            website: Some("ftp://example.com"),
+        };
+        assert_eq!(
+            errors(request.check()),
+            vec![
+                ("name".to_string(), "required"),
+                ("email".to_string(), "invalid_email"),
+                ("password".to_string(), "weak_password"),
+                ("website".to_string(), "invalid_url"),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_the_first_failure_per_field() {
+        let mut v = Validator::default();
+        v.field("name", "").required().max_chars(0);
+        v.check("name", false, "custom", "fails too");
+        assert_eq!(errors(v.finish()), vec![("name".to_string(), "required")]);
+    }
+
+    #[test]
+    fn skips_absent_optional_fields() {
+        let mut v = Validator::default();
+        v.optional::<str>("website", None).required().url();
+        assert!(v.finish().is_ok());
+    }
+
+    #[test]
+    fn max_chars_counts_characters_not_bytes() {
+        assert!(codes("héllo", |f| f.max_chars(5)).is_empty());
+        assert_eq!(codes("héllos", |f| f.max_chars(5)), vec!["too_long"]);
+    }
+
+    #[test]
+    fn checks_emails() {
+        for email in ["ada@example.com", "a.b+c@mail.example.org"] {
+            assert!(codes(email, |f| f.email()).is_empty(), "{}", email);
+        }
+        for email in [
+            "",
+            "ada",
+            "@example.com",
+            "ada@example",
+            "ada@@example.com",
+            "ada @example.com",
+            "ada@-example.com",
+            "ada@example..com",
+        ] {
+            assert_eq!(
+                codes(email, |f| f.email()),
+                vec!["invalid_email"],
+                "{}",
+                email
+            );
+        }
+    }
+
+    #[test]
+    fn checks_passwords() {
+        assert!(codes("Passw0rdx", |f| f.password()).is_empty());
+        for password in ["Pa0", "password1", "PASSWORD1", "Passwordx"] {
+            assert_eq!(codes(password, |f| f.password()), vec!["weak_password"]);
+        }
+        let long = format!("Aa1{}", "x".repeat(MAX_PASSWORD_LENGTH));
+        assert_eq!(codes(&long, |f| f.password()), vec!["weak_password"]);
+    }
+
+    #[test]
+    fn checks_urls() {
+        for url in ["https://example.com/a", "http://localhost:3000"] {
+            assert!(codes(url, |f| f.url()).is_empty(), "{}", url);
+        }
+        for url in [
+            "example.com",
+            "https://",
+            "https:///path",
+            "https://a b",
+            "ftp://x",
+        ] {
+            assert_eq!(codes(url, |f| f.url()), vec!["invalid_url"], "{}", url);
+        }
+    }
+
+    #[test]
+    fn checks_numbers() {
+        let mut v = Validator::default();
+        v.field("amount", &Decimal::NEGATIVE_ONE).non_negative();
+        v.field("price", &Decimal::ZERO).positive();
+        v.field("year", &1999).range(2000..=2100);
+        v.field("month", &12).range(1..=12);
+        assert_eq!(
+            errors(v.finish()),
+            vec![
+                ("amount".to_string(), "negative"),
+                ("price".to_string(), "not_positive"),
+                ("year".to_string(), "out_of_range"),
+            ]
+        );
+    }
+
+    #[test]
+    fn failures_are_unprocessable() {
+        let error = Signup {
+            email: "ada",
+            ..valid()
+        }
+        .check()
+        .unwrap_err();
+        assert_eq!(error.code(), "validation_failed");
+        assert_eq!(error.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+}